@@ -0,0 +1,381 @@
+//! Build guest programs for Ceno.
+//!
+//! A guest is any crate that depends on `ceno_rt`. It is compiled for [`TARGET`] with the
+//! linker scripts of `ceno_rt`, and the resulting ELFs are returned with their [`ImageId`].
+//!
+//! From the `build.rs` of a host crate:
+//!
+//! ```no_run
+//! let elfs = ceno_build::GuestBuilder::new("../guest")
+//!     .all_examples()
+//!     .build()
+//!     .expect("failed to build the guest");
+//! let out_dir = std::env::var_os("OUT_DIR").unwrap();
+//! ceno_build::write_consts(std::path::Path::new(&out_dir).join("guests.rs"), &elfs).unwrap();
+//! ```
+//!
+//! Then `include!(concat!(env!("OUT_DIR"), "/guests.rs"));` defines one `&[u8]` const per ELF.
+use std::{
+    collections::BTreeSet,
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::{Context, Result, bail};
+use ceno_elf::Program;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// The target triple of guest programs.
+pub const TARGET: &str = "riscv32im-unknown-none-elf";
+
+/// The linker flags of guest programs. The scripts are provided by the build script of `ceno_rt`.
+///
+/// They are passed as `build.rustflags`, so they are appended to the `build.rustflags` of the
+/// guest configuration, and ignored if it sets `target.riscv32im-unknown-none-elf.rustflags`,
+/// which must then include the linker scripts.
+const RUSTFLAGS: &[&str] = &["-C", "link-arg=-Tmemory.x", "-C", "link-arg=-Tceno_link.x"];
+
+/// Variables of the calling cargo that must not leak into the guest build, so that the guest
+/// uses its own toolchain and flags.
+const CLEARED_ENV: &[&str] = &[
+    "RUSTC",
+    "RUSTDOC",
+    "RUSTC_WRAPPER",
+    "RUSTC_WORKSPACE_WRAPPER",
+    "RUSTFLAGS",
+    // Set by cargo for build scripts, see below.
+    "CARGO_ENCODED_RUSTFLAGS",
+    "CARGO_PRIMARY_PACKAGE",
+];
+
+/// Prefixes of the variables that cargo sets for the build script of the calling crate, which
+/// describe that crate. Other `CARGO_*` variables, such as `CARGO_HOME` or `CARGO_TARGET_DIR`,
+/// are the configuration of the user and are kept.
+const BUILD_SCRIPT_ENV_PREFIXES: &[&str] = &[
+    "CARGO_CFG_",
+    "CARGO_FEATURE_",
+    "CARGO_MANIFEST_",
+    "CARGO_PKG_",
+];
+
+/// The SHA-256 digest of a loaded program: its entry point, code and initial memory image.
+pub type ImageId = [u8; 32];
+
+/// A compiled guest program.
+#[derive(Clone, Debug)]
+pub struct GuestElf {
+    /// The name of the cargo target (binary or example).
+    pub name: String,
+    pub path: PathBuf,
+    pub elf: Vec<u8>,
+    pub image_id: ImageId,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum GuestTarget {
+    Bin(String),
+    Example(String),
+    AllBins,
+    AllExamples,
+}
+
+/// Configure and run the build of a guest crate.
+#[derive(Clone, Debug)]
+pub struct GuestBuilder {
+    manifest_dir: PathBuf,
+    target_dir: Option<PathBuf>,
+    profile: String,
+    targets: Vec<GuestTarget>,
+    features: Vec<String>,
+    no_default_features: bool,
+}
+
+impl GuestBuilder {
+    /// Build the crate at `manifest_dir`, in release mode, and all its binaries by default.
+    pub fn new(manifest_dir: impl Into<PathBuf>) -> Self {
+        GuestBuilder {
+            manifest_dir: manifest_dir.into(),
+            target_dir: None,
+            profile: "release".to_string(),
+            targets: vec![],
+            features: vec![],
+            no_default_features: false,
+        }
+    }
+
+    /// Set the cargo target directory. Defaults to the one of the guest crate.
+    pub fn target_dir(mut self, target_dir: impl Into<PathBuf>) -> Self {
+        self.target_dir = Some(target_dir.into());
+        self
+    }
+
+    pub fn profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = profile.into();
+        self
+    }
+
+    pub fn bin(mut self, name: impl Into<String>) -> Self {
+        self.targets.push(GuestTarget::Bin(name.into()));
+        self
+    }
+
+    pub fn example(mut self, name: impl Into<String>) -> Self {
+        self.targets.push(GuestTarget::Example(name.into()));
+        self
+    }
+
+    pub fn all_bins(mut self) -> Self {
+        self.targets.push(GuestTarget::AllBins);
+        self
+    }
+
+    pub fn all_examples(mut self) -> Self {
+        self.targets.push(GuestTarget::AllExamples);
+        self
+    }
+
+    pub fn feature(mut self, feature: impl Into<String>) -> Self {
+        self.features.push(feature.into());
+        self
+    }
+
+    pub fn no_default_features(mut self) -> Self {
+        self.no_default_features = true;
+        self
+    }
+
+    /// The `cargo build` command of the guest.
+    pub fn command(&self) -> Command {
+        let mut cmd = Command::new("cargo");
+        cmd.current_dir(&self.manifest_dir)
+            .args(["build", "--message-format=json-render-diagnostics"])
+            .args(["--target", TARGET, "--profile", &self.profile])
+            .arg("--config")
+            .arg(format!("build.rustflags = {RUSTFLAGS:?}"));
+        if let Some(target_dir) = &self.target_dir {
+            cmd.arg("--target-dir").arg(target_dir);
+        }
+        for target in &self.targets {
+            match target {
+                GuestTarget::Bin(name) => cmd.args(["--bin", name]),
+                GuestTarget::Example(name) => cmd.args(["--example", name]),
+                GuestTarget::AllBins => cmd.arg("--bins"),
+                GuestTarget::AllExamples => cmd.arg("--examples"),
+            };
+        }
+        if !self.features.is_empty() {
+            cmd.args(["--features", &self.features.join(",")]);
+        }
+        if self.no_default_features {
+            cmd.arg("--no-default-features");
+        }
+
+        // When called from a build script, the environment describes the host crate.
+        for (key, _) in std::env::vars_os() {
+            if key.to_str().is_some_and(|key| {
+                BUILD_SCRIPT_ENV_PREFIXES
+                    .iter()
+                    .any(|prefix| key.starts_with(prefix))
+            }) {
+                cmd.env_remove(&key);
+            }
+        }
+        for key in CLEARED_ENV {
+            cmd.env_remove(key);
+        }
+        cmd
+    }
+
+    /// Build the guest, and load the ELFs of the selected binaries and examples.
+    pub fn build(&self) -> Result<Vec<GuestElf>> {
+        let output = self
+            .command()
+            .stderr(Stdio::inherit())
+            .output()
+            .context("failed to run cargo")?;
+        if !output.status.success() {
+            bail!(
+                "cargo build of {:?} failed with {}",
+                self.manifest_dir,
+                output.status
+            );
+        }
+        parse_artifacts(&String::from_utf8_lossy(&output.stdout))?
+            .into_iter()
+            .map(|(name, path)| {
+                let elf = fs::read(&path).with_context(|| format!("failed to read {path:?}"))?;
+                let image_id = image_id(&elf).with_context(|| format!("invalid ELF {path:?}"))?;
+                Ok(GuestElf {
+                    name,
+                    path,
+                    elf,
+                    image_id,
+                })
+            })
+            .collect()
+    }
+
+    /// Tell cargo to rerun the calling build script when the sources of the guest change.
+    ///
+    /// Path dependencies outside of the guest directory must be declared separately.
+    pub fn rerun_if_changed(&self) -> Result<()> {
+        let target_dir = self.manifest_dir.join("target");
+        for entry in fs::read_dir(&self.manifest_dir)? {
+            let path = entry?.path();
+            if path != target_dir {
+                println!("cargo:rerun-if-changed={}", path.display());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct ArtifactMessage {
+    reason: String,
+    target: Option<ArtifactTarget>,
+    executable: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct ArtifactTarget {
+    name: String,
+}
+
+/// Find the executables in the JSON messages of `cargo build`.
+fn parse_artifacts(messages: &str) -> Result<Vec<(String, PathBuf)>> {
+    let mut artifacts = vec![];
+    for line in messages.lines().filter(|line| line.starts_with('{')) {
+        let message: ArtifactMessage = serde_json::from_str(line)
+            .with_context(|| format!("invalid cargo message {line:?}"))?;
+        if let (true, Some(target), Some(executable)) = (
+            message.reason == "compiler-artifact",
+            message.target,
+            message.executable,
+        ) {
+            artifacts.push((target.name, executable));
+        }
+    }
+    Ok(artifacts)
+}
+
+/// Compute the image ID of a guest ELF.
+///
+/// It depends only on the loaded program, not on symbols or debug information. It hashes the
+/// program as loaded by the VM, see [`Program::load_elf`]: the entry point, the base address of
+/// the code, the instructions and the initial memory image.
+pub fn image_id(elf: &[u8]) -> Result<ImageId> {
+    let program = Program::load_elf(elf, u32::MAX)?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(program.entry.to_le_bytes());
+    hasher.update(program.base_address.to_le_bytes());
+    hasher.update((program.instructions.len() as u32).to_le_bytes());
+    for insn in &program.instructions {
+        hasher.update(insn.to_le_bytes());
+    }
+    for (addr, value) in &program.image {
+        hasher.update(addr.to_le_bytes());
+        hasher.update(value.to_le_bytes());
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// Format an image ID as hexadecimal.
+pub fn image_id_hex(image_id: &ImageId) -> String {
+    image_id.iter().fold(String::new(), |mut hex, byte| {
+        write!(hex, "{byte:02x}").unwrap();
+        hex
+    })
+}
+
+/// Write a Rust file that defines one const per guest, holding its ELF, to be `include!`d.
+pub fn write_consts(path: impl AsRef<Path>, elfs: &[GuestElf]) -> Result<()> {
+    let mut names = BTreeSet::new();
+    let mut code = String::new();
+    for guest in elfs {
+        let name = guest.name.replace('-', "_");
+        if !names.insert(name.clone()) {
+            bail!("duplicate guest name {name}");
+        }
+        writeln!(
+            code,
+            r#"#[allow(non_upper_case_globals)]
+pub const {name}: &[u8] = include_bytes!(r"{}");"#,
+            guest.path.display()
+        )?;
+    }
+    fs::write(path, code)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_artifacts() -> Result<()> {
+        let messages = r#"
+{"reason":"compiler-artifact","target":{"name":"ceno_rt","kind":["lib"]},"executable":null}
+{"reason":"compiler-artifact","target":{"name":"ceno_rt_mini","kind":["example"]},"executable":"/t/examples/ceno_rt_mini"}
+{"reason":"build-finished","success":true}
+"#;
+        assert_eq!(parse_artifacts(messages)?, vec![(
+            "ceno_rt_mini".to_string(),
+            PathBuf::from("/t/examples/ceno_rt_mini")
+        )]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_command() {
+        let cmd = GuestBuilder::new("guest")
+            .example("a")
+            .all_bins()
+            .feature("x")
+            .feature("y")
+            .command();
+        let args = cmd
+            .get_args()
+            .map(|a| a.to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(args, [
+            "build",
+            "--message-format=json-render-diagnostics",
+            "--target",
+            TARGET,
+            "--profile",
+            "release",
+            "--config",
+            r#"build.rustflags = ["-C", "link-arg=-Tmemory.x", "-C", "link-arg=-Tceno_link.x"]"#,
+            "--example",
+            "a",
+            "--bins",
+            "--features",
+            "x,y",
+        ]);
+
+        // cargo sets the variables of the package for its tests too
+        let removed = cmd
+            .get_envs()
+            .filter(|(_, value)| value.is_none())
+            .map(|(key, _)| key.to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert!(removed.contains(&"CARGO_MANIFEST_DIR"));
+        assert!(removed.contains(&"CARGO_PKG_NAME"));
+        assert!(!removed.contains(&"CARGO"));
+    }
+
+    #[test]
+    fn test_image_id() -> Result<()> {
+        let elf = include_bytes!("../../ceno_emul/tests/riscv-arch-test/elf/ceno-add.elf");
+        assert_eq!(
+            image_id_hex(&image_id(elf)?),
+            "e106dbf705c3059d71ab0e605b04e8016fb68246c3f5fc79bf566fbed9ebfdb7"
+        );
+        Ok(())
+    }
+}
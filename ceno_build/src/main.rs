@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use ceno_build::{GuestBuilder, image_id_hex};
+use clap::Parser;
+
+/// Build a guest crate for Ceno, and print the image ID and path of each ELF.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Directory of the guest crate
+    #[arg(default_value = ".")]
+    manifest_dir: PathBuf,
+
+    /// Build only the given binary
+    #[arg(long)]
+    bin: Vec<String>,
+
+    /// Build only the given example
+    #[arg(long)]
+    example: Vec<String>,
+
+    /// Build all binaries
+    #[arg(long)]
+    bins: bool,
+
+    /// Build all examples
+    #[arg(long)]
+    examples: bool,
+
+    /// Cargo profile
+    #[arg(long, default_value = "release")]
+    profile: String,
+
+    /// Cargo target directory
+    #[arg(long)]
+    target_dir: Option<PathBuf>,
+
+    /// Features to activate in the guest
+    #[arg(short = 'F', long)]
+    features: Vec<String>,
+
+    /// Do not activate the default features of the guest
+    #[arg(long)]
+    no_default_features: bool,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let mut builder = GuestBuilder::new(args.manifest_dir).profile(args.profile);
+    if let Some(target_dir) = args.target_dir {
+        builder = builder.target_dir(target_dir);
+    }
+    for name in args.bin {
+        builder = builder.bin(name);
+    }
+    for name in args.example {
+        builder = builder.example(name);
+    }
+    if args.bins {
+        builder = builder.all_bins();
+    }
+    if args.examples {
+        builder = builder.all_examples();
+    }
+    for feature in args.features {
+        builder = builder.feature(feature);
+    }
+    if args.no_default_features {
+        builder = builder.no_default_features();
+    }
+
+    for guest in builder.build()? {
+        println!("{} {}", image_id_hex(&guest.image_id), guest.path.display());
+    }
+    Ok(())
+}
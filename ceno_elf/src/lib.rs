@@ -14,11 +14,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+//! Load the programs of the Ceno VM from ELF files.
+
 extern crate alloc;
 
 use alloc::collections::BTreeMap;
 
-use crate::addr::WORD_SIZE;
 use anyhow::{Context, Result, anyhow, bail};
 use elf::{
     ElfBytes,
@@ -27,6 +28,9 @@ use elf::{
     file::Class,
 };
 
+/// The size of an instruction and of a memory word.
+const WORD_SIZE: usize = 4;
+
 /// A RISC Zero program
 #[derive(Clone, Debug)]
 pub struct Program {
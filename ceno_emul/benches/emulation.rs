@@ -0,0 +1,111 @@
+use std::{collections::HashMap, time::Duration};
+
+use ceno_emul::{
+    ByteAddr, CENO_PLATFORM,
+    InsnKind::{ADD, ADDI, BNE, EANY, LUI, LW, SW},
+    PagedMemory, Program, StepRecord, VMState, WORD_SIZE, Word, WordAddr, encode_rv32,
+};
+use criterion::*;
+
+criterion_group! {
+  name = emulation;
+  config = Criterion::default().warm_up_time(Duration::from_millis(3000));
+  targets = bench_memory_loop, bench_memory_backends
+}
+
+criterion_main!(emulation);
+
+const NUM_SAMPLES: usize = 10;
+
+/// Number of loop iterations, each of them writing and reading a fresh RAM word.
+const NUM_ITERATIONS: u32 = 1 << 18;
+
+/// A memory-heavy guest: fill a RAM array and read it back, one word per iteration.
+fn memory_loop_program() -> Program {
+    let code = [
+        encode_rv32(LUI, 0, 0, 10, CENO_PLATFORM.ram_start()), // lui x10, ram_start
+        encode_rv32(LUI, 0, 0, 1, NUM_ITERATIONS),             // lui x1, NUM_ITERATIONS >> 12
+        encode_rv32(ADDI, 1, 0, 1, 0),                         // addi x1, x1, 0
+        // Main loop.
+        encode_rv32(SW, 10, 1, 0, 0),              // sw x1, 0(x10)
+        encode_rv32(LW, 10, 0, 2, 0),              // lw x2, 0(x10)
+        encode_rv32(ADD, 2, 3, 3, 0),              // add x3, x2, x3
+        encode_rv32(ADDI, 10, 0, 10, 4),           // addi x10, x10, 4
+        encode_rv32(ADDI, 1, 0, 1, -1_i32 as u32), // addi x1, x1, -1
+        encode_rv32(BNE, 1, 0, 0, -20_i32 as u32), // bne x1, x0, -20
+        // End.
+        encode_rv32(EANY, 0, 0, 0, 0), // ecall halt
+    ];
+    Program::new(
+        CENO_PLATFORM.pc_base(),
+        CENO_PLATFORM.pc_base(),
+        code.to_vec(),
+        code.iter()
+            .enumerate()
+            .map(|(insn_idx, &insn)| {
+                (
+                    CENO_PLATFORM.pc_base() + (WORD_SIZE * insn_idx) as u32,
+                    insn,
+                )
+            })
+            .collect(),
+    )
+}
+
+fn run(program: Program) -> Vec<StepRecord> {
+    let mut vm = VMState::new(CENO_PLATFORM, program);
+    vm.iter_until_halt()
+        .collect::<anyhow::Result<Vec<_>>>()
+        .expect("vm exec failed")
+}
+
+fn bench_memory_loop(c: &mut Criterion) {
+    let program = memory_loop_program();
+    let num_steps = run(program.clone()).len();
+
+    let mut group = c.benchmark_group("emulation");
+    group.sample_size(NUM_SAMPLES);
+    // Elements are instructions, so the reported throughput is in MIPS (Melem/s).
+    group.throughput(Throughput::Elements(num_steps as u64));
+    group.bench_function("memory_loop", |b| {
+        b.iter_batched(
+            || program.clone(),
+            |program| black_box(run(program)),
+            BatchSize::LargeInput,
+        );
+    });
+    group.finish();
+}
+
+/// The memory accesses of `memory_loop_program`, on a `HashMap` as the emulator used before
+/// `PagedMemory`, and on a `PagedMemory`.
+fn bench_memory_backends(c: &mut Criterion) {
+    let addrs = (0..NUM_ITERATIONS)
+        .map(|i| ByteAddr(CENO_PLATFORM.ram_start() + i * WORD_SIZE as u32).waddr())
+        .collect::<Vec<WordAddr>>();
+
+    let mut group = c.benchmark_group("memory_backend");
+    group.sample_size(NUM_SAMPLES);
+    group.throughput(Throughput::Elements(addrs.len() as u64));
+    group.bench_function("hash_map", |b| {
+        b.iter(|| {
+            let mut memory = HashMap::<WordAddr, Word>::new();
+            for (i, &addr) in addrs.iter().enumerate() {
+                memory.insert(addr, i as Word + 1);
+                black_box(memory.get(&addr));
+            }
+            memory
+        });
+    });
+    group.bench_function("paged", |b| {
+        b.iter(|| {
+            let mut memory = PagedMemory::<Word>::new(&CENO_PLATFORM);
+            for (i, &addr) in addrs.iter().enumerate() {
+                memory.insert(addr, i as Word + 1);
+                black_box(memory.get(&addr));
+            }
+            memory
+        });
+    });
+    group.finish();
+}
@@ -0,0 +1,156 @@
+//! The host side of the IO channel of `ceno_rt::io`.
+//!
+//! Both directions use the same encoding: a sequence of frames, each made of a word holding
+//! the length in bytes, followed by the bytes padded to whole words. Typed values are encoded
+//! with `postcard`, one value per frame.
+//!
+//! The guest reads its input from the public IO region, and writes its output to the
+//! INFO_OUT region of `ceno_rt`, at `Platform::info_out_start`.
+
+use anyhow::{Context, Result, bail};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    EmuContext, VMState,
+    addr::{Addr, ByteAddr, WORD_SIZE, Word, WordAddr},
+};
+
+/// Build the input frames of a guest.
+#[derive(Clone, Debug, Default)]
+pub struct InputBuilder {
+    words: Vec<Word>,
+}
+
+impl InputBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a frame of raw bytes.
+    pub fn write_frame(&mut self, bytes: &[u8]) -> &mut Self {
+        self.words.push(bytes.len() as Word);
+        self.words.extend(bytes.chunks(WORD_SIZE).map(|chunk| {
+            let mut word = [0; WORD_SIZE];
+            word[..chunk.len()].copy_from_slice(chunk);
+            Word::from_le_bytes(word)
+        }));
+        self
+    }
+
+    /// Append a frame holding a serialized value.
+    pub fn write<T: Serialize>(&mut self, value: &T) -> Result<&mut Self> {
+        let bytes = postcard::to_allocvec(value).context("failed to serialize the input")?;
+        Ok(self.write_frame(&bytes))
+    }
+
+    /// The encoded frames, to be placed at the start of the public IO region.
+    pub fn words(&self) -> &[Word] {
+        &self.words
+    }
+
+    /// Write the frames into the public IO region of a machine.
+    pub fn init_memory(&self, state: &mut VMState) -> Result<()> {
+        let start = state.platform().public_io_start();
+        let capacity = (state.platform().public_io_end() - start + 1) as usize / WORD_SIZE;
+        if self.words.len() > capacity {
+            bail!(
+                "input of {} words does not fit in the public IO region of {capacity} words",
+                self.words.len()
+            );
+        }
+        let start = ByteAddr(start).waddr();
+        for (i, &word) in self.words.iter().enumerate() {
+            state.init_memory(start + i as u32, word);
+        }
+        Ok(())
+    }
+}
+
+/// Read the frames written by a guest, after its execution.
+pub struct OutputReader<'a> {
+    state: &'a VMState,
+    cursor: WordAddr,
+}
+
+impl<'a> OutputReader<'a> {
+    /// Read the frames starting at `addr`.
+    pub fn new(state: &'a VMState, addr: Addr) -> Self {
+        OutputReader {
+            state,
+            cursor: ByteAddr(addr).waddr(),
+        }
+    }
+
+    /// Read the output written by `ceno_rt` to INFO_OUT.
+    pub fn info_out(state: &'a VMState) -> Self {
+        Self::new(state, state.platform().info_out_start())
+    }
+
+    /// Read the next frame. Past the last frame, this returns an empty frame.
+    pub fn read_frame(&mut self) -> Vec<u8> {
+        let byte_len = self.state.peek_memory(self.cursor) as usize;
+        let word_len = byte_len.div_ceil(WORD_SIZE);
+        let mut bytes = (1..=word_len as u32)
+            .flat_map(|i| self.state.peek_memory(self.cursor + i).to_le_bytes())
+            .collect::<Vec<u8>>();
+        bytes.truncate(byte_len);
+        self.cursor += 1 + word_len as u32;
+        bytes
+    }
+
+    /// Read the next frame as a serialized value.
+    pub fn read<T: DeserializeOwned>(&mut self) -> Result<T> {
+        let bytes = self.read_frame();
+        postcard::from_bytes(&bytes).context("failed to deserialize the output")
+    }
+
+    /// Iterate over the remaining frames, until an empty frame.
+    pub fn frames(mut self) -> impl Iterator<Item = Vec<u8>> + 'a {
+        std::iter::from_fn(move || Some(self.read_frame()).filter(|frame| !frame.is_empty()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CENO_PLATFORM, Program};
+
+    #[test]
+    fn test_frames() -> Result<()> {
+        let mut state = VMState::new(
+            CENO_PLATFORM,
+            Program::new(0, 0, vec![], Default::default()),
+        );
+        let mut input = InputBuilder::new();
+        input
+            .write_frame(b"hello")
+            .write(&(7u32, vec![1u64, 2, 3]))?
+            .write(&"typed".to_string())?;
+        assert_eq!(input.words()[..3], [
+            5,
+            u32::from_le_bytes(*b"hell"),
+            b'o' as u32
+        ]);
+        input.init_memory(&mut state)?;
+
+        let mut reader = OutputReader::new(&state, CENO_PLATFORM.public_io_start());
+        assert_eq!(reader.read_frame(), b"hello");
+        assert_eq!(reader.read::<(u32, Vec<u64>)>()?, (7, vec![1, 2, 3]));
+        let frames = reader.frames().collect::<Vec<_>>();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(postcard::from_bytes::<String>(&frames[0])?, "typed");
+
+        let mut reader = OutputReader::new(&state, CENO_PLATFORM.public_io_start());
+        assert!(reader.read::<String>().is_err());
+
+        let too_large =
+            vec![0u8; (CENO_PLATFORM.public_io_end - CENO_PLATFORM.public_io_start) as usize];
+        assert!(
+            InputBuilder::new()
+                .write_frame(&too_large)
+                .init_memory(&mut state)
+                .is_err()
+        );
+        Ok(())
+    }
+}
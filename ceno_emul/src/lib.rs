@@ -2,19 +2,34 @@ mod addr;
 pub use addr::*;
 
 mod platform;
-pub use platform::{CENO_PLATFORM, Platform};
+pub use platform::{CENO_PLATFORM, Platform, StackGuard};
+
+mod io;
+pub use io::{InputBuilder, OutputReader};
+
+mod paged_memory;
+pub use paged_memory::PagedMemory;
 
 mod tracer;
 pub use tracer::{Change, MemOp, ReadOp, StepRecord, Tracer, WriteOp};
 
 mod vm_state;
-pub use vm_state::VMState;
+pub use vm_state::{VMSnapshot, VMState};
 
 mod rv32im;
 pub use rv32im::{DecodedInstruction, EmuContext, InsnCodes, InsnKind};
 
-mod elf;
-pub use elf::Program;
+mod syscalls;
+pub use syscalls::{
+    EC_POINT_WORDS, ED25519_D, ED25519_MODULUS, EcPoint, KECCAK_RHO_OFFSETS,
+    KECCAK_ROUND_CONSTANTS, KECCAK_ROUNDS, KECCAK_STATE_WORDS, POSEIDON_STATE_WORDS,
+    SECP256K1_MODULUS, SHA256_BLOCK_WORDS, SHA256_INIT, SHA256_ROUND_CONSTANTS, SHA256_ROUNDS,
+    SHA256_STATE_WORDS, SyscallWitness, UINT256_WORDS, ed25519_add, ed25519_double, keccak_f,
+    keccak_round, poseidon_permutation, secp256k1_add, secp256k1_double, sha256_compress_block,
+    sha256_next_word, sha256_round, uint256_add, uint256_mul, uint256_mulmod,
+};
+
+pub use ceno_elf::Program;
 
 mod rv32im_encode;
 pub use rv32im_encode::encode_rv32;
@@ -0,0 +1,234 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    addr::{Addr, ByteAddr, WORD_SIZE, WordAddr},
+    platform::Platform,
+    vm_state::VMState,
+};
+
+/// The number of words in a page (4 KiB).
+const PAGE_WORDS: usize = 1024;
+
+/// The number of pages in a page table (4 MiB).
+const TABLE_PAGES: usize = 1024;
+
+type Page<T> = Box<[T; PAGE_WORDS]>;
+
+type Table<T> = Box<[Option<Page<T>>]>;
+
+/// A map from word addresses to values, stored in flat pages that are allocated on first write.
+///
+/// Each region of the `Platform` (registers, ROM, RAM, public IO, program data) gets its own
+/// directory of page tables, so a lookup is a bounds check and three array indexations. Page
+/// tables are allocated on first write too, so that a large region only costs its directory.
+/// Addresses outside of all regions fall back to a `HashMap`.
+///
+/// Like a `HashMap` where `T::default()` values are never stored: a missing entry reads as
+/// `None`, and inserting `T::default()` removes the entry.
+#[derive(Clone)]
+pub struct PagedMemory<T> {
+    regions: Vec<Region<T>>,
+    others: HashMap<WordAddr, T>,
+}
+
+#[derive(Clone)]
+struct Region<T> {
+    /// The first word index (addr / 4) of the region.
+    start: u32,
+    /// The number of words in the region.
+    len: u32,
+    tables: Vec<Option<Table<T>>>,
+}
+
+impl<T: Copy + Default + PartialEq> Region<T> {
+    fn new(start: Addr, end: Addr) -> Self {
+        let start = start / WORD_SIZE as u32;
+        let len = end / WORD_SIZE as u32 - start + 1;
+        let num_pages = (len as usize).div_ceil(PAGE_WORDS);
+        Region {
+            start,
+            len,
+            tables: vec![None; num_pages.div_ceil(TABLE_PAGES)],
+        }
+    }
+
+    /// The offset of a word index in this region, if it belongs to it.
+    #[inline(always)]
+    fn offset(&self, word_index: u32) -> Option<usize> {
+        let offset = word_index.wrapping_sub(self.start);
+        (offset < self.len).then_some(offset as usize)
+    }
+
+    #[inline(always)]
+    fn page(&self, page: usize) -> Option<&Page<T>> {
+        self.tables[page / TABLE_PAGES].as_ref()?[page % TABLE_PAGES].as_ref()
+    }
+
+    /// The page, allocated along with its table if needed.
+    #[inline(always)]
+    fn page_mut(&mut self, page: usize) -> &mut Page<T> {
+        let table = self.tables[page / TABLE_PAGES]
+            .get_or_insert_with(|| vec![None; TABLE_PAGES].into_boxed_slice());
+        table[page % TABLE_PAGES].get_or_insert_with(|| Box::new([T::default(); PAGE_WORDS]))
+    }
+
+    /// Iterate over the allocated pages, with their index.
+    fn pages(&self) -> impl Iterator<Item = (usize, &Page<T>)> + '_ {
+        self.tables
+            .iter()
+            .enumerate()
+            .filter_map(|(table_idx, table)| table.as_ref().map(|table| (table_idx, table)))
+            .flat_map(|(table_idx, table)| {
+                table.iter().enumerate().filter_map(move |(idx, page)| {
+                    page.as_ref()
+                        .map(|page| (table_idx * TABLE_PAGES + idx, page))
+                })
+            })
+    }
+}
+
+impl<T: Copy + Default + PartialEq> PagedMemory<T> {
+    /// Create an empty memory with a page table for each region of the platform.
+    pub fn new(platform: &Platform) -> Self {
+        let mut regions: Vec<Region<T>> = vec![];
+        for (start, end) in [
+            (
+                platform.register_vma(0),
+                platform.register_vma(VMState::REG_COUNT - 1),
+            ),
+            (platform.rom_start(), platform.rom_end()),
+            (platform.ram_start(), platform.ram_end()),
+            (platform.public_io_start(), platform.public_io_end()),
+            (platform.program_data_start(), platform.program_data_end()),
+        ] {
+            if start > end {
+                continue;
+            }
+            let region = Region::new(start, end);
+            // The first region wins if the platform defines overlapping regions.
+            let overlaps = regions
+                .iter()
+                .any(|r| r.start < region.start + region.len && region.start < r.start + r.len);
+            if !overlaps {
+                regions.push(region);
+            }
+        }
+        PagedMemory {
+            regions,
+            others: HashMap::new(),
+        }
+    }
+
+    /// Find the region, page and offset in page of an address.
+    #[inline(always)]
+    fn locate(&self, addr: WordAddr) -> Option<(usize, usize, usize)> {
+        let word_index = u32::from(addr) / WORD_SIZE as u32;
+        self.regions
+            .iter()
+            .enumerate()
+            .find_map(|(region_idx, region)| {
+                region
+                    .offset(word_index)
+                    .map(|offset| (region_idx, offset / PAGE_WORDS, offset % PAGE_WORDS))
+            })
+    }
+
+    /// Get the value at an address, or `None` if it was never set to a non-default value.
+    #[inline]
+    pub fn get(&self, addr: &WordAddr) -> Option<&T> {
+        let value = match self.locate(*addr) {
+            Some((region, page, offset)) => {
+                self.regions[region].page(page).map(|page| &page[offset])
+            }
+            None => self.others.get(addr),
+        };
+        value.filter(|&value| *value != T::default())
+    }
+
+    /// Set the value at an address, and return the previous value if it was not the default.
+    #[inline]
+    pub fn insert(&mut self, addr: WordAddr, value: T) -> Option<T> {
+        let previous = match self.locate(addr) {
+            Some((region, page, offset)) => {
+                let region = &mut self.regions[region];
+                if value == T::default() && region.page(page).is_none() {
+                    return None;
+                }
+                std::mem::replace(&mut region.page_mut(page)[offset], value)
+            }
+            None if value == T::default() => self.others.remove(&addr).unwrap_or_default(),
+            None => self.others.insert(addr, value).unwrap_or_default(),
+        };
+        (previous != T::default()).then_some(previous)
+    }
+
+    /// Iterate over all entries with a non-default value, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (WordAddr, T)> + '_ {
+        let paged = self.regions.iter().flat_map(|region| {
+            region.pages().flat_map(move |(page_idx, page)| {
+                page.iter()
+                    .enumerate()
+                    .filter(|(_, value)| **value != T::default())
+                    .map(move |(offset, &value)| {
+                        let word_index = region.start + (page_idx * PAGE_WORDS + offset) as u32;
+                        (ByteAddr(word_index * WORD_SIZE as u32).waddr(), value)
+                    })
+            })
+        });
+        paged.chain(self.others.iter().map(|(&addr, &value)| (addr, value)))
+    }
+
+    /// Iterate over all addresses with a non-default value, in no particular order.
+    pub fn keys(&self) -> impl Iterator<Item = WordAddr> + '_ {
+        self.iter().map(|(addr, _)| addr)
+    }
+}
+
+impl<T: Copy + Default + PartialEq + fmt::Debug> fmt::Debug for PagedMemory<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CENO_PLATFORM;
+
+    #[test]
+    fn test_paged_memory() {
+        let mut memory = PagedMemory::<u32>::new(&CENO_PLATFORM);
+        let addrs = [
+            CENO_PLATFORM.register_vma(1),
+            CENO_PLATFORM.rom_start(),
+            CENO_PLATFORM.ram_start() + (PAGE_WORDS * WORD_SIZE) as u32,
+            CENO_PLATFORM.ram_end() - 3,
+            CENO_PLATFORM.public_io_start(),
+            // Between the public IO and the RAM, outside of all regions.
+            CENO_PLATFORM.public_io_end() + 1,
+        ]
+        .map(WordAddr::from);
+
+        for (i, &addr) in addrs.iter().enumerate() {
+            assert_eq!(memory.get(&addr), None);
+            assert_eq!(memory.insert(addr, i as u32 + 1), None);
+            assert_eq!(memory.insert(addr, i as u32 + 10), Some(i as u32 + 1));
+        }
+        let mut entries = memory.iter().collect::<Vec<_>>();
+        entries.sort();
+        let mut expected = addrs
+            .iter()
+            .enumerate()
+            .map(|(i, &addr)| (addr, i as u32 + 10))
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(entries, expected);
+
+        // Storing the default value removes the entry.
+        for &addr in &addrs {
+            assert!(memory.insert(addr, 0).is_some());
+            assert_eq!(memory.get(&addr), None);
+        }
+        assert_eq!(memory.iter().count(), 0);
+    }
+}
@@ -1,16 +1,31 @@
-use crate::addr::{Addr, RegIdx};
+use anyhow::{Context, Result, anyhow, bail};
+use elf::{ElfBytes, endian::LittleEndian};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Program,
+    addr::{Addr, RegIdx, WORD_SIZE},
+};
 
 /// The Platform struct holds the parameters of the VM.
 /// It defines:
 /// - the layout of virtual memory,
 /// - special addresses, such as the initial PC,
 /// - codes of environment calls.
-#[derive(Copy, Clone)]
+///
+/// All regions are inclusive ranges `start..=end`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Platform {
     pub rom_start: Addr,
     pub rom_end: Addr,
     pub ram_start: Addr,
     pub ram_end: Addr,
+    pub program_data_start: Addr,
+    pub program_data_end: Addr,
+    pub public_io_start: Addr,
+    pub public_io_end: Addr,
+    /// The start of the output of the guest, in RAM, see `OutputReader::info_out`.
+    pub info_out_start: Addr,
 }
 
 pub const CENO_PLATFORM: Platform = Platform {
@@ -18,8 +33,27 @@ pub const CENO_PLATFORM: Platform = Platform {
     rom_end: 0x3000_0000 - 1,
     ram_start: 0x8000_0000,
     ram_end: 0xFFFF_FFFF,
+    // TODO figure out proper region for program_data
+    program_data_start: 0x3000_0000,
+    program_data_end: 0x3000_1000 - 1,
+    // TODO figure out a proper region for public io
+    public_io_start: 0x3000_1000,
+    public_io_end: 0x3000_2000 - 1,
+    info_out_start: 0xC000_0000,
 };
 
+/// The symbols that the linker script of `ceno_rt` defines for the memory layout.
+/// Each end symbol is the address just past its region.
+const ROM_START_SYMBOL: &str = "_ceno_rom_start";
+const ROM_END_SYMBOL: &str = "_ceno_rom_end";
+const RAM_START_SYMBOL: &str = "_ceno_ram_start";
+const RAM_END_SYMBOL: &str = "_ceno_ram_end";
+const PUBLIC_IO_START_SYMBOL: &str = "_ceno_public_io_start";
+const PUBLIC_IO_END_SYMBOL: &str = "_ceno_public_io_end";
+const INFO_OUT_START_SYMBOL: &str = "_ceno_info_out_start";
+const STACK_LIMIT_SYMBOL: &str = "_ceno_stack_limit";
+const HEAP_TOP_SYMBOL: &str = "_ceno_heap_top";
+
 impl Platform {
     // Virtual memory layout.
 
@@ -35,22 +69,24 @@ impl Platform {
         (self.rom_start()..=self.rom_end()).contains(&addr)
     }
 
-    // TODO figure out proper region for program_data
     pub const fn program_data_start(&self) -> Addr {
-        0x3000_0000
+        self.program_data_start
     }
 
     pub const fn program_data_end(&self) -> Addr {
-        0x3000_1000 - 1
+        self.program_data_end
     }
 
-    // TODO figure out a proper region for public io
     pub const fn public_io_start(&self) -> Addr {
-        0x3000_1000
+        self.public_io_start
     }
 
     pub const fn public_io_end(&self) -> Addr {
-        0x3000_2000 - 1
+        self.public_io_end
+    }
+
+    pub const fn info_out_start(&self) -> Addr {
+        self.info_out_start
     }
 
     pub const fn ram_start(&self) -> Addr {
@@ -118,6 +154,13 @@ impl Platform {
         self.is_rom(addr)
     }
 
+    // Registers.
+
+    /// Register containing the stack pointer. (x2, sp)
+    pub const fn reg_sp(&self) -> RegIdx {
+        2
+    }
+
     // Environment calls.
 
     /// Register containing the ecall function code. (x5, t0)
@@ -140,10 +183,241 @@ impl Platform {
         0
     }
 
+    /// The code of ecall KECCAK_PERMUTE, with the address of the state in arg0.
+    pub const fn ecall_keccak_permute(&self) -> u32 {
+        0x00_01_01_09
+    }
+
+    /// The code of ecall SHA256_COMPRESS, with the address of the state in arg0 and the address
+    /// of the block in arg1.
+    pub const fn ecall_sha256_compress(&self) -> u32 {
+        0x00_01_01_06
+    }
+
+    /// The code of ecall POSEIDON_PERMUTE, with the address of the state in arg0.
+    pub const fn ecall_poseidon_permute(&self) -> u32 {
+        0x00_01_01_20
+    }
+
+    /// The code of ecall UINT256_ADD, with the address of `x` in arg0 and the address of `y` in
+    /// arg1. It replaces `x` with `x + y mod 2^256`.
+    pub const fn ecall_uint256_add(&self) -> u32 {
+        0x00_01_01_1C
+    }
+
+    /// The code of ecall UINT256_MUL, with the address of `x` in arg0 and the address of `y` in
+    /// arg1. It replaces `x` with `x * y mod 2^256`.
+    pub const fn ecall_uint256_mul(&self) -> u32 {
+        0x00_01_01_1D
+    }
+
+    /// The code of ecall UINT256_MULMOD, with the address of `x` in arg0 and the address of `y`
+    /// followed by `m` in arg1. It replaces `x` with `x * y mod m`.
+    pub const fn ecall_uint256_mulmod(&self) -> u32 {
+        0x00_01_01_1E
+    }
+
+    /// The code of ecall SECP256K1_ADD, with the address of a point in arg0 and the address of
+    /// another point in arg1. It replaces the first point with the sum.
+    pub const fn ecall_secp256k1_add(&self) -> u32 {
+        0x00_01_01_0A
+    }
+
+    /// The code of ecall SECP256K1_DOUBLE, with the address of a point in arg0. It replaces the
+    /// point with its double.
+    pub const fn ecall_secp256k1_double(&self) -> u32 {
+        0x00_00_01_0B
+    }
+
+    /// The code of ecall ED25519_ADD, with the address of a point in arg0 and the address of
+    /// another point in arg1. It replaces the first point with the sum.
+    pub const fn ecall_ed25519_add(&self) -> u32 {
+        0x00_01_01_07
+    }
+
+    /// The code of ecall ED25519_DOUBLE, with the address of a point in arg0. It replaces the
+    /// point with its double.
+    pub const fn ecall_ed25519_double(&self) -> u32 {
+        0x00_00_01_08
+    }
+
     /// The code of success.
     pub const fn code_success(&self) -> u32 {
         0
     }
+
+    // Configuration.
+
+    /// Derive the platform of a guest program from the memory layout symbols of its linker script.
+    ///
+    /// The ROM, RAM and public IO regions come from the symbols `_ceno_rom_start`,
+    /// `_ceno_rom_end`, `_ceno_ram_start`, `_ceno_ram_end`, `_ceno_public_io_start` and
+    /// `_ceno_public_io_end`, and the start of the output from `_ceno_info_out_start`, if the
+    /// ELF defines them. Other parameters are those of `CENO_PLATFORM`. The program is checked
+    /// against the resulting layout.
+    pub fn from_elf(elf: &[u8]) -> Result<Platform> {
+        let symbols = LayoutSymbols::parse(elf)?;
+        let mut platform = CENO_PLATFORM;
+        if let Some((start, end)) = symbols.region(ROM_START_SYMBOL, ROM_END_SYMBOL)? {
+            platform.rom_start = start;
+            platform.rom_end = end;
+        }
+        if let Some((start, end)) = symbols.region(RAM_START_SYMBOL, RAM_END_SYMBOL)? {
+            platform.ram_start = start;
+            platform.ram_end = end;
+        }
+        if let Some((start, end)) = symbols.region(PUBLIC_IO_START_SYMBOL, PUBLIC_IO_END_SYMBOL)? {
+            platform.public_io_start = start;
+            platform.public_io_end = end;
+        }
+        if let Some(start) = symbols.get(INFO_OUT_START_SYMBOL) {
+            if !platform.is_ram(start) || start % WORD_SIZE as Addr != 0 {
+                bail!("info out start 0x{start:08x} is not an aligned word of RAM");
+            }
+            platform.info_out_start = start;
+        }
+        platform.check_layout()?;
+        platform.check_program(&Program::load_elf(elf, u32::MAX)?)?;
+        Ok(platform)
+    }
+
+    /// Check that the regions of the memory layout are well-formed and do not overlap.
+    pub fn check_layout(&self) -> Result<()> {
+        let regions = [
+            ("ROM", self.rom_start, self.rom_end),
+            ("RAM", self.ram_start, self.ram_end),
+            (
+                "program data",
+                self.program_data_start,
+                self.program_data_end,
+            ),
+            ("public IO", self.public_io_start, self.public_io_end),
+        ];
+        for (name, start, end) in regions {
+            let word = WORD_SIZE as Addr;
+            if start > end || start % word != 0 || end % word != word - 1 {
+                bail!("invalid {name} region 0x{start:08x}..=0x{end:08x}");
+            }
+            if start <= self.register_vma(crate::VMState::REG_COUNT - 1) {
+                bail!("{name} region overlaps with registers");
+            }
+        }
+        for (i, (name_a, start_a, end_a)) in regions.iter().enumerate() {
+            for (name_b, start_b, end_b) in &regions[i + 1..] {
+                if start_a <= end_b && start_b <= end_a {
+                    bail!("{name_a} region overlaps with {name_b} region");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the segments of a program fit in the memory layout: code in ROM, and
+    /// initialized data in readable memory.
+    pub fn check_program(&self, program: &Program) -> Result<()> {
+        let code_len = (program.instructions.len() * WORD_SIZE) as Addr;
+        let code_end = program
+            .base_address
+            .checked_add(code_len)
+            .context("code segment overflows the address space")?;
+        if program.base_address != self.pc_base() {
+            bail!(
+                "code starts at 0x{:08x}, expected the ROM start 0x{:08x}",
+                program.base_address,
+                self.pc_base()
+            );
+        }
+        if code_len > 0 && !self.is_rom(code_end - 1) {
+            bail!("code segment ends at 0x{code_end:08x}, past the end of ROM");
+        }
+        if !self.can_execute(program.entry) {
+            bail!("entry point 0x{:08x} is not in ROM", program.entry);
+        }
+        if let Some(&addr) = program.image.keys().find(|&&addr| !self.can_read(addr)) {
+            bail!("segment data at 0x{addr:08x} is outside of the memory layout");
+        }
+        Ok(())
+    }
+}
+
+/// The detection of stack overflows by the emulator.
+///
+/// It is a setting of the emulator rather than of the `Platform`: it only turns the stores of a
+/// stack overflow into traps, and the proofs do not depend on it.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StackGuard {
+    /// The lowest address of the stack.
+    pub stack_limit: Option<Addr>,
+    /// The address of the word where the runtime records the top of the heap.
+    pub heap_top: Option<Addr>,
+}
+
+impl StackGuard {
+    /// Detect stack overflows with the symbols `_ceno_stack_limit` and `_ceno_heap_top`, if the
+    /// ELF defines them.
+    pub fn from_elf(elf: &[u8]) -> Result<StackGuard> {
+        let symbols = LayoutSymbols::parse(elf)?;
+        Ok(StackGuard {
+            stack_limit: symbols.get(STACK_LIMIT_SYMBOL),
+            heap_top: symbols.get(HEAP_TOP_SYMBOL),
+        })
+    }
+
+    /// Check that the stack limit and the heap top are in the RAM of the platform.
+    pub fn check(&self, platform: &Platform) -> Result<()> {
+        if let Some(limit) = self.stack_limit {
+            if !platform.is_ram(limit) {
+                bail!("stack limit 0x{limit:08x} is outside of RAM");
+            }
+        }
+        if let Some(heap_top) = self.heap_top {
+            if !platform.is_ram(heap_top) || heap_top % WORD_SIZE as Addr != 0 {
+                bail!("heap top 0x{heap_top:08x} is not an aligned word of RAM");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The absolute symbols of an ELF file, by name.
+struct LayoutSymbols(Vec<(String, Addr)>);
+
+impl LayoutSymbols {
+    fn parse(elf: &[u8]) -> Result<Self> {
+        let elf = ElfBytes::<LittleEndian>::minimal_parse(elf)
+            .map_err(|err| anyhow!("Elf parse error: {err}"))?;
+        let Some((symbols, strings)) = elf
+            .symbol_table()
+            .map_err(|err| anyhow!("Elf symbol table error: {err}"))?
+        else {
+            return Ok(LayoutSymbols(vec![]));
+        };
+        let symbols = symbols
+            .iter()
+            .filter_map(|symbol| {
+                let name = strings.get(symbol.st_name as usize).ok()?;
+                Some((name.to_string(), symbol.st_value as Addr))
+            })
+            .collect();
+        Ok(LayoutSymbols(symbols))
+    }
+
+    fn get(&self, name: &str) -> Option<Addr> {
+        self.0
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|&(_, value)| value)
+    }
+
+    /// The inclusive range between a start symbol and an end symbol, if both are defined.
+    fn region(&self, start: &str, end: &str) -> Result<Option<(Addr, Addr)>> {
+        match (self.get(start), self.get(end)) {
+            (Some(start), Some(end)) if start < end => Ok(Some((start, end - 1))),
+            (Some(_), Some(_)) => bail!("empty region {start}..{end}"),
+            (None, None) => Ok(None),
+            _ => bail!("only one of {start} and {end} is defined"),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -165,5 +439,42 @@ mod tests {
             assert!(!p.is_rom(reg));
             assert!(!p.is_ram(reg));
         }
+        p.check_layout().unwrap();
+    }
+
+    #[test]
+    fn test_check_layout() {
+        let overlapping = Platform {
+            public_io_start: CENO_PLATFORM.program_data_end + 1 - 0x100,
+            ..CENO_PLATFORM
+        };
+        assert!(overlapping.check_layout().is_err());
+
+        let unaligned = Platform {
+            ram_end: CENO_PLATFORM.ram_end - 1,
+            ..CENO_PLATFORM
+        };
+        assert!(unaligned.check_layout().is_err());
+    }
+
+    #[test]
+    fn test_check_stack_guard() {
+        let stack_outside_ram = StackGuard {
+            stack_limit: Some(CENO_PLATFORM.rom_start),
+            heap_top: None,
+        };
+        assert!(stack_outside_ram.check(&CENO_PLATFORM).is_err());
+
+        let heap_top_outside_ram = StackGuard {
+            stack_limit: None,
+            heap_top: Some(CENO_PLATFORM.rom_start),
+        };
+        assert!(heap_top_outside_ram.check(&CENO_PLATFORM).is_err());
+
+        let in_ram = StackGuard {
+            stack_limit: Some(CENO_PLATFORM.ram_start + 0x100),
+            heap_top: Some(CENO_PLATFORM.ram_start),
+        };
+        assert!(in_ram.check(&CENO_PLATFORM).is_ok());
     }
 }
@@ -77,6 +77,11 @@ pub trait EmuContext {
     fn check_data_store(&self, _addr: ByteAddr) -> bool {
         true
     }
+
+    // Check a data store against the boundary of the heap and the stack, for stack overflows
+    fn check_stack_store(&self, _addr: ByteAddr) -> bool {
+        true
+    }
 }
 
 /// An implementation of the basic ISA (RV32IM), that is instruction decoding and functional units.
@@ -94,6 +99,7 @@ pub enum TrapCause {
     LoadAccessFault(ByteAddr),
     StoreAddressMisaligned(ByteAddr),
     StoreAccessFault,
+    StackOverflow(ByteAddr),
     EcallError,
 }
 
@@ -779,6 +785,10 @@ impl Emulator {
             tracing::error!("mstore: addr={:x?},rs1={:x}", addr, rs1);
             return ctx.trap(TrapCause::StoreAccessFault);
         }
+        if !ctx.check_stack_store(addr) {
+            tracing::error!("stack overflow: addr={:x?},rs1={:x}", addr, rs1);
+            return ctx.trap(TrapCause::StackOverflow(addr));
+        }
         let mut data = ctx.peek_memory(addr.waddr());
         match kind {
             InsnKind::SB => {
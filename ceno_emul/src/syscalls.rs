@@ -0,0 +1,55 @@
+//! Precompiled functions, called by the guest with `ecall`.
+//!
+//! The function code is in the register `Platform::reg_ecall`, and the arguments in
+//! `Platform::reg_arg0` and `Platform::reg_arg1`. A syscall may access many memory words: these
+//! accesses are recorded in the `SyscallWitness` of the step, all at the subcycle
+//! `Tracer::SUBCYCLE_MEM`. The register arg1, if used, is also recorded there.
+
+use crate::{
+    addr::{Word, WordAddr},
+    tracer::{Change, ReadOp, WriteOp},
+};
+
+mod elliptic_curve;
+pub use elliptic_curve::{
+    EC_POINT_WORDS, ED25519_D, ED25519_MODULUS, EcPoint, SECP256K1_MODULUS, ed25519_add,
+    ed25519_double, secp256k1_add, secp256k1_double,
+};
+pub(crate) use elliptic_curve::{EcOp, ec_op};
+
+mod keccak_permute;
+pub(crate) use keccak_permute::keccak_permute;
+pub use keccak_permute::{
+    KECCAK_RHO_OFFSETS, KECCAK_ROUND_CONSTANTS, KECCAK_ROUNDS, KECCAK_STATE_WORDS, keccak_f,
+    keccak_round,
+};
+
+mod poseidon_permute;
+pub(crate) use poseidon_permute::poseidon_permute;
+pub use poseidon_permute::{POSEIDON_STATE_WORDS, poseidon_permutation};
+
+mod uint256;
+pub use uint256::{UINT256_WORDS, uint256_add, uint256_mul, uint256_mulmod};
+pub(crate) use uint256::{Uint256Op, uint256_op};
+
+mod sha256;
+pub(crate) use sha256::sha256_compress;
+pub use sha256::{
+    SHA256_BLOCK_WORDS, SHA256_INIT, SHA256_ROUND_CONSTANTS, SHA256_ROUNDS, SHA256_STATE_WORDS,
+    sha256_compress_block, sha256_next_word, sha256_round,
+};
+
+/// The memory accesses of a syscall, in addition to the register accesses of the step.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyscallWitness {
+    /// The accessed words in order, with their value before and after the syscall.
+    pub mem_ops: Vec<WriteOp>,
+    /// The reads of registers other than the two reads of the step, at the subcycle
+    /// `Tracer::SUBCYCLE_RD`.
+    pub reg_ops: Vec<ReadOp>,
+}
+
+/// The effects of a syscall, to be applied to the machine state and traced.
+pub(crate) struct SyscallEffects {
+    pub mem_ops: Vec<(WordAddr, Change<Word>)>,
+}
@@ -0,0 +1,246 @@
+use super::{SyscallEffects, UINT256_WORDS};
+use crate::{
+    EmuContext, VMState,
+    addr::{ByteAddr, Word, WordAddr},
+    platform::Platform,
+    tracer::Change,
+};
+use num_bigint::BigUint;
+
+/// A point in affine coordinates is stored as `x` followed by `y`, each as 8 little-endian words.
+pub const EC_POINT_WORDS: usize = 2 * UINT256_WORDS;
+
+pub type EcPoint = [Word; EC_POINT_WORDS];
+
+/// The order of the field of secp256k1, `2^256 - 2^32 - 977`.
+pub const SECP256K1_MODULUS: [Word; UINT256_WORDS] = [
+    0xFFFFFC2F, 0xFFFFFFFE, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF,
+];
+
+/// The order of the field of ed25519, `2^255 - 19`.
+pub const ED25519_MODULUS: [Word; UINT256_WORDS] = [
+    0xFFFFFFED, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0x7FFFFFFF,
+];
+
+/// The constant `d = -121665 / 121666` of the curve ed25519, `-x^2 + y^2 = 1 + d x^2 y^2`.
+pub const ED25519_D: [Word; UINT256_WORDS] = [
+    0x135978A3, 0x75EB4DCA, 0x4141D8AB, 0x00700A4D, 0x7779E898, 0x8CC74079, 0x2B6FFE73, 0x52036CEE,
+];
+
+fn to_biguint(x: &[Word]) -> BigUint {
+    BigUint::from_slice(x)
+}
+
+/// The words of `x < 2^256`.
+fn to_words(x: &BigUint) -> [Word; UINT256_WORDS] {
+    let mut words = [0; UINT256_WORDS];
+    for (word, digit) in words.iter_mut().zip(x.iter_u32_digits()) {
+        *word = digit;
+    }
+    words
+}
+
+/// The coordinates of a point, reduced modulo `m`.
+fn coordinates(point: &EcPoint, m: &BigUint) -> (BigUint, BigUint) {
+    let (x, y) = point.split_at(UINT256_WORDS);
+    (to_biguint(x) % m, to_biguint(y) % m)
+}
+
+fn to_point(x: &BigUint, y: &BigUint) -> EcPoint {
+    let mut point = [0; EC_POINT_WORDS];
+    point[..UINT256_WORDS].copy_from_slice(&to_words(x));
+    point[UINT256_WORDS..].copy_from_slice(&to_words(y));
+    point
+}
+
+/// The inverse of `x` modulo the prime `m`, or `None` if `x` is zero.
+fn inverse(x: &BigUint, m: &BigUint) -> Option<BigUint> {
+    let x = x % m;
+    (x != BigUint::ZERO).then(|| x.modpow(&(m - 2u32), m))
+}
+
+/// The sum of two points of secp256k1 with distinct `x`, and `None` otherwise.
+///
+/// The coordinates may be unreduced, and the result is reduced. The points are not checked to be
+/// on the curve.
+pub fn secp256k1_add(p: &EcPoint, q: &EcPoint) -> Option<EcPoint> {
+    let m = to_biguint(&SECP256K1_MODULUS);
+    let ((x1, y1), (x2, y2)) = (coordinates(p, &m), coordinates(q, &m));
+    let lambda = (&y2 + &m - &y1) * inverse(&(&x2 + &m - &x1), &m)? % &m;
+    Some(secp256k1_chord(&m, &lambda, &x1, &y1, &x2))
+}
+
+/// The double of a point of secp256k1 with a non-zero `y`, and `None` otherwise.
+///
+/// The coordinates may be unreduced, and the result is reduced. The point is not checked to be on
+/// the curve.
+pub fn secp256k1_double(p: &EcPoint) -> Option<EcPoint> {
+    let m = to_biguint(&SECP256K1_MODULUS);
+    let (x1, y1) = coordinates(p, &m);
+    let lambda = 3u32 * &x1 * &x1 * inverse(&(2u32 * &y1), &m)? % &m;
+    Some(secp256k1_chord(&m, &lambda, &x1, &y1, &x1))
+}
+
+/// The third point on the line of slope `lambda` through `(x1, y1)` and a point of abscissa
+/// `x2`, reflected.
+fn secp256k1_chord(
+    m: &BigUint,
+    lambda: &BigUint,
+    x1: &BigUint,
+    y1: &BigUint,
+    x2: &BigUint,
+) -> EcPoint {
+    let x3 = (lambda * lambda + 2u32 * m - x1 - x2) % m;
+    let y3 = (lambda * (x1 + m - &x3) + m - y1) % m;
+    to_point(&x3, &y3)
+}
+
+/// The sum of two points of ed25519, or `None` if a denominator of the formula is zero, which
+/// does not happen for points on the curve.
+///
+/// The coordinates may be unreduced, and the result is reduced. The points are not checked to be
+/// on the curve.
+pub fn ed25519_add(p: &EcPoint, q: &EcPoint) -> Option<EcPoint> {
+    let m = to_biguint(&ED25519_MODULUS);
+    let d = to_biguint(&ED25519_D);
+    let ((x1, y1), (x2, y2)) = (coordinates(p, &m), coordinates(q, &m));
+    let e = d * &x1 * &x2 % &m * &y1 * &y2 % &m;
+    let x3 = (&x1 * &y2 + &y1 * &x2) * inverse(&(1u32 + &e), &m)? % &m;
+    let y3 = (&y1 * &y2 + &x1 * &x2) * inverse(&(1u32 + &m - &e), &m)? % &m;
+    Some(to_point(&x3, &y3))
+}
+
+/// The double of a point of ed25519, see `ed25519_add`.
+pub fn ed25519_double(p: &EcPoint) -> Option<EcPoint> {
+    ed25519_add(p, p)
+}
+
+/// The operations on points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum EcOp {
+    Secp256k1Add,
+    Secp256k1Double,
+    Ed25519Add,
+    Ed25519Double,
+}
+
+impl EcOp {
+    pub fn from_ecall(platform: &Platform, function: u32) -> Option<Self> {
+        if function == platform.ecall_secp256k1_add() {
+            Some(EcOp::Secp256k1Add)
+        } else if function == platform.ecall_secp256k1_double() {
+            Some(EcOp::Secp256k1Double)
+        } else if function == platform.ecall_ed25519_add() {
+            Some(EcOp::Ed25519Add)
+        } else if function == platform.ecall_ed25519_double() {
+            Some(EcOp::Ed25519Double)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the operation reads a second point at the address in arg1.
+    pub fn is_add(self) -> bool {
+        matches!(self, EcOp::Secp256k1Add | EcOp::Ed25519Add)
+    }
+}
+
+/// Replace the point at `p_ptr` with the result of `op`, and return the changes of its words
+/// followed by the reads of the words of the second point at `q_ptr`, if any.
+///
+/// Return `None` if the operation is not defined on the points. The caller must check that the
+/// points are aligned, that the first one is writable and the second one readable, and that they
+/// do not overlap.
+pub(crate) fn ec_op(
+    vm: &VMState,
+    op: EcOp,
+    p_ptr: ByteAddr,
+    q_ptr: Option<ByteAddr>,
+) -> Option<SyscallEffects> {
+    let read = |ptr: ByteAddr| -> [(WordAddr, Word); EC_POINT_WORDS] {
+        std::array::from_fn(|i| {
+            let addr = ptr.waddr() + i as u32;
+            (addr, vm.peek_memory(addr))
+        })
+    };
+    let p = read(p_ptr);
+    let q = q_ptr.map(read);
+    let p_value = p.map(|(_, value)| value);
+    let q_value = q.map(|q| q.map(|(_, value)| value));
+
+    let output = match (op, q_value) {
+        (EcOp::Secp256k1Add, Some(q)) => secp256k1_add(&p_value, &q)?,
+        (EcOp::Secp256k1Double, None) => secp256k1_double(&p_value)?,
+        (EcOp::Ed25519Add, Some(q)) => ed25519_add(&p_value, &q)?,
+        (EcOp::Ed25519Double, None) => ed25519_double(&p_value)?,
+        _ => panic!("{op:?} with the wrong number of points"),
+    };
+
+    let p_writes = (p.into_iter().zip(output))
+        .map(|((addr, before), after)| (addr, Change::new(before, after)));
+    let q_reads = (q.into_iter().flatten()).map(|(addr, value)| (addr, Change::new(value, value)));
+    Some(SyscallEffects {
+        mem_ops: p_writes.chain(q_reads).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECP256K1_G: EcPoint = [
+        0x16F81798, 0x59F2815B, 0x2DCE28D9, 0x029BFCDB, 0xCE870B07, 0x55A06295, 0xF9DCBBAC,
+        0x79BE667E, 0xFB10D4B8, 0x9C47D08F, 0xA6855419, 0xFD17B448, 0x0E1108A8, 0x5DA4FBFC,
+        0x26A3C465, 0x483ADA77,
+    ];
+    const SECP256K1_G3: EcPoint = [
+        0xBCE036F9, 0x8601F113, 0x836F99B0, 0xB531C845, 0xF89D5229, 0x49344F85, 0x9258C310,
+        0xF9308A01, 0x84B8E672, 0x6CB9FD75, 0x34C2231B, 0x6500A999, 0x2A37F356, 0x0FE337E6,
+        0x632DE814, 0x388F7B0F,
+    ];
+    const ED25519_B: EcPoint = [
+        0x8F25D51A, 0xC9562D60, 0x9525A7B2, 0x692CC760, 0xFDD6DC5C, 0xC0A4E231, 0xCD6E53FE,
+        0x216936D3, 0x66666658, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666,
+        0x66666666, 0x66666666,
+    ];
+    const ED25519_B3: EcPoint = [
+        0xD3F8E25C, 0xAC62485F, 0x81624886, 0x63439819, 0x3EDAC83A, 0x1FF4AE74, 0x22928F49,
+        0x67AE9C4A, 0x78F5B4D4, 0x02C36848, 0x67240304, 0x9F16EC17, 0x60269EF7, 0xA126A18E,
+        0x77EE69AB, 0x1267B1D1,
+    ];
+
+    #[test]
+    fn test_secp256k1() {
+        let g2 = secp256k1_double(&SECP256K1_G).unwrap();
+        assert_eq!(secp256k1_add(&SECP256K1_G, &g2), Some(SECP256K1_G3));
+        assert_eq!(secp256k1_add(&g2, &SECP256K1_G), Some(SECP256K1_G3));
+
+        // Undefined cases.
+        assert_eq!(secp256k1_add(&SECP256K1_G, &SECP256K1_G), None);
+        assert_eq!(secp256k1_double(&[0; EC_POINT_WORDS]), None);
+    }
+
+    #[test]
+    fn test_ed25519() {
+        let b2 = ed25519_double(&ED25519_B).unwrap();
+        assert_eq!(ed25519_add(&ED25519_B, &b2), Some(ED25519_B3));
+
+        // The identity is (0, 1).
+        let mut identity = [0; EC_POINT_WORDS];
+        identity[UINT256_WORDS] = 1;
+        assert_eq!(ed25519_add(&ED25519_B, &identity), Some(ED25519_B));
+
+        // Unreduced coordinates.
+        let mut b = ED25519_B;
+        let y = to_biguint(&ED25519_B[UINT256_WORDS..]) + to_biguint(&ED25519_MODULUS);
+        b[UINT256_WORDS..].copy_from_slice(&to_words(&y));
+        assert_eq!(ed25519_double(&b), Some(b2));
+    }
+
+    #[test]
+    fn test_ed25519_d() {
+        let m = to_biguint(&ED25519_MODULUS);
+        let d = to_biguint(&ED25519_D);
+        assert_eq!((d * 121666u32 + 121665u32) % m, BigUint::ZERO);
+    }
+}
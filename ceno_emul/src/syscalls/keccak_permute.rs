@@ -0,0 +1,124 @@
+use super::SyscallEffects;
+use crate::{
+    EmuContext, VMState,
+    addr::{ByteAddr, Word, WordAddr},
+    tracer::Change,
+};
+
+/// The number of rounds of Keccak-f[1600].
+pub const KECCAK_ROUNDS: usize = 24;
+
+/// The state is 25 lanes of 64 bits, stored as 50 little-endian words.
+pub const KECCAK_STATE_WORDS: usize = 50;
+
+/// The round constants of the step iota.
+pub const KECCAK_ROUND_CONSTANTS: [u64; KECCAK_ROUNDS] = [
+    0x0000_0000_0000_0001,
+    0x0000_0000_0000_8082,
+    0x8000_0000_0000_808A,
+    0x8000_0000_8000_8000,
+    0x0000_0000_0000_808B,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8009,
+    0x0000_0000_0000_008A,
+    0x0000_0000_0000_0088,
+    0x0000_0000_8000_8009,
+    0x0000_0000_8000_000A,
+    0x0000_0000_8000_808B,
+    0x8000_0000_0000_008B,
+    0x8000_0000_0000_8089,
+    0x8000_0000_0000_8003,
+    0x8000_0000_0000_8002,
+    0x8000_0000_0000_0080,
+    0x0000_0000_0000_800A,
+    0x8000_0000_8000_000A,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8080,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8008,
+];
+
+/// The rotation of each lane in the step rho, indexed by `x + 5 * y`.
+pub const KECCAK_RHO_OFFSETS: [u32; 25] = [
+    0, 1, 62, 28, 27, //
+    36, 44, 6, 55, 20, //
+    3, 10, 43, 25, 39, //
+    41, 45, 15, 21, 8, //
+    18, 2, 61, 56, 14,
+];
+
+/// Apply one round of Keccak-f[1600] to the lanes, indexed by `x + 5 * y`.
+pub fn keccak_round(state: &mut [u64; 25], round: usize) {
+    // Theta.
+    let c: [u64; 5] = std::array::from_fn(|x| (0..5).fold(0, |c, y| c ^ state[x + 5 * y]));
+    for x in 0..5 {
+        let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+        for y in 0..5 {
+            state[x + 5 * y] ^= d;
+        }
+    }
+
+    // Rho and pi: the lane (x, y) moves to (y, 2x + 3y).
+    let mut b = [0; 25];
+    for x in 0..5 {
+        for y in 0..5 {
+            b[y + 5 * ((2 * x + 3 * y) % 5)] =
+                state[x + 5 * y].rotate_left(KECCAK_RHO_OFFSETS[x + 5 * y]);
+        }
+    }
+
+    // Chi.
+    for x in 0..5 {
+        for y in 0..5 {
+            state[x + 5 * y] = b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+        }
+    }
+
+    // Iota.
+    state[0] ^= KECCAK_ROUND_CONSTANTS[round];
+}
+
+/// The permutation Keccak-f[1600].
+pub fn keccak_f(state: &mut [u64; 25]) {
+    for round in 0..KECCAK_ROUNDS {
+        keccak_round(state, round);
+    }
+}
+
+/// Apply Keccak-f to the state at `state_ptr`, and return the changes of all its words.
+///
+/// The caller must check that the state is aligned and writable.
+pub(crate) fn keccak_permute(vm: &VMState, state_ptr: ByteAddr) -> SyscallEffects {
+    let addrs: [WordAddr; KECCAK_STATE_WORDS] =
+        std::array::from_fn(|i| state_ptr.waddr() + i as u32);
+    let input = addrs.map(|addr| vm.peek_memory(addr));
+
+    let mut state: [u64; 25] =
+        std::array::from_fn(|i| input[2 * i] as u64 | (input[2 * i + 1] as u64) << 32);
+    keccak_f(&mut state);
+    let output: [Word; KECCAK_STATE_WORDS] =
+        std::array::from_fn(|i| (state[i / 2] >> (32 * (i % 2))) as Word);
+
+    SyscallEffects {
+        mem_ops: (0..KECCAK_STATE_WORDS)
+            .map(|i| (addrs[i], Change::new(input[i], output[i])))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keccak_f() {
+        let mut state = [0; 25];
+        keccak_f(&mut state);
+        assert_eq!(state[..2], [0xF125_8F79_40E1_DDE7, 0x84D5_CCF9_33C0_478A]);
+        assert_eq!(state[24], 0xEAF1_FF7B_5CEC_A249);
+
+        keccak_f(&mut state);
+        assert_eq!(state[..2], [0x2D5C_954D_F96E_CB3C, 0x6A33_2CD0_7057_B56D]);
+    }
+}
@@ -0,0 +1,75 @@
+use super::SyscallEffects;
+use crate::{
+    EmuContext, VMState,
+    addr::{ByteAddr, Word, WordAddr},
+    tracer::Change,
+};
+use goldilocks::{Goldilocks, SmallField};
+use poseidon::{SPONGE_WIDTH, poseidon::Poseidon};
+
+/// The state is 12 elements of the Goldilocks field, stored as 24 little-endian words.
+pub const POSEIDON_STATE_WORDS: usize = 2 * SPONGE_WIDTH;
+
+/// The Poseidon permutation over the Goldilocks field, as used by the transcript and the Merkle
+/// trees of the prover. The inputs are reduced modulo the field order, and the outputs are
+/// canonical.
+pub fn poseidon_permutation(state: &mut [u64; SPONGE_WIDTH]) {
+    let input = state.map(Goldilocks::from);
+    let output = <Goldilocks as Poseidon>::poseidon(input);
+    *state = output.map(|x| x.to_canonical_u64());
+}
+
+/// Apply the Poseidon permutation to the state at `state_ptr`, and return the changes of all its
+/// words.
+///
+/// The caller must check that the state is aligned and writable.
+pub(crate) fn poseidon_permute(vm: &VMState, state_ptr: ByteAddr) -> SyscallEffects {
+    let addrs: [WordAddr; POSEIDON_STATE_WORDS] =
+        std::array::from_fn(|i| state_ptr.waddr() + i as u32);
+    let input = addrs.map(|addr| vm.peek_memory(addr));
+
+    let mut state: [u64; SPONGE_WIDTH] =
+        std::array::from_fn(|i| input[2 * i] as u64 | (input[2 * i + 1] as u64) << 32);
+    poseidon_permutation(&mut state);
+    let output: [Word; POSEIDON_STATE_WORDS] =
+        std::array::from_fn(|i| (state[i / 2] >> (32 * (i % 2))) as Word);
+
+    SyscallEffects {
+        mem_ops: (0..POSEIDON_STATE_WORDS)
+            .map(|i| (addrs[i], Change::new(input[i], output[i])))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poseidon_permutation() {
+        // A known answer for the width 12.
+        let mut state: [u64; SPONGE_WIDTH] = std::array::from_fn(|i| i as u64);
+        poseidon_permutation(&mut state);
+        assert_eq!(state, [
+            0xd64e1e3efc5b8e9e,
+            0x53666633020aaa47,
+            0xd40285597c6a8825,
+            0x613a4f81e81231d2,
+            0x414754bfebd051f0,
+            0xcb1f8980294a023f,
+            0x6eb2a9e4d54a9d0f,
+            0x1902bc3af467e056,
+            0xf045d5eafdc6021f,
+            0xe4150f77caaa3be5,
+            0xc9bfd01d39b50cce,
+            0x5c0a27fcb0e1459b,
+        ]);
+
+        // Non-canonical inputs are reduced.
+        let mut reduced = [0; SPONGE_WIDTH];
+        let mut unreduced = [Goldilocks::MODULUS_U64; SPONGE_WIDTH];
+        poseidon_permutation(&mut reduced);
+        poseidon_permutation(&mut unreduced);
+        assert_eq!(reduced, unreduced);
+    }
+}
@@ -0,0 +1,124 @@
+use super::SyscallEffects;
+use crate::{
+    EmuContext, VMState,
+    addr::{ByteAddr, Word, WordAddr},
+    tracer::Change,
+};
+
+/// The number of rounds of the SHA-256 compression function.
+pub const SHA256_ROUNDS: usize = 64;
+
+/// The state is the 8 words `a, b, c, d, e, f, g, h`.
+pub const SHA256_STATE_WORDS: usize = 8;
+
+/// A block of the message is 16 words, with the bytes of each word in big-endian order.
+pub const SHA256_BLOCK_WORDS: usize = 16;
+
+/// The initial state of SHA-256.
+pub const SHA256_INIT: [u32; SHA256_STATE_WORDS] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// The round constants `K`.
+pub const SHA256_ROUND_CONSTANTS: [u32; SHA256_ROUNDS] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// The next word of the message schedule, from the window of the previous 16 words.
+pub fn sha256_next_word(window: &[u32; SHA256_BLOCK_WORDS]) -> u32 {
+    let (w1, w14) = (window[1], window[14]);
+    let s0 = w1.rotate_right(7) ^ w1.rotate_right(18) ^ (w1 >> 3);
+    let s1 = w14.rotate_right(17) ^ w14.rotate_right(19) ^ (w14 >> 10);
+    s1.wrapping_add(window[9])
+        .wrapping_add(s0)
+        .wrapping_add(window[0])
+}
+
+/// Apply the round `round` to the state, with the word `w` of the message schedule.
+pub fn sha256_round(state: &mut [u32; SHA256_STATE_WORDS], round: usize, w: u32) {
+    let [a, b, c, d, e, f, g, h] = *state;
+    let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+    let ch = (e & f) ^ (!e & g);
+    let t1 = h
+        .wrapping_add(s1)
+        .wrapping_add(ch)
+        .wrapping_add(SHA256_ROUND_CONSTANTS[round])
+        .wrapping_add(w);
+    let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+    let maj = (a & b) ^ (a & c) ^ (b & c);
+    let t2 = s0.wrapping_add(maj);
+    *state = [t1.wrapping_add(t2), a, b, c, d.wrapping_add(t1), e, f, g];
+}
+
+/// The compression function of SHA-256: update the state with a block of the message.
+pub fn sha256_compress_block(
+    state: &mut [u32; SHA256_STATE_WORDS],
+    block: &[u32; SHA256_BLOCK_WORDS],
+) {
+    let mut window = *block;
+    let mut working = *state;
+    for round in 0..SHA256_ROUNDS {
+        sha256_round(&mut working, round, window[0]);
+        let next = sha256_next_word(&window);
+        window.rotate_left(1);
+        window[SHA256_BLOCK_WORDS - 1] = next;
+    }
+    for (s, w) in state.iter_mut().zip(working) {
+        *s = s.wrapping_add(w);
+    }
+}
+
+/// Compress the block at `block_ptr` into the state at `state_ptr`, and return the changes of
+/// the words of the state followed by the reads of the words of the block.
+///
+/// The caller must check that the state is aligned and writable, that the block is aligned and
+/// readable, and that they do not overlap.
+pub(crate) fn sha256_compress(
+    vm: &VMState,
+    state_ptr: ByteAddr,
+    block_ptr: ByteAddr,
+) -> SyscallEffects {
+    let state_addrs: [WordAddr; SHA256_STATE_WORDS] =
+        std::array::from_fn(|i| state_ptr.waddr() + i as u32);
+    let block_addrs: [WordAddr; SHA256_BLOCK_WORDS] =
+        std::array::from_fn(|i| block_ptr.waddr() + i as u32);
+    let input = state_addrs.map(|addr| vm.peek_memory(addr));
+    let block = block_addrs.map(|addr| vm.peek_memory(addr));
+
+    let mut output: [Word; SHA256_STATE_WORDS] = input;
+    sha256_compress_block(&mut output, &block);
+
+    let state_writes =
+        (0..SHA256_STATE_WORDS).map(|i| (state_addrs[i], Change::new(input[i], output[i])));
+    let block_reads =
+        (0..SHA256_BLOCK_WORDS).map(|i| (block_addrs[i], Change::new(block[i], block[i])));
+    SyscallEffects {
+        mem_ops: state_writes.chain(block_reads).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256_compress_block() {
+        // The padded message "abc".
+        let mut block = [0; SHA256_BLOCK_WORDS];
+        block[0] = 0x61626380;
+        block[15] = 0x18;
+        let mut state = SHA256_INIT;
+        sha256_compress_block(&mut state, &block);
+        assert_eq!(state, [
+            0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+            0xf20015ad,
+        ]);
+    }
+}
@@ -0,0 +1,151 @@
+use super::SyscallEffects;
+use crate::{
+    EmuContext, VMState,
+    addr::{ByteAddr, Word, WordAddr},
+    platform::Platform,
+    tracer::Change,
+};
+use num_bigint::BigUint;
+
+/// A 256-bit integer is stored as 8 little-endian words.
+pub const UINT256_WORDS: usize = 8;
+
+fn to_biguint(x: &[Word; UINT256_WORDS]) -> BigUint {
+    BigUint::from_slice(x)
+}
+
+/// The low 256 bits of `x`.
+fn to_words(x: &BigUint) -> [Word; UINT256_WORDS] {
+    let mut words = [0; UINT256_WORDS];
+    for (word, digit) in words.iter_mut().zip(x.iter_u32_digits()) {
+        *word = digit;
+    }
+    words
+}
+
+/// `x + y mod 2^256`.
+pub fn uint256_add(x: &[Word; UINT256_WORDS], y: &[Word; UINT256_WORDS]) -> [Word; UINT256_WORDS] {
+    to_words(&(to_biguint(x) + to_biguint(y)))
+}
+
+/// `x * y mod 2^256`.
+pub fn uint256_mul(x: &[Word; UINT256_WORDS], y: &[Word; UINT256_WORDS]) -> [Word; UINT256_WORDS] {
+    to_words(&(to_biguint(x) * to_biguint(y)))
+}
+
+/// The quotient and the remainder of `x * y` by `m`.
+///
+/// Return `None` if `m` is zero, or if the quotient does not fit in 256 bits. The latter cannot
+/// happen when `x < m` or `y < m`.
+pub fn uint256_mulmod(
+    x: &[Word; UINT256_WORDS],
+    y: &[Word; UINT256_WORDS],
+    m: &[Word; UINT256_WORDS],
+) -> Option<([Word; UINT256_WORDS], [Word; UINT256_WORDS])> {
+    let m = to_biguint(m);
+    if m == BigUint::ZERO {
+        return None;
+    }
+    let product = to_biguint(x) * to_biguint(y);
+    let (q, r) = (&product / &m, &product % &m);
+    (q.bits() <= 256).then(|| (to_words(&q), to_words(&r)))
+}
+
+/// The operations on 256-bit integers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Uint256Op {
+    Add,
+    Mul,
+    MulMod,
+}
+
+impl Uint256Op {
+    pub fn from_ecall(platform: &Platform, function: u32) -> Option<Self> {
+        if function == platform.ecall_uint256_add() {
+            Some(Uint256Op::Add)
+        } else if function == platform.ecall_uint256_mul() {
+            Some(Uint256Op::Mul)
+        } else if function == platform.ecall_uint256_mulmod() {
+            Some(Uint256Op::MulMod)
+        } else {
+            None
+        }
+    }
+
+    /// The number of integers at the address in arg1.
+    pub fn num_inputs(self) -> usize {
+        match self {
+            Uint256Op::Add | Uint256Op::Mul => 1,
+            Uint256Op::MulMod => 2,
+        }
+    }
+}
+
+/// Replace `x` at `x_ptr` with the result of `op`, and return the changes of the words of `x`
+/// followed by the reads of the words of the inputs at `inputs_ptr`.
+///
+/// Return `None` if the operation is not defined on the inputs, see `uint256_mulmod`. The caller
+/// must check that `x` is aligned and writable, that the inputs are aligned and readable, and
+/// that they do not overlap.
+pub(crate) fn uint256_op(
+    vm: &VMState,
+    op: Uint256Op,
+    x_ptr: ByteAddr,
+    inputs_ptr: ByteAddr,
+) -> Option<SyscallEffects> {
+    let x_addrs: [WordAddr; UINT256_WORDS] = std::array::from_fn(|i| x_ptr.waddr() + i as u32);
+    let input_addrs = (0..op.num_inputs() * UINT256_WORDS)
+        .map(|i| inputs_ptr.waddr() + i as u32)
+        .collect::<Vec<_>>();
+    let x = x_addrs.map(|addr| vm.peek_memory(addr));
+    let inputs = input_addrs
+        .iter()
+        .map(|&addr| vm.peek_memory(addr))
+        .collect::<Vec<_>>();
+    let input = |i: usize| -> [Word; UINT256_WORDS] {
+        inputs[i * UINT256_WORDS..(i + 1) * UINT256_WORDS]
+            .try_into()
+            .unwrap()
+    };
+
+    let output = match op {
+        Uint256Op::Add => uint256_add(&x, &input(0)),
+        Uint256Op::Mul => uint256_mul(&x, &input(0)),
+        Uint256Op::MulMod => uint256_mulmod(&x, &input(0), &input(1))?.1,
+    };
+
+    let x_writes = (0..UINT256_WORDS).map(|i| (x_addrs[i], Change::new(x[i], output[i])));
+    let input_reads = input_addrs
+        .iter()
+        .zip(&inputs)
+        .map(|(&addr, &value)| (addr, Change::new(value, value)));
+    Some(SyscallEffects {
+        mem_ops: x_writes.chain(input_reads).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uint256() {
+        let max = [u32::MAX; UINT256_WORDS];
+        let one = [1, 0, 0, 0, 0, 0, 0, 0];
+        let two = [2, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(uint256_add(&max, &one), [0; UINT256_WORDS]);
+        assert_eq!(uint256_mul(&max, &max), one);
+
+        assert_eq!(
+            uint256_mulmod(&max, &two, &max),
+            Some((two, [0; UINT256_WORDS]))
+        );
+        let mut half = max;
+        half[UINT256_WORDS - 1] >>= 1;
+        assert_eq!(uint256_mulmod(&max, &one, &two), Some((half, one)));
+
+        // The quotient does not fit, or the modulus is zero.
+        assert_eq!(uint256_mulmod(&max, &max, &two), None);
+        assert_eq!(uint256_mulmod(&max, &one, &[0; UINT256_WORDS]), None);
+    }
+}
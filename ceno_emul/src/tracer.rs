@@ -1,9 +1,15 @@
-use std::{collections::HashMap, fmt, mem};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, mem,
+};
 
 use crate::{
     CENO_PLATFORM, PC_STEP_SIZE,
     addr::{ByteAddr, Cycle, RegIdx, Word, WordAddr},
+    paged_memory::PagedMemory,
+    platform::Platform,
     rv32im::DecodedInstruction,
+    syscalls::SyscallWitness,
 };
 
 /// An instruction and its context in an execution trace. That is concrete values of registers and memory.
@@ -29,6 +35,8 @@ pub struct StepRecord {
     rd: Option<WriteOp>,
 
     memory_op: Option<WriteOp>,
+
+    syscall: Option<SyscallWitness>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -221,6 +229,7 @@ impl StepRecord {
                 previous_cycle,
             }),
             memory_op,
+            syscall: None,
         }
     }
 
@@ -258,6 +267,11 @@ impl StepRecord {
         self.memory_op.clone()
     }
 
+    /// The memory accesses of the syscall, if this step is an ecall to one.
+    pub fn syscall(&self) -> Option<&SyscallWitness> {
+        self.syscall.as_ref()
+    }
+
     pub fn is_busy_loop(&self) -> bool {
         self.pc.before == self.pc.after
     }
@@ -267,7 +281,7 @@ impl StepRecord {
 pub struct Tracer {
     record: StepRecord,
 
-    latest_accesses: HashMap<WordAddr, Cycle>,
+    latest_accesses: PagedMemory<Cycle>,
 }
 
 impl Default for Tracer {
@@ -284,12 +298,17 @@ impl Tracer {
     pub const SUBCYCLES_PER_INSN: Cycle = 4;
 
     pub fn new() -> Tracer {
+        Self::with_platform(&CENO_PLATFORM)
+    }
+
+    /// Create a tracer whose access tracking is laid out for the memory regions of `platform`.
+    pub fn with_platform(platform: &Platform) -> Tracer {
         Tracer {
             record: StepRecord {
                 cycle: Self::SUBCYCLES_PER_INSN,
                 ..StepRecord::default()
             },
-            latest_accesses: HashMap::new(),
+            latest_accesses: PagedMemory::new(platform),
         }
     }
 
@@ -362,6 +381,37 @@ impl Tracer {
         });
     }
 
+    /// Record a memory access of a syscall. A step may have any number of these, on distinct words.
+    pub fn store_syscall_memory(&mut self, addr: WordAddr, value: Change<Word>) {
+        let previous_cycle = self.track_access(addr, Self::SUBCYCLE_MEM);
+        self.record
+            .syscall
+            .get_or_insert_with(SyscallWitness::default)
+            .mem_ops
+            .push(WriteOp {
+                addr,
+                value,
+                previous_cycle,
+            });
+    }
+
+    /// Record a read of a register by a syscall, after the two register reads of the step.
+    ///
+    /// It is tracked at the subcycle of rd, which an ecall does not write.
+    pub fn load_syscall_register(&mut self, idx: RegIdx, value: Word) {
+        let addr = CENO_PLATFORM.register_vma(idx).into();
+        let previous_cycle = self.track_access(addr, Self::SUBCYCLE_RD);
+        self.record
+            .syscall
+            .get_or_insert_with(SyscallWitness::default)
+            .reg_ops
+            .push(ReadOp {
+                addr,
+                value,
+                previous_cycle,
+            });
+    }
+
     /// - Return the cycle when an address was last accessed.
     /// - Return 0 if this is the first access.
     /// - Record the current instruction as the origin of the latest access.
@@ -372,11 +422,29 @@ impl Tracer {
             .unwrap_or(0)
     }
 
+    /// Resume tracing at `cycle`, with the last access cycles of a previous execution.
+    pub(crate) fn resume(&mut self, cycle: Cycle, latest_accesses: &BTreeMap<WordAddr, Cycle>) {
+        self.record = StepRecord {
+            cycle,
+            ..StepRecord::default()
+        };
+        for (&addr, &cycle) in latest_accesses {
+            self.latest_accesses.insert(addr, cycle);
+        }
+    }
+
     /// Return all the addresses that were accessed and the cycle when they were last accessed.
-    pub fn final_accesses(&self) -> &HashMap<WordAddr, Cycle> {
+    pub fn latest_accesses(&self) -> &PagedMemory<Cycle> {
         &self.latest_accesses
     }
 
+    /// Return all the addresses that were accessed and the cycle when they were last accessed,
+    /// copied into a map.
+    #[deprecated(note = "use `latest_accesses`, which does not copy the accesses")]
+    pub fn final_accesses(&self) -> HashMap<WordAddr, Cycle> {
+        self.latest_accesses.iter().collect()
+    }
+
     /// Return the cycle of the pending instruction (after the last completed step).
     pub fn cycle(&self) -> Cycle {
         self.record.cycle
@@ -1,14 +1,21 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use super::rv32im::EmuContext;
 use crate::{
-    Program,
-    addr::{ByteAddr, RegIdx, Word, WordAddr},
-    platform::Platform,
+    PC_STEP_SIZE, Program,
+    addr::{Addr, ByteAddr, Cycle, RegIdx, WORD_SIZE, Word, WordAddr},
+    paged_memory::PagedMemory,
+    platform::{Platform, StackGuard},
     rv32im::{DecodedInstruction, Emulator, TrapCause},
+    syscalls::{
+        EC_POINT_WORDS, EcOp, KECCAK_STATE_WORDS, POSEIDON_STATE_WORDS, SHA256_BLOCK_WORDS,
+        SHA256_STATE_WORDS, SyscallEffects, UINT256_WORDS, Uint256Op, ec_op, keccak_permute,
+        poseidon_permute, sha256_compress, uint256_op,
+    },
     tracer::{Change, StepRecord, Tracer},
 };
 use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
 use std::{iter::from_fn, ops::Deref, sync::Arc};
 
 /// An implementation of the machine state and of the side-effects of operations.
@@ -17,11 +24,14 @@ pub struct VMState {
     platform: Platform,
     pc: Word,
     /// Map a word-address (addr/4) to a word.
-    memory: HashMap<WordAddr, Word>,
+    memory: PagedMemory<Word>,
     registers: [Word; VMState::REG_COUNT],
     // Termination.
     halted: bool,
     tracer: Tracer,
+    stack_guard: StackGuard,
+    /// The value of the heap top word of `stack_guard`, kept up to date by the stores.
+    heap_top: Option<Addr>,
 }
 
 impl VMState {
@@ -37,10 +47,12 @@ impl VMState {
             pc,
             platform,
             program: program.clone(),
-            memory: HashMap::new(),
+            memory: PagedMemory::new(&platform),
             registers: [0; VMState::REG_COUNT],
             halted: false,
-            tracer: Tracer::new(),
+            tracer: Tracer::with_platform(&platform),
+            stack_guard: StackGuard::default(),
+            heap_top: None,
         };
 
         // init memory from program.image
@@ -53,16 +65,8 @@ impl VMState {
 
     pub fn new_from_elf(platform: Platform, elf: &[u8]) -> Result<Self> {
         let program = Program::load_elf(elf, u32::MAX)?;
-        let state = Self::new(platform, program);
-
-        if state.program.base_address != state.platform.rom_start() {
-            return Err(anyhow!(
-                "Invalid base_address {:x}",
-                state.program.base_address
-            ));
-        }
-
-        Ok(state)
+        platform.check_program(&program)?;
+        Ok(Self::new(platform, program))
     }
 
     pub fn halted(&self) -> bool {
@@ -77,9 +81,29 @@ impl VMState {
         self.program.deref()
     }
 
+    pub fn platform(&self) -> &Platform {
+        &self.platform
+    }
+
+    /// Detect stack overflows: stores into the stack must stay above the stack limit and above
+    /// the heap top recorded by the runtime.
+    pub fn set_stack_guard(&mut self, stack_guard: StackGuard) -> Result<()> {
+        stack_guard.check(&self.platform)?;
+        self.stack_guard = stack_guard;
+        self.load_heap_top();
+        Ok(())
+    }
+
+    /// The current boundary between the heap and the stack: the highest of the static stack
+    /// limit and of the heap top recorded by the runtime, once it points into RAM.
+    pub fn stack_boundary(&self) -> Option<Addr> {
+        let heap_top = self.heap_top.filter(|&top| self.platform.is_ram(top));
+        heap_top.max(self.stack_guard.stack_limit)
+    }
+
     /// Set a word in memory without side effects.
     pub fn init_memory(&mut self, addr: WordAddr, value: Word) {
-        self.memory.insert(addr, value);
+        self.write_memory(addr, value);
     }
 
     pub fn iter_until_halt(&mut self) -> impl Iterator<Item = Result<StepRecord>> + '_ {
@@ -103,18 +127,163 @@ impl VMState {
         }
     }
 
+    /// Take a copy of the machine state, to be restored later with `VMState::restore`.
+    pub fn snapshot(&self) -> VMSnapshot {
+        VMSnapshot {
+            pc: self.pc,
+            registers: self.registers.to_vec(),
+            memory: self.memory.iter().collect(),
+            halted: self.halted,
+            cycle: self.tracer.cycle(),
+            final_accesses: self.tracer.latest_accesses().iter().collect(),
+        }
+    }
+
+    /// Reset the machine state to a snapshot taken from an execution of the same program.
+    pub fn restore(&mut self, snapshot: &VMSnapshot) -> Result<()> {
+        self.registers = snapshot.registers.as_slice().try_into().map_err(|_| {
+            anyhow!(
+                "Invalid snapshot: expected {} registers, got {}",
+                VMState::REG_COUNT,
+                snapshot.registers.len()
+            )
+        })?;
+        self.pc = snapshot.pc;
+        self.memory = PagedMemory::new(&self.platform);
+        for (&addr, &value) in &snapshot.memory {
+            self.memory.insert(addr, value);
+        }
+        self.load_heap_top();
+        self.halted = snapshot.halted;
+        self.tracer = Tracer::with_platform(&self.platform);
+        self.tracer.resume(snapshot.cycle, &snapshot.final_accesses);
+        Ok(())
+    }
+
+    /// Create a machine that resumes the execution of `program` from a snapshot.
+    pub fn from_snapshot(
+        platform: Platform,
+        program: Program,
+        snapshot: &VMSnapshot,
+    ) -> Result<Self> {
+        let mut state = Self::new(platform, program);
+        state.restore(snapshot)?;
+        Ok(state)
+    }
+
     pub fn init_register_unsafe(&mut self, idx: RegIdx, value: Word) {
         self.registers[idx] = value;
     }
 
+    /// Write a memory word, and follow the heap top recorded by the runtime.
+    fn write_memory(&mut self, addr: WordAddr, value: Word) {
+        if self.stack_guard.heap_top.map(|top| ByteAddr(top).waddr()) == Some(addr) {
+            self.heap_top = Some(value);
+        }
+        self.memory.insert(addr, value);
+    }
+
+    fn load_heap_top(&mut self) {
+        self.heap_top = self
+            .stack_guard
+            .heap_top
+            .map(|top| self.peek_memory(ByteAddr(top).waddr()));
+    }
+
     fn halt(&mut self) {
         self.set_pc(0.into());
         self.halted = true;
     }
+
+    /// Check that a syscall may access `num_words` words from `addr`.
+    fn check_syscall_memory(&self, addr: ByteAddr, num_words: usize) -> Result<(), TrapCause> {
+        if !addr.is_aligned() {
+            return Err(TrapCause::StoreAddressMisaligned(addr));
+        }
+        let writable = (0..num_words as u32).all(|i| {
+            addr.0
+                .checked_add(i * WORD_SIZE as u32)
+                .is_some_and(|addr| self.platform.can_write(addr))
+        });
+        if !writable {
+            return Err(TrapCause::StoreAccessFault);
+        }
+        Ok(())
+    }
+
+    /// Check that a syscall may read `num_words` words from `addr`.
+    fn check_syscall_read(&self, addr: ByteAddr, num_words: usize) -> Result<(), TrapCause> {
+        if !addr.is_aligned() {
+            return Err(TrapCause::LoadAddressMisaligned);
+        }
+        let readable = (0..num_words as u32).all(|i| {
+            addr.0
+                .checked_add(i * WORD_SIZE as u32)
+                .is_some_and(|addr| self.platform.can_read(addr))
+        });
+        if !readable {
+            return Err(TrapCause::LoadAccessFault(addr));
+        }
+        Ok(())
+    }
+
+    /// Check that a syscall may write `num_words` words from `addr` and read `num_inputs` words
+    /// from `inputs`, which must not overlap since each word may be accessed only once by a step.
+    fn check_syscall_operands(
+        &self,
+        addr: ByteAddr,
+        num_words: usize,
+        inputs: ByteAddr,
+        num_inputs: usize,
+    ) -> Result<(), TrapCause> {
+        self.check_syscall_memory(addr, num_words)?;
+        self.check_syscall_read(inputs, num_inputs)?;
+        let (start, inputs_start) = (addr.0 as u64, inputs.0 as u64);
+        let end = start + (num_words * WORD_SIZE) as u64;
+        let inputs_end = inputs_start + (num_inputs * WORD_SIZE) as u64;
+        if start < inputs_end && inputs_start < end {
+            return Err(TrapCause::EcallError);
+        }
+        Ok(())
+    }
+
+    /// Load a register beyond the two register reads of the step, and record it for the syscall.
+    fn load_syscall_register(&mut self, idx: RegIdx) -> Word {
+        let value = self.peek_register(idx);
+        self.tracer.load_syscall_register(idx, value);
+        value
+    }
+
+    /// Apply and record the memory accesses of a syscall.
+    fn apply_syscall(&mut self, effects: SyscallEffects) {
+        for (addr, change) in effects.mem_ops {
+            self.tracer.store_syscall_memory(addr, change);
+            self.write_memory(addr, change.after);
+        }
+    }
+}
+
+/// A serializable copy of the machine state between two steps.
+///
+/// The program and the platform are not part of the snapshot: it must be restored on top of the
+/// program it was taken from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VMSnapshot {
+    pub pc: Word,
+    /// All registers, including the internal RD_NULL.
+    pub registers: Vec<Word>,
+    /// Map a word-address (addr/4) to a word.
+    pub memory: BTreeMap<WordAddr, Word>,
+    pub halted: bool,
+    /// The cycle of the next instruction.
+    pub cycle: Cycle,
+    /// The cycle when each address was last accessed.
+    pub final_accesses: BTreeMap<WordAddr, Cycle>,
 }
 
 impl EmuContext for VMState {
-    // Expect an ecall to terminate the program: function HALT with argument exit_code.
+    // Either terminate the program: function HALT with argument exit_code,
+    // or run a syscall, see `crate::syscalls`.
     fn ecall(&mut self) -> Result<bool> {
         let function = self.load_register(self.platform.reg_ecall())?;
         if function == self.platform.ecall_halt() {
@@ -123,6 +292,78 @@ impl EmuContext for VMState {
 
             self.halt();
             Ok(true)
+        } else if function == self.platform.ecall_keccak_permute() {
+            let state_ptr = ByteAddr(self.load_register(self.platform.reg_arg0())?);
+            if let Err(cause) = self.check_syscall_memory(state_ptr, KECCAK_STATE_WORDS) {
+                return self.trap(cause);
+            }
+            let effects = keccak_permute(self, state_ptr);
+            self.apply_syscall(effects);
+            self.set_pc(ByteAddr(self.pc) + PC_STEP_SIZE);
+            Ok(true)
+        } else if function == self.platform.ecall_sha256_compress() {
+            let state_ptr = ByteAddr(self.load_register(self.platform.reg_arg0())?);
+            let block_ptr = ByteAddr(self.load_syscall_register(self.platform.reg_arg1()));
+            if let Err(cause) = self.check_syscall_operands(
+                state_ptr,
+                SHA256_STATE_WORDS,
+                block_ptr,
+                SHA256_BLOCK_WORDS,
+            ) {
+                return self.trap(cause);
+            }
+            let effects = sha256_compress(self, state_ptr, block_ptr);
+            self.apply_syscall(effects);
+            self.set_pc(ByteAddr(self.pc) + PC_STEP_SIZE);
+            Ok(true)
+        } else if function == self.platform.ecall_poseidon_permute() {
+            let state_ptr = ByteAddr(self.load_register(self.platform.reg_arg0())?);
+            if let Err(cause) = self.check_syscall_memory(state_ptr, POSEIDON_STATE_WORDS) {
+                return self.trap(cause);
+            }
+            let effects = poseidon_permute(self, state_ptr);
+            self.apply_syscall(effects);
+            self.set_pc(ByteAddr(self.pc) + PC_STEP_SIZE);
+            Ok(true)
+        } else if let Some(op) = Uint256Op::from_ecall(&self.platform, function) {
+            let x_ptr = ByteAddr(self.load_register(self.platform.reg_arg0())?);
+            let inputs_ptr = ByteAddr(self.load_syscall_register(self.platform.reg_arg1()));
+            if let Err(cause) = self.check_syscall_operands(
+                x_ptr,
+                UINT256_WORDS,
+                inputs_ptr,
+                op.num_inputs() * UINT256_WORDS,
+            ) {
+                return self.trap(cause);
+            }
+            let Some(effects) = uint256_op(self, op, x_ptr, inputs_ptr) else {
+                return self.trap(TrapCause::EcallError);
+            };
+            self.apply_syscall(effects);
+            self.set_pc(ByteAddr(self.pc) + PC_STEP_SIZE);
+            Ok(true)
+        } else if let Some(op) = EcOp::from_ecall(&self.platform, function) {
+            let p_ptr = ByteAddr(self.load_register(self.platform.reg_arg0())?);
+            let q_ptr = if op.is_add() {
+                let q_ptr = ByteAddr(self.load_syscall_register(self.platform.reg_arg1()));
+                if let Err(cause) =
+                    self.check_syscall_operands(p_ptr, EC_POINT_WORDS, q_ptr, EC_POINT_WORDS)
+                {
+                    return self.trap(cause);
+                }
+                Some(q_ptr)
+            } else {
+                if let Err(cause) = self.check_syscall_memory(p_ptr, EC_POINT_WORDS) {
+                    return self.trap(cause);
+                }
+                None
+            };
+            let Some(effects) = ec_op(self, op, p_ptr, q_ptr) else {
+                return self.trap(TrapCause::EcallError);
+            };
+            self.apply_syscall(effects);
+            self.set_pc(ByteAddr(self.pc) + PC_STEP_SIZE);
+            Ok(true)
         } else {
             self.trap(TrapCause::EcallError)
         }
@@ -171,7 +412,7 @@ impl EmuContext for VMState {
     fn store_memory(&mut self, addr: WordAddr, after: Word) -> Result<()> {
         let before = self.peek_memory(addr);
         self.tracer.store_memory(addr, Change { after, before });
-        self.memory.insert(addr, after);
+        self.write_memory(addr, after);
         Ok(())
     }
 
@@ -199,6 +440,16 @@ impl EmuContext for VMState {
         self.platform.can_write(addr.0)
     }
 
+    /// A store into the stack, at or above the stack pointer, must be above the stack limit and
+    /// above the heap.
+    fn check_stack_store(&self, addr: ByteAddr) -> bool {
+        let Some(boundary) = self.stack_boundary() else {
+            return true;
+        };
+        let sp = self.peek_register(self.platform.reg_sp());
+        !(sp..boundary).contains(&addr.0)
+    }
+
     fn check_insn_load(&self, addr: ByteAddr) -> bool {
         self.platform.can_execute(addr.0)
     }
@@ -0,0 +1,106 @@
+use ceno_emul::CENO_PLATFORM;
+
+/// Build a minimal ELF with code in ROM, data at the start of RAM, and a symbol table.
+pub fn build_elf(code: &[u32], data: &[u32], symbols: &[(&str, u32)]) -> Vec<u8> {
+    const EHDR_SIZE: u32 = 52;
+    const PHDR_SIZE: u32 = 32;
+    const SHDR_SIZE: u32 = 40;
+    const SYM_SIZE: u32 = 16;
+    let words = |words: &[u32]| {
+        words
+            .iter()
+            .flat_map(|w| w.to_le_bytes())
+            .collect::<Vec<u8>>()
+    };
+
+    let code = words(code);
+    let data = words(data);
+    let mut strtab = vec![0u8];
+    let mut symtab = vec![0u8; SYM_SIZE as usize];
+    for (name, value) in symbols {
+        let name_offset = strtab.len() as u32;
+        strtab.extend(name.as_bytes());
+        strtab.push(0);
+        symtab.extend(name_offset.to_le_bytes());
+        symtab.extend(value.to_le_bytes());
+        symtab.extend(0u32.to_le_bytes()); // st_size
+        symtab.extend([0x10, 0]); // st_info = GLOBAL NOTYPE, st_other
+        symtab.extend(0xfff1u16.to_le_bytes()); // st_shndx = SHN_ABS
+    }
+
+    let code_offset = EHDR_SIZE + 2 * PHDR_SIZE;
+    let data_offset = code_offset + code.len() as u32;
+    let symtab_offset = data_offset + data.len() as u32;
+    let strtab_offset = symtab_offset + symtab.len() as u32;
+    let shdr_offset = (strtab_offset + strtab.len() as u32).next_multiple_of(4);
+
+    let mut elf = vec![0x7f, b'E', b'L', b'F', 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    let u16s =
+        |elf: &mut Vec<u8>, values: &[u16]| values.iter().for_each(|v| elf.extend(v.to_le_bytes()));
+    let u32s =
+        |elf: &mut Vec<u8>, values: &[u32]| values.iter().for_each(|v| elf.extend(v.to_le_bytes()));
+    u16s(&mut elf, &[2, 0xf3]); // e_type = EXEC, e_machine = RISCV
+    u32s(&mut elf, &[
+        1,
+        CENO_PLATFORM.pc_base(),
+        EHDR_SIZE,
+        shdr_offset,
+        0,
+    ]);
+    u16s(&mut elf, &[
+        EHDR_SIZE as u16,
+        PHDR_SIZE as u16,
+        2,
+        SHDR_SIZE as u16,
+        3,
+        0,
+    ]);
+    // Program headers: PT_LOAD code (R+X) and data (R+W).
+    for (offset, vaddr, size, flags) in [
+        (code_offset, CENO_PLATFORM.pc_base(), code.len(), 0x5),
+        (data_offset, CENO_PLATFORM.ram_start(), data.len(), 0x6),
+    ] {
+        u32s(&mut elf, &[
+            1,
+            offset,
+            vaddr,
+            vaddr,
+            size as u32,
+            size as u32,
+            flags,
+            4,
+        ]);
+    }
+    elf.extend(code);
+    elf.extend(data);
+    elf.extend(&symtab);
+    elf.extend(&strtab);
+    elf.resize(shdr_offset as usize, 0);
+    // Section headers: null, .symtab (linked to .strtab), .strtab.
+    u32s(&mut elf, &[0; 10]);
+    u32s(&mut elf, &[
+        0,
+        2,
+        0,
+        0,
+        symtab_offset,
+        symtab.len() as u32,
+        2,
+        1,
+        4,
+        SYM_SIZE,
+    ]);
+    u32s(&mut elf, &[
+        0,
+        3,
+        0,
+        0,
+        strtab_offset,
+        strtab.len() as u32,
+        0,
+        0,
+        1,
+        0,
+    ]);
+    elf
+}
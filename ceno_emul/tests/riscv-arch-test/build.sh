@@ -0,0 +1,23 @@
+#!/usr/bin/env bash
+# Compile the RV32IM tests of riscv-arch-test for the Ceno model, into ./elf.
+#
+# Usage: ./build.sh <path to a riscv-arch-test checkout>
+# Requires a riscv32 GCC toolchain, e.g. `riscv32-unknown-elf-gcc` (override with $CC).
+set -euo pipefail
+
+ARCH_TEST=$(realpath "$1")
+HERE=$(dirname "$(realpath "$0")")
+CC=${CC:-riscv32-unknown-elf-gcc}
+SUITE="$ARCH_TEST/riscv-test-suite"
+
+mkdir -p "$HERE/elf"
+for ext in I M; do
+    for src in "$SUITE/rv32i_m/$ext/src/"*.S; do
+        name=$(basename "$src" .S)
+        "$CC" -march=rv32im -mabi=ilp32 -static -mcmodel=medany -fvisibility=hidden \
+            -nostdlib -nostartfiles -DXLEN=32 -DTEST_CASE_1=True \
+            -I "$SUITE/env" -I "$HERE/model" -T "$HERE/model/link.ld" \
+            -o "$HERE/elf/$name.elf" "$src"
+        cp "$SUITE/rv32i_m/$ext/references/$name.reference_output" "$HERE/elf/"
+    done
+done
@@ -0,0 +1,274 @@
+#!/usr/bin/env python3
+"""Generate and compile the `ceno-*` tests of ./elf, in the format of the architectural tests.
+
+Each test runs one RV32IM instruction on a set of operands and stores its results between
+`begin_signature` and `end_signature`. The reference signatures are computed here from the
+RISC-V specification, independently of ceno_emul.
+
+Usage: ./gen_ceno_suite.py
+Requires `llvm-mc` with the RISC-V target, and `ld.lld` (or `rust-lld`, override with $LD).
+"""
+
+import os
+import shutil
+import subprocess
+import sys
+import tempfile
+
+HERE = os.path.dirname(os.path.realpath(__file__))
+MASK = 0xFFFFFFFF
+FILL = 0xDEADBEEF
+
+# Interesting 32-bit operands.
+VALUES = [
+    0x00000000, 0x00000001, 0xFFFFFFFF, 0x7FFFFFFF, 0x80000000,
+    0x12345678, 0xFEDCBA98, 0x00000005, 0xFFFFFFF9, 0x0000001F,
+    0x00000020, 0x55555555,
+]
+# 12-bit signed immediates.
+IMMEDIATES = [0, 1, -1, 2047, -2048, 0x555, -0x556, 31, 5, -7]
+SHAMTS = [0, 1, 5, 15, 16, 31]
+
+
+def s32(x):
+    x &= MASK
+    return x - (1 << 32) if x & 0x80000000 else x
+
+
+def div(a, b):
+    a, b = s32(a), s32(b)
+    if b == 0:
+        return MASK
+    if a == -(1 << 31) and b == -1:
+        return a & MASK
+    q = abs(a) // abs(b)
+    return (q if (a < 0) == (b < 0) else -q) & MASK
+
+
+def rem(a, b):
+    a, b = s32(a), s32(b)
+    if b == 0:
+        return a & MASK
+    if a == -(1 << 31) and b == -1:
+        return 0
+    r = abs(a) % abs(b)
+    return (r if a >= 0 else -r) & MASK
+
+
+R_OPS = {
+    "add": lambda a, b: a + b,
+    "sub": lambda a, b: a - b,
+    "sll": lambda a, b: a << (b & 31),
+    "slt": lambda a, b: int(s32(a) < s32(b)),
+    "sltu": lambda a, b: int(a < b),
+    "xor": lambda a, b: a ^ b,
+    "srl": lambda a, b: a >> (b & 31),
+    "sra": lambda a, b: s32(a) >> (b & 31),
+    "or": lambda a, b: a | b,
+    "and": lambda a, b: a & b,
+    "mul": lambda a, b: a * b,
+    "mulh": lambda a, b: (s32(a) * s32(b)) >> 32,
+    "mulhsu": lambda a, b: (s32(a) * b) >> 32,
+    "mulhu": lambda a, b: (a * b) >> 32,
+    "div": div,
+    "divu": lambda a, b: a // b if b else MASK,
+    "rem": rem,
+    "remu": lambda a, b: a % b if b else a,
+}
+
+I_OPS = {
+    "addi": lambda a, i: a + i,
+    "slti": lambda a, i: int(s32(a) < i),
+    "sltiu": lambda a, i: int(a < (i & MASK)),
+    "xori": lambda a, i: a ^ (i & MASK),
+    "ori": lambda a, i: a | (i & MASK),
+    "andi": lambda a, i: a & (i & MASK),
+}
+
+SHIFT_OPS = {
+    "slli": lambda a, s: a << s,
+    "srli": lambda a, s: a >> s,
+    "srai": lambda a, s: s32(a) >> s,
+}
+
+BRANCH_OPS = {
+    "beq": lambda a, b: a == b,
+    "bne": lambda a, b: a != b,
+    "blt": lambda a, b: s32(a) < s32(b),
+    "bge": lambda a, b: s32(a) >= s32(b),
+    "bltu": lambda a, b: a < b,
+    "bgeu": lambda a, b: a >= b,
+}
+
+# The data that load tests read.
+LOAD_DATA = [0x80FF7F01, 0x12345678, 0xFEDCBA98, 0x00008000]
+
+
+def load(op, offset):
+    data = b"".join(w.to_bytes(4, "little") for w in LOAD_DATA)
+    size = {"lb": 1, "lbu": 1, "lh": 2, "lhu": 2, "lw": 4}[op]
+    value = int.from_bytes(data[offset:offset + size], "little")
+    if op in ("lb", "lh") and value >> (8 * size - 1):
+        value -= 1 << (8 * size)
+    return value
+
+
+class Test:
+    def __init__(self):
+        self.code = []
+        self.data = []
+        self.signature = []
+
+    def li(self, reg, value):
+        self.code.append(f"li {reg}, {s32(value)}")
+
+    def store(self, reg, value):
+        """Store `reg` in the next signature word, expected to hold `value`."""
+        self.code.append(f"sw {reg}, {4 * len(self.signature)}(x10)")
+        self.signature.append(value & MASK)
+
+    def source(self):
+        lines = [
+            ".section .text.init",
+            ".globl rvtest_entry_point",
+            "rvtest_entry_point:",
+            "la x10, begin_signature",
+            "la x11, test_data",
+            *self.code,
+            # The Ceno HALT ecall, as RVMODEL_HALT.
+            "li t0, 0",
+            "li a0, 0",
+            "ecall",
+            "1: j 1b",
+            ".data",
+            ".align 4",
+            "test_data:",
+            *(f".word {w:#010x}" for w in self.data or [0]),
+            ".align 4",
+            ".globl begin_signature",
+            "begin_signature:",
+            *(f".word {FILL:#010x}" for _ in self.padded_signature()),
+            ".globl end_signature",
+            "end_signature:",
+        ]
+        return "\n".join(lines) + "\n"
+
+    def padded_signature(self):
+        # Like the architectural tests, the signature is padded to 16 bytes.
+        return self.signature + [FILL] * (-len(self.signature) % 4)
+
+    def reference(self):
+        return "".join(f"{w:08x}\n" for w in self.padded_signature())
+
+
+def generate():
+    tests = {}
+    for op, f in R_OPS.items():
+        t = tests[op] = Test()
+        for a in VALUES:
+            for b in VALUES:
+                t.li("x1", a)
+                t.li("x2", b)
+                t.code.append(f"{op} x3, x1, x2")
+                t.store("x3", f(a, b))
+    for op, f in I_OPS.items():
+        t = tests[op] = Test()
+        for a in VALUES:
+            for i in IMMEDIATES:
+                t.li("x1", a)
+                t.code.append(f"{op} x3, x1, {i}")
+                t.store("x3", f(a, i))
+    for op, f in SHIFT_OPS.items():
+        t = tests[op] = Test()
+        for a in VALUES:
+            for s in SHAMTS:
+                t.li("x1", a)
+                t.code.append(f"{op} x3, x1, {s}")
+                t.store("x3", f(a, s))
+
+    t = tests["lui"] = Test()
+    for imm in [0, 1, 0x80000, 0xFFFFF, 0x12345]:
+        t.code.append(f"lui x3, {imm}")
+        t.store("x3", imm << 12)
+    t = tests["auipc"] = Test()
+    for imm in [0, 1, 0x80000, 0xFFFFF, 0x12345]:
+        # The distance between two consecutive auipc.
+        t.code += [f"auipc x3, {imm}", "auipc x4, 0", "sub x3, x3, x4"]
+        t.store("x3", (imm << 12) - 4)
+
+    for op, f in BRANCH_OPS.items():
+        t = tests[op] = Test()
+        for a in VALUES:
+            for b in VALUES:
+                t.li("x1", a)
+                t.li("x2", b)
+                t.code += ["li x3, 1", f"{op} x1, x2, 1f", "li x3, 0", "1:"]
+                t.store("x3", int(f(a, b)))
+    t = tests["jal"] = Test()
+    t.code += ["li x5, 0", "jal x3, 1f", "li x5, 1", "1: auipc x4, 0", "sub x3, x4, x3"]
+    t.store("x5", 0)
+    t.store("x3", 4)
+    t = tests["jalr"] = Test()
+    for low_bit in [0, 1]:
+        # The lowest bit of the target is cleared.
+        t.code += [
+            "li x5, 0",
+            "la x1, 1f",
+            f"addi x1, x1, {low_bit}",
+            "jalr x3, 0(x1)",
+            "li x5, 1",
+            "1: auipc x4, 0",
+            "sub x3, x4, x3",
+        ]
+        t.store("x5", 0)
+        t.store("x3", 4)
+
+    for op in ["lb", "lbu", "lh", "lhu", "lw"]:
+        t = tests[op] = Test()
+        t.data = LOAD_DATA
+        size = {"lb": 1, "lbu": 1, "lh": 2, "lhu": 2, "lw": 4}[op]
+        for offset in range(0, 4 * len(LOAD_DATA), size):
+            t.code.append(f"{op} x3, {offset}(x11)")
+            t.store("x3", load(op, offset))
+    for op, size in [("sb", 1), ("sh", 2), ("sw", 4)]:
+        t = tests[op] = Test()
+        for value in VALUES:
+            word = FILL
+            base = 4 * len(t.signature)
+            t.li("x1", value)
+            for offset in range(0, 4, size):
+                mask = ((1 << (8 * size)) - 1) << (8 * offset)
+                word = (word & ~mask) | ((value << (8 * offset)) & mask)
+                t.code.append(f"{op} x1, {base + offset}(x10)")
+                value = (value * 3 + 1) & MASK
+                t.li("x1", value)
+            # The stores write the signature word in place.
+            t.signature.append(word & MASK)
+    return tests
+
+
+def main():
+    llvm_mc = os.environ.get("LLVM_MC", "llvm-mc")
+    ld = os.environ.get("LD") or shutil.which("ld.lld") or "rust-lld"
+    ld_args = [ld] + (["-flavor", "gnu"] if os.path.basename(ld) == "rust-lld" else [])
+    out = os.path.join(HERE, "elf")
+    os.makedirs(out, exist_ok=True)
+    with tempfile.TemporaryDirectory() as tmp:
+        for op, test in generate().items():
+            name = f"ceno-{op}"
+            src = os.path.join(tmp, f"{name}.S")
+            obj = os.path.join(tmp, f"{name}.o")
+            with open(src, "w") as f:
+                f.write(test.source())
+            subprocess.run([llvm_mc, "-triple=riscv32", "-mattr=+m,-relax", "-filetype=obj",
+                            "-o", obj, src], check=True)
+            subprocess.run(ld_args + ["-m", "elf32lriscv", "-T",
+                                      os.path.join(HERE, "model/link.ld"),
+                                      "-o", os.path.join(out, f"{name}.elf"), obj], check=True)
+            with open(os.path.join(out, f"{name}.reference_output"), "w") as f:
+                f.write(test.reference())
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,41 @@
+// Ceno target model for the RISC-V architectural test suite (riscv-arch-test).
+//
+// Code runs from ROM, the test data and the signature live in RAM, and the test ends with the
+// Ceno HALT ecall. There is no trap handler and no IO.
+
+#ifndef _COMPLIANCE_MODEL_H
+#define _COMPLIANCE_MODEL_H
+
+#define RVMODEL_DATA_SECTION
+
+// Halt with the Ceno HALT ecall: t0 = 0 (HALT), a0 = 0 (exit code).
+#define RVMODEL_HALT                                                   \
+  li t0, 0;                                                            \
+  li a0, 0;                                                            \
+  ecall;                                                               \
+  1: j 1b;
+
+#define RVMODEL_BOOT
+
+#define RVMODEL_DATA_BEGIN                                             \
+  RVMODEL_DATA_SECTION                                                 \
+  .align 4;                                                            \
+  .global begin_signature; begin_signature:
+
+#define RVMODEL_DATA_END                                               \
+  .align 4;                                                            \
+  .global end_signature; end_signature:
+
+#define RVMODEL_IO_INIT
+#define RVMODEL_IO_WRITE_STR(_R, _STR)
+#define RVMODEL_IO_CHECK()
+#define RVMODEL_IO_ASSERT_GPR_EQ(_S, _R, _I)
+#define RVMODEL_IO_ASSERT_SFPR_EQ(_F, _R, _I)
+#define RVMODEL_IO_ASSERT_DFPR_EQ(_D, _R, _I)
+
+#define RVMODEL_SET_MSW_INT
+#define RVMODEL_CLEAR_MSW_INT
+#define RVMODEL_CLEAR_MTIMER_INT
+#define RVMODEL_CLEAR_MEXT_INT
+
+#endif // _COMPLIANCE_MODEL_H
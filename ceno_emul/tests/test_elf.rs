@@ -1,5 +1,10 @@
 use anyhow::Result;
-use ceno_emul::{ByteAddr, CENO_PLATFORM, EmuContext, InsnKind, StepRecord, VMState};
+use ceno_emul::{
+    CENO_PLATFORM, EcPoint, EmuContext, InputBuilder, InsnKind, OutputReader, Platform, StackGuard,
+    StepRecord, VMState, ed25519_add, ed25519_double, keccak_f, secp256k1_add, secp256k1_double,
+};
+use goldilocks::{Goldilocks, SmallField};
+use poseidon::{digest::Digest, poseidon_hash::PoseidonHash};
 
 #[test]
 fn test_ceno_rt_mini() -> Result<()> {
@@ -40,7 +45,7 @@ fn test_ceno_rt_alloc() -> Result<()> {
 
     // Search for the RAM action of the test program.
     let mut found = (false, false);
-    for &addr in state.tracer().final_accesses().keys() {
+    for addr in state.tracer().latest_accesses().keys() {
         if !CENO_PLATFORM.is_ram(addr.into()) {
             continue;
         }
@@ -57,13 +62,76 @@ fn test_ceno_rt_alloc() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_ceno_rt_alloc_loop() -> Result<()> {
+    let bump = run_footprint(ceno_examples::ceno_rt_alloc_loop)?;
+    let free_list = run_footprint(ceno_examples::ceno_rt_alloc_loop_free_list)?;
+
+    assert_eq!(bump.0, "3781190\n");
+    assert_eq!(free_list.0, bump.0);
+    // The free list allocator reuses the memory of dropped vectors.
+    assert!(
+        free_list.1 < bump.1,
+        "free list: {} words, bump: {} words",
+        free_list.1,
+        bump.1
+    );
+    Ok(())
+}
+
+#[test]
+fn test_ceno_rt_oom() -> Result<()> {
+    for program_elf in [
+        ceno_examples::ceno_rt_oom,
+        ceno_examples::ceno_rt_oom_free_list,
+    ] {
+        let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+        let steps = run(&mut state)?;
+        let last = steps.last().unwrap();
+        assert_eq!(last.insn().codes().kind, InsnKind::EANY);
+        assert_eq!(last.rs1().unwrap().value, CENO_PLATFORM.ecall_halt());
+        assert_eq!(last.rs2().unwrap().value, EXIT_CODE_OUT_OF_MEMORY);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_ceno_rt_io_typed() -> Result<()> {
+    let program_elf = ceno_examples::ceno_rt_io_typed;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    InputBuilder::new()
+        .write(&(3u32, vec![1u32, 2, 3]))?
+        .write(&"Ceno")?
+        .init_memory(&mut state)?;
+    let _steps = run(&mut state)?;
+
+    let mut output = OutputReader::info_out(&state);
+    assert_eq!(output.read::<Vec<u32>>()?, [3, 6, 9]);
+    assert_eq!(output.read::<String>()?, "Hello, Ceno");
+    Ok(())
+}
+
+#[test]
+fn test_ceno_rt_stack_overflow() -> Result<()> {
+    let program_elf = ceno_examples::ceno_rt_stack_overflow;
+    let platform = Platform::from_elf(program_elf)?;
+    let stack_guard = StackGuard::from_elf(program_elf)?;
+    assert!(stack_guard.stack_limit.is_some());
+    assert!(stack_guard.heap_top.is_some());
+    let mut state = VMState::new_from_elf(platform, program_elf)?;
+    state.set_stack_guard(stack_guard)?;
+    let err = run(&mut state).unwrap_err();
+    assert!(err.to_string().contains("StackOverflow"), "{err}");
+    Ok(())
+}
+
 #[test]
 fn test_ceno_rt_io() -> Result<()> {
     let program_elf = ceno_examples::ceno_rt_io;
     let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
     let _steps = run(&mut state)?;
 
-    let all_messages = read_all_messages(&state);
+    let all_messages = OutputReader::info_out(&state).frames().collect::<Vec<_>>();
     for msg in &all_messages {
         print!("{}", String::from_utf8_lossy(msg));
     }
@@ -72,39 +140,158 @@ fn test_ceno_rt_io() -> Result<()> {
     Ok(())
 }
 
-fn run(state: &mut VMState) -> Result<Vec<StepRecord>> {
-    let steps = state.iter_until_halt().collect::<Result<Vec<_>>>()?;
-    eprintln!("Emulator ran for {} steps.", steps.len());
-    Ok(steps)
+#[test]
+fn test_ceno_rt_keccak() -> Result<()> {
+    let program_elf = ceno_examples::ceno_rt_keccak;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    let steps = run(&mut state)?;
+
+    let num_syscalls = steps.iter().filter(|step| step.syscall().is_some()).count();
+    assert_eq!(num_syscalls, 100);
+
+    let mut expected = [0_u64; 25];
+    for _ in 0..num_syscalls {
+        keccak_f(&mut expected);
+    }
+    let output = OutputReader::info_out(&state).read::<[u64; 25]>()?;
+    assert_eq!(output, expected);
+    Ok(())
 }
 
-const WORD_SIZE: usize = 4;
-const INFO_OUT_ADDR: u32 = 0xC000_0000;
+#[test]
+fn test_ceno_rt_sha256() -> Result<()> {
+    let program_elf = ceno_examples::ceno_rt_sha256;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    let steps = run(&mut state)?;
+
+    // One block for "abc", and two blocks for the 56 bytes of the second message.
+    let num_syscalls = steps.iter().filter(|step| step.syscall().is_some()).count();
+    assert_eq!(num_syscalls, 3);
 
-fn read_all_messages(state: &VMState) -> Vec<Vec<u8>> {
-    let mut all_messages = Vec::new();
-    let mut word_offset = 0;
-    loop {
-        let out = read_message(state, word_offset);
-        if out.is_empty() {
-            break;
+    let mut output = OutputReader::info_out(&state);
+    assert_eq!(
+        output.read::<[u8; 32]>()?,
+        hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+    );
+    assert_eq!(
+        output.read::<[u8; 32]>()?,
+        hex("248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_ceno_rt_poseidon() -> Result<()> {
+    let program_elf = ceno_examples::ceno_rt_poseidon;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    let steps = run(&mut state)?;
+
+    // Two permutations for the leaf, and three for the root.
+    let num_syscalls = steps.iter().filter(|step| step.syscall().is_some()).count();
+    assert_eq!(num_syscalls, 5);
+
+    // The same hashes with the prover's implementation.
+    let elements = |xs: &[u64]| xs.iter().map(|&x| Goldilocks::from(x)).collect::<Vec<_>>();
+    let digest = |xs: [u64; 4]| Digest(elements(&xs).try_into().unwrap());
+    let canonical = |digest: Digest<Goldilocks>| digest.0.map(|x| x.to_canonical_u64());
+    let leaf = PoseidonHash::hash_or_noop(&elements(&(0..10).collect::<Vec<_>>()));
+    let node = PoseidonHash::two_to_one(&digest([5, 6, 7, 8]), &digest([9, 10, 11, 12]));
+    let root = PoseidonHash::two_to_one(
+        &node,
+        &PoseidonHash::two_to_one(&leaf, &digest([1, 2, 3, 4])),
+    );
+
+    let mut output = OutputReader::info_out(&state);
+    assert_eq!(output.read::<[u64; 4]>()?, canonical(leaf));
+    assert_eq!(output.read::<[u64; 4]>()?, canonical(root));
+    Ok(())
+}
+
+#[test]
+fn test_ceno_rt_uint256() -> Result<()> {
+    let program_elf = ceno_examples::ceno_rt_uint256;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    let steps = run(&mut state)?;
+
+    // One addition, then 256 squarings and 249 multiplications for the bits of the exponent.
+    let num_syscalls = steps.iter().filter(|step| step.syscall().is_some()).count();
+    assert_eq!(num_syscalls, 1 + 256 + 249);
+
+    let mut output = OutputReader::info_out(&state);
+    assert_eq!(output.read::<[u32; 8]>()?, [1, 0, 0, 0, 0, 0, 0, 0]);
+    Ok(())
+}
+
+#[test]
+fn test_ceno_rt_ecc() -> Result<()> {
+    let program_elf = ceno_examples::ceno_rt_ecc;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    let steps = run(&mut state)?;
+
+    // On each curve, 19 doublings and 8 additions for the 20 bits of the scalar.
+    let num_syscalls = steps.iter().filter(|step| step.syscall().is_some()).count();
+    assert_eq!(num_syscalls, 2 * (19 + 8));
+
+    // The same double-and-add with the emulator's implementation.
+    let scalar_mul = |point: EcPoint,
+                      add: fn(&EcPoint, &EcPoint) -> Option<EcPoint>,
+                      double: fn(&EcPoint) -> Option<EcPoint>| {
+        let k = 1_000_003_u32;
+        let mut acc = point;
+        for i in (0..31 - k.leading_zeros()).rev() {
+            acc = double(&acc).unwrap();
+            if k >> i & 1 == 1 {
+                acc = add(&acc, &point).unwrap();
+            }
         }
-        word_offset += out.len().div_ceil(WORD_SIZE) as u32 + 1;
-        all_messages.push(out);
-    }
-    all_messages
+        acc
+    };
+    let secp256k1_g = [
+        0x16F81798, 0x59F2815B, 0x2DCE28D9, 0x029BFCDB, 0xCE870B07, 0x55A06295, 0xF9DCBBAC,
+        0x79BE667E, 0xFB10D4B8, 0x9C47D08F, 0xA6855419, 0xFD17B448, 0x0E1108A8, 0x5DA4FBFC,
+        0x26A3C465, 0x483ADA77,
+    ];
+    let ed25519_b = [
+        0x8F25D51A, 0xC9562D60, 0x9525A7B2, 0x692CC760, 0xFDD6DC5C, 0xC0A4E231, 0xCD6E53FE,
+        0x216936D3, 0x66666658, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666,
+        0x66666666, 0x66666666,
+    ];
+
+    let mut output = OutputReader::info_out(&state);
+    assert_eq!(
+        output.read::<EcPoint>()?,
+        scalar_mul(secp256k1_g, secp256k1_add, secp256k1_double)
+    );
+    assert_eq!(
+        output.read::<EcPoint>()?,
+        scalar_mul(ed25519_b, ed25519_add, ed25519_double)
+    );
+    Ok(())
 }
 
-fn read_message(state: &VMState, word_offset: u32) -> Vec<u8> {
-    let out_addr = ByteAddr(INFO_OUT_ADDR).waddr() + word_offset;
-    let byte_len = state.peek_memory(out_addr);
-    let word_len_up = byte_len.div_ceil(4);
+fn hex(digest: &str) -> [u8; 32] {
+    std::array::from_fn(|i| u8::from_str_radix(&digest[2 * i..2 * i + 2], 16).unwrap())
+}
 
-    let mut info_out = Vec::with_capacity(WORD_SIZE * word_len_up as usize);
-    for i in 1..1 + word_len_up {
-        let value = state.peek_memory(out_addr + i);
-        info_out.extend_from_slice(&value.to_le_bytes());
-    }
-    info_out.truncate(byte_len as usize);
-    info_out
+fn run(state: &mut VMState) -> Result<Vec<StepRecord>> {
+    let steps = state.iter_until_halt().collect::<Result<Vec<_>>>()?;
+    eprintln!("Emulator ran for {} steps.", steps.len());
+    Ok(steps)
 }
+
+/// Run a program, and return its output and the number of RAM words that it accessed.
+fn run_footprint(program_elf: &[u8]) -> Result<(String, usize)> {
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    let _steps = run(&mut state)?;
+    let output = OutputReader::info_out(&state).frames().flatten().collect();
+    let footprint = state
+        .tracer()
+        .latest_accesses()
+        .keys()
+        .map(u32::from)
+        .filter(|&addr| CENO_PLATFORM.is_ram(addr) && addr < CENO_PLATFORM.info_out_start())
+        .count();
+    Ok((String::from_utf8(output)?, footprint))
+}
+
+const EXIT_CODE_OUT_OF_MEMORY: u32 = 2;
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use ceno_emul::{CENO_PLATFORM, InsnKind::EANY, Platform, encode_rv32};
+
+mod common;
+use common::build_elf;
+
+#[test]
+fn test_platform_from_elf() -> Result<()> {
+    let code = [encode_rv32(EANY, 0, 0, 0, 0)];
+    let rom_start = CENO_PLATFORM.rom_start();
+    let ram_start = CENO_PLATFORM.ram_start();
+
+    // Without layout symbols, the default layout is used.
+    let elf = build_elf(&code, &[1, 2], &[]);
+    assert_eq!(Platform::from_elf(&elf)?, CENO_PLATFORM);
+
+    // The layout symbols of the linker script override the ROM and RAM regions.
+    let elf = build_elf(&code, &[1, 2], &[
+        ("_ceno_rom_start", rom_start),
+        ("_ceno_rom_end", rom_start + 0x1000),
+        ("_ceno_ram_start", ram_start),
+        ("_ceno_ram_end", ram_start + 0x10_0000),
+    ]);
+    let platform = Platform::from_elf(&elf)?;
+    assert_eq!(platform.rom_start, rom_start);
+    assert_eq!(platform.rom_end, rom_start + 0x0FFF);
+    assert_eq!(platform.ram_start, ram_start);
+    assert_eq!(platform.ram_end, ram_start + 0x0F_FFFF);
+
+    // The IO symbols of the linker script override the IO addresses.
+    let public_io_start = CENO_PLATFORM.public_io_start() + 0x100;
+    let info_out_start = ram_start + 0x8_0000;
+    let elf = build_elf(&code, &[1, 2], &[
+        ("_ceno_ram_start", ram_start),
+        ("_ceno_ram_end", ram_start + 0x10_0000),
+        ("_ceno_public_io_start", public_io_start),
+        ("_ceno_public_io_end", public_io_start + 0x100),
+        ("_ceno_info_out_start", info_out_start),
+    ]);
+    let platform = Platform::from_elf(&elf)?;
+    assert_eq!(platform.public_io_start(), public_io_start);
+    assert_eq!(platform.public_io_end(), public_io_start + 0xFF);
+    assert_eq!(platform.info_out_start(), info_out_start);
+
+    // The output must be in RAM.
+    let elf = build_elf(&code, &[1, 2], &[
+        ("_ceno_ram_start", ram_start),
+        ("_ceno_ram_end", ram_start + 0x10_0000),
+        ("_ceno_info_out_start", ram_start + 0x20_0000),
+    ]);
+    assert!(Platform::from_elf(&elf).is_err());
+
+    // The data of the program does not fit in the declared RAM.
+    let elf = build_elf(&code, &[1, 2], &[
+        ("_ceno_ram_start", ram_start + 0x1000),
+        ("_ceno_ram_end", ram_start + 0x2000),
+    ]);
+    assert!(Platform::from_elf(&elf).is_err());
+    Ok(())
+}
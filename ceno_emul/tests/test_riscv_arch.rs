@@ -0,0 +1,151 @@
+//! Run the RISC-V architectural tests, see `riscv-arch-test/README.md`.
+#![allow(clippy::unusual_byte_groupings)]
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result, anyhow, bail};
+use ceno_emul::{
+    ByteAddr, CENO_PLATFORM, EmuContext,
+    InsnKind::{ADDI, DIV, EANY, LUI, REMU, SRAI, SW},
+    VMState, WORD_SIZE, Word, encode_rv32,
+};
+use elf::{ElfBytes, endian::LittleEndian};
+
+mod common;
+use common::build_elf;
+
+/// Stop tests that do not halt.
+const MAX_STEPS: usize = 1 << 24;
+
+#[test]
+fn test_riscv_arch() -> Result<()> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/riscv-arch-test/elf");
+    let mut elf_paths = fs::read_dir(&dir)
+        .with_context(|| format!("no compiled tests in {dir:?}, see riscv-arch-test/README.md"))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>>>()?;
+    elf_paths.retain(|path| path.extension().is_some_and(|ext| ext == "elf"));
+    elf_paths.sort();
+    if elf_paths.is_empty() {
+        bail!("no compiled tests in {dir:?}, see riscv-arch-test/README.md");
+    }
+
+    let mut failures = vec![];
+    for elf_path in &elf_paths {
+        let name = elf_path.file_stem().unwrap().to_string_lossy();
+        let elf = fs::read(elf_path)?;
+        let reference = fs::read_to_string(elf_path.with_extension("reference_output"))
+            .with_context(|| format!("missing reference signature of {name}"))?;
+        if let Err(err) = check_signature(&elf, &parse_reference(&reference)?) {
+            failures.push(format!("{name}: {err}"));
+        }
+    }
+    if !failures.is_empty() {
+        bail!(
+            "{} of {} tests failed:\n{}",
+            failures.len(),
+            elf_paths.len(),
+            failures.join("\n")
+        );
+    }
+    Ok(())
+}
+
+/// Run a test program to completion, and compare its signature with the reference.
+fn check_signature(elf: &[u8], reference: &[Word]) -> Result<()> {
+    let signature = run_signature(elf)?;
+    if signature.len() != reference.len() {
+        bail!(
+            "signature has {} words, expected {}",
+            signature.len(),
+            reference.len()
+        );
+    }
+    if let Some(i) = (0..signature.len()).find(|&i| signature[i] != reference[i]) {
+        bail!(
+            "signature word {i} is 0x{:08x}, expected 0x{:08x}",
+            signature[i],
+            reference[i]
+        );
+    }
+    Ok(())
+}
+
+/// Run a test program to completion, and read the memory between the symbols `begin_signature`
+/// and `end_signature`.
+fn run_signature(elf: &[u8]) -> Result<Vec<Word>> {
+    let begin = find_symbol(elf, "begin_signature")?;
+    let end = find_symbol(elf, "end_signature")?;
+
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, elf)?;
+    for step in state.iter_until_halt().take(MAX_STEPS) {
+        step?;
+    }
+    if !state.halted() {
+        bail!("did not halt after {MAX_STEPS} steps");
+    }
+
+    Ok((begin..end)
+        .step_by(WORD_SIZE)
+        .map(|addr| state.peek_memory(ByteAddr(addr).waddr()))
+        .collect())
+}
+
+fn find_symbol(elf: &[u8], name: &str) -> Result<u32> {
+    let elf = ElfBytes::<LittleEndian>::minimal_parse(elf)
+        .map_err(|err| anyhow!("Elf parse error: {err}"))?;
+    let (symbols, strings) = elf
+        .symbol_table()
+        .map_err(|err| anyhow!("Elf symbol table error: {err}"))?
+        .context("missing symbol table")?;
+    symbols
+        .iter()
+        .find(|symbol| {
+            strings
+                .get(symbol.st_name as usize)
+                .is_ok_and(|s| s == name)
+        })
+        .map(|symbol| symbol.st_value as u32)
+        .with_context(|| format!("missing symbol {name}"))
+}
+
+/// Parse a reference signature: one hexadecimal word per line.
+fn parse_reference(reference: &str) -> Result<Vec<Word>> {
+    reference
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            Word::from_str_radix(line.trim(), 16)
+                .with_context(|| format!("invalid signature line {line:?}"))
+        })
+        .collect()
+}
+
+/// Check the harness itself on a small program in the layout of the architectural tests.
+#[test]
+fn test_signature_harness() -> Result<()> {
+    let signature_start = CENO_PLATFORM.ram_start();
+    let code = [
+        encode_rv32(LUI, 0, 0, 10, signature_start), // lui x10, begin_signature
+        encode_rv32(ADDI, 0, 0, 1, -7_i32 as u32),   // addi x1, x0, -7
+        encode_rv32(SRAI, 1, 0, 2, 1),               // srai x2, x1, 1
+        encode_rv32(SW, 10, 2, 0, 0),                // sw x2, 0(x10)
+        encode_rv32(ADDI, 0, 0, 3, 2),               // addi x3, x0, 2
+        encode_rv32(DIV, 1, 3, 4, 0),                // div x4, x1, x3
+        encode_rv32(SW, 10, 4, 0, 4),                // sw x4, 4(x10)
+        encode_rv32(REMU, 1, 0, 6, 0),               // remu x6, x1, x0
+        encode_rv32(SW, 10, 6, 0, 8),                // sw x6, 8(x10)
+        encode_rv32(EANY, 0, 0, 0, 0),               // ecall halt
+    ];
+    let elf = build_elf(&code, &[0xdeadbeef; 4], &[
+        ("begin_signature", signature_start),
+        ("end_signature", signature_start + 3 * WORD_SIZE as u32),
+    ]);
+
+    let reference = parse_reference("fffffffc\nfffffffd\nfffffff9\n")?;
+    check_signature(&elf, &reference)?;
+
+    let wrong_reference = parse_reference("fffffffc\nfffffffc\nfffffff9\n")?;
+    let err = check_signature(&elf, &wrong_reference).unwrap_err();
+    assert!(err.to_string().contains("signature word 1"), "{err}");
+    Ok(())
+}
@@ -3,28 +3,17 @@ use anyhow::Result;
 use std::collections::{BTreeMap, HashMap};
 
 use ceno_emul::{
-    CENO_PLATFORM, Cycle, EmuContext, InsnKind, Program, StepRecord, Tracer, VMState, WORD_SIZE,
-    WordAddr,
+    ByteAddr, CENO_PLATFORM, Change, Cycle, EC_POINT_WORDS, EcPoint, EmuContext,
+    InsnKind::{self, ADDI, SW},
+    KECCAK_STATE_WORDS, POSEIDON_STATE_WORDS, Platform, Program, SHA256_BLOCK_WORDS, SHA256_INIT,
+    SHA256_STATE_WORDS, StackGuard, StepRecord, Tracer, UINT256_WORDS, VMSnapshot, VMState,
+    WORD_SIZE, WordAddr, ed25519_add, encode_rv32, keccak_f, poseidon_permutation,
+    secp256k1_double, sha256_compress_block, uint256_mulmod,
 };
 
 #[test]
 fn test_vm_trace() -> Result<()> {
-    let program = Program::new(
-        CENO_PLATFORM.pc_base(),
-        CENO_PLATFORM.pc_base(),
-        PROGRAM_FIBONACCI_20.to_vec(),
-        PROGRAM_FIBONACCI_20
-            .iter()
-            .enumerate()
-            .map(|(insn_idx, &insn)| {
-                (
-                    CENO_PLATFORM.pc_base() + (WORD_SIZE * insn_idx) as u32,
-                    insn,
-                )
-            })
-            .collect(),
-    );
-    let mut ctx = VMState::new(CENO_PLATFORM, program);
+    let mut ctx = VMState::new(CENO_PLATFORM, fibonacci_20_program());
 
     let steps = run(&mut ctx)?;
 
@@ -37,10 +26,50 @@ fn test_vm_trace() -> Result<()> {
     assert_eq!(ops, expected_ops_fibonacci_20());
 
     assert_eq!(
-        ctx.tracer().final_accesses(),
-        &expected_final_accesses_fibonacci_20()
+        ctx.tracer()
+            .latest_accesses()
+            .iter()
+            .collect::<HashMap<_, _>>(),
+        expected_final_accesses_fibonacci_20()
+    );
+    #[allow(deprecated)]
+    let final_accesses = ctx.tracer().final_accesses();
+    assert_eq!(final_accesses, expected_final_accesses_fibonacci_20());
+
+    Ok(())
+}
+
+#[test]
+fn test_vm_snapshot() -> Result<()> {
+    let mut uninterrupted = VMState::new(CENO_PLATFORM, fibonacci_20_program());
+    let expected_steps = run(&mut uninterrupted)?;
+
+    // Interrupt a second execution in the middle of the loop.
+    let mut ctx = VMState::new(CENO_PLATFORM, fibonacci_20_program());
+    let mut steps = ctx.iter_until_halt().take(13).collect::<Result<Vec<_>>>()?;
+    let snapshot = ctx.snapshot();
+    assert!(!snapshot.halted);
+
+    // Resume from a serialized snapshot.
+    let snapshot: VMSnapshot = serde_json::from_str(&serde_json::to_string(&snapshot)?)?;
+    let mut resumed = VMState::from_snapshot(CENO_PLATFORM, fibonacci_20_program(), &snapshot)?;
+    assert_eq!(resumed.snapshot(), snapshot);
+    steps.extend(run(&mut resumed)?);
+
+    assert_eq!(steps, expected_steps);
+    assert_eq!(resumed.snapshot(), uninterrupted.snapshot());
+    assert_eq!(
+        resumed
+            .tracer()
+            .latest_accesses()
+            .iter()
+            .collect::<HashMap<_, _>>(),
+        expected_final_accesses_fibonacci_20()
     );
 
+    // Rewind the finished execution to the snapshot, and run it again.
+    uninterrupted.restore(&snapshot)?;
+    assert_eq!(run(&mut uninterrupted)?, expected_steps[13..]);
     Ok(())
 }
 
@@ -58,6 +87,486 @@ fn test_empty_program() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_stack_overflow() -> Result<()> {
+    let platform = CENO_PLATFORM;
+    let stack_limit = platform.ram_start() + 0x100;
+    let code = [
+        encode_rv32(SW, 2, 1, 0, 0),                // sw x1, 0(sp)
+        encode_rv32(SW, 3, 1, 0, 0),                // sw x1, 0(x3): below sp, in the heap.
+        encode_rv32(ADDI, 2, 0, 2, -16_i32 as u32), // addi sp, sp, -16
+        encode_rv32(SW, 2, 1, 0, 12),               // sw x1, 12(sp)
+        encode_rv32(SW, 8, 1, 0, 0),                // sw x1, 0(s0): not relative to sp.
+    ];
+    let mut ctx = VMState::new(platform, program_of(&platform, &code));
+    ctx.set_stack_guard(StackGuard {
+        stack_limit: Some(stack_limit),
+        heap_top: None,
+    })?;
+    ctx.init_register_unsafe(2, stack_limit + 8);
+    ctx.init_register_unsafe(3, stack_limit - 8);
+    ctx.init_register_unsafe(8, stack_limit - 8);
+    let res = run(&mut ctx);
+    let expected = format!("StackOverflow({:?})", ByteAddr(stack_limit - 8));
+    assert!(
+        matches!(&res, Err(e) if e.to_string().contains(&expected)),
+        "{res:?}"
+    );
+    // The last store traps.
+    assert_eq!(ctx.get_pc(), ByteAddr(platform.pc_base() + 16));
+    Ok(())
+}
+
+#[test]
+fn test_heap_stack_collision() -> Result<()> {
+    let platform = CENO_PLATFORM;
+    let heap_top = platform.ram_start();
+    let stack_limit = platform.ram_start() + 0x100;
+    let heap_end = platform.ram_start() + 0x200;
+    let code = [
+        encode_rv32(SW, 2, 1, 0, 0), // sw x1, 0(sp): above the stack limit.
+        encode_rv32(SW, 6, 5, 0, 0), // sw x5, 0(x6): the heap grows to heap_end.
+        encode_rv32(SW, 2, 1, 0, 0), // sw x1, 0(sp): now in the heap.
+    ];
+    let mut ctx = VMState::new(platform, program_of(&platform, &code));
+    ctx.set_stack_guard(StackGuard {
+        stack_limit: Some(stack_limit),
+        heap_top: Some(heap_top),
+    })?;
+    let sp = stack_limit + 0x80;
+    ctx.init_register_unsafe(2, sp);
+    ctx.init_register_unsafe(5, heap_end);
+    ctx.init_register_unsafe(6, heap_top);
+    assert_eq!(ctx.stack_boundary(), Some(stack_limit));
+    let res = run(&mut ctx);
+    let expected = format!("StackOverflow({:?})", ByteAddr(sp));
+    assert!(
+        matches!(&res, Err(e) if e.to_string().contains(&expected)),
+        "{res:?}"
+    );
+    assert_eq!(ctx.stack_boundary(), Some(heap_end));
+    assert_eq!(ctx.get_pc(), ByteAddr(platform.pc_base() + 8));
+    Ok(())
+}
+
+#[test]
+fn test_stack_overflow_above_heap() -> Result<()> {
+    let platform = CENO_PLATFORM;
+    let heap_top = platform.ram_start();
+    let heap_end = platform.ram_start() + 0x80;
+    let stack_limit = platform.ram_start() + 0x100;
+    let code = [
+        encode_rv32(SW, 2, 1, 0, 0), // sw x1, 0(sp): above the heap, below the stack limit.
+    ];
+    let mut ctx = VMState::new(platform, program_of(&platform, &code));
+    ctx.init_memory(ByteAddr(heap_top).waddr(), heap_end);
+    ctx.set_stack_guard(StackGuard {
+        stack_limit: Some(stack_limit),
+        heap_top: Some(heap_top),
+    })?;
+    let sp = stack_limit - 0x40;
+    ctx.init_register_unsafe(2, sp);
+    assert_eq!(ctx.stack_boundary(), Some(stack_limit));
+    let res = run(&mut ctx);
+    let expected = format!("StackOverflow({:?})", ByteAddr(sp));
+    assert!(
+        matches!(&res, Err(e) if e.to_string().contains(&expected)),
+        "{res:?}"
+    );
+    Ok(())
+}
+
+fn program_of(platform: &Platform, code: &[u32]) -> Program {
+    Program::new(
+        platform.pc_base(),
+        platform.pc_base(),
+        code.to_vec(),
+        (platform.pc_base()..)
+            .step_by(WORD_SIZE)
+            .zip(code.iter().copied())
+            .collect(),
+    )
+}
+
+#[test]
+fn test_keccak_syscall() -> Result<()> {
+    let state_ptr = CENO_PLATFORM.ram_start() + 0x100;
+    let code = [
+        ECALL,                         // ecall KECCAK_PERMUTE
+        encode_rv32(ADDI, 0, 0, 5, 0), // addi t0, x0, 0
+        ECALL,                         // ecall HALT
+    ];
+    let new_vm = || {
+        let program = Program::new(
+            CENO_PLATFORM.pc_base(),
+            CENO_PLATFORM.pc_base(),
+            code.to_vec(),
+            (CENO_PLATFORM.pc_base()..)
+                .step_by(WORD_SIZE)
+                .zip(code)
+                .collect(),
+        );
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        vm.init_register_unsafe(5, CENO_PLATFORM.ecall_keccak_permute());
+        vm
+    };
+
+    let input: [u64; 25] = std::array::from_fn(|i| (i as u64 + 1) * 0x0101_0101_0101_0101);
+    let mut output = input;
+    keccak_f(&mut output);
+    let words = |lanes: [u64; 25]| {
+        lanes
+            .map(|lane| [lane as u32, (lane >> 32) as u32])
+            .concat()
+    };
+    let (input_words, output_words) = (words(input), words(output));
+
+    let mut vm = new_vm();
+    vm.init_register_unsafe(10, state_ptr);
+    let state_addr = ByteAddr(state_ptr).waddr();
+    for (i, &word) in input_words.iter().enumerate() {
+        vm.init_memory(state_addr + i, word);
+    }
+    let steps = run(&mut vm)?;
+    assert_eq!(steps.len(), 3);
+    assert!(vm.halted());
+
+    let mem_ops = &steps[0].syscall().unwrap().mem_ops;
+    assert_eq!(mem_ops.len(), KECCAK_STATE_WORDS);
+    for (i, op) in mem_ops.iter().enumerate() {
+        assert_eq!(op.addr, state_addr + i);
+        assert_eq!(op.value, Change::new(input_words[i], output_words[i]));
+        assert_eq!(op.previous_cycle, 0);
+        assert_eq!(vm.peek_memory(state_addr + i), output_words[i]);
+    }
+    assert_eq!(
+        vm.tracer().latest_accesses().get(&state_addr),
+        Some(&(steps[0].cycle() + Tracer::SUBCYCLE_MEM))
+    );
+    assert_eq!(steps[0].rs2().unwrap().value, state_ptr);
+    assert!(steps[2].syscall().is_none());
+
+    // The state must be aligned and writable.
+    for (ptr, expected) in [
+        (
+            state_ptr + 2,
+            format!("StoreAddressMisaligned({:?})", ByteAddr(state_ptr + 2)),
+        ),
+        (CENO_PLATFORM.rom_start(), "StoreAccessFault".to_string()),
+    ] {
+        let mut vm = new_vm();
+        vm.init_register_unsafe(10, ptr);
+        let res = run(&mut vm);
+        assert!(
+            matches!(&res, Err(e) if e.to_string().contains(&expected)),
+            "{res:?}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_poseidon_syscall() -> Result<()> {
+    let state_ptr = CENO_PLATFORM.ram_start() + 0x100;
+    let code = [
+        ECALL,                         // ecall POSEIDON_PERMUTE
+        encode_rv32(ADDI, 0, 0, 5, 0), // addi t0, x0, 0
+        ECALL,                         // ecall HALT
+    ];
+    let new_vm = || {
+        let program = Program::new(
+            CENO_PLATFORM.pc_base(),
+            CENO_PLATFORM.pc_base(),
+            code.to_vec(),
+            (CENO_PLATFORM.pc_base()..)
+                .step_by(WORD_SIZE)
+                .zip(code)
+                .collect(),
+        );
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        vm.init_register_unsafe(5, CENO_PLATFORM.ecall_poseidon_permute());
+        vm
+    };
+
+    // The last element is not canonical.
+    let mut input: [u64; 12] = std::array::from_fn(|i| (i as u64 + 1) * 0x0123_4567_89AB_CDEF);
+    input[11] = u64::MAX;
+    let mut output = input;
+    poseidon_permutation(&mut output);
+    let words = |elements: [u64; 12]| elements.map(|x| [x as u32, (x >> 32) as u32]).concat();
+    let (input_words, output_words) = (words(input), words(output));
+
+    let mut vm = new_vm();
+    vm.init_register_unsafe(10, state_ptr);
+    let state_addr = ByteAddr(state_ptr).waddr();
+    for (i, &word) in input_words.iter().enumerate() {
+        vm.init_memory(state_addr + i, word);
+    }
+    let steps = run(&mut vm)?;
+    assert_eq!(steps.len(), 3);
+    assert!(vm.halted());
+
+    let mem_ops = &steps[0].syscall().unwrap().mem_ops;
+    assert_eq!(mem_ops.len(), POSEIDON_STATE_WORDS);
+    for (i, op) in mem_ops.iter().enumerate() {
+        assert_eq!(op.addr, state_addr + i);
+        assert_eq!(op.value, Change::new(input_words[i], output_words[i]));
+        assert_eq!(vm.peek_memory(state_addr + i), output_words[i]);
+    }
+    assert_eq!(steps[0].rs2().unwrap().value, state_ptr);
+
+    // The state must be aligned and writable.
+    for (ptr, expected) in [
+        (
+            state_ptr + 2,
+            format!("StoreAddressMisaligned({:?})", ByteAddr(state_ptr + 2)),
+        ),
+        (CENO_PLATFORM.rom_start(), "StoreAccessFault".to_string()),
+    ] {
+        let mut vm = new_vm();
+        vm.init_register_unsafe(10, ptr);
+        let res = run(&mut vm);
+        assert!(
+            matches!(&res, Err(e) if e.to_string().contains(&expected)),
+            "{res:?}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_sha256_syscall() -> Result<()> {
+    let state_ptr = CENO_PLATFORM.ram_start() + 0x100;
+    let block_ptr = CENO_PLATFORM.ram_start() + 0x200;
+    let code = [
+        ECALL,                         // ecall SHA256_COMPRESS
+        encode_rv32(ADDI, 0, 0, 5, 0), // addi t0, x0, 0
+        ECALL,                         // ecall HALT
+    ];
+    let new_vm = |state_ptr: u32, block_ptr: u32| {
+        let program = Program::new(
+            CENO_PLATFORM.pc_base(),
+            CENO_PLATFORM.pc_base(),
+            code.to_vec(),
+            (CENO_PLATFORM.pc_base()..)
+                .step_by(WORD_SIZE)
+                .zip(code)
+                .collect(),
+        );
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        vm.init_register_unsafe(5, CENO_PLATFORM.ecall_sha256_compress());
+        vm.init_register_unsafe(10, state_ptr);
+        vm.init_register_unsafe(11, block_ptr);
+        vm
+    };
+
+    let block: [u32; SHA256_BLOCK_WORDS] = std::array::from_fn(|i| i as u32 * 0x0101_0101);
+    let mut output = SHA256_INIT;
+    sha256_compress_block(&mut output, &block);
+
+    let mut vm = new_vm(state_ptr, block_ptr);
+    let (state_addr, block_addr) = (ByteAddr(state_ptr).waddr(), ByteAddr(block_ptr).waddr());
+    for (i, &word) in SHA256_INIT.iter().enumerate() {
+        vm.init_memory(state_addr + i, word);
+    }
+    for (i, &word) in block.iter().enumerate() {
+        vm.init_memory(block_addr + i, word);
+    }
+    let steps = run(&mut vm)?;
+    assert_eq!(steps.len(), 3);
+    assert!(vm.halted());
+
+    let syscall = steps[0].syscall().unwrap();
+    assert_eq!(
+        syscall.mem_ops.len(),
+        SHA256_STATE_WORDS + SHA256_BLOCK_WORDS
+    );
+    let (state_ops, block_ops) = syscall.mem_ops.split_at(SHA256_STATE_WORDS);
+    for (i, op) in state_ops.iter().enumerate() {
+        assert_eq!(op.addr, state_addr + i);
+        assert_eq!(op.value, Change::new(SHA256_INIT[i], output[i]));
+        assert_eq!(vm.peek_memory(state_addr + i), output[i]);
+    }
+    for (i, op) in block_ops.iter().enumerate() {
+        assert_eq!(op.addr, block_addr + i);
+        assert_eq!(op.value, Change::new(block[i], block[i]));
+    }
+    assert_eq!(steps[0].rs2().unwrap().value, state_ptr);
+    assert_eq!(syscall.reg_ops.len(), 1);
+    assert_eq!(syscall.reg_ops[0].value, block_ptr);
+    assert_eq!(
+        vm.tracer()
+            .latest_accesses()
+            .get(&CENO_PLATFORM.register_vma(11).into()),
+        Some(&(steps[0].cycle() + Tracer::SUBCYCLE_RD))
+    );
+
+    // The block must be aligned and readable, and must not overlap the state.
+    for (block_ptr, expected) in [
+        (block_ptr + 2, "LoadAddressMisaligned".to_string()),
+        (
+            0x1000_0000,
+            format!("LoadAccessFault({:?})", ByteAddr(0x1000_0000)),
+        ),
+        (state_ptr + 4, "EcallError".to_string()),
+    ] {
+        let mut vm = new_vm(state_ptr, block_ptr);
+        let res = run(&mut vm);
+        assert!(
+            matches!(&res, Err(e) if e.to_string().contains(&expected)),
+            "{res:?}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_uint256_syscall() -> Result<()> {
+    let x_ptr = CENO_PLATFORM.ram_start() + 0x100;
+    let inputs_ptr = CENO_PLATFORM.ram_start() + 0x200;
+    let code = [
+        ECALL,                         // ecall UINT256_MULMOD
+        encode_rv32(ADDI, 0, 0, 5, 0), // addi t0, x0, 0
+        ECALL,                         // ecall HALT
+    ];
+    let new_vm = |inputs_ptr: u32, inputs: &[u32]| {
+        let program = Program::new(
+            CENO_PLATFORM.pc_base(),
+            CENO_PLATFORM.pc_base(),
+            code.to_vec(),
+            (CENO_PLATFORM.pc_base()..)
+                .step_by(WORD_SIZE)
+                .zip(code)
+                .collect(),
+        );
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        vm.init_register_unsafe(5, CENO_PLATFORM.ecall_uint256_mulmod());
+        vm.init_register_unsafe(10, x_ptr);
+        vm.init_register_unsafe(11, inputs_ptr);
+        for (i, &word) in inputs.iter().enumerate() {
+            vm.init_memory(ByteAddr(inputs_ptr).waddr() + i, word);
+        }
+        vm
+    };
+
+    // x and y modulo the order of the field of secp256k1, 2^256 - 2^32 - 977.
+    let x: [u32; UINT256_WORDS] =
+        std::array::from_fn(|i| 0x1234_5678_u32.wrapping_mul(i as u32 + 1));
+    let y = [0xFFFF_FFFF; UINT256_WORDS];
+    let mut m = [0xFFFF_FFFF; UINT256_WORDS];
+    m[0] = 0xFFFF_FC2F;
+    m[1] = 0xFFFF_FFFE;
+    let (_, output) = uint256_mulmod(&x, &y, &m).unwrap();
+    let inputs = [y, m].concat();
+
+    let mut vm = new_vm(inputs_ptr, &inputs);
+    let x_addr = ByteAddr(x_ptr).waddr();
+    for (i, &word) in x.iter().enumerate() {
+        vm.init_memory(x_addr + i, word);
+    }
+    let steps = run(&mut vm)?;
+    assert!(vm.halted());
+
+    let syscall = steps[0].syscall().unwrap();
+    assert_eq!(syscall.mem_ops.len(), 3 * UINT256_WORDS);
+    let (x_ops, input_ops) = syscall.mem_ops.split_at(UINT256_WORDS);
+    for (i, op) in x_ops.iter().enumerate() {
+        assert_eq!(op.addr, x_addr + i);
+        assert_eq!(op.value, Change::new(x[i], output[i]));
+        assert_eq!(vm.peek_memory(x_addr + i), output[i]);
+    }
+    for (i, op) in input_ops.iter().enumerate() {
+        assert_eq!(op.addr, ByteAddr(inputs_ptr).waddr() + i);
+        assert_eq!(op.value, Change::new(inputs[i], inputs[i]));
+    }
+    assert_eq!(syscall.reg_ops[0].value, inputs_ptr);
+
+    // The inputs must not overlap x, and the modulus must not be zero.
+    let zero_modulus = [y, [0; UINT256_WORDS]].concat();
+    for (inputs_ptr, inputs) in [(x_ptr + 16, &inputs[..]), (inputs_ptr, &zero_modulus[..])] {
+        let mut vm = new_vm(inputs_ptr, inputs);
+        let res = run(&mut vm);
+        assert!(
+            matches!(&res, Err(e) if e.to_string().contains("EcallError")),
+            "{res:?}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_elliptic_curve_syscalls() -> Result<()> {
+    let p_ptr = CENO_PLATFORM.ram_start() + 0x100;
+    let q_ptr = CENO_PLATFORM.ram_start() + 0x200;
+    let code = [
+        ECALL,                         // ecall on points
+        encode_rv32(ADDI, 0, 0, 5, 0), // addi t0, x0, 0
+        ECALL,                         // ecall HALT
+    ];
+    let new_vm = |function: u32, p: &EcPoint, q: &EcPoint| {
+        let program = Program::new(
+            CENO_PLATFORM.pc_base(),
+            CENO_PLATFORM.pc_base(),
+            code.to_vec(),
+            (CENO_PLATFORM.pc_base()..)
+                .step_by(WORD_SIZE)
+                .zip(code)
+                .collect(),
+        );
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        vm.init_register_unsafe(5, function);
+        vm.init_register_unsafe(10, p_ptr);
+        vm.init_register_unsafe(11, q_ptr);
+        for i in 0..EC_POINT_WORDS {
+            vm.init_memory(ByteAddr(p_ptr).waddr() + i, p[i]);
+            vm.init_memory(ByteAddr(q_ptr).waddr() + i, q[i]);
+        }
+        vm
+    };
+    // The points are not checked to be on the curves.
+    let p: EcPoint = std::array::from_fn(|i| 0x1234_5678_u32.wrapping_mul(i as u32 + 1));
+    let q: EcPoint = std::array::from_fn(|i| 0x8765_4321_u32.wrapping_mul(i as u32 + 1));
+    let p_addr = ByteAddr(p_ptr).waddr();
+
+    // Double: the point at arg0 only.
+    let output = secp256k1_double(&p).unwrap();
+    let mut vm = new_vm(CENO_PLATFORM.ecall_secp256k1_double(), &p, &q);
+    let steps = run(&mut vm)?;
+    let syscall = steps[0].syscall().unwrap();
+    assert_eq!(syscall.mem_ops.len(), EC_POINT_WORDS);
+    assert!(syscall.reg_ops.is_empty());
+    for (i, op) in syscall.mem_ops.iter().enumerate() {
+        assert_eq!(op.addr, p_addr + i);
+        assert_eq!(op.value, Change::new(p[i], output[i]));
+    }
+
+    // Add: the point at arg0, then the point at arg1.
+    let output = ed25519_add(&p, &q).unwrap();
+    let mut vm = new_vm(CENO_PLATFORM.ecall_ed25519_add(), &p, &q);
+    let steps = run(&mut vm)?;
+    let syscall = steps[0].syscall().unwrap();
+    assert_eq!(syscall.mem_ops.len(), 2 * EC_POINT_WORDS);
+    let (p_ops, q_ops) = syscall.mem_ops.split_at(EC_POINT_WORDS);
+    for i in 0..EC_POINT_WORDS {
+        assert_eq!(p_ops[i].value, Change::new(p[i], output[i]));
+        assert_eq!(vm.peek_memory(p_addr + i), output[i]);
+        assert_eq!(q_ops[i].addr, ByteAddr(q_ptr).waddr() + i);
+        assert_eq!(q_ops[i].value, Change::new(q[i], q[i]));
+    }
+    assert_eq!(syscall.reg_ops[0].value, q_ptr);
+
+    // The sum of secp256k1 is not defined for points with the same x.
+    let mut vm = new_vm(CENO_PLATFORM.ecall_secp256k1_add(), &p, &p);
+    let res = run(&mut vm);
+    assert!(
+        matches!(&res, Err(e) if e.to_string().contains("EcallError")),
+        "{res:?}"
+    );
+    Ok(())
+}
+
+const ECALL: u32 = 0b_000000000000_00000_000_00000_1110011;
+
 fn run(state: &mut VMState) -> Result<Vec<StepRecord>> {
     state.iter_until_halt().collect()
 }
@@ -85,6 +594,24 @@ const PROGRAM_FIBONACCI_20: [u32; 7] = [
     0b_000000000000_00000_000_00000_1110011,
 ];
 
+fn fibonacci_20_program() -> Program {
+    Program::new(
+        CENO_PLATFORM.pc_base(),
+        CENO_PLATFORM.pc_base(),
+        PROGRAM_FIBONACCI_20.to_vec(),
+        PROGRAM_FIBONACCI_20
+            .iter()
+            .enumerate()
+            .map(|(insn_idx, &insn)| {
+                (
+                    CENO_PLATFORM.pc_base() + (WORD_SIZE * insn_idx) as u32,
+                    insn,
+                )
+            })
+            .collect(),
+    )
+}
+
 /// Rust version of the example. Reconstruct the output.
 fn expected_fibonacci_20() -> (u32, u32, u32) {
     let mut x1 = 10;
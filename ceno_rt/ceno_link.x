@@ -1,6 +1,15 @@
 
 _stack_start = ORIGIN(REGION_STACK) + LENGTH(REGION_STACK);
 
+/* The heap grows up from `_sheap` to `_eheap`, below the stack.
+   Define `_stack_size` to reserve more memory for the stack. */
+PROVIDE(_stack_size = 0x100000);
+_eheap = _stack_start - _stack_size;
+
+/* The emulator detects stores of the stack below this limit, until the allocator records
+   the heap top at `_ceno_heap_top`. Read by `ceno_emul::Platform::from_elf`. */
+_ceno_stack_limit = _eheap;
+
 SECTIONS
 {
   .text :
@@ -35,3 +44,5 @@ SECTIONS
     _sheap = .;
   } > RAM
 }
+
+ASSERT(_sheap <= _eheap, "not enough RAM for the stack");
@@ -1,7 +1,9 @@
 MEMORY
 {
   RAM : ORIGIN = 0x80000000, LENGTH = 1024M
+  INFO_OUT : ORIGIN = 0xC0000000, LENGTH = 1023M
   ROM : ORIGIN = 0x20000000, LENGTH = 16M
+  PUBLIC_IO : ORIGIN = 0x30001000, LENGTH = 4K
 }
 
 REGION_ALIAS("REGION_TEXT", ROM);
@@ -10,3 +12,14 @@ REGION_ALIAS("REGION_DATA", RAM);
 REGION_ALIAS("REGION_BSS", RAM);
 REGION_ALIAS("REGION_HEAP", RAM);
 REGION_ALIAS("REGION_STACK", RAM);
+
+/* The memory layout, read by `ceno_emul::Platform::from_elf`. End symbols are exclusive.
+   The writable memory spans RAM and the INFO_OUT window, where the guest writes its output.
+   The host writes the input of the guest in PUBLIC_IO. */
+_ceno_rom_start = ORIGIN(ROM);
+_ceno_rom_end = ORIGIN(ROM) + LENGTH(ROM);
+_ceno_ram_start = ORIGIN(RAM);
+_ceno_ram_end = ORIGIN(INFO_OUT) + LENGTH(INFO_OUT);
+_ceno_info_out_start = ORIGIN(INFO_OUT);
+_ceno_public_io_start = ORIGIN(PUBLIC_IO);
+_ceno_public_io_end = ORIGIN(PUBLIC_IO) + LENGTH(PUBLIC_IO);
@@ -1,49 +1,89 @@
-//! A bump allocator.
+//! The global allocator.
+//!
+//! By default, a bump allocator that never frees memory.
+//! With the feature `free_list_allocator`, freed memory is reused, see `free_list`.
+//!
+//! The heap spans from `_sheap` to `_eheap`. An allocation beyond that halts the program with
+//! `EXIT_CODE_OUT_OF_MEMORY`. An allocation that would overwrite the stack, because the stack
+//! grew beyond `_eheap`, halts with `EXIT_CODE_STACK_OVERFLOW`.
+//!
+//! The current top of the heap is exported as `_ceno_heap_top`, for the emulator to detect
+//! stores of the stack into the heap.
 //! Based on https://doc.rust-lang.org/std/alloc/trait.GlobalAlloc.html
 
-use core::alloc::{GlobalAlloc, Layout};
+use core::{alloc::Layout, arch::asm};
 
+use crate::{EXIT_CODE_OUT_OF_MEMORY, EXIT_CODE_STACK_OVERFLOW, halt};
+
+#[cfg(feature = "free_list_allocator")]
+mod free_list;
+
+// `next_alloc` comes first, at the address of `_ceno_heap_top`.
+#[repr(C)]
 struct SimpleAllocator {
     next_alloc: usize,
+    end: usize,
+}
+
+/// Allocate fresh memory from the heap. Fresh memory is zero.
+///
+/// SAFETY: Single threaded, so nothing else can touch the heap while we're working.
+unsafe fn bump(layout: Layout) -> *mut u8 {
+    let mut heap_pos = HEAP.next_alloc;
+
+    let align = layout.align();
+    // `Layout` contract forbids making a `Layout` with align=0, or align not power of 2.
+    // So we can safely use subtraction and a mask to ensure alignment without worrying about UB.
+    let offset = heap_pos & (align - 1);
+    if offset != 0 {
+        heap_pos = heap_pos.strict_add(align.strict_sub(offset));
+    }
+
+    let ptr = heap_pos as *mut u8;
+    // Panic on overflow.  We don't want to wrap around, and overwrite stack etc.
+    // (We could also return a null pointer, but only malicious programs would ever hit this.)
+    heap_pos = heap_pos.strict_add(layout.size());
+    if heap_pos > HEAP.end {
+        halt(EXIT_CODE_OUT_OF_MEMORY);
+    }
+    if heap_pos > stack_pointer() {
+        halt(EXIT_CODE_STACK_OVERFLOW);
+    }
+
+    HEAP.next_alloc = heap_pos;
+    ptr
+}
+
+fn stack_pointer() -> usize {
+    let sp: usize;
+    unsafe { asm!("mv {}, sp", out(reg) sp) };
+    sp
 }
 
-unsafe impl GlobalAlloc for SimpleAllocator {
+#[cfg(not(feature = "free_list_allocator"))]
+unsafe impl core::alloc::GlobalAlloc for SimpleAllocator {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        // SAFETY: Single threaded, so nothing else can touch this while we're working.
-        let mut heap_pos = HEAP.next_alloc;
-
-        let align = layout.align();
-        // `Layout` contract forbids making a `Layout` with align=0, or align not power of 2.
-        // So we can safely use subtraction and a mask to ensure alignment without worrying about UB.
-        let offset = heap_pos & (align - 1);
-        if offset != 0 {
-            heap_pos = heap_pos.strict_add(align.strict_sub(offset));
-        }
-
-        let ptr = heap_pos as *mut u8;
-        // Panic on overflow.  We don't want to wrap around, and overwrite stack etc.
-        // (We could also return a null pointer, but only malicious programs would ever hit this.)
-        heap_pos = heap_pos.strict_add(layout.size());
-
-        HEAP.next_alloc = heap_pos;
-        ptr
+        bump(layout)
     }
 
     unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
-        self.alloc(layout)
+        bump(layout)
     }
 
     /// Never deallocate.
     unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
 }
 
-#[global_allocator]
+#[cfg_attr(not(feature = "free_list_allocator"), global_allocator)]
 // We initialize `next_alloc` to 0xFFFF_FFFF to indicate that the heap has not been initialized.
 // The value is chosen to make any premature allocation fail.
+#[export_name = "_ceno_heap_top"]
 static mut HEAP: SimpleAllocator = SimpleAllocator {
     next_alloc: 0xFFFF_FFFF,
+    end: 0,
 };
 
 pub unsafe fn init_heap() {
     HEAP.next_alloc = core::ptr::from_ref::<u8>(&crate::_sheap).cast::<u8>() as usize;
+    HEAP.end = core::ptr::from_ref::<u8>(&crate::_eheap).cast::<u8>() as usize;
 }
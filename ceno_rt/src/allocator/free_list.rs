@@ -0,0 +1,94 @@
+//! An allocator with a free list per size class.
+//!
+//! Every allocation takes a block of a power-of-two size. Freed blocks are pushed on the free
+//! list of their size, linked through their first word, and reused by later allocations of the
+//! same size. New blocks come from the bump allocator. Blocks are never split or merged.
+
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    ptr,
+};
+
+use super::bump;
+use crate::{EXIT_CODE_OUT_OF_MEMORY, halt};
+
+/// The smallest block holds the link of the free list.
+const MIN_BLOCK: usize = size_of::<*mut u8>();
+
+/// New blocks are aligned to their size up to this bound, so a freed block can be reused for
+/// any layout of the same size class, unless it requires a larger alignment.
+const MAX_BLOCK_ALIGN: usize = 4096;
+
+const NUM_CLASSES: usize = usize::BITS as usize;
+
+struct FreeListAllocator;
+
+/// The first free block of each size class, or null.
+static mut FREE_LISTS: [*mut u8; NUM_CLASSES] = [ptr::null_mut(); NUM_CLASSES];
+
+/// The size class of a layout: the log2 of the size of its block.
+fn size_class(layout: Layout) -> usize {
+    let size = layout.size().max(layout.align()).max(MIN_BLOCK);
+    match size.checked_next_power_of_two() {
+        Some(block) => block.trailing_zeros() as usize,
+        None => halt(EXIT_CODE_OUT_OF_MEMORY),
+    }
+}
+
+/// Take a free block of the given class, if one is available with the alignment.
+///
+/// SAFETY: Single threaded, so nothing else can touch the free lists while we're working.
+unsafe fn take(class: usize, align: usize) -> Option<*mut u8> {
+    let block = FREE_LISTS[class];
+    if block.is_null() || block as usize & (align - 1) != 0 {
+        return None;
+    }
+    FREE_LISTS[class] = *block.cast::<*mut u8>();
+    Some(block)
+}
+
+/// Allocate a new block of the given class.
+unsafe fn new_block(class: usize, align: usize) -> *mut u8 {
+    let size = 1 << class;
+    let align = align.max(size.min(MAX_BLOCK_ALIGN));
+    bump(Layout::from_size_align_unchecked(size, align))
+}
+
+unsafe impl GlobalAlloc for FreeListAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let class = size_class(layout);
+        take(class, layout.align()).unwrap_or_else(|| new_block(class, layout.align()))
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let class = size_class(layout);
+        match take(class, layout.align()) {
+            Some(block) => {
+                ptr::write_bytes(block, 0, layout.size());
+                block
+            }
+            None => new_block(class, layout.align()),
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let class = size_class(layout);
+        *ptr.cast::<*mut u8>() = FREE_LISTS[class];
+        FREE_LISTS[class] = ptr;
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        // The block is large enough already.
+        if size_class(new_layout) == size_class(layout) {
+            return ptr;
+        }
+        let new_ptr = self.alloc(new_layout);
+        ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+        self.dealloc(ptr, layout);
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: FreeListAllocator = FreeListAllocator;
@@ -1,12 +1,37 @@
-use crate::{INFO_OUT_ADDR, WORD_SIZE};
-use core::{cell::Cell, fmt, mem::size_of, slice};
+//! Frames exchanged with the host: a word holding the length in bytes, followed by the bytes
+//! padded to whole words. Typed values are encoded with `postcard`, one value per frame.
+//! See `ceno_emul::InputBuilder` and `ceno_emul::OutputReader` for the host side.
+//!
+//! Typed values are only available on the guest target, where `serde` is a dependency.
+use crate::{_ceno_info_out_start, _ceno_public_io_start, WORD_SIZE};
+use core::{cell::Cell, fmt, mem::size_of, ptr::addr_of, slice};
+#[cfg(target_arch = "riscv32")]
+use serde::{Deserialize, Serialize};
 
-static INFO_OUT: IOWriter = IOWriter::new(INFO_OUT_ADDR);
+static INFO_OUT: IOWriter = IOWriter::new(addr_of!(_ceno_info_out_start).cast_mut());
+
+static INPUT: IOReader = IOReader::new(addr_of!(_ceno_public_io_start));
 
 pub fn info_out() -> &'static IOWriter {
     &INFO_OUT
 }
 
+pub fn input() -> &'static IOReader {
+    &INPUT
+}
+
+/// Read the next value sent by the host.
+#[cfg(target_arch = "riscv32")]
+pub fn read<T: Deserialize<'static>>() -> T {
+    INPUT.read()
+}
+
+/// Send a value to the host.
+#[cfg(target_arch = "riscv32")]
+pub fn write<T: Serialize>(value: &T) {
+    INFO_OUT.write_value(value)
+}
+
 pub struct IOWriter {
     cursor: Cell<*mut u32>,
 }
@@ -16,10 +41,9 @@ pub struct IOWriter {
 unsafe impl Sync for IOWriter {}
 
 impl IOWriter {
-    const fn new(addr: u32) -> Self {
-        assert!(addr % WORD_SIZE as u32 == 0);
+    const fn new(start: *mut u32) -> Self {
         IOWriter {
-            cursor: Cell::new(addr as *mut u32),
+            cursor: Cell::new(start),
         }
     }
 
@@ -51,6 +75,44 @@ impl IOWriter {
             unsafe { slice::from_raw_parts_mut(words[1..].as_mut_ptr() as *mut u8, msg.len()) };
         bytes.copy_from_slice(msg);
     }
+
+    #[cfg(target_arch = "riscv32")]
+    pub fn write_value<T: Serialize>(&self, value: &T) {
+        let bytes = postcard::to_allocvec(value).expect("failed to serialize the output");
+        self.write_frame(&bytes);
+    }
+}
+
+pub struct IOReader {
+    cursor: Cell<*const u32>,
+}
+
+// Safety: Only single-threaded programs are supported.
+unsafe impl Sync for IOReader {}
+
+impl IOReader {
+    const fn new(start: *const u32) -> Self {
+        IOReader {
+            cursor: Cell::new(start),
+        }
+    }
+
+    /// Read the next frame. The input memory is never modified, so the frame lives forever.
+    pub fn read_frame(&self) -> &'static [u8] {
+        let cursor = self.cursor.get();
+        let byte_len = unsafe { *cursor } as usize;
+        let word_len = byte_len.div_ceil(WORD_SIZE);
+
+        // Move the cursor to the next frame.
+        self.cursor.set(unsafe { cursor.add(1 + word_len) });
+
+        unsafe { slice::from_raw_parts(cursor.add(1) as *const u8, byte_len) }
+    }
+
+    #[cfg(target_arch = "riscv32")]
+    pub fn read<T: Deserialize<'static>>(&self) -> T {
+        postcard::from_bytes(self.read_frame()).expect("failed to deserialize the input")
+    }
 }
 
 impl fmt::Write for &IOWriter {
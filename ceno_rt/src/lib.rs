@@ -6,11 +6,22 @@ use core::arch::{asm, global_asm};
 mod allocator;
 
 mod io;
-pub use io::info_out;
+pub use io::{info_out, input};
+#[cfg(target_arch = "riscv32")]
+pub use io::{read, write};
 
 mod params;
 pub use params::*;
 
+#[cfg(target_arch = "riscv32")]
+pub mod poseidon;
+
+#[cfg(target_arch = "riscv32")]
+pub mod sha256;
+
+mod syscalls;
+pub use syscalls::*;
+
 #[cfg(not(test))]
 mod panic_handler {
     use core::panic::PanicInfo;
@@ -18,7 +29,7 @@ mod panic_handler {
     #[panic_handler]
     #[inline(never)]
     fn panic_handler(_panic: &PanicInfo<'_>) -> ! {
-        super::halt(1)
+        super::halt(super::EXIT_CODE_PANIC)
     }
 }
 
@@ -93,4 +104,10 @@ extern "C" {
     static _stack_start: u8;
     // The address of this variable is the start of the heap (growing upwards).
     static _sheap: u8;
+    // The address of this variable is the end of the heap.
+    static _eheap: u8;
+    // The address of this variable is the start of the output, see `io`.
+    static _ceno_info_out_start: u32;
+    // The address of this variable is the start of the input, see `io`.
+    static _ceno_public_io_start: u32;
 }
@@ -1,3 +1,10 @@
 pub const WORD_SIZE: usize = 4;
 
-pub const INFO_OUT_ADDR: u32 = 0xC000_0000;
+/// The exit code of a program that panicked.
+pub const EXIT_CODE_PANIC: u32 = 1;
+
+/// The exit code of a program that ran out of heap memory.
+pub const EXIT_CODE_OUT_OF_MEMORY: u32 = 2;
+
+/// The exit code of a program whose stack grew into the heap.
+pub const EXIT_CODE_STACK_OVERFLOW: u32 = 3;
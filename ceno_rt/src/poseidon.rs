@@ -0,0 +1,69 @@
+//! Poseidon over the Goldilocks field, accelerated by the ecall POSEIDON_PERMUTE.
+//!
+//! The hashes match those of the crate `poseidon`, used by the prover for its transcript and
+//! its Merkle trees. Field elements are represented by their canonical `u64`.
+
+use crate::syscall_poseidon_permute;
+
+/// The number of elements of the state.
+pub const WIDTH: usize = 12;
+
+/// The number of elements absorbed per permutation.
+pub const RATE: usize = 8;
+
+/// The number of elements of a digest.
+pub const DIGEST_WIDTH: usize = 4;
+
+pub type Digest = [u64; DIGEST_WIDTH];
+
+/// Apply the Poseidon permutation to `state`, in place.
+pub fn permute(state: &mut [u64; WIDTH]) {
+    syscall_poseidon_permute(state);
+}
+
+/// Hash `inputs` with a sponge in overwrite mode, without padding.
+pub fn hash_no_pad(inputs: &[u64]) -> Digest {
+    let mut state = [0; WIDTH];
+    for chunk in inputs.chunks(RATE) {
+        state[..chunk.len()].copy_from_slice(chunk);
+        permute(&mut state);
+    }
+    state[..DIGEST_WIDTH].try_into().unwrap()
+}
+
+/// Hash `inputs`, or pad them with zeros if they fit in a digest.
+pub fn hash_or_noop(inputs: &[u64]) -> Digest {
+    if inputs.len() <= DIGEST_WIDTH {
+        let mut digest = [0; DIGEST_WIDTH];
+        digest[..inputs.len()].copy_from_slice(inputs);
+        digest
+    } else {
+        hash_no_pad(inputs)
+    }
+}
+
+/// Compress two digests into one, as for the nodes of a Merkle tree.
+pub fn two_to_one(left: &Digest, right: &Digest) -> Digest {
+    let mut state = [0; WIDTH];
+    state[..DIGEST_WIDTH].copy_from_slice(left);
+    state[DIGEST_WIDTH..2 * DIGEST_WIDTH].copy_from_slice(right);
+    permute(&mut state);
+    state[..DIGEST_WIDTH].try_into().unwrap()
+}
+
+/// Compute the root of a Merkle tree from the digest of the leaf at `index` and the digests of
+/// its siblings, from the bottom up.
+///
+/// The bit `i` of `index` tells whether the node at height `i` is a right child.
+pub fn merkle_root(leaf: &Digest, mut index: usize, siblings: &[Digest]) -> Digest {
+    let mut node = *leaf;
+    for sibling in siblings {
+        node = if index & 1 == 0 {
+            two_to_one(&node, sibling)
+        } else {
+            two_to_one(sibling, &node)
+        };
+        index >>= 1;
+    }
+    node
+}
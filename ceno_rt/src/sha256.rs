@@ -0,0 +1,84 @@
+//! SHA-256, accelerated by the ecall SHA256_COMPRESS.
+
+use crate::syscall_sha256_compress;
+
+const BLOCK_BYTES: usize = 64;
+
+/// The initial state of SHA-256.
+const INIT: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// An incremental SHA-256 hasher.
+#[derive(Clone)]
+pub struct Sha256 {
+    state: [u32; 8],
+    buffer: [u8; BLOCK_BYTES],
+    buffer_len: usize,
+    total_len: u64,
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha256 {
+    pub fn new() -> Self {
+        Sha256 {
+            state: INIT,
+            buffer: [0; BLOCK_BYTES],
+            buffer_len: 0,
+            total_len: 0,
+        }
+    }
+
+    /// Append `data` to the message.
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.total_len += data.len() as u64;
+        while !data.is_empty() {
+            let n = (BLOCK_BYTES - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + n].copy_from_slice(&data[..n]);
+            self.buffer_len += n;
+            data = &data[n..];
+            if self.buffer_len == BLOCK_BYTES {
+                self.compress();
+            }
+        }
+    }
+
+    /// Pad the message and return its digest.
+    pub fn finalize(mut self) -> [u8; 32] {
+        let bit_len = self.total_len * 8;
+        self.buffer[self.buffer_len] = 0x80;
+        self.buffer[self.buffer_len + 1..].fill(0);
+        if self.buffer_len + 1 > BLOCK_BYTES - 8 {
+            self.compress();
+            self.buffer.fill(0);
+        }
+        self.buffer[BLOCK_BYTES - 8..].copy_from_slice(&bit_len.to_be_bytes());
+        self.compress();
+
+        let mut digest = [0; 32];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+
+    fn compress(&mut self) {
+        let block: [u32; 16] = core::array::from_fn(|i| {
+            u32::from_be_bytes(self.buffer[4 * i..4 * i + 4].try_into().unwrap())
+        });
+        syscall_sha256_compress(&mut self.state, &block);
+        self.buffer_len = 0;
+    }
+}
+
+/// The SHA-256 digest of `data`.
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hasher.finalize()
+}
@@ -0,0 +1,145 @@
+//! Precompiled functions of the Ceno VM, see `ceno_emul::syscalls`.
+//!
+//! The functions only exist on the guest target: the codes of the ecalls are also available on
+//! the host.
+
+#[cfg(target_arch = "riscv32")]
+use core::arch::asm;
+
+/// The code of ecall KECCAK_PERMUTE.
+pub const KECCAK_PERMUTE: u32 = 0x00_01_01_09;
+
+/// The code of ecall SHA256_COMPRESS.
+pub const SHA256_COMPRESS: u32 = 0x00_01_01_06;
+
+/// The code of ecall POSEIDON_PERMUTE.
+pub const POSEIDON_PERMUTE: u32 = 0x00_01_01_20;
+
+/// The code of ecall UINT256_ADD.
+pub const UINT256_ADD: u32 = 0x00_01_01_1C;
+
+/// The code of ecall UINT256_MUL.
+pub const UINT256_MUL: u32 = 0x00_01_01_1D;
+
+/// The code of ecall UINT256_MULMOD.
+pub const UINT256_MULMOD: u32 = 0x00_01_01_1E;
+
+/// The code of ecall SECP256K1_ADD.
+pub const SECP256K1_ADD: u32 = 0x00_01_01_0A;
+
+/// The code of ecall SECP256K1_DOUBLE.
+pub const SECP256K1_DOUBLE: u32 = 0x00_00_01_0B;
+
+/// The code of ecall ED25519_ADD.
+pub const ED25519_ADD: u32 = 0x00_01_01_07;
+
+/// The code of ecall ED25519_DOUBLE.
+pub const ED25519_DOUBLE: u32 = 0x00_00_01_08;
+
+/// Call the precompile `code`, with the arguments `arg0` and `arg1`.
+///
+/// The arguments point to memory that the precompile may read and write.
+#[cfg(target_arch = "riscv32")]
+fn ecall(code: u32, arg0: usize, arg1: usize) {
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") code,
+            in("a0") arg0,
+            in("a1") arg1,
+        );
+    }
+}
+
+/// Apply the Keccak-f[1600] permutation to the 25 lanes of `state`, in place.
+///
+/// Lane `(x, y)` is at index `x + 5 * y`, as in the Keccak reference.
+#[cfg(target_arch = "riscv32")]
+pub fn syscall_keccak_permute(state: &mut [u64; 25]) {
+    ecall(KECCAK_PERMUTE, state.as_mut_ptr() as usize, 0);
+}
+
+/// Apply the SHA-256 compression function to `state` with the message block `block`.
+///
+/// The words of the block are the big-endian words of the message, see `sha256` for a hasher.
+#[cfg(target_arch = "riscv32")]
+pub fn syscall_sha256_compress(state: &mut [u32; 8], block: &[u32; 16]) {
+    ecall(
+        SHA256_COMPRESS,
+        state.as_mut_ptr() as usize,
+        block.as_ptr() as usize,
+    );
+}
+
+/// Apply the Poseidon permutation over the Goldilocks field to the 12 elements of `state`, in
+/// place.
+///
+/// Elements that are not canonical are reduced; the resulting elements are canonical. See
+/// `poseidon` for a hasher.
+#[cfg(target_arch = "riscv32")]
+pub fn syscall_poseidon_permute(state: &mut [u64; 12]) {
+    ecall(POSEIDON_PERMUTE, state.as_mut_ptr() as usize, 0);
+}
+
+/// Replace `x` with `x + y mod 2^256`.
+///
+/// The integers are 8 little-endian words. `x` and `y` must not overlap.
+#[cfg(target_arch = "riscv32")]
+pub fn syscall_uint256_add(x: &mut [u32; 8], y: &[u32; 8]) {
+    ecall(UINT256_ADD, x.as_mut_ptr() as usize, y.as_ptr() as usize);
+}
+
+/// Replace `x` with `x * y mod 2^256`.
+///
+/// The integers are 8 little-endian words. `x` and `y` must not overlap.
+#[cfg(target_arch = "riscv32")]
+pub fn syscall_uint256_mul(x: &mut [u32; 8], y: &[u32; 8]) {
+    ecall(UINT256_MUL, x.as_mut_ptr() as usize, y.as_ptr() as usize);
+}
+
+/// Replace `x` with `x * y mod m`, where `[y, m] = y_and_modulus`.
+///
+/// The integers are 8 little-endian words. `x` must not overlap the other operands. The modulus
+/// must not be zero, and `x < m` or `y < m` must hold.
+#[cfg(target_arch = "riscv32")]
+pub fn syscall_uint256_mulmod(x: &mut [u32; 8], y_and_modulus: &[[u32; 8]; 2]) {
+    ecall(
+        UINT256_MULMOD,
+        x.as_mut_ptr() as usize,
+        y_and_modulus.as_ptr() as usize,
+    );
+}
+
+/// Replace the point `p` of secp256k1 with `p + q`.
+///
+/// A point is `x` followed by `y` in affine coordinates, each as 8 little-endian words. The
+/// points must have distinct `x`: use `syscall_secp256k1_double` for `p + p`, and handle the
+/// point at infinity and `p - p` in the caller. `p` and `q` must not overlap.
+#[cfg(target_arch = "riscv32")]
+pub fn syscall_secp256k1_add(p: &mut [u32; 16], q: &[u32; 16]) {
+    ecall(SECP256K1_ADD, p.as_mut_ptr() as usize, q.as_ptr() as usize);
+}
+
+/// Replace the point `p` of secp256k1 with `2 * p`, see `syscall_secp256k1_add`.
+///
+/// `y` must not be zero.
+#[cfg(target_arch = "riscv32")]
+pub fn syscall_secp256k1_double(p: &mut [u32; 16]) {
+    ecall(SECP256K1_DOUBLE, p.as_mut_ptr() as usize, 0);
+}
+
+/// Replace the point `p` of ed25519 with `p + q`.
+///
+/// A point is `x` followed by `y` in affine coordinates, each as 8 little-endian words. The sum
+/// is complete on the curve: `p` and `q` may be equal or the identity `(0, 1)`. `p` and `q` must
+/// not overlap.
+#[cfg(target_arch = "riscv32")]
+pub fn syscall_ed25519_add(p: &mut [u32; 16], q: &[u32; 16]) {
+    ecall(ED25519_ADD, p.as_mut_ptr() as usize, q.as_ptr() as usize);
+}
+
+/// Replace the point `p` of ed25519 with `2 * p`, see `syscall_ed25519_add`.
+#[cfg(target_arch = "riscv32")]
+pub fn syscall_ed25519_double(p: &mut [u32; 16]) {
+    ecall(ED25519_DOUBLE, p.as_mut_ptr() as usize, 0);
+}
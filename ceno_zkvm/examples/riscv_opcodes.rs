@@ -1,10 +1,10 @@
-use std::{panic, time::Instant};
+use std::{panic, path::PathBuf, time::Instant};
 
 use ceno_zkvm::{
     declare_program,
     instructions::riscv::{Rv32imConfig, constants::EXIT_PC},
-    scheme::prover::ZKVMProver,
-    state::GlobalState,
+    scheme::{distributed::run_worker, prover::ZKVMProver},
+    state::{GlobalState, HashedIOGlobalState},
     tables::{
         DynVolatileRamTable, MemFinalRecord, MemTable, ProgramTableCircuit, init_program_data,
         init_public_io, initial_registers,
@@ -71,6 +71,18 @@ struct Args {
     /// end round
     #[arg(short, long, default_value_t = 9)]
     end: u8,
+
+    /// prove only the Poseidon digest of the public io as a public value
+    #[arg(long)]
+    hash_public_io: bool,
+
+    /// serve as a worker proving circuits on this unix socket, instead of proving the program
+    #[arg(long)]
+    worker: Option<PathBuf>,
+
+    /// prove the circuits on the workers listening on these comma-separated unix sockets
+    #[arg(long, value_delimiter = ',')]
+    workers: Vec<PathBuf>,
 }
 
 fn main() {
@@ -105,14 +117,27 @@ fn main() {
         .with(flame_layer.with_threads_collapsed(true));
     tracing::subscriber::set_global_default(subscriber).unwrap();
 
+    if let Some(socket) = args.worker {
+        run_worker::<E, Pcs>(socket).expect("worker failed");
+        return;
+    }
+
     // keygen
     let pcs_param = Pcs::setup(1 << MAX_NUM_VARIABLES).expect("Basefold PCS setup");
     let (pp, vp) = Pcs::trim(&pcs_param, 1 << MAX_NUM_VARIABLES).expect("Basefold trim");
     let mut zkvm_cs = ZKVMConstraintSystem::default();
 
-    let config = Rv32imConfig::<E>::construct_circuits(&mut zkvm_cs);
+    let config = if args.hash_public_io {
+        Rv32imConfig::<E>::construct_circuits_with_hashed_io(&mut zkvm_cs)
+    } else {
+        Rv32imConfig::<E>::construct_circuits(&mut zkvm_cs)
+    };
     let prog_config = zkvm_cs.register_table_circuit::<ExampleProgramTableCircuit<E>>();
-    zkvm_cs.register_global_state::<GlobalState>();
+    if args.hash_public_io {
+        zkvm_cs.register_global_state::<HashedIOGlobalState>();
+    } else {
+        zkvm_cs.register_global_state::<GlobalState>();
+    }
 
     let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
 
@@ -122,10 +147,10 @@ fn main() {
         &program,
     );
 
-    let reg_init = initial_registers();
+    let reg_init = initial_registers(&CENO_PLATFORM);
     // Define program constant here
     let program_data: &[u32] = &[];
-    let program_data_init = init_program_data(program_data);
+    let program_data_init = init_program_data(&CENO_PLATFORM, program_data);
 
     config.generate_fixed_traces(
         &zkvm_cs,
@@ -151,7 +176,7 @@ fn main() {
         let step_loop = 1 << (instance_num_vars - 1);
 
         // init vm.x1 = 1, vm.x2 = -1, vm.x3 = step_loop
-        let public_io_init = init_public_io(&[1, u32::MAX, step_loop]);
+        let public_io_init = init_public_io(&CENO_PLATFORM, &[1, u32::MAX, step_loop]);
 
         let mut vm = VMState::new(CENO_PLATFORM, program.clone());
 
@@ -174,22 +199,34 @@ fn main() {
             })
             .expect("halt record not found");
 
-        let final_access = vm.tracer().final_accesses();
+        let final_access = vm.tracer().latest_accesses();
 
         let end_cycle: u32 = vm.tracer().cycle().try_into().unwrap();
         let exit_code = halt_record.rs2().unwrap().value;
-        let pi = PublicValues::new(
-            exit_code,
-            CENO_PLATFORM.rom_start(),
-            Tracer::SUBCYCLES_PER_INSN as u32,
-            EXIT_PC as u32,
-            end_cycle,
-            public_io_init.iter().map(|v| v.value).collect(),
-        );
+        let public_io = public_io_init.iter().map(|v| v.value).collect_vec();
+        let pi = if args.hash_public_io {
+            PublicValues::new_with_hashed_io(
+                exit_code,
+                CENO_PLATFORM.rom_start(),
+                Tracer::SUBCYCLES_PER_INSN as u32,
+                EXIT_PC as u32,
+                end_cycle,
+                &public_io,
+            )
+        } else {
+            PublicValues::new(
+                exit_code,
+                CENO_PLATFORM.rom_start(),
+                Tracer::SUBCYCLES_PER_INSN as u32,
+                EXIT_PC as u32,
+                end_cycle,
+                public_io,
+            )
+        };
 
         let mut zkvm_witness = ZKVMWitnesses::default();
         // assign opcode circuits
-        config
+        let keccak_steps = config
             .assign_opcode_circuit(&zkvm_cs, &mut zkvm_witness, all_records)
             .unwrap();
         zkvm_witness.finalize_lk_multiplicities();
@@ -249,7 +286,7 @@ fn main() {
         let num_entry = 1 << 12;
         let mem_final = (0..num_entry)
             .map(|entry_index| {
-                let byte_addr = ByteAddr::from(MemTable::addr(entry_index));
+                let byte_addr = ByteAddr::from(MemTable::addr(&CENO_PLATFORM, entry_index));
                 let vma = byte_addr.waddr();
                 MemFinalRecord {
                     addr: byte_addr.0,
@@ -268,6 +305,7 @@ fn main() {
                 &mem_final,
                 &program_data_final,
                 &public_io_final,
+                &keccak_steps,
             )
             .unwrap();
 
@@ -279,9 +317,12 @@ fn main() {
         let timer = Instant::now();
 
         let transcript = Transcript::new(b"riscv");
-        let mut zkvm_proof = prover
-            .create_proof(zkvm_witness, pi, transcript)
-            .expect("create_proof failed");
+        let mut zkvm_proof = if args.workers.is_empty() {
+            prover.create_proof(zkvm_witness, pi, transcript)
+        } else {
+            prover.create_proof_distributed(zkvm_witness, pi, transcript, &args.workers)
+        }
+        .expect("create_proof failed");
 
         println!(
             "riscv_opcodes::create_proof, instance_num_vars = {}, time = {}",
@@ -1,12 +1,13 @@
 use ff_ext::ExtensionField;
+use poseidon::DIGEST_WIDTH;
 
 use crate::{
     circuit_builder::{CircuitBuilder, ConstraintSystem, SetTableSpec},
     error::ZKVMError,
     expression::{Expression, Fixed, Instance, ToExpr, WitIn},
     instructions::riscv::constants::{
-        END_CYCLE_IDX, END_PC_IDX, EXIT_CODE_IDX, INIT_CYCLE_IDX, INIT_PC_IDX, PUBLIC_IO_IDX,
-        UINT_LIMBS,
+        END_CYCLE_IDX, END_PC_IDX, EXIT_CODE_IDX, INIT_CYCLE_IDX, INIT_PC_IDX,
+        PUBLIC_IO_DIGEST_IDX, PUBLIC_IO_IDX, UINT_LIMBS,
     },
     structs::ROMType,
     tables::InsnRecord,
@@ -61,6 +62,21 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
         self.cs.query_instance(|| "public_io", PUBLIC_IO_IDX)
     }
 
+    /// The elements of the digest of the public IO, each as its low and high words.
+    pub fn query_public_io_digest(&mut self) -> Result<Vec<[Instance; 2]>, ZKVMError> {
+        (0..DIGEST_WIDTH)
+            .map(|i| {
+                let idx = PUBLIC_IO_DIGEST_IDX + 2 * i;
+                Ok([
+                    self.cs
+                        .query_instance(|| format!("public_io_digest_{i}_low"), idx)?,
+                    self.cs
+                        .query_instance(|| format!("public_io_digest_{i}_high"), idx + 1)?,
+                ])
+            })
+            .collect()
+    }
+
     pub fn lk_record<NR, N>(
         &mut self,
         name_fn: N,
@@ -4,6 +4,7 @@ use std::{collections::HashMap, marker::PhantomData};
 
 use ff_ext::ExtensionField;
 use mpcs::PolynomialCommitmentScheme;
+use multilinear_extensions::mle::DenseMultilinearExtension;
 
 use crate::{
     ROMType,
@@ -14,6 +15,10 @@ use crate::{
     witness::RowMajorMatrix,
 };
 
+pub mod analysis;
+pub mod ir;
+mod optimize;
+
 /// namespace used for annotation, preserve meta info during circuit construction
 #[derive(Clone, Debug)]
 pub struct NameSpace {
@@ -200,18 +205,25 @@ impl<E: ExtensionField> ConstraintSystem<E> {
         let fixed_commit_wd = fixed_traces
             .as_ref()
             .map(|traces| PCS::batch_commit(pp, traces).unwrap());
+        self.key_gen_with_commitment(fixed_traces, fixed_commit_wd)
+    }
+
+    /// Generate the key with the commitment to the fixed traces, computed beforehand.
+    pub(crate) fn key_gen_with_commitment<PCS: PolynomialCommitmentScheme<E>>(
+        mut self,
+        fixed_traces: Option<Vec<DenseMultilinearExtension<E>>>,
+        fixed_commit_wd: Option<PCS::CommitmentWithData>,
+    ) -> ProvingKey<E, PCS> {
+        self.optimize();
+
         let fixed_commit = fixed_commit_wd
             .as_ref()
             .map(|commit_wd| PCS::get_pure_commitment(commit_wd));
 
-        ProvingKey {
-            fixed_traces,
-            fixed_commit_wd,
-            vk: VerifyingKey {
-                cs: self,
-                fixed_commit,
-            },
-        }
+        ProvingKey::new(fixed_traces, fixed_commit_wd, VerifyingKey {
+            cs: self,
+            fixed_commit,
+        })
     }
 
     pub fn create_witin<NR: Into<String>, N: FnOnce() -> NR>(&mut self, n: N) -> WitIn {
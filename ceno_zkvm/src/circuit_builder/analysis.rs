@@ -0,0 +1,306 @@
+//! Statistics and lints of a [`ConstraintSystem`], checked at key generation.
+
+use ff_ext::ExtensionField;
+use itertools::chain;
+use serde::Serialize;
+
+use crate::{
+    circuit_builder::ConstraintSystem,
+    expression::{Expression, Fixed},
+    scheme::constants::{MAX_MAIN_SUMCHECK_DEGREE, SEL_DEGREE},
+};
+
+/// The shape of a circuit, its unused witness columns, and the violations of the assumptions of
+/// the prover.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CircuitStats {
+    pub num_witin: usize,
+    pub num_fixed: usize,
+    pub num_reads: usize,
+    pub num_writes: usize,
+    pub num_lookups: usize,
+    /// The name and degree of each `assert_zero` constraint.
+    pub constraint_degrees: Vec<(String, usize)>,
+    pub max_degree: usize,
+    /// Witness columns which are not referenced by any expression, hence unconstrained.
+    pub unused_witin: Vec<String>,
+    /// Violations which make the circuit unprovable.
+    pub errors: Vec<String>,
+}
+
+impl CircuitStats {
+    /// Whether the circuit is provable and has no unconstrained witnesses.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty() && self.unused_witin.is_empty()
+    }
+}
+
+/// The columns referenced by the expressions of a circuit.
+struct Columns {
+    witin: Vec<bool>,
+    fixed: Vec<bool>,
+    errors: Vec<String>,
+}
+
+impl Columns {
+    fn visit<E: ExtensionField>(&mut self, name: &str, expr: &Expression<E>) {
+        match expr {
+            Expression::WitIn(id) => match self.witin.get_mut(*id as usize) {
+                Some(used) => *used = true,
+                None => self.errors.push(format!("{name}: undeclared witness {id}")),
+            },
+            Expression::Fixed(Fixed(id)) => match self.fixed.get_mut(*id) {
+                Some(used) => *used = true,
+                None => self.errors.push(format!("{name}: undeclared fixed {id}")),
+            },
+            Expression::Instance(_) | Expression::Constant(_) | Expression::Challenge(..) => {}
+            Expression::Sum(a, b) | Expression::Product(a, b) => {
+                self.visit(name, a);
+                self.visit(name, b);
+            }
+            Expression::ScaledSum(x, a, b) => {
+                self.visit(name, x);
+                self.visit(name, a);
+                self.visit(name, b);
+            }
+        }
+    }
+}
+
+impl<E: ExtensionField> ConstraintSystem<E> {
+    pub fn stats(&self) -> CircuitStats {
+        let mut errors = vec![];
+        let lists = [
+            (
+                "read",
+                self.r_expressions.len(),
+                self.r_expressions_namespace_map.len(),
+            ),
+            (
+                "write",
+                self.w_expressions.len(),
+                self.w_expressions_namespace_map.len(),
+            ),
+            (
+                "lookup",
+                self.lk_expressions.len(),
+                self.lk_expressions_namespace_map.len(),
+            ),
+            (
+                "read table",
+                self.r_table_expressions.len(),
+                self.r_table_expressions_namespace_map.len(),
+            ),
+            (
+                "write table",
+                self.w_table_expressions.len(),
+                self.w_table_expressions_namespace_map.len(),
+            ),
+            (
+                "lookup table",
+                self.lk_table_expressions.len(),
+                self.lk_table_expressions_namespace_map.len(),
+            ),
+            (
+                "assert_zero",
+                self.assert_zero_expressions.len(),
+                self.assert_zero_expressions_namespace_map.len(),
+            ),
+            (
+                "assert_zero_sumcheck",
+                self.assert_zero_sumcheck_expressions.len(),
+                self.assert_zero_sumcheck_expressions_namespace_map.len(),
+            ),
+            (
+                "witness",
+                self.num_witin as usize,
+                self.witin_namespace_map.len(),
+            ),
+            ("fixed", self.num_fixed, self.fixed_namespace_map.len()),
+        ];
+        for (list, len, names) in lists {
+            if len != names {
+                errors.push(format!("{len} {list} expressions with {names} names"));
+            }
+        }
+
+        // The records are linear in the columns, see `create_opcode_proof` and
+        // `create_table_proof`.
+        let records = chain!(
+            self.r_expressions
+                .iter()
+                .zip(&self.r_expressions_namespace_map),
+            self.w_expressions
+                .iter()
+                .zip(&self.w_expressions_namespace_map),
+            self.lk_expressions
+                .iter()
+                .zip(&self.lk_expressions_namespace_map),
+            self.r_table_expressions
+                .iter()
+                .map(|table| &table.expr)
+                .zip(&self.r_table_expressions_namespace_map),
+            self.w_table_expressions
+                .iter()
+                .map(|table| &table.expr)
+                .zip(&self.w_table_expressions_namespace_map),
+            self.lk_table_expressions
+                .iter()
+                .flat_map(|table| [&table.multiplicity, &table.values])
+                .zip(
+                    self.lk_table_expressions_namespace_map
+                        .iter()
+                        .flat_map(|name| [name, name])
+                ),
+        )
+        .collect::<Vec<_>>();
+        for (expr, name) in &records {
+            if expr.degree() != 1 {
+                errors.push(format!("record {name} has degree {}", expr.degree()));
+            }
+        }
+
+        // Linear constraints are proven by the tower sumcheck, and the others by the main
+        // sumcheck in monomial form, where each monomial is multiplied by a selector of degree 1
+        // like the records, which have degree `SEL_DEGREE` with their selector.
+        let max_constraint_degree = MAX_MAIN_SUMCHECK_DEGREE + 1 - SEL_DEGREE;
+        let mut constraint_degrees = vec![];
+        for (expr, name) in self
+            .assert_zero_expressions
+            .iter()
+            .zip(&self.assert_zero_expressions_namespace_map)
+        {
+            if expr.degree() != 1 {
+                errors.push(format!(
+                    "linear constraint {name} has degree {}",
+                    expr.degree()
+                ));
+            }
+            constraint_degrees.push((name.clone(), expr.degree()));
+        }
+        for (expr, name) in self
+            .assert_zero_sumcheck_expressions
+            .iter()
+            .zip(&self.assert_zero_sumcheck_expressions_namespace_map)
+        {
+            if expr.degree() > max_constraint_degree {
+                errors.push(format!(
+                    "constraint {name} has degree {}, above the maximal degree {}",
+                    expr.degree(),
+                    max_constraint_degree
+                ));
+            }
+            if !expr.is_monomial_form() {
+                errors.push(format!("constraint {name} is not in monomial form"));
+            }
+            constraint_degrees.push((name.clone(), expr.degree()));
+        }
+
+        let mut columns = Columns {
+            witin: vec![false; self.num_witin as usize],
+            fixed: vec![false; self.num_fixed],
+            errors: vec![],
+        };
+        let constraints = chain!(
+            self.assert_zero_expressions
+                .iter()
+                .zip(&self.assert_zero_expressions_namespace_map),
+            self.assert_zero_sumcheck_expressions
+                .iter()
+                .zip(&self.assert_zero_sumcheck_expressions_namespace_map),
+        );
+        for (expr, name) in records.into_iter().chain(constraints) {
+            columns.visit(name, expr);
+        }
+        for table in chain!(&self.r_table_expressions, &self.w_table_expressions) {
+            if let Some(used) =
+                (table.table_spec.addr_witin_id).and_then(|id| columns.witin.get_mut(id))
+            {
+                *used = true;
+            }
+        }
+        errors.extend(columns.errors);
+
+        CircuitStats {
+            num_witin: self.num_witin as usize,
+            num_fixed: self.num_fixed,
+            num_reads: self.r_expressions.len() + self.r_table_expressions.len(),
+            num_writes: self.w_expressions.len() + self.w_table_expressions.len(),
+            num_lookups: self.lk_expressions.len() + self.lk_table_expressions.len(),
+            max_degree: constraint_degrees
+                .iter()
+                .map(|(_, d)| *d)
+                .max()
+                .unwrap_or(0),
+            constraint_degrees,
+            unused_witin: (columns.witin.iter().zip(&self.witin_namespace_map))
+                .filter(|(used, _)| !**used)
+                .map(|(_, name)| name.clone())
+                .collect(),
+            errors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::GoldilocksExt2;
+
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        expression::{Expression, ToExpr},
+        instructions::riscv::Rv32imConfig,
+        structs::ZKVMConstraintSystem,
+    };
+
+    type E = GoldilocksExt2;
+
+    #[test]
+    fn test_rv32im_stats() {
+        let mut zkvm_cs = ZKVMConstraintSystem::<E>::default();
+        Rv32imConfig::construct_circuits(&mut zkvm_cs);
+        for (name, stats) in zkvm_cs.stats() {
+            assert!(stats.is_ok(), "{name}: {stats:?}");
+            assert!(stats.max_degree <= 2, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_lints() {
+        let mut cs = ConstraintSystem::<E>::new(|| "test");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let a = cb.create_witin(|| "a");
+        let b = cb.create_witin(|| "b");
+        let _unused = cb.create_witin(|| "unused");
+        cb.require_zero(|| "a*b", a.expr() * b.expr()).unwrap();
+
+        let stats = cs.stats();
+        assert!(!stats.is_ok());
+        assert!(stats.errors.is_empty());
+        assert_eq!(stats.num_witin, 3);
+        assert_eq!(stats.constraint_degrees, vec![(
+            "test/require_zero/a*b".to_string(),
+            2
+        )]);
+        assert_eq!(stats.unused_witin, vec!["test/unused".to_string()]);
+
+        // A record of degree 2, and a reference to an undeclared witness.
+        cs.r_expressions.push(a.expr() * b.expr());
+        cs.r_expressions_namespace_map
+            .push("test/record".to_string());
+        cs.assert_zero_expressions.push(Expression::WitIn(7));
+        cs.assert_zero_expressions_namespace_map
+            .push("test/undeclared".to_string());
+        // A constraint of degree 3, beyond the main sumcheck.
+        let mut cb = CircuitBuilder::new(&mut cs);
+        cb.require_zero(|| "a*b*b", a.expr() * b.expr() * b.expr())
+            .unwrap();
+        let stats = cs.stats();
+        assert_eq!(stats.errors, vec![
+            "record test/record has degree 2".to_string(),
+            "constraint test/require_zero/a*b*b has degree 3, above the maximal degree 2"
+                .to_string(),
+            "test/undeclared: undeclared witness 7".to_string(),
+        ]);
+    }
+}
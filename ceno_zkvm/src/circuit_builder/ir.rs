@@ -0,0 +1,483 @@
+//! A portable description of a [`ConstraintSystem`], to diff circuits, feed them to external tools
+//! or rebuild a verifier elsewhere.
+//!
+//! The description serializes to JSON as an object with the fields of [`CircuitIr`]:
+//!
+//! - `version`: [`CIRCUIT_IR_VERSION`], bumped on incompatible changes of the format.
+//! - `name`: the root namespace of the circuit.
+//! - `witnesses`, `fixed`: the names of the witness and fixed columns, by id.
+//! - `instances`: the names of the public values used by the circuit, by index.
+//! - `reads`, `writes`, `lookups`: the records of the circuit, see [`NamedExpr`].
+//! - `read_tables`, `write_tables`: the read and write set tables, see [`SetTableIr`].
+//! - `lookup_tables`: the lookup tables, see [`LookupTableIr`].
+//! - `assert_zero`, `assert_zero_sumcheck`: the main constraints, of degree 1 and more.
+//! - `max_non_lc_degree`: the maximum degree of `assert_zero_sumcheck`.
+//! - `chip_record_alpha`, `chip_record_beta`: the challenges combining records.
+//!
+//! Expressions are trees of objects tagged by `kind`, see [`ExprIr`]. Base field elements are
+//! canonical `u64`, and extension field elements are arrays of their base field coordinates.
+
+use std::collections::BTreeMap;
+
+use ceno_emul::Addr;
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    circuit_builder::{
+        ConstraintSystem, LogupTableExpression, NameSpace, SetTableAddrType, SetTableExpression,
+        SetTableSpec,
+    },
+    error::ZKVMError,
+    expression::{Expression, Fixed, Instance},
+    structs::{ChallengeId, WitnessId},
+};
+
+pub const CIRCUIT_IR_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitIr {
+    pub version: u32,
+    pub name: String,
+    pub witnesses: Vec<String>,
+    pub fixed: Vec<String>,
+    pub instances: BTreeMap<usize, String>,
+    pub reads: Vec<NamedExpr>,
+    pub writes: Vec<NamedExpr>,
+    pub lookups: Vec<NamedExpr>,
+    pub read_tables: Vec<SetTableIr>,
+    pub write_tables: Vec<SetTableIr>,
+    pub lookup_tables: Vec<LookupTableIr>,
+    pub assert_zero: Vec<NamedExpr>,
+    pub assert_zero_sumcheck: Vec<NamedExpr>,
+    pub max_non_lc_degree: usize,
+    pub chip_record_alpha: ExprIr,
+    pub chip_record_beta: ExprIr,
+}
+
+/// An expression with the namespace path under which it was added.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamedExpr {
+    pub name: String,
+    pub expr: ExprIr,
+}
+
+/// A record of a read or write set table of `len` rows, at fixed addresses from `offset` if
+/// `addr_witin_id` is `None`, and at the addresses in that witness column otherwise.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetTableIr {
+    pub name: String,
+    pub expr: ExprIr,
+    pub addr_witin_id: Option<usize>,
+    pub offset: Addr,
+    pub len: usize,
+}
+
+/// A lookup table of `table_len` rows, each providing `values` with `multiplicity`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LookupTableIr {
+    pub name: String,
+    pub multiplicity: ExprIr,
+    pub values: ExprIr,
+    pub table_len: usize,
+}
+
+/// The JSON form of an [`Expression`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExprIr {
+    Witness {
+        id: WitnessId,
+    },
+    Fixed {
+        id: usize,
+    },
+    Instance {
+        id: usize,
+    },
+    Constant {
+        value: u64,
+    },
+    Sum {
+        a: Box<ExprIr>,
+        b: Box<ExprIr>,
+    },
+    Product {
+        a: Box<ExprIr>,
+        b: Box<ExprIr>,
+    },
+    /// `x * a + b`.
+    ScaledSum {
+        x: Box<ExprIr>,
+        a: Box<ExprIr>,
+        b: Box<ExprIr>,
+    },
+    /// `challenge^power * scalar + offset`.
+    Challenge {
+        id: ChallengeId,
+        power: usize,
+        scalar: Vec<u64>,
+        offset: Vec<u64>,
+    },
+}
+
+impl<E: ExtensionField> From<&Expression<E>> for ExprIr {
+    fn from(expr: &Expression<E>) -> Self {
+        let boxed = |expr: &Expression<E>| Box::new(ExprIr::from(expr));
+        match expr {
+            Expression::WitIn(id) => ExprIr::Witness { id: *id },
+            Expression::Fixed(Fixed(id)) => ExprIr::Fixed { id: *id },
+            Expression::Instance(Instance(id)) => ExprIr::Instance { id: *id },
+            Expression::Constant(value) => ExprIr::Constant {
+                value: value.to_canonical_u64(),
+            },
+            Expression::Sum(a, b) => ExprIr::Sum {
+                a: boxed(a),
+                b: boxed(b),
+            },
+            Expression::Product(a, b) => ExprIr::Product {
+                a: boxed(a),
+                b: boxed(b),
+            },
+            Expression::ScaledSum(x, a, b) => ExprIr::ScaledSum {
+                x: boxed(x),
+                a: boxed(a),
+                b: boxed(b),
+            },
+            Expression::Challenge(id, power, scalar, offset) => ExprIr::Challenge {
+                id: *id,
+                power: *power,
+                scalar: scalar.to_canonical_u64_vec(),
+                offset: offset.to_canonical_u64_vec(),
+            },
+        }
+    }
+}
+
+impl ExprIr {
+    /// The expression, checking that its columns exist and its field elements are canonical.
+    pub fn to_expression<E: ExtensionField>(
+        &self,
+        num_witin: usize,
+        num_fixed: usize,
+    ) -> Result<Expression<E>, ZKVMError> {
+        let boxed = |expr: &ExprIr| expr.to_expression(num_witin, num_fixed).map(Box::new);
+        Ok(match self {
+            ExprIr::Witness { id } => {
+                check(usize::from(*id) < num_witin, || format!("witness {id}"))?;
+                Expression::WitIn(*id)
+            }
+            ExprIr::Fixed { id } => {
+                check(*id < num_fixed, || format!("fixed {id}"))?;
+                Expression::Fixed(Fixed(*id))
+            }
+            ExprIr::Instance { id } => Expression::Instance(Instance(*id)),
+            ExprIr::Constant { value } => Expression::Constant(base_field(*value)?),
+            ExprIr::Sum { a, b } => Expression::Sum(boxed(a)?, boxed(b)?),
+            ExprIr::Product { a, b } => Expression::Product(boxed(a)?, boxed(b)?),
+            ExprIr::ScaledSum { x, a, b } => Expression::ScaledSum(boxed(x)?, boxed(a)?, boxed(b)?),
+            ExprIr::Challenge {
+                id,
+                power,
+                scalar,
+                offset,
+            } => Expression::Challenge(
+                *id,
+                *power,
+                extension_field(scalar)?,
+                extension_field(offset)?,
+            ),
+        })
+    }
+}
+
+fn check(condition: bool, what: impl FnOnce() -> String) -> Result<(), ZKVMError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(ZKVMError::InvalidCircuitIR(what()))
+    }
+}
+
+fn base_field<F: SmallField>(value: u64) -> Result<F, ZKVMError> {
+    check(value < F::MODULUS_U64, || {
+        format!("non-canonical field element {value}")
+    })?;
+    Ok(F::from(value))
+}
+
+fn extension_field<E: ExtensionField>(values: &[u64]) -> Result<E, ZKVMError> {
+    check(values.len() == E::DEGREE, || {
+        format!("extension field element of degree {}", values.len())
+    })?;
+    let bases = values
+        .iter()
+        .map(|value| base_field(*value))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(E::from_bases(&bases))
+}
+
+fn named_exprs<E: ExtensionField>(exprs: &[Expression<E>], names: &[String]) -> Vec<NamedExpr> {
+    exprs
+        .iter()
+        .zip(names)
+        .map(|(expr, name)| NamedExpr {
+            name: name.clone(),
+            expr: expr.into(),
+        })
+        .collect()
+}
+
+fn set_tables<E: ExtensionField>(
+    tables: &[SetTableExpression<E>],
+    names: &[String],
+) -> Vec<SetTableIr> {
+    tables
+        .iter()
+        .zip(names)
+        .map(|(table, name)| SetTableIr {
+            name: name.clone(),
+            expr: (&table.expr).into(),
+            addr_witin_id: table.table_spec.addr_witin_id,
+            offset: table.table_spec.offset,
+            len: table.table_spec.len,
+        })
+        .collect()
+}
+
+impl<E: ExtensionField> From<&ConstraintSystem<E>> for CircuitIr {
+    fn from(cs: &ConstraintSystem<E>) -> Self {
+        CircuitIr {
+            version: CIRCUIT_IR_VERSION,
+            name: cs.ns.get_namespaces()[0].clone(),
+            witnesses: cs.witin_namespace_map.clone(),
+            fixed: cs.fixed_namespace_map.clone(),
+            instances: cs
+                .instance_name_map
+                .iter()
+                .map(|(Instance(id), name)| (*id, name.clone()))
+                .collect(),
+            reads: named_exprs(&cs.r_expressions, &cs.r_expressions_namespace_map),
+            writes: named_exprs(&cs.w_expressions, &cs.w_expressions_namespace_map),
+            lookups: named_exprs(&cs.lk_expressions, &cs.lk_expressions_namespace_map),
+            read_tables: set_tables(
+                &cs.r_table_expressions,
+                &cs.r_table_expressions_namespace_map,
+            ),
+            write_tables: set_tables(
+                &cs.w_table_expressions,
+                &cs.w_table_expressions_namespace_map,
+            ),
+            lookup_tables: cs
+                .lk_table_expressions
+                .iter()
+                .zip(&cs.lk_table_expressions_namespace_map)
+                .map(|(table, name)| LookupTableIr {
+                    name: name.clone(),
+                    multiplicity: (&table.multiplicity).into(),
+                    values: (&table.values).into(),
+                    table_len: table.table_len,
+                })
+                .collect(),
+            assert_zero: named_exprs(
+                &cs.assert_zero_expressions,
+                &cs.assert_zero_expressions_namespace_map,
+            ),
+            assert_zero_sumcheck: named_exprs(
+                &cs.assert_zero_sumcheck_expressions,
+                &cs.assert_zero_sumcheck_expressions_namespace_map,
+            ),
+            max_non_lc_degree: cs.max_non_lc_degree,
+            chip_record_alpha: (&cs.chip_record_alpha).into(),
+            chip_record_beta: (&cs.chip_record_beta).into(),
+        }
+    }
+}
+
+impl CircuitIr {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a circuit is always serializable")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ZKVMError> {
+        serde_json::from_str(json).map_err(|e| ZKVMError::InvalidCircuitIR(e.to_string()))
+    }
+
+    /// Rebuild the constraint system, checking the version and that the expressions only use
+    /// the declared columns.
+    pub fn into_constraint_system<E: ExtensionField>(
+        self,
+    ) -> Result<ConstraintSystem<E>, ZKVMError> {
+        check(self.version == CIRCUIT_IR_VERSION, || {
+            format!("unsupported version {}", self.version)
+        })?;
+        let num_witin = self.witnesses.len();
+        let num_fixed = self.fixed.len();
+        check(num_witin <= usize::from(WitnessId::MAX), || {
+            format!("{num_witin} witnesses")
+        })?;
+        let expr = |expr: &ExprIr| expr.to_expression::<E>(num_witin, num_fixed);
+        let unzip = |named: Vec<NamedExpr>| -> Result<(Vec<_>, Vec<_>), ZKVMError> {
+            named
+                .into_iter()
+                .map(|NamedExpr { name, expr: e }| Ok((expr(&e)?, name)))
+                .collect::<Result<Vec<_>, _>>()
+                .map(|pairs| pairs.into_iter().unzip())
+        };
+        let set_tables = |tables: Vec<SetTableIr>| -> Result<(Vec<_>, Vec<_>), ZKVMError> {
+            tables
+                .into_iter()
+                .map(|table| {
+                    let addr_type = match table.addr_witin_id {
+                        Some(id) => {
+                            check(id < num_witin, || format!("address witness {id}"))?;
+                            SetTableAddrType::DynamicAddr
+                        }
+                        None => SetTableAddrType::FixedAddr,
+                    };
+                    let set_table = SetTableExpression {
+                        expr: expr(&table.expr)?,
+                        table_spec: SetTableSpec {
+                            addr_type,
+                            addr_witin_id: table.addr_witin_id,
+                            offset: table.offset,
+                            len: table.len,
+                        },
+                    };
+                    Ok((set_table, table.name))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(|pairs| pairs.into_iter().unzip())
+        };
+
+        let mut cs = ConstraintSystem::new(|| self.name.clone());
+        cs.ns = NameSpace::new(|| self.name);
+        cs.num_witin = num_witin as WitnessId;
+        cs.witin_namespace_map = self.witnesses;
+        cs.num_fixed = num_fixed;
+        cs.fixed_namespace_map = self.fixed;
+        cs.instance_name_map = (self.instances.into_iter())
+            .map(|(id, name)| (Instance(id), name))
+            .collect();
+        (cs.r_expressions, cs.r_expressions_namespace_map) = unzip(self.reads)?;
+        (cs.w_expressions, cs.w_expressions_namespace_map) = unzip(self.writes)?;
+        (cs.lk_expressions, cs.lk_expressions_namespace_map) = unzip(self.lookups)?;
+        (cs.r_table_expressions, cs.r_table_expressions_namespace_map) =
+            set_tables(self.read_tables)?;
+        (cs.w_table_expressions, cs.w_table_expressions_namespace_map) =
+            set_tables(self.write_tables)?;
+        (
+            cs.lk_table_expressions,
+            cs.lk_table_expressions_namespace_map,
+        ) = self
+            .lookup_tables
+            .into_iter()
+            .map(|table| {
+                let lookup_table = LogupTableExpression {
+                    multiplicity: expr(&table.multiplicity)?,
+                    values: expr(&table.values)?,
+                    table_len: table.table_len,
+                };
+                Ok((lookup_table, table.name))
+            })
+            .collect::<Result<Vec<_>, ZKVMError>>()?
+            .into_iter()
+            .unzip();
+        (
+            cs.assert_zero_expressions,
+            cs.assert_zero_expressions_namespace_map,
+        ) = unzip(self.assert_zero)?;
+        (
+            cs.assert_zero_sumcheck_expressions,
+            cs.assert_zero_sumcheck_expressions_namespace_map,
+        ) = unzip(self.assert_zero_sumcheck)?;
+        cs.max_non_lc_degree = self.max_non_lc_degree;
+        cs.chip_record_alpha = expr(&self.chip_record_alpha)?;
+        cs.chip_record_beta = expr(&self.chip_record_beta)?;
+        Ok(cs)
+    }
+}
+
+impl<E: ExtensionField> ConstraintSystem<E> {
+    pub fn to_ir(&self) -> CircuitIr {
+        self.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ceno_emul::CENO_PLATFORM;
+    use goldilocks::GoldilocksExt2;
+
+    use super::*;
+    use crate::{
+        circuit_builder::CircuitBuilder,
+        instructions::{Instruction, riscv::arith::AddInstruction},
+        tables::{MemCircuit, ProgramTableCircuit, TableCircuit},
+    };
+
+    type E = GoldilocksExt2;
+
+    fn assert_round_trip(cs: &ConstraintSystem<E>) {
+        let ir = cs.to_ir();
+        let imported = CircuitIr::from_json(&ir.to_json())
+            .unwrap()
+            .into_constraint_system::<E>()
+            .unwrap();
+        assert_eq!(imported.to_ir(), ir);
+        assert_eq!(imported.num_witin, cs.num_witin);
+        assert_eq!(imported.r_expressions, cs.r_expressions);
+        assert_eq!(imported.lk_expressions, cs.lk_expressions);
+        assert_eq!(imported.assert_zero_expressions, cs.assert_zero_expressions);
+    }
+
+    #[test]
+    fn test_opcode_circuit_ir() {
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        AddInstruction::<E>::construct_circuit(&mut cb).unwrap();
+        assert_round_trip(&cs);
+    }
+
+    #[test]
+    fn test_table_circuit_ir() {
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        ProgramTableCircuit::<E, 4>::construct_circuit(&mut cb, &CENO_PLATFORM).unwrap();
+        let ir = cs.to_ir();
+        assert!(!ir.fixed.is_empty());
+        assert_eq!(ir.lookup_tables.len(), 1);
+        assert_round_trip(&cs);
+
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        MemCircuit::<E>::construct_circuit(&mut cb, &CENO_PLATFORM).unwrap();
+        let ir = cs.to_ir();
+        assert!(ir.read_tables[0].addr_witin_id.is_some());
+        assert_round_trip(&cs);
+    }
+
+    #[test]
+    fn test_invalid_ir() {
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        AddInstruction::<E>::construct_circuit(&mut cb).unwrap();
+        let ir = cs.to_ir();
+
+        let mut bad = ir.clone();
+        bad.witnesses.pop();
+        assert!(bad.into_constraint_system::<E>().is_err());
+
+        let mut bad = ir.clone();
+        bad.version += 1;
+        assert!(bad.into_constraint_system::<E>().is_err());
+
+        let mut bad = ir;
+        bad.assert_zero.push(NamedExpr {
+            name: "bad".into(),
+            expr: ExprIr::Constant { value: u64::MAX },
+        });
+        assert!(bad.into_constraint_system::<E>().is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use ff_ext::ExtensionField;
+
+use crate::{circuit_builder::ConstraintSystem, expression::Expression};
+
+impl<E: ExtensionField> ConstraintSystem<E> {
+    /// Simplify the expressions of the circuit and combine the like monomials of its sumcheck
+    /// constraints. An expression is kept as is if simplifying it would change its degree, which
+    /// decides how it is proven.
+    pub fn optimize(&mut self) {
+        let simplify = |expr: &mut Expression<E>| {
+            let simplified = expr.simplify();
+            if simplified.degree() == expr.degree() {
+                *expr = simplified;
+            }
+        };
+        self.r_expressions.iter_mut().for_each(simplify);
+        self.w_expressions.iter_mut().for_each(simplify);
+        self.lk_expressions.iter_mut().for_each(simplify);
+        self.assert_zero_expressions.iter_mut().for_each(simplify);
+        (self.r_table_expressions.iter_mut())
+            .chain(self.w_table_expressions.iter_mut())
+            .for_each(|table| simplify(&mut table.expr));
+        self.lk_table_expressions.iter_mut().for_each(|table| {
+            simplify(&mut table.multiplicity);
+            simplify(&mut table.values);
+        });
+
+        for expr in self.assert_zero_sumcheck_expressions.iter_mut() {
+            let simplified = expr.to_monomial_form().simplify();
+            if simplified.degree() == expr.degree() && simplified.is_monomial_form() {
+                *expr = simplified;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::GoldilocksExt2;
+
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        expression::{Expression, ToExpr},
+        scheme::utils::eval_by_expr,
+    };
+
+    type E = GoldilocksExt2;
+
+    #[test]
+    fn test_optimize() {
+        let mut cs = ConstraintSystem::<E>::new(|| "test");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let x = cb.create_witin(|| "x").expr();
+        let y = cb.create_witin(|| "y").expr();
+        let z = cb.create_witin(|| "z").expr();
+        // x * y + y * x + 0 * z - 2 * z * z
+        let sumcheck = x.clone() * y.clone()
+            + y.clone() * x.clone()
+            + Expression::Product(Box::new(Expression::ZERO), Box::new(z.clone()))
+            - z.clone() * z.clone() * Expression::from(2u64);
+        cb.require_zero(|| "sumcheck", sumcheck).unwrap();
+        // x - x * 0, whose simplification would have degree 1
+        cb.require_zero(
+            || "linear",
+            x.clone() - Expression::Product(Box::new(x.clone()), Box::new(Expression::ZERO)),
+        )
+        .unwrap();
+        // 0 * z is kept, as simplifying it would lower its degree
+        cs.lk_expressions
+            .push(Expression::Product(Box::new(Expression::ZERO), Box::new(z)));
+        cs.lk_expressions_namespace_map
+            .push("test/lookup".to_string());
+
+        let exprs = |cs: &ConstraintSystem<E>| {
+            (cs.assert_zero_expressions.iter())
+                .chain(&cs.assert_zero_sumcheck_expressions)
+                .chain(&cs.lk_expressions)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let before = exprs(&cs);
+        cs.optimize();
+        let after = exprs(&cs);
+
+        assert_eq!(cs.assert_zero_expressions[0], x);
+        assert!(cs.assert_zero_sumcheck_expressions[0].is_monomial_form());
+        assert_eq!(cs.lk_expressions, before[before.len() - 1..]);
+        let witnesses = [E::from(3), E::from(5), E::from(7)];
+        for (before, after) in before.iter().zip(&after) {
+            assert_eq!(before.degree(), after.degree());
+            assert_eq!(
+                eval_by_expr(&witnesses, &[], before),
+                eval_by_expr(&witnesses, &[], after)
+            );
+        }
+    }
+}
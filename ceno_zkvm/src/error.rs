@@ -15,6 +15,10 @@ pub enum ZKVMError {
     FixedTraceNotFound(String),
     VerifyError(String),
     PCSError(Error),
+    InvalidCircuitIR(String),
+    InvalidCircuit(String),
+    InvalidPublicValues(String),
+    WorkerError(String),
 }
 
 impl From<UtilError> for ZKVMError {
@@ -1,4 +1,8 @@
+mod compiled;
 mod monomial;
+mod simplify;
+
+pub use compiled::CompiledExpressions;
 
 use std::{
     cmp::max,
@@ -756,6 +760,8 @@ impl WitIn {
         value: E::BaseField,
     ) {
         instance[self.id as usize] = MaybeUninit::new(value);
+        #[cfg(feature = "checked_witness")]
+        crate::witness::mark_assigned(&instance[self.id as usize]);
     }
 }
 
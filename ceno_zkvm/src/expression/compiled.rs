@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+
+use ff_ext::ExtensionField;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use super::{Expression, Fixed, Instance};
+use crate::structs::{ChallengeId, WitnessId};
+
+/// A node of a compiled expression, whose operands are earlier nodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Node<F> {
+    WitIn(WitnessId),
+    Fixed(usize),
+    Instance(usize),
+    Constant(F),
+    /// An index in `CompiledExpressions::challenges`.
+    Challenge(usize),
+    Sum(usize, usize),
+    Product(usize, usize),
+    ScaledSum(usize, usize, usize),
+}
+
+/// A list of expressions compiled into a flat list of nodes, where the sub-expressions common to
+/// several expressions, or repeated within one, are evaluated once.
+#[derive(Clone, Debug)]
+pub struct CompiledExpressions<E: ExtensionField> {
+    nodes: Vec<Node<E::BaseField>>,
+    challenges: Vec<(ChallengeId, usize, E, E)>,
+    outputs: Vec<usize>,
+    /// The nodes grouped by depth, where the nodes of a level only read nodes of earlier levels.
+    levels: Vec<Vec<usize>>,
+    /// The last level which reads each node, after which its value is dropped.
+    last_level: Vec<usize>,
+}
+
+impl<E: ExtensionField> CompiledExpressions<E> {
+    pub fn new<'a>(exprs: impl IntoIterator<Item = &'a Expression<E>>) -> Self {
+        let mut compiled = CompiledExpressions {
+            nodes: vec![],
+            challenges: vec![],
+            outputs: vec![],
+            levels: vec![],
+            last_level: vec![],
+        };
+        let mut index = HashMap::new();
+        for expr in exprs {
+            let output = compiled.push(&mut index, expr);
+            compiled.outputs.push(output);
+        }
+        // operands are pushed before the nodes reading them, so one pass sets all the depths
+        let mut depths = Vec::with_capacity(compiled.nodes.len());
+        compiled.last_level = vec![0; compiled.nodes.len()];
+        for node in compiled.nodes.iter() {
+            let depth = node.operands().map(|j| depths[j] + 1).max().unwrap_or(0);
+            for j in node.operands() {
+                compiled.last_level[j] = compiled.last_level[j].max(depth);
+            }
+            depths.push(depth);
+        }
+        for &output in &compiled.outputs {
+            compiled.last_level[output] = usize::MAX;
+        }
+        let num_levels = depths.iter().max().map_or(0, |depth| depth + 1);
+        compiled.levels = vec![vec![]; num_levels];
+        for (i, depth) in depths.into_iter().enumerate() {
+            compiled.levels[depth].push(i);
+        }
+        compiled
+    }
+
+    /// The number of nodes to evaluate.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    fn push(
+        &mut self,
+        index: &mut HashMap<Node<E::BaseField>, usize>,
+        expr: &Expression<E>,
+    ) -> usize {
+        let node = match expr {
+            Expression::WitIn(id) => Node::WitIn(*id),
+            Expression::Fixed(Fixed(id)) => Node::Fixed(*id),
+            Expression::Instance(Instance(id)) => Node::Instance(*id),
+            Expression::Constant(c) => Node::Constant(*c),
+            Expression::Challenge(id, pow, scalar, offset) => {
+                let challenge = (*id, *pow, *scalar, *offset);
+                let i = match self.challenges.iter().position(|c| *c == challenge) {
+                    Some(i) => i,
+                    None => {
+                        self.challenges.push(challenge);
+                        self.challenges.len() - 1
+                    }
+                };
+                Node::Challenge(i)
+            }
+            // sums and products are commutative, so order their operands to share more nodes
+            Expression::Sum(a, b) => {
+                let (a, b) = (self.push(index, a), self.push(index, b));
+                Node::Sum(a.min(b), a.max(b))
+            }
+            Expression::Product(a, b) => {
+                let (a, b) = (self.push(index, a), self.push(index, b));
+                Node::Product(a.min(b), a.max(b))
+            }
+            Expression::ScaledSum(x, a, b) => Node::ScaledSum(
+                self.push(index, x),
+                self.push(index, a),
+                self.push(index, b),
+            ),
+        };
+        *index.entry(node).or_insert_with(|| {
+            self.nodes.push(node);
+            self.nodes.len() - 1
+        })
+    }
+
+    /// Evaluate the expressions, with the same callbacks as `Expression::evaluate_with_instance`.
+    /// The nodes of a level are independent, so each level is evaluated in parallel.
+    #[allow(clippy::too_many_arguments)]
+    pub fn evaluate_with_instance<T: Clone + Send + Sync>(
+        &self,
+        fixed_in: &(impl Fn(&Fixed) -> T + Sync),
+        wit_in: &(impl Fn(WitnessId) -> T + Sync),
+        instance: &(impl Fn(Instance) -> T + Sync),
+        constant: &(impl Fn(E::BaseField) -> T + Sync),
+        challenge: &(impl Fn(ChallengeId, usize, E, E) -> T + Sync),
+        sum: &(impl Fn(T, T) -> T + Sync),
+        product: &(impl Fn(T, T) -> T + Sync),
+        scaled: &(impl Fn(T, T, T) -> T + Sync),
+    ) -> Vec<T> {
+        let mut values: Vec<Option<T>> = vec![None; self.nodes.len()];
+        for (level, nodes) in self.levels.iter().enumerate() {
+            let level_values = nodes
+                .par_iter()
+                .map(|&i| {
+                    let value = |j: usize| {
+                        values[j]
+                            .clone()
+                            .expect("value dropped before its last use")
+                    };
+                    match self.nodes[i] {
+                        Node::WitIn(id) => wit_in(id),
+                        Node::Fixed(id) => fixed_in(&Fixed(id)),
+                        Node::Instance(id) => instance(Instance(id)),
+                        Node::Constant(c) => constant(c),
+                        Node::Challenge(c) => {
+                            let (id, pow, scalar, offset) = self.challenges[c];
+                            challenge(id, pow, scalar, offset)
+                        }
+                        Node::Sum(a, b) => sum(value(a), value(b)),
+                        Node::Product(a, b) => product(value(a), value(b)),
+                        Node::ScaledSum(x, a, b) => scaled(value(x), value(a), value(b)),
+                    }
+                })
+                .collect::<Vec<_>>();
+            for (&i, value) in nodes.iter().zip(level_values) {
+                values[i] = Some(value);
+            }
+            for &i in nodes {
+                for j in self.nodes[i].operands() {
+                    if self.last_level[j] == level {
+                        values[j] = None;
+                    }
+                }
+            }
+        }
+        self.outputs
+            .iter()
+            .map(|&output| values[output].clone().unwrap())
+            .collect()
+    }
+}
+
+impl<F> Node<F> {
+    /// The nodes read by this node.
+    fn operands(&self) -> impl Iterator<Item = usize> {
+        let operands = match *self {
+            Node::Sum(a, b) | Node::Product(a, b) => [Some(a), Some(b), None],
+            Node::ScaledSum(x, a, b) => [Some(x), Some(a), Some(b)],
+            _ => [None; 3],
+        };
+        operands.into_iter().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use goldilocks::GoldilocksExt2;
+
+    use super::*;
+    use crate::{
+        instructions::riscv::Rv32imConfig, scheme::utils::eval_by_expr_with_instance,
+        structs::ZKVMConstraintSystem,
+    };
+
+    type E = GoldilocksExt2;
+
+    fn eval_by_exprs<'a>(
+        witnesses: &[E],
+        challenges: &[E],
+        exprs: impl IntoIterator<Item = &'a Expression<E>>,
+    ) -> Vec<E> {
+        CompiledExpressions::new(exprs).evaluate_with_instance(
+            &|_| unreachable!(),
+            &|witness_id| witnesses[witness_id as usize],
+            &|_| unreachable!(),
+            &|scalar| scalar.into(),
+            &|challenge_id, pow, scalar, offset| {
+                challenges[challenge_id as usize].pow([pow as u64]) * scalar + offset
+            },
+            &|a, b| a + b,
+            &|a, b| a * b,
+            &|x, a, b| a * x + b,
+        )
+    }
+
+    #[test]
+    fn test_common_subexpressions() {
+        let x = || Expression::<E>::WitIn(0);
+        let y = || Expression::<E>::WitIn(1);
+        let exprs = [x() * y() + x(), (y() * x()) * (x() * y())];
+        let compiled = CompiledExpressions::new(&exprs);
+        // x, y, x * y, x * y + x, (x * y)^2
+        assert_eq!(compiled.len(), 5);
+
+        let witnesses = [E::from(3), E::from(5)];
+        let evals = eval_by_exprs(&witnesses, &[], &exprs);
+        assert_eq!(evals, vec![E::from(18), E::from(225)]);
+    }
+
+    #[test]
+    fn test_rv32im_records() {
+        let mut zkvm_cs = ZKVMConstraintSystem::<E>::default();
+        Rv32imConfig::construct_circuits(&mut zkvm_cs);
+        let challenges = [E::from(101), E::from(103)];
+        for (name, cs) in zkvm_cs.circuit_css.iter() {
+            if cs.num_fixed > 0 || !cs.instance_name_map.is_empty() {
+                continue;
+            }
+            let witnesses = (0..cs.num_witin as u64).map(E::from).collect::<Vec<_>>();
+            let records = cs
+                .r_expressions
+                .iter()
+                .chain(&cs.w_expressions)
+                .chain(&cs.lk_expressions)
+                .chain(cs.r_table_expressions.iter().map(|r| &r.expr))
+                .chain(cs.w_table_expressions.iter().map(|w| &w.expr))
+                .collect::<Vec<_>>();
+            let evals = eval_by_exprs(&witnesses, &challenges, records.clone());
+            for (record, eval) in records.iter().zip(evals) {
+                let expected =
+                    eval_by_expr_with_instance(&[], &witnesses, &[], &challenges, record);
+                assert_eq!(expected, eval, "{name}");
+            }
+
+            // The records share their challenges and many of their columns.
+            if records.len() < 2 {
+                continue;
+            }
+            let num_nodes: usize = records
+                .iter()
+                .map(|r| CompiledExpressions::new([*r]).len())
+                .sum();
+            assert!(
+                CompiledExpressions::new(records).len() < num_nodes,
+                "{name}"
+            );
+        }
+    }
+}
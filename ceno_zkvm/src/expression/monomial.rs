@@ -75,6 +75,8 @@ impl<E: ExtensionField> Expression<E> {
                 res.push(term);
             }
         }
+        // drop the monomials which cancel out
+        res.retain(|term| term.coeff != Expression::ZERO);
         res
     }
 
@@ -191,6 +193,10 @@ mod tests {
             let monomials = eval(&monomials);
             assert_eq!(monomials, factored);
         }
+
+        // Like monomials are combined, and dropped if they cancel out.
+        let cancelled = (x() * y() + a() * x()) - (y() * x() + x() * a());
+        assert_eq!(cancelled.to_monomial_form_inner(), Expression::ZERO);
     }
 
     /// Create an evaluator of expressions. Fixed, witness, and challenge values are pseudo-random.
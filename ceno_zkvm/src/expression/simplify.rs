@@ -0,0 +1,114 @@
+use ff::Field;
+use ff_ext::ExtensionField;
+
+use super::Expression;
+use Expression::*;
+
+impl<E: ExtensionField> Expression<E> {
+    /// Fold the constants and challenges of the expression, and drop the terms multiplied by
+    /// zero. The result may have a lower degree, and is in monomial form if the input is.
+    pub fn simplify(&self) -> Self {
+        match self {
+            Fixed(_) | WitIn(_) | Instance(_) | Constant(_) | Challenge(..) => self.clone(),
+
+            Sum(a, b) => match (a.simplify(), b.simplify()) {
+                (Constant(zero), x) | (x, Constant(zero)) if zero == E::BaseField::ZERO => x,
+                (Constant(c1), Constant(c2)) => Constant(c1 + c2),
+                (Constant(c), Challenge(id, pow, scalar, offset))
+                | (Challenge(id, pow, scalar, offset), Constant(c)) => {
+                    Challenge(id, pow, scalar, offset + c)
+                }
+                (Challenge(id1, pow1, s1, o1), Challenge(id2, pow2, s2, o2))
+                    if id1 == id2 && pow1 == pow2 =>
+                {
+                    Challenge(id1, pow1, s1 + s2, o1 + o2)
+                }
+                (a, b) => Sum(Box::new(a), Box::new(b)),
+            },
+
+            Product(a, b) => match (a.simplify(), b.simplify()) {
+                (Constant(zero), _) | (_, Constant(zero)) if zero == E::BaseField::ZERO => {
+                    Expression::ZERO
+                }
+                (Constant(one), x) | (x, Constant(one)) if one == E::BaseField::ONE => x,
+                (Constant(c1), Constant(c2)) => Constant(c1 * c2),
+                (Constant(c), Challenge(id, pow, scalar, offset))
+                | (Challenge(id, pow, scalar, offset), Constant(c)) => {
+                    Challenge(id, pow, scalar * c, offset * c)
+                }
+                (a, b) => Product(Box::new(a), Box::new(b)),
+            },
+
+            ScaledSum(x, a, b) => match (x.simplify(), a.simplify(), b.simplify()) {
+                (_, Constant(zero), b) if zero == E::BaseField::ZERO => b,
+                (Constant(x), Constant(a), Constant(b)) => Constant(x * a + b),
+                (x, a, b) => ScaledSum(Box::new(x), Box::new(a), Box::new(b)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        expression::ToExpr,
+        scheme::utils::eval_by_expr,
+    };
+    use goldilocks::GoldilocksExt2;
+
+    type E = GoldilocksExt2;
+
+    #[test]
+    fn test_simplify() {
+        let mut cs = ConstraintSystem::<E>::new(|| "test");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let x = cb.create_witin(|| "x").expr();
+        let y = cb.create_witin(|| "y").expr();
+        let zero = || Box::new(Expression::<E>::ZERO);
+        let one = || Box::new(Expression::<E>::ONE);
+        let c = |v: u64| Box::new(Constant(v.into()));
+
+        // x + 0 * y
+        let expr = Sum(
+            Box::new(x.clone()),
+            Box::new(Product(zero(), Box::new(y.clone()))),
+        );
+        assert_eq!(expr.simplify(), x);
+
+        // 1 * (x * (2 * 3 + 1))
+        let expr = Product(
+            one(),
+            Box::new(Product(
+                Box::new(x.clone()),
+                Box::new(Sum(Box::new(Product(c(2), c(3))), one())),
+            )),
+        );
+        assert_eq!(
+            expr.simplify(),
+            Product(Box::new(x.clone()), Box::new(Constant(7.into())))
+        );
+
+        // y * 0 + 5
+        let expr = ScaledSum(Box::new(y.clone()), zero(), c(5));
+        assert_eq!(expr.simplify(), Constant(5.into()));
+
+        // (alpha + 1) * 2, with the challenge `2 * alpha + 3`
+        let alpha = Challenge(0, 1, E::from(2), E::from(3));
+        let expr = Product(Box::new(Sum(Box::new(alpha), one())), c(2));
+        assert_eq!(expr.simplify(), Challenge(0, 1, E::from(4), E::from(8)));
+
+        // The simplification preserves the value.
+        let expr = Sum(
+            Box::new(Product(Box::new(x.clone()), Box::new(y.clone()))),
+            Box::new(ScaledSum(Box::new(x), c(3), Box::new(Sum(zero(), c(4))))),
+        );
+        let witnesses = [E::from(5), E::from(7)];
+        let challenges = [E::from(11), E::from(13)];
+        assert_eq!(
+            eval_by_expr(&witnesses, &challenges, &expr.simplify()),
+            eval_by_expr(&witnesses, &challenges, &expr),
+        );
+    }
+}
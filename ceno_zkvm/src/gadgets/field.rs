@@ -0,0 +1,377 @@
+use std::{
+    fmt::Display,
+    mem::MaybeUninit,
+    ops::{Add, Mul},
+};
+
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+use itertools::Itertools;
+use num_bigint::BigUint;
+
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    set_val,
+    utils::i64_to_base,
+    witness::LkMultiplicity,
+};
+
+use super::AssertLTConfig;
+
+const LIMB_BITS: usize = 16;
+const LIMB_MAX: u64 = (1 << LIMB_BITS) - 1;
+
+/// A term of a `FieldEquationConfig`: a constant times the product of at most two variables.
+#[derive(Clone, Debug)]
+pub struct FieldTerm {
+    coeff: BigUint,
+    vars: Vec<usize>,
+}
+
+impl FieldTerm {
+    /// The constant `c`.
+    pub fn constant(c: impl Into<BigUint>) -> Self {
+        FieldTerm {
+            coeff: c.into(),
+            vars: vec![],
+        }
+    }
+
+    /// The variable `i`.
+    pub fn var(i: usize) -> Self {
+        FieldTerm {
+            coeff: 1u32.into(),
+            vars: vec![i],
+        }
+    }
+
+    /// The product of the variables `i` and `j`.
+    pub fn product(i: usize, j: usize) -> Self {
+        FieldTerm {
+            coeff: 1u32.into(),
+            vars: vec![i, j],
+        }
+    }
+
+    /// This term times the constant `c`.
+    pub fn scaled(self, c: impl Into<BigUint>) -> Self {
+        FieldTerm {
+            coeff: self.coeff * c.into(),
+            ..self
+        }
+    }
+
+    fn value(&self, vars: &[BigUint]) -> BigUint {
+        self.vars
+            .iter()
+            .fold(self.coeff.clone(), |acc, &i| acc * &vars[i])
+    }
+
+    /// The term as a polynomial in `2^16`, given the limbs of the variables.
+    fn poly<T>(&self, vars: &[Vec<T>]) -> Vec<T>
+    where
+        T: Clone + From<u64> + Add<Output = T> + Mul<Output = T>,
+    {
+        // Skip the coefficient 1 of the variables.
+        let mut factors = self.vars.iter().map(|&i| vars[i].clone());
+        let first = if self.coeff == BigUint::from(1u32) && !self.vars.is_empty() {
+            factors.next().unwrap()
+        } else {
+            limbs(&self.coeff).into_iter().map(T::from).collect_vec()
+        };
+        factors.fold(first, |acc, factor| convolve(&acc, &factor))
+    }
+}
+
+/// The 16-bit limbs of `x`, without the leading zeros but at least one.
+fn limbs(x: &BigUint) -> Vec<u64> {
+    let mut limbs = (x.iter_u32_digits())
+        .flat_map(|digit| [digit as u64 & LIMB_MAX, digit as u64 >> LIMB_BITS])
+        .collect_vec();
+    while limbs.len() > 1 && limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    if limbs.is_empty() {
+        limbs.push(0);
+    }
+    limbs
+}
+
+/// The product of two polynomials.
+fn convolve<T>(a: &[T], b: &[T]) -> Vec<T>
+where
+    T: Clone + Add<Output = T> + Mul<Output = T>,
+{
+    let mut c: Vec<Option<T>> = vec![None; a.len() + b.len() - 1];
+    for (i, a) in a.iter().enumerate() {
+        for (j, b) in b.iter().enumerate() {
+            let product = a.clone() * b.clone();
+            c[i + j] = Some(match c[i + j].take() {
+                Some(sum) => sum + product,
+                None => product,
+            });
+        }
+    }
+    c.into_iter().map(Option::unwrap).collect()
+}
+
+/// The sum of polynomials, with `n` coefficients.
+fn sum_polys<T>(polys: impl IntoIterator<Item = Vec<T>>, n: usize) -> Vec<T>
+where
+    T: Clone + From<u64> + Add<Output = T>,
+{
+    let mut sum: Vec<Option<T>> = vec![None; n];
+    for poly in polys {
+        for (sum, coeff) in sum
+            .iter_mut()
+            .zip_eq(poly.into_iter().pad_using(n, |_| 0.into()))
+        {
+            *sum = Some(match sum.take() {
+                Some(sum) => sum + coeff,
+                None => coeff,
+            });
+        }
+    }
+    sum.into_iter()
+        .map(|coeff| coeff.unwrap_or_else(|| 0.into()))
+        .collect()
+}
+
+/// Constrain `sum(lhs) = sum(rhs) mod modulus`, where the variables are integers given by their
+/// 16-bit limbs, range-checked by the caller.
+///
+/// The gadget proves `sum(lhs) + offset - sum(rhs) = quotient * modulus` over the integers, with
+/// a constant `offset`, a multiple of the modulus large enough for the quotient not to be
+/// negative. The limbs of the quotient are range-checked. Both sides are polynomials in `2^16`
+/// whose difference is divisible by `X - 2^16`, checked limb by limb with signed carries: the
+/// carries are range-checked so that no limb constraint wraps around the field.
+pub struct FieldEquationConfig {
+    lhs: Vec<FieldTerm>,
+    rhs: Vec<FieldTerm>,
+    modulus: BigUint,
+    offset: BigUint,
+    quotient: Vec<WitIn>,
+    carries: Vec<WitIn>,
+    carries_range: Vec<AssertLTConfig>,
+    /// The carries plus this offset are in `[0, 2 * carry_offset)`.
+    carry_offset: u64,
+}
+
+impl FieldEquationConfig {
+    pub fn construct_circuit<
+        E: ExtensionField,
+        NR: Into<String> + Display + Clone,
+        N: FnOnce() -> NR,
+    >(
+        cb: &mut CircuitBuilder<E>,
+        name_fn: N,
+        vars: &[Vec<Expression<E>>],
+        modulus: &BigUint,
+        lhs: Vec<FieldTerm>,
+        rhs: Vec<FieldTerm>,
+    ) -> Result<Self, ZKVMError> {
+        assert!(
+            lhs.iter().chain(&rhs).all(|term| term.vars.len() <= 2),
+            "a term of degree more than 2"
+        );
+        let max_vars = vars
+            .iter()
+            .map(|var| (BigUint::from(1u32) << (LIMB_BITS * var.len())) - 1u32)
+            .collect_vec();
+        let max_limbs = vars
+            .iter()
+            .map(|var| vec![LIMB_MAX as u128; var.len()])
+            .collect_vec();
+        let max_sum = |terms: &[FieldTerm]| -> BigUint {
+            terms.iter().map(|term| term.value(&max_vars)).sum()
+        };
+
+        // The offset covers the right-hand side, and the quotient covers both sides.
+        let offset = (max_sum(&rhs) + modulus - 1u32) / modulus * modulus;
+        let max_quotient = (max_sum(&lhs) + &offset) / modulus;
+        let num_quotient_limbs = limbs(&max_quotient).len();
+
+        let modulus_limbs = limbs(modulus);
+        let num_columns = lhs
+            .iter()
+            .chain(&rhs)
+            .map(|term| term.poly(&max_limbs).len())
+            .chain([
+                limbs(&offset).len(),
+                num_quotient_limbs + modulus_limbs.len() - 1,
+            ])
+            .max()
+            .unwrap();
+
+        // Bound the carries by the columns of each sign.
+        let max_quotient_limbs = vec![LIMB_MAX as u128; num_quotient_limbs];
+        let modulus_u128 = modulus_limbs.iter().map(|&limb| limb as u128).collect_vec();
+        let offset_u128 = limbs(&offset).into_iter().map(u128::from).collect_vec();
+        let positive = sum_polys(
+            (lhs.iter().map(|term| term.poly(&max_limbs))).chain([offset_u128]),
+            num_columns,
+        );
+        let negative = sum_polys(
+            (rhs.iter().map(|term| term.poly(&max_limbs)))
+                .chain([convolve(&max_quotient_limbs, &modulus_u128)]),
+            num_columns,
+        );
+        let max_column = positive.into_iter().chain(negative).max().unwrap();
+        let max_carry = max_column / LIMB_MAX as u128 + 1;
+        let carry_offset = (max_carry + 1).next_power_of_two() as u64;
+        assert!(
+            max_column + ((carry_offset as u128) << (LIMB_BITS + 1)) < 1 << 62,
+            "the columns of the field equation may overflow"
+        );
+        let carry_range_limbs = (u64::BITS - (2 * carry_offset).leading_zeros()).div_ceil(16);
+
+        cb.namespace(name_fn, |cb| {
+            let quotient = (0..num_quotient_limbs)
+                .map(|i| {
+                    let limb = cb.create_witin(|| format!("quotient_{i}"));
+                    cb.assert_ux::<_, _, 16>(|| format!("quotient_{i}_in_u16"), limb.expr())?;
+                    Ok(limb)
+                })
+                .collect::<Result<Vec<_>, ZKVMError>>()?;
+            let carries = (0..num_columns - 1)
+                .map(|i| cb.create_witin(|| format!("carry_{i}")))
+                .collect_vec();
+            let carries_range = carries
+                .iter()
+                .enumerate()
+                .map(|(i, carry)| {
+                    AssertLTConfig::construct_circuit(
+                        cb,
+                        || format!("carry_{i}_in_range"),
+                        carry.expr() + Expression::from(carry_offset),
+                        (2 * carry_offset).into(),
+                        carry_range_limbs as usize,
+                    )
+                })
+                .collect::<Result<Vec<_>, ZKVMError>>()?;
+
+            let quotient_exprs = quotient.iter().map(|limb| limb.expr()).collect_vec();
+            let modulus_exprs = modulus_limbs
+                .iter()
+                .map(|&limb| Expression::from(limb))
+                .collect_vec();
+            let offset_exprs = limbs(&offset)
+                .into_iter()
+                .map(Expression::from)
+                .collect_vec();
+            let positive = sum_polys(
+                (lhs.iter().map(|term| term.poly(vars))).chain([offset_exprs]),
+                num_columns,
+            );
+            let negative = sum_polys(
+                (rhs.iter().map(|term| term.poly(vars)))
+                    .chain([convolve(&quotient_exprs, &modulus_exprs)]),
+                num_columns,
+            );
+            for (i, (positive, negative)) in positive.into_iter().zip_eq(negative).enumerate() {
+                let carry_in = if i > 0 {
+                    carries[i - 1].expr()
+                } else {
+                    Expression::ZERO
+                };
+                let carry_out = carries
+                    .get(i)
+                    .map_or(Expression::ZERO, |carry| carry.expr() << LIMB_BITS);
+                cb.require_equal(
+                    || format!("column_{i}"),
+                    positive + carry_in,
+                    negative + carry_out,
+                )?;
+            }
+
+            Ok(FieldEquationConfig {
+                lhs,
+                rhs,
+                modulus: modulus.clone(),
+                offset,
+                quotient,
+                carries,
+                carries_range,
+                carry_offset,
+            })
+        })
+    }
+
+    /// Assign the quotient and the carries, given the limbs of the variables.
+    pub fn assign_instance<F: SmallField>(
+        &self,
+        instance: &mut [MaybeUninit<F>],
+        lkm: &mut LkMultiplicity,
+        vars: &[Vec<u16>],
+    ) -> Result<(), ZKVMError> {
+        let var_values = vars
+            .iter()
+            .map(|limbs| {
+                (limbs.iter().rev()).fold(BigUint::ZERO, |acc, &limb| (acc << LIMB_BITS) + limb)
+            })
+            .collect_vec();
+        let sum = |terms: &[FieldTerm]| -> BigUint {
+            terms.iter().map(|term| term.value(&var_values)).sum()
+        };
+        let difference = sum(&self.lhs) + &self.offset - sum(&self.rhs);
+        assert_eq!(
+            &difference % &self.modulus,
+            BigUint::ZERO,
+            "the field equation does not hold"
+        );
+        let quotient = limbs(&(difference / &self.modulus));
+        assert!(quotient.len() <= self.quotient.len());
+        for (wit, &limb) in self
+            .quotient
+            .iter()
+            .zip(quotient.iter().pad_using(self.quotient.len(), |_| &0))
+        {
+            set_val!(instance, wit, limb);
+            lkm.assert_ux::<16>(limb);
+        }
+
+        let num_columns = self.carries.len() + 1;
+        let var_limbs = vars
+            .iter()
+            .map(|limbs| limbs.iter().map(|&limb| limb as i128).collect_vec())
+            .collect_vec();
+        let quotient = quotient.into_iter().map(i128::from).collect_vec();
+        let modulus = limbs(&self.modulus)
+            .into_iter()
+            .map(i128::from)
+            .collect_vec();
+        let offset = limbs(&self.offset)
+            .into_iter()
+            .map(i128::from)
+            .collect_vec();
+        let positive = sum_polys(
+            (self.lhs.iter().map(|term| term.poly(&var_limbs))).chain([offset]),
+            num_columns,
+        );
+        let negative = sum_polys(
+            (self.rhs.iter().map(|term| term.poly(&var_limbs)))
+                .chain([convolve(&quotient, &modulus)]),
+            num_columns,
+        );
+
+        let mut carry = 0;
+        for (i, (positive, negative)) in positive.into_iter().zip_eq(negative).enumerate() {
+            let column = positive - negative + carry;
+            assert_eq!(column % (1 << LIMB_BITS), 0);
+            carry = column >> LIMB_BITS;
+            if let Some(wit) = self.carries.get(i) {
+                set_val!(instance, wit, i64_to_base::<F>(carry as i64));
+                self.carries_range[i].assign_instance(
+                    instance,
+                    lkm,
+                    (carry + self.carry_offset as i128) as u64,
+                    2 * self.carry_offset,
+                )?;
+            }
+        }
+        assert_eq!(carry, 0);
+        Ok(())
+    }
+}
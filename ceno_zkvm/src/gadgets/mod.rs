@@ -1,11 +1,15 @@
 mod div;
+mod field;
 mod is_lt;
 mod is_zero;
+mod poseidon;
 mod signed_ext;
 
 pub use div::DivConfig;
+pub use field::{FieldEquationConfig, FieldTerm};
 pub use is_lt::{
     AssertLTConfig, AssertSignedLtConfig, InnerLtConfig, IsLtConfig, SignedLtConfig, cal_lt_diff,
 };
 pub use is_zero::{IsEqualConfig, IsZeroConfig};
+pub use poseidon::PoseidonPermutationConfig;
 pub use signed_ext::SignedExtendConfig;
@@ -0,0 +1,193 @@
+use std::mem::MaybeUninit;
+
+use ff_ext::ExtensionField;
+use goldilocks::{Goldilocks, SmallField};
+use itertools::Itertools;
+use poseidon::{
+    ALL_ROUND_CONSTANTS, HALF_N_FULL_ROUNDS, N_PARTIAL_ROUNDS, N_ROUNDS, SPONGE_WIDTH,
+    poseidon::Poseidon,
+};
+
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    set_val,
+};
+
+/// The coefficient of the MDS matrix at the row `i` and the column `j`: a circulant matrix plus
+/// a diagonal matrix.
+fn mds_coeff(i: usize, j: usize) -> u64 {
+    let circ = <Goldilocks as Poseidon>::MDS_MATRIX_CIRC[(j + SPONGE_WIDTH - i) % SPONGE_WIDTH];
+    let diag = if i == j {
+        <Goldilocks as Poseidon>::MDS_MATRIX_DIAG[i]
+    } else {
+        0
+    };
+    circ + diag
+}
+
+/// Whether all the elements go through the S-box in the round, or only the first one.
+fn is_full_round(round: usize) -> bool {
+    !(HALF_N_FULL_ROUNDS..HALF_N_FULL_ROUNDS + N_PARTIAL_ROUNDS).contains(&round)
+}
+
+/// The S-box `x^7`, as the witnesses `x^2`, `x^3` and `x^4`, so that `x^7 = x^3 * x^4` has
+/// degree 2.
+struct SboxConfig {
+    x2: WitIn,
+    x3: WitIn,
+    x4: WitIn,
+}
+
+impl SboxConfig {
+    fn construct_circuit<E: ExtensionField>(
+        cb: &mut CircuitBuilder<E>,
+        x: Expression<E>,
+    ) -> Result<Self, ZKVMError> {
+        let x2 = cb.create_witin(|| "x2");
+        let x3 = cb.create_witin(|| "x3");
+        let x4 = cb.create_witin(|| "x4");
+        cb.require_equal(|| "x2", x2.expr(), x.clone() * x.clone())?;
+        cb.require_equal(|| "x3", x3.expr(), x2.expr() * x)?;
+        cb.require_equal(|| "x4", x4.expr(), x2.expr() * x2.expr())?;
+        Ok(SboxConfig { x2, x3, x4 })
+    }
+
+    fn expr<E: ExtensionField>(&self) -> Expression<E> {
+        self.x3.expr() * self.x4.expr()
+    }
+
+    fn assign_instance<F: SmallField>(&self, instance: &mut [MaybeUninit<F>], x: F) -> F {
+        let x2 = x * x;
+        let x3 = x2 * x;
+        let x4 = x2 * x2;
+        set_val!(instance, self.x2, x2);
+        set_val!(instance, self.x3, x3);
+        set_val!(instance, self.x4, x4);
+        x3 * x4
+    }
+}
+
+/// A round: the addition of the round constants, the S-boxes, and the MDS matrix.
+struct RoundConfig {
+    sboxes: Vec<SboxConfig>,
+    /// The state after the round, except after the last round where it is the output.
+    state: Vec<WitIn>,
+}
+
+/// The Poseidon permutation over the Goldilocks field, in a single row.
+///
+/// It is computed natively on field elements, with the plain round schedule: 4 full rounds, 22
+/// partial rounds and 4 full rounds. All constraints have degree 2.
+pub struct PoseidonPermutationConfig {
+    rounds: Vec<RoundConfig>,
+}
+
+impl PoseidonPermutationConfig {
+    /// Constrain `output` to be the permutation of `input`, both of `SPONGE_WIDTH` elements.
+    pub fn construct_circuit<E: ExtensionField>(
+        cb: &mut CircuitBuilder<E>,
+        input: Vec<Expression<E>>,
+        output: Vec<Expression<E>>,
+    ) -> Result<Self, ZKVMError> {
+        assert_eq!(input.len(), SPONGE_WIDTH);
+        assert_eq!(output.len(), SPONGE_WIDTH);
+        let mut state = input;
+        let rounds = (0..N_ROUNDS)
+            .map(|round| {
+                cb.namespace(
+                    || format!("round_{round}"),
+                    |cb| {
+                        let num_sboxes = if is_full_round(round) {
+                            SPONGE_WIDTH
+                        } else {
+                            1
+                        };
+                        let mut sboxes = Vec::with_capacity(num_sboxes);
+                        let mut layer = Vec::with_capacity(SPONGE_WIDTH);
+                        for (i, x) in state.iter().enumerate() {
+                            let x = x.clone()
+                                + Expression::from(ALL_ROUND_CONSTANTS[round * SPONGE_WIDTH + i]);
+                            if i < num_sboxes {
+                                let sbox = cb.namespace(
+                                    || format!("sbox_{i}"),
+                                    |cb| SboxConfig::construct_circuit(cb, x),
+                                )?;
+                                layer.push(sbox.expr());
+                                sboxes.push(sbox);
+                            } else {
+                                layer.push(x);
+                            }
+                        }
+
+                        let next_state = if round + 1 < N_ROUNDS {
+                            (0..SPONGE_WIDTH)
+                                .map(|i| cb.create_witin(|| format!("state_{i}")))
+                                .collect_vec()
+                        } else {
+                            vec![]
+                        };
+                        let next_exprs = if next_state.is_empty() {
+                            output.clone()
+                        } else {
+                            next_state.iter().map(|wit| wit.expr()).collect()
+                        };
+                        for (i, next) in next_exprs.iter().enumerate() {
+                            let mds = layer
+                                .iter()
+                                .enumerate()
+                                .map(|(j, y)| y.clone() * Expression::from(mds_coeff(i, j)))
+                                .reduce(|acc, term| acc + term)
+                                .unwrap();
+                            cb.require_equal(|| format!("mds_{i}"), next.clone(), mds)?;
+                        }
+                        state = next_exprs;
+
+                        Ok(RoundConfig {
+                            sboxes,
+                            state: next_state,
+                        })
+                    },
+                )
+            })
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+        Ok(PoseidonPermutationConfig { rounds })
+    }
+
+    /// Assign the intermediate states of the permutation of `input`, and return its output.
+    pub fn assign_instance<F: SmallField>(
+        &self,
+        instance: &mut [MaybeUninit<F>],
+        input: &[F],
+    ) -> Vec<F> {
+        assert_eq!(input.len(), SPONGE_WIDTH);
+        let mut state = input.to_vec();
+        for (round, round_cfg) in self.rounds.iter().enumerate() {
+            let layer = state
+                .iter()
+                .enumerate()
+                .map(|(i, &x)| {
+                    let x = x + F::from(ALL_ROUND_CONSTANTS[round * SPONGE_WIDTH + i]);
+                    match round_cfg.sboxes.get(i) {
+                        Some(sbox) => sbox.assign_instance(instance, x),
+                        None => x,
+                    }
+                })
+                .collect_vec();
+            state = (0..SPONGE_WIDTH)
+                .map(|i| {
+                    layer
+                        .iter()
+                        .enumerate()
+                        .map(|(j, &y)| y * F::from(mds_coeff(i, j)))
+                        .sum()
+                })
+                .collect();
+            for (wit, &x) in round_cfg.state.iter().zip(&state) {
+                set_val!(instance, wit, x);
+            }
+        }
+        state
+    }
+}
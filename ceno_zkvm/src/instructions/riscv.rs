@@ -1,7 +1,7 @@
 use ceno_emul::InsnKind;
 
 mod rv32im;
-pub use rv32im::Rv32imConfig;
+pub use rv32im::{PublicIOConfig, Rv32imConfig};
 
 pub mod arith;
 pub mod arith_imm;
@@ -29,6 +29,8 @@ mod u_insn;
 
 mod ecall_insn;
 
+#[cfg(test)]
+mod differential_test;
 mod im_insn;
 mod memory;
 mod s_insn;
@@ -2,6 +2,16 @@ use crate::uint::UIntLimbs;
 pub use ceno_emul::PC_STEP_SIZE;
 
 pub const ECALL_HALT_OPCODE: [usize; 2] = [0x00_00, 0x00_00];
+pub const ECALL_KECCAK_PERMUTE_OPCODE: [usize; 2] = [0x01_09, 0x00_01];
+pub const ECALL_SHA256_COMPRESS_OPCODE: [usize; 2] = [0x01_06, 0x00_01];
+pub const ECALL_POSEIDON_PERMUTE_OPCODE: [usize; 2] = [0x01_20, 0x00_01];
+pub const ECALL_UINT256_ADD_OPCODE: [usize; 2] = [0x01_1C, 0x00_01];
+pub const ECALL_UINT256_MUL_OPCODE: [usize; 2] = [0x01_1D, 0x00_01];
+pub const ECALL_UINT256_MULMOD_OPCODE: [usize; 2] = [0x01_1E, 0x00_01];
+pub const ECALL_SECP256K1_ADD_OPCODE: [usize; 2] = [0x01_0A, 0x00_01];
+pub const ECALL_SECP256K1_DOUBLE_OPCODE: [usize; 2] = [0x01_0B, 0x00_00];
+pub const ECALL_ED25519_ADD_OPCODE: [usize; 2] = [0x01_07, 0x00_01];
+pub const ECALL_ED25519_DOUBLE_OPCODE: [usize; 2] = [0x01_08, 0x00_00];
 pub const EXIT_PC: usize = 0;
 pub const EXIT_CODE_IDX: usize = 0;
 
@@ -10,6 +20,8 @@ pub const INIT_CYCLE_IDX: usize = 3;
 pub const END_PC_IDX: usize = 4;
 pub const END_CYCLE_IDX: usize = 5;
 pub const PUBLIC_IO_IDX: usize = 6;
+/// The elements of the digest of the public IO, each as its low and high words.
+pub const PUBLIC_IO_DIGEST_IDX: usize = 7;
 
 pub const LIMB_BITS: usize = 16;
 pub const LIMB_MASK: u32 = 0xFFFF;
@@ -20,6 +32,9 @@ pub const BIT_WIDTH: usize = 32usize;
 pub const BIT_WIDTH: usize = 64usize;
 pub type UInt<E> = UIntLimbs<BIT_WIDTH, LIMB_BITS, E>;
 pub type UIntMul<E> = UIntLimbs<{ 2 * BIT_WIDTH }, LIMB_BITS, E>;
+/// 256-bit integers for the big-integer precompiles, and their products.
+pub type UInt256<E> = UIntLimbs<256, LIMB_BITS, E>;
+pub type UInt512<E> = UIntLimbs<512, LIMB_BITS, E>;
 /// use UInt<x> for x bits limb size
 pub type UInt8<E> = UIntLimbs<BIT_WIDTH, 8, E>;
 pub const UINT_LIMBS: usize = BIT_WIDTH.div_ceil(LIMB_BITS);
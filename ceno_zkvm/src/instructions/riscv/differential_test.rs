@@ -0,0 +1,314 @@
+//! Differential tests of the opcode circuits against the emulator.
+//!
+//! For every instruction kind with a circuit, random operands and register indices are executed
+//! by `ceno_emul`. The resulting `StepRecord` is assigned to the circuit and checked with the
+//! `MockProver`. On failure, proptest shrinks the inputs to a minimal counterexample.
+
+use std::collections::BTreeMap;
+
+use ceno_emul::{
+    ByteAddr, CENO_PLATFORM,
+    InsnKind::{self, *},
+    Program, StepRecord, VMState, Word, encode_rv32,
+};
+use goldilocks::GoldilocksExt2;
+use itertools::Itertools;
+use multilinear_extensions::mle::IntoMLEs;
+use proptest::{prelude::*, test_runner::TestCaseError};
+
+use super::{
+    arith::{AddInstruction, MulInstruction, SubInstruction},
+    arith_imm::AddiInstruction,
+    branch::{
+        BeqInstruction, BgeInstruction, BgeuInstruction, BltInstruction, BltuInstruction,
+        BneInstruction,
+    },
+    divu::DivUInstruction,
+    ecall::HaltInstruction,
+    jump::{AuipcInstruction, JalInstruction, JalrInstruction, LuiInstruction},
+    logic::{AndInstruction, OrInstruction, XorInstruction},
+    logic_imm::{AndiInstruction, OriInstruction, XoriInstruction},
+    memory::{
+        LwInstruction, SbInstruction, ShInstruction, SwInstruction,
+        load::{LbInstruction, LbuInstruction, LhInstruction, LhuInstruction},
+    },
+    mulh::{MulhInstruction, MulhuInstruction},
+    shift::{ShiftLogicalInstruction, SllOp, SraOp, SrlOp},
+    shift_imm::{ShiftImmInstruction, SlliOp, SraiOp, SrliOp},
+    slt::SltInstruction,
+    slti::{SetLessThanImmInstruction, SltiOp, SltiuOp},
+    sltu::SltuInstruction,
+};
+use crate::{
+    circuit_builder::{CircuitBuilder, ConstraintSystem},
+    instructions::Instruction,
+    scheme::mock_prover::{MOCK_PC_START, MockProver},
+};
+
+type E = GoldilocksExt2;
+
+/// The number of random inputs per instruction kind, unless overridden by `PROPTEST_CASES`.
+const CASES: u32 = 16;
+
+/// Values at the boundaries of 16-bit limbs and of signed and unsigned 32-bit arithmetic.
+const EDGE_VALUES: &[Word] = &[
+    0,
+    1,
+    2,
+    0x7fff,
+    0x8000,
+    0xffff,
+    0x1_0000,
+    0x7fff_ffff,
+    0x8000_0000,
+    0x8000_0001,
+    0xffff_fffe,
+    0xffff_ffff,
+];
+
+/// The inputs of a single instruction.
+#[derive(Clone, Debug)]
+struct Operands {
+    rs1: u32,
+    rs2: u32,
+    rd: u32,
+    rs1_value: Word,
+    rs2_value: Word,
+    /// The immediate, as passed to `encode_rv32`.
+    imm: u32,
+    /// The initial memory word at the address of a load or store.
+    mem_value: Word,
+}
+
+/// A register value, biased towards edge cases.
+fn value() -> impl Strategy<Value = Word> {
+    prop_oneof![
+        1 => prop::sample::select(EDGE_VALUES),
+        3 => any::<Word>(),
+    ]
+}
+
+/// A signed offset which is a non-zero multiple of 4, encoded in `bits` bits.
+fn jump_offset(bits: u32) -> impl Strategy<Value = u32> {
+    let max = 1 << (bits - 3);
+    (-max..max)
+        .prop_filter("no jump to self", |&offset| offset != 0)
+        .prop_map(move |offset| (offset * 4) as u32 & ((1 << bits) - 1))
+}
+
+/// Sign-extend a 12-bit immediate.
+fn sext_imm_i(imm: u32) -> u32 {
+    (((imm << 20) as i32) >> 20) as u32
+}
+
+fn operands(kind: InsnKind) -> BoxedStrategy<Operands> {
+    let imm_i = 0..1u32 << 12;
+    let align = match kind {
+        LW | SW => 4,
+        LH | LHU | SH => 2,
+        _ => 1,
+    };
+    match kind {
+        LB | LBU | LH | LHU | LW | SB | SH | SW => {
+            // The address rs1 + imm must be aligned and in RAM.
+            let ram_slots = (CENO_PLATFORM.ram_end() - CENO_PLATFORM.ram_start()) / align;
+            (
+                1..32u32,
+                0..32u32,
+                0..32u32,
+                0..ram_slots,
+                imm_i,
+                value(),
+                value(),
+            )
+                .prop_map(move |(rs1, rs2, rd, slot, imm, rs2_value, mem_value)| {
+                    let addr = CENO_PLATFORM.ram_start() + slot * align;
+                    Operands {
+                        rs1,
+                        rs2,
+                        rd,
+                        rs1_value: addr.wrapping_sub(sext_imm_i(imm)),
+                        rs2_value,
+                        imm,
+                        mem_value,
+                    }
+                })
+                .boxed()
+        }
+        JALR => {
+            // The target rs1 + imm must be aligned, and differ from the current pc.
+            (1..32u32, 0..32u32, any::<u32>(), imm_i)
+                .prop_filter("no jump to self", |&(_, _, target, _)| {
+                    target & !3 != MOCK_PC_START.0
+                })
+                .prop_map(|(rs1, rd, target, imm)| Operands {
+                    rs1,
+                    rs2: 0,
+                    rd,
+                    rs1_value: (target & !3).wrapping_sub(sext_imm_i(imm)),
+                    rs2_value: 0,
+                    imm,
+                    mem_value: 0,
+                })
+                .boxed()
+        }
+        EANY => value()
+            .prop_map(|exit_code| Operands {
+                rs1: 0,
+                rs2: 0,
+                rd: 0,
+                rs1_value: 0,
+                rs2_value: exit_code,
+                imm: 0,
+                mem_value: 0,
+            })
+            .boxed(),
+        _ => {
+            let imm = match kind {
+                SLLI | SRLI | SRAI => (0..32u32).boxed(),
+                BEQ | BNE | BLT | BGE | BLTU | BGEU => jump_offset(13).boxed(),
+                JAL => jump_offset(21).boxed(),
+                LUI | AUIPC => any::<u32>().boxed(),
+                ADDI | ANDI | ORI | XORI | SLTI | SLTIU => imm_i.boxed(),
+                _ => Just(0).boxed(),
+            };
+            (0..32u32, 0..32u32, 0..32u32, value(), value(), imm)
+                .prop_map(|(rs1, rs2, rd, rs1_value, rs2_value, imm)| Operands {
+                    rs1,
+                    rs2,
+                    rd,
+                    rs1_value,
+                    rs2_value,
+                    imm,
+                    mem_value: 0,
+                })
+                .boxed()
+        }
+    }
+}
+
+/// Execute a single instruction in the emulator.
+fn emulate(kind: InsnKind, ops: &Operands) -> Result<StepRecord, TestCaseError> {
+    let insn_code = encode_rv32(kind, ops.rs1, ops.rs2, ops.rd, ops.imm);
+    let pc = MOCK_PC_START.0;
+    let program = Program::new(pc, pc, vec![insn_code], BTreeMap::from([(pc, insn_code)]));
+    let mut vm = VMState::new(CENO_PLATFORM, program);
+
+    if kind == EANY {
+        vm.init_register_unsafe(CENO_PLATFORM.reg_ecall(), CENO_PLATFORM.ecall_halt());
+        vm.init_register_unsafe(CENO_PLATFORM.reg_arg0(), ops.rs2_value);
+    }
+    // x0 is hardwired to zero. If rs1 == rs2, the value of rs1 wins.
+    for (reg, value) in [(ops.rs2, ops.rs2_value), (ops.rs1, ops.rs1_value)] {
+        if reg != 0 {
+            vm.init_register_unsafe(reg as usize, value);
+        }
+    }
+    if matches!(kind, LB | LBU | LH | LHU | LW | SB | SH | SW) {
+        let addr = ops.rs1_value.wrapping_add(sext_imm_i(ops.imm));
+        vm.init_memory(ByteAddr(addr).waddr(), ops.mem_value);
+    }
+
+    let step = vm
+        .iter_until_halt()
+        .next()
+        .expect("the machine is not halted");
+    step.map_err(|err| TestCaseError::fail(format!("emulator error: {err}")))
+}
+
+/// Execute a single instruction in the emulator, and check the circuit on its step record.
+fn check<I: Instruction<E>>(kind: InsnKind, ops: &Operands) -> Result<(), TestCaseError> {
+    let step = emulate(kind, ops)?;
+    let insn_code = step.insn_code();
+
+    let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+    let mut cb = CircuitBuilder::new(&mut cs);
+    let config = cb
+        .namespace(|| I::name(), |cb| I::construct_circuit(cb))
+        .unwrap();
+    let (raw_witin, lkm) = I::assign_instances(&config, cb.cs.num_witin as usize, vec![step])
+        .map_err(|err| TestCaseError::fail(format!("assignment error: {err:?}")))?;
+
+    let wits_in = raw_witin
+        .de_interleaving()
+        .into_mles()
+        .into_iter()
+        .map(|v| v.into())
+        .collect_vec();
+    MockProver::run(&cb, &wits_in, &[insn_code], Some(lkm)).map_err(|errors| {
+        for error in &errors {
+            error.print(&wits_in, &cb.cs.witin_namespace_map);
+        }
+        TestCaseError::fail(format!(
+            "{} {kind:?} constraints not satisfied",
+            errors.len()
+        ))
+    })
+}
+
+fn config() -> ProptestConfig {
+    let cases = std::env::var("PROPTEST_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(CASES);
+    ProptestConfig::with_cases(cases)
+}
+
+macro_rules! differential_tests {
+    ($($test_name:ident: $kind:ident => $instruction:ty,)*) => {
+        proptest! {
+            #![proptest_config(config())]
+            $(
+                #[test]
+                fn $test_name(ops in operands($kind)) {
+                    check::<$instruction>($kind, &ops)?;
+                }
+            )*
+        }
+    };
+}
+
+differential_tests! {
+    test_differential_add: ADD => AddInstruction<E>,
+    test_differential_sub: SUB => SubInstruction<E>,
+    test_differential_mul: MUL => MulInstruction<E>,
+    test_differential_mulh: MULH => MulhInstruction<E>,
+    test_differential_mulhu: MULHU => MulhuInstruction<E>,
+    test_differential_divu: DIVU => DivUInstruction<E>,
+    test_differential_and: AND => AndInstruction<E>,
+    test_differential_or: OR => OrInstruction<E>,
+    test_differential_xor: XOR => XorInstruction<E>,
+    test_differential_sll: SLL => ShiftLogicalInstruction<E, SllOp>,
+    test_differential_srl: SRL => ShiftLogicalInstruction<E, SrlOp>,
+    test_differential_sra: SRA => ShiftLogicalInstruction<E, SraOp>,
+    test_differential_slt: SLT => SltInstruction<E>,
+    test_differential_sltu: SLTU => SltuInstruction<E>,
+    test_differential_addi: ADDI => AddiInstruction<E>,
+    test_differential_andi: ANDI => AndiInstruction<E>,
+    test_differential_ori: ORI => OriInstruction<E>,
+    test_differential_xori: XORI => XoriInstruction<E>,
+    test_differential_slli: SLLI => ShiftImmInstruction<E, SlliOp>,
+    test_differential_srli: SRLI => ShiftImmInstruction<E, SrliOp>,
+    test_differential_srai: SRAI => ShiftImmInstruction<E, SraiOp>,
+    test_differential_slti: SLTI => SetLessThanImmInstruction<E, SltiOp>,
+    test_differential_sltiu: SLTIU => SetLessThanImmInstruction<E, SltiuOp>,
+    test_differential_beq: BEQ => BeqInstruction<E>,
+    test_differential_bne: BNE => BneInstruction<E>,
+    test_differential_blt: BLT => BltInstruction,
+    test_differential_bge: BGE => BgeInstruction,
+    test_differential_bltu: BLTU => BltuInstruction,
+    test_differential_bgeu: BGEU => BgeuInstruction,
+    test_differential_jal: JAL => JalInstruction<E>,
+    test_differential_jalr: JALR => JalrInstruction<E>,
+    test_differential_lui: LUI => LuiInstruction<E>,
+    test_differential_auipc: AUIPC => AuipcInstruction<E>,
+    test_differential_lw: LW => LwInstruction<E>,
+    test_differential_lh: LH => LhInstruction<E>,
+    test_differential_lhu: LHU => LhuInstruction<E>,
+    test_differential_lb: LB => LbInstruction<E>,
+    test_differential_lbu: LBU => LbuInstruction<E>,
+    test_differential_sw: SW => SwInstruction<E>,
+    test_differential_sh: SH => ShInstruction<E>,
+    test_differential_sb: SB => SbInstruction<E>,
+    test_differential_halt: EANY => HaltInstruction<E>,
+}
@@ -1,3 +1,25 @@
+mod elliptic_curve;
 mod halt;
+mod keccak;
+mod poseidon;
+mod sha256;
+mod sha256_rounds;
+mod uint256;
 
+pub use elliptic_curve::{
+    EcConfig, EcInstruction, EcOpSpec, Ed25519Add, Ed25519AddInstruction, Ed25519Double,
+    Ed25519DoubleInstruction, Secp256k1Add, Secp256k1AddInstruction, Secp256k1Double,
+    Secp256k1DoubleInstruction,
+};
 pub use halt::HaltInstruction;
+pub use keccak::{KeccakConfig, KeccakInstruction};
+pub use poseidon::{PoseidonConfig, PoseidonInstruction};
+pub use sha256::{Sha256Config, Sha256Instruction};
+pub use sha256_rounds::{Sha256RoundsConfig, Sha256RoundsInstruction, sha256_state_record};
+pub use uint256::{
+    Uint256AddConfig, Uint256AddInstruction, Uint256MulConfig, Uint256MulInstruction,
+    Uint256MulModConfig, Uint256MulModInstruction,
+};
+
+#[cfg(test)]
+mod test_utils;
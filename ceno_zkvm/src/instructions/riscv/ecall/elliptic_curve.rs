@@ -0,0 +1,501 @@
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr},
+    gadgets::{FieldEquationConfig, FieldTerm},
+    instructions::{
+        Instruction,
+        riscv::constants::{
+            ECALL_ED25519_ADD_OPCODE, ECALL_ED25519_DOUBLE_OPCODE, ECALL_SECP256K1_ADD_OPCODE,
+            ECALL_SECP256K1_DOUBLE_OPCODE, UInt256,
+        },
+    },
+    witness::LkMultiplicity,
+};
+use ceno_emul::{ED25519_D, ED25519_MODULUS, SECP256K1_MODULUS, StepRecord, UINT256_WORDS, Word};
+use ff_ext::ExtensionField;
+use itertools::Itertools;
+use num_bigint::BigUint;
+use std::{marker::PhantomData, mem::MaybeUninit};
+
+use super::uint256::{Limbs, OperandsConfig, add_outcome, assign_uint256, u16_limbs};
+
+/// A field equation `sum(lhs) = sum(rhs)`, see `FieldEquationConfig`.
+type Equation = (Vec<FieldTerm>, Vec<FieldTerm>);
+
+/// An operation on points, given by its formulas as equations in the field of the curve.
+///
+/// The variables of the equations are the coordinates of the input points, then of the result,
+/// then the auxiliary variables.
+pub trait EcOpSpec {
+    const NAME: &'static str;
+    const OPCODE: [usize; 2];
+    const MODULUS: [Word; UINT256_WORDS];
+    /// Whether the operation reads a second point at the address in arg1.
+    const IS_ADD: bool;
+    const NUM_AUX: usize;
+
+    /// The equations, which must determine the result from the inputs.
+    fn equations() -> Vec<Equation>;
+
+    /// The auxiliary variables, given the reduced coordinates of the input points and of the
+    /// result.
+    fn aux(modulus: &BigUint, coordinates: &[BigUint]) -> Vec<BigUint>;
+}
+
+/// The variables of the coordinates: `(x1, y1, x2, y2, x3, y3)`, where the second point is the
+/// first one for a double, and the index of the first auxiliary variable.
+fn coordinate_vars(is_add: bool) -> ([usize; 6], usize) {
+    if is_add {
+        ([0, 1, 2, 3, 4, 5], 6)
+    } else {
+        ([0, 1, 0, 1, 2, 3], 4)
+    }
+}
+
+fn inverse(x: &BigUint, modulus: &BigUint) -> BigUint {
+    x.modpow(&(modulus - 2u32), modulus)
+}
+
+/// The formulas of secp256k1, `y^2 = x^3 + 7`, with the slope `lambda`, and the inverse of the
+/// denominator of the slope which must not be zero.
+fn secp256k1_equations(is_add: bool) -> Vec<Equation> {
+    use FieldTerm as T;
+    let ([x1, y1, x2, y2, x3, y3], aux) = coordinate_vars(is_add);
+    let (lambda, inv) = (aux, aux + 1);
+    let slope = if is_add {
+        vec![
+            // inv * (x2 - x1) = 1
+            (vec![T::product(inv, x2)], vec![
+                T::product(inv, x1),
+                T::constant(1u32),
+            ]),
+            // lambda * (x2 - x1) = y2 - y1
+            (vec![T::product(lambda, x2), T::var(y1)], vec![
+                T::product(lambda, x1),
+                T::var(y2),
+            ]),
+        ]
+    } else {
+        vec![
+            // inv * y1 = 1
+            (vec![T::product(inv, y1)], vec![T::constant(1u32)]),
+            // lambda * 2 * y1 = 3 * x1^2
+            (vec![T::product(lambda, y1).scaled(2u32)], vec![
+                T::product(x1, x1).scaled(3u32),
+            ]),
+        ]
+    };
+    let chord = vec![
+        // x3 = lambda^2 - x1 - x2
+        (vec![T::product(lambda, lambda)], vec![
+            T::var(x3),
+            T::var(x1),
+            T::var(x2),
+        ]),
+        // y3 = lambda * (x1 - x3) - y1
+        (vec![T::product(lambda, x1)], vec![
+            T::product(lambda, x3),
+            T::var(y3),
+            T::var(y1),
+        ]),
+    ];
+    [slope, chord].concat()
+}
+
+fn secp256k1_aux(is_add: bool, m: &BigUint, coordinates: &[BigUint]) -> Vec<BigUint> {
+    let ([x1, y1, x2, y2, ..], _) = coordinate_vars(is_add);
+    let c = |i: usize| &coordinates[i];
+    let (lambda, inv) = if is_add {
+        let inv = inverse(&((c(x2) + m - c(x1)) % m), m);
+        ((c(y2) + m - c(y1)) * &inv % m, inv)
+    } else {
+        let inv = inverse(c(y1), m);
+        let half = inverse(&2u32.into(), m);
+        (3u32 * c(x1) * c(x1) % m * &inv % m * half % m, inv)
+    };
+    vec![lambda, inv]
+}
+
+/// The formulas of ed25519, `-x^2 + y^2 = 1 + d x^2 y^2`:
+/// `x3 = (x1 y2 + y1 x2) / (1 + d e)` and `y3 = (y1 y2 + x1 x2) / (1 - d e)`, with
+/// `e = x1 x2 y1 y2`, and the inverses `z1` and `z2` of the denominators.
+fn ed25519_equations(is_add: bool) -> Vec<Equation> {
+    use FieldTerm as T;
+    let ([x1, y1, x2, y2, x3, y3], aux) = coordinate_vars(is_add);
+    let [f, g, e, z1, s1, n, z2, s2] = std::array::from_fn(|i| aux + i);
+    let d = || BigUint::from_slice(&ED25519_D);
+    let one = || T::constant(1u32);
+    vec![
+        // f = x1 x2, g = y1 y2, e = f g
+        (vec![T::var(f)], vec![T::product(x1, x2)]),
+        (vec![T::var(g)], vec![T::product(y1, y2)]),
+        (vec![T::var(e)], vec![T::product(f, g)]),
+        // z1 (1 + d e) = 1, with s1 = z1 e
+        (vec![T::var(s1)], vec![T::product(z1, e)]),
+        (vec![T::var(z1), T::var(s1).scaled(d())], vec![one()]),
+        // x3 = (x1 y2 + y1 x2) z1
+        (vec![T::var(n)], vec![
+            T::product(x1, y2),
+            T::product(y1, x2),
+        ]),
+        (vec![T::var(x3)], vec![T::product(n, z1)]),
+        // z2 (1 - d e) = 1, with s2 = z2 e
+        (vec![T::var(s2)], vec![T::product(z2, e)]),
+        (vec![T::var(z2)], vec![one(), T::var(s2).scaled(d())]),
+        // y3 = (g + f) z2
+        (vec![T::var(y3)], vec![T::product(g, z2), T::product(f, z2)]),
+    ]
+}
+
+fn ed25519_aux(is_add: bool, m: &BigUint, coordinates: &[BigUint]) -> Vec<BigUint> {
+    let ([x1, y1, x2, y2, ..], _) = coordinate_vars(is_add);
+    let c = |i: usize| &coordinates[i];
+    let d = BigUint::from_slice(&ED25519_D);
+    let f = c(x1) * c(x2) % m;
+    let g = c(y1) * c(y2) % m;
+    let e = &f * &g % m;
+    let de = &d * &e % m;
+    let z1 = inverse(&((1u32 + &de) % m), m);
+    let z2 = inverse(&((1u32 + m - &de) % m), m);
+    let s1 = &z1 * &e % m;
+    let s2 = &z2 * &e % m;
+    let n = (c(x1) * c(y2) + c(y1) * c(x2)) % m;
+    vec![f, g, e, z1, s1, n, z2, s2]
+}
+
+pub struct Secp256k1Add;
+
+impl EcOpSpec for Secp256k1Add {
+    const NAME: &'static str = "ECALL_SECP256K1_ADD";
+    const OPCODE: [usize; 2] = ECALL_SECP256K1_ADD_OPCODE;
+    const MODULUS: [Word; UINT256_WORDS] = SECP256K1_MODULUS;
+    const IS_ADD: bool = true;
+    const NUM_AUX: usize = 2;
+
+    fn equations() -> Vec<Equation> {
+        secp256k1_equations(Self::IS_ADD)
+    }
+
+    fn aux(modulus: &BigUint, coordinates: &[BigUint]) -> Vec<BigUint> {
+        secp256k1_aux(Self::IS_ADD, modulus, coordinates)
+    }
+}
+
+pub struct Secp256k1Double;
+
+impl EcOpSpec for Secp256k1Double {
+    const NAME: &'static str = "ECALL_SECP256K1_DOUBLE";
+    const OPCODE: [usize; 2] = ECALL_SECP256K1_DOUBLE_OPCODE;
+    const MODULUS: [Word; UINT256_WORDS] = SECP256K1_MODULUS;
+    const IS_ADD: bool = false;
+    const NUM_AUX: usize = 2;
+
+    fn equations() -> Vec<Equation> {
+        secp256k1_equations(Self::IS_ADD)
+    }
+
+    fn aux(modulus: &BigUint, coordinates: &[BigUint]) -> Vec<BigUint> {
+        secp256k1_aux(Self::IS_ADD, modulus, coordinates)
+    }
+}
+
+pub struct Ed25519Add;
+
+impl EcOpSpec for Ed25519Add {
+    const NAME: &'static str = "ECALL_ED25519_ADD";
+    const OPCODE: [usize; 2] = ECALL_ED25519_ADD_OPCODE;
+    const MODULUS: [Word; UINT256_WORDS] = ED25519_MODULUS;
+    const IS_ADD: bool = true;
+    const NUM_AUX: usize = 8;
+
+    fn equations() -> Vec<Equation> {
+        ed25519_equations(Self::IS_ADD)
+    }
+
+    fn aux(modulus: &BigUint, coordinates: &[BigUint]) -> Vec<BigUint> {
+        ed25519_aux(Self::IS_ADD, modulus, coordinates)
+    }
+}
+
+pub struct Ed25519Double;
+
+impl EcOpSpec for Ed25519Double {
+    const NAME: &'static str = "ECALL_ED25519_DOUBLE";
+    const OPCODE: [usize; 2] = ECALL_ED25519_DOUBLE_OPCODE;
+    const MODULUS: [Word; UINT256_WORDS] = ED25519_MODULUS;
+    const IS_ADD: bool = false;
+    const NUM_AUX: usize = 8;
+
+    fn equations() -> Vec<Equation> {
+        ed25519_equations(Self::IS_ADD)
+    }
+
+    fn aux(modulus: &BigUint, coordinates: &[BigUint]) -> Vec<BigUint> {
+        ed25519_aux(Self::IS_ADD, modulus, coordinates)
+    }
+}
+
+/// The check that a coordinate of the result is reduced: `value + 1 + gap = modulus`.
+struct ReducedConfig<E: ExtensionField> {
+    value: UInt256<E>,
+    gap: UInt256<E>,
+    value_plus_one: UInt256<E>,
+    value_plus_gap: UInt256<E>,
+}
+
+impl<E: ExtensionField> ReducedConfig<E> {
+    fn construct_circuit(
+        cb: &mut CircuitBuilder<E>,
+        name: &str,
+        modulus: &[u16],
+    ) -> Result<Self, ZKVMError> {
+        let value = UInt256::new(|| name.to_owned(), cb)?;
+        let gap = UInt256::new(|| format!("{name}_gap"), cb)?;
+        let value_plus_one = value.add_const(|| format!("{name}_plus_one"), cb, 1.into(), false)?;
+        let value_plus_gap = value_plus_one.add(|| format!("{name}_plus_gap"), cb, &gap, false)?;
+        for (i, (limb, &modulus)) in value_plus_gap
+            .expr()
+            .into_iter()
+            .zip_eq(modulus)
+            .enumerate()
+        {
+            cb.require_equal(|| format!("{name}_lt_modulus_{i}"), limb, modulus.into())?;
+        }
+        Ok(ReducedConfig {
+            value,
+            gap,
+            value_plus_one,
+            value_plus_gap,
+        })
+    }
+
+    fn assign_instance(
+        &self,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        value: &[u16],
+        modulus: &[u16],
+    ) {
+        assign_uint256(&self.value, instance, lk_multiplicity, value);
+        let mut borrow = 1;
+        let gap = modulus
+            .iter()
+            .zip_eq(value)
+            .map(|(&m, &v)| {
+                let diff = m as i64 - v as i64 - borrow;
+                borrow = (diff < 0) as i64;
+                diff.rem_euclid(1 << 16) as u16
+            })
+            .collect_vec();
+        assert_eq!(borrow, 0, "the value is not reduced");
+        assign_uint256(&self.gap, instance, lk_multiplicity, &gap);
+        let mut one = vec![0; value.len()];
+        one[0] = 1;
+        let value_plus_one = add_outcome(value, &one, false, lk_multiplicity);
+        self.value_plus_one
+            .assign_add_outcome(instance, &value_plus_one);
+        let value_plus_gap = add_outcome(&value_plus_one.limbs, &gap, false, lk_multiplicity);
+        self.value_plus_gap
+            .assign_add_outcome(instance, &value_plus_gap);
+    }
+}
+
+pub struct EcConfig<E: ExtensionField> {
+    operands: OperandsConfig<E>,
+    /// The coordinates of the result.
+    result: Vec<ReducedConfig<E>>,
+    aux: Vec<UInt256<E>>,
+    equations: Vec<FieldEquationConfig>,
+}
+
+/// An ecall on points of an elliptic curve, which replaces the point at the address in arg0 with
+/// the result of `Op`, see `EcOpSpec`.
+///
+/// A point is stored as its affine coordinates `x` and `y`. The coordinates of the input points
+/// may be unreduced, and those of the result are reduced. The equations prove that the
+/// denominators of the formulas are not zero, so that the result is determined: the ecall is not
+/// provable when the emulator rejects it.
+pub struct EcInstruction<E, Op>(PhantomData<(E, Op)>);
+
+pub type Secp256k1AddInstruction<E> = EcInstruction<E, Secp256k1Add>;
+pub type Secp256k1DoubleInstruction<E> = EcInstruction<E, Secp256k1Double>;
+pub type Ed25519AddInstruction<E> = EcInstruction<E, Ed25519Add>;
+pub type Ed25519DoubleInstruction<E> = EcInstruction<E, Ed25519Double>;
+
+fn modulus_limbs<Op: EcOpSpec>() -> Limbs {
+    u16_limbs(&Op::MODULUS)
+}
+
+impl<E: ExtensionField, Op: EcOpSpec> Instruction<E> for EcInstruction<E, Op> {
+    type InstructionConfig = EcConfig<E>;
+
+    fn name() -> String {
+        Op::NAME.into()
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let num_inputs = if Op::IS_ADD { 2 } else { 0 };
+        let mut operands = OperandsConfig::construct_circuit(cb, Op::OPCODE, 2, num_inputs)?;
+        let modulus = modulus_limbs::<Op>();
+        let result = ["x3", "y3"]
+            .into_iter()
+            .map(|name| ReducedConfig::construct_circuit(cb, name, &modulus))
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+        let aux = (0..Op::NUM_AUX)
+            .map(|i| UInt256::new(|| format!("aux_{i}"), cb))
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+
+        let vars: Vec<Vec<Expression<E>>> = (operands.x.iter())
+            .chain(&operands.inputs)
+            .chain(result.iter().map(|coordinate| &coordinate.value))
+            .chain(&aux)
+            .map(|uint| uint.expr())
+            .collect();
+        let modulus = BigUint::from_slice(&Op::MODULUS);
+        let equations = Op::equations()
+            .into_iter()
+            .enumerate()
+            .map(|(i, (lhs, rhs))| {
+                FieldEquationConfig::construct_circuit(
+                    cb,
+                    || format!("equation_{i}"),
+                    &vars,
+                    &modulus,
+                    lhs,
+                    rhs,
+                )
+            })
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+
+        let results = result.iter().map(|coordinate| coordinate.value.expr());
+        operands.write_results(cb, &results.collect_vec())?;
+        Ok(EcConfig {
+            operands,
+            result,
+            aux,
+            equations,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        let (p, q) =
+            config
+                .operands
+                .assign_instance(instance, lk_multiplicity, step, Op::OPCODE)?;
+        let result_words = step.syscall().unwrap().mem_ops[..2 * UINT256_WORDS]
+            .iter()
+            .map(|op| op.value.after)
+            .collect_vec();
+        let result = result_words
+            .chunks(UINT256_WORDS)
+            .map(u16_limbs)
+            .collect_vec();
+        let modulus_limbs = modulus_limbs::<Op>();
+        for (coordinate, limbs) in config.result.iter().zip_eq(&result) {
+            coordinate.assign_instance(instance, lk_multiplicity, limbs, &modulus_limbs);
+        }
+
+        let modulus = BigUint::from_slice(&Op::MODULUS);
+        let to_biguint = |limbs: &Limbs| {
+            (limbs.iter().rev()).fold(BigUint::ZERO, |acc, &limb| (acc << 16) + limb)
+        };
+        let mut vars = [p, q, result].concat();
+        let coordinates = vars
+            .iter()
+            .map(|limbs| to_biguint(limbs) % &modulus)
+            .collect_vec();
+        for (uint, value) in config.aux.iter().zip_eq(Op::aux(&modulus, &coordinates)) {
+            let words: [Word; UINT256_WORDS] =
+                std::array::from_fn(|i| value.iter_u32_digits().nth(i).unwrap_or(0));
+            let limbs = u16_limbs(&words);
+            assign_uint256(uint, instance, lk_multiplicity, &limbs);
+            vars.push(limbs);
+        }
+
+        for equation in &config.equations {
+            equation.assign_instance(instance, lk_multiplicity, &vars)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        circuit_builder::ConstraintSystem,
+        instructions::riscv::ecall::test_utils::{ECALL, ecall_step, into_mles},
+        scheme::mock_prover::MockProver,
+    };
+    use ceno_emul::{
+        ByteAddr, CENO_PLATFORM, EC_POINT_WORDS, EcPoint, ed25519_add, ed25519_double,
+        secp256k1_add, secp256k1_double,
+    };
+    use goldilocks::GoldilocksExt2;
+
+    type E = GoldilocksExt2;
+
+    const SECP256K1_G: EcPoint = [
+        0x16F81798, 0x59F2815B, 0x2DCE28D9, 0x029BFCDB, 0xCE870B07, 0x55A06295, 0xF9DCBBAC,
+        0x79BE667E, 0xFB10D4B8, 0x9C47D08F, 0xA6855419, 0xFD17B448, 0x0E1108A8, 0x5DA4FBFC,
+        0x26A3C465, 0x483ADA77,
+    ];
+    const ED25519_B: EcPoint = [
+        0x8F25D51A, 0xC9562D60, 0x9525A7B2, 0x692CC760, 0xFDD6DC5C, 0xC0A4E231, 0xCD6E53FE,
+        0x216936D3, 0x66666658, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666,
+        0x66666666, 0x66666666,
+    ];
+
+    /// Run the ecall on the points, check the circuit, and return the result.
+    fn verify<Op: EcOpSpec>(function: u32, points: &[EcPoint]) -> EcPoint {
+        let p_ptr = ByteAddr(CENO_PLATFORM.ram_start());
+        let q_ptr = ByteAddr(CENO_PLATFORM.ram_start() + 0x100);
+        let memory = [p_ptr, q_ptr]
+            .into_iter()
+            .zip(points)
+            .map(|(ptr, point)| (ptr, point.to_vec()))
+            .collect_vec();
+        let step = ecall_step(function, &[p_ptr.0, q_ptr.0], &memory);
+        let result = step.syscall().unwrap().mem_ops[..EC_POINT_WORDS]
+            .iter()
+            .map(|op| op.value.after)
+            .collect_vec();
+
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = cb
+            .namespace(
+                || Op::NAME,
+                |cb| Ok(EcInstruction::<E, Op>::construct_circuit(cb)),
+            )
+            .unwrap()
+            .unwrap();
+        let (raw_witin, lkm) =
+            EcInstruction::<E, Op>::assign_instances(&config, cb.cs.num_witin as usize, vec![step])
+                .unwrap();
+        MockProver::assert_satisfied(&cb, &into_mles(raw_witin), &[ECALL], None, Some(lkm));
+        result.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_secp256k1() {
+        let g2 = verify::<Secp256k1Double>(CENO_PLATFORM.ecall_secp256k1_double(), &[SECP256K1_G]);
+        assert_eq!(Some(g2), secp256k1_double(&SECP256K1_G));
+        let g3 = verify::<Secp256k1Add>(CENO_PLATFORM.ecall_secp256k1_add(), &[g2, SECP256K1_G]);
+        assert_eq!(Some(g3), secp256k1_add(&g2, &SECP256K1_G));
+    }
+
+    #[test]
+    fn test_ed25519() {
+        let b2 = verify::<Ed25519Double>(CENO_PLATFORM.ecall_ed25519_double(), &[ED25519_B]);
+        assert_eq!(Some(b2), ed25519_double(&ED25519_B));
+        let b3 = verify::<Ed25519Add>(CENO_PLATFORM.ecall_ed25519_add(), &[b2, ED25519_B]);
+        assert_eq!(Some(b3), ed25519_add(&b2, &ED25519_B));
+    }
+}
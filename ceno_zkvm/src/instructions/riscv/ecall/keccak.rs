@@ -0,0 +1,258 @@
+use crate::{
+    Value,
+    chip_handler::{MemoryChipOperations, RegisterChipOperations},
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{ToExpr, WitIn},
+    gadgets::AssertLTConfig,
+    instructions::{
+        Instruction,
+        riscv::{
+            constants::{ECALL_KECCAK_PERMUTE_OPCODE, UInt},
+            ecall_insn::EcallInstructionConfig,
+        },
+    },
+    set_val,
+    tables::keccak_state_record,
+    witness::LkMultiplicity,
+};
+use ceno_emul::{CENO_PLATFORM, KECCAK_ROUNDS, KECCAK_STATE_WORDS, StepRecord, Tracer, WORD_SIZE};
+use ff_ext::ExtensionField;
+use itertools::Itertools;
+use std::{marker::PhantomData, mem::MaybeUninit};
+
+/// The write of a word of the Keccak state.
+struct StateWordConfig {
+    prev_value: WitIn,
+    value: WitIn,
+    prev_ts: WitIn,
+    lt_cfg: AssertLTConfig,
+}
+
+pub struct KeccakConfig<E: ExtensionField> {
+    ecall_cfg: EcallInstructionConfig,
+    state_ptr: UInt<E>,
+    prev_x10_ts: WitIn,
+    lt_x10_cfg: AssertLTConfig,
+    words: Vec<StateWordConfig>,
+}
+
+/// The ecall KECCAK_PERMUTE, which applies Keccak-f[1600] to the state at the address in arg0.
+///
+/// This circuit replaces the state in memory, and delegates the permutation to the rows of
+/// `KeccakRoundsCircuit`: it writes the input of the first round and reads the output of the
+/// last round as `RAMType::KeccakState` records, identified by the cycle of the ecall. Both
+/// circuits must be registered together.
+pub struct KeccakInstruction<E>(PhantomData<E>);
+
+impl<E: ExtensionField> Instruction<E> for KeccakInstruction<E> {
+    type InstructionConfig = KeccakConfig<E>;
+
+    fn name() -> String {
+        "ECALL_KECCAK_PERMUTE".into()
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let ecall_cfg = EcallInstructionConfig::construct_circuit(
+            cb,
+            [
+                ECALL_KECCAK_PERMUTE_OPCODE[0].into(),
+                ECALL_KECCAK_PERMUTE_OPCODE[1].into(),
+            ],
+            None,
+            None,
+        )?;
+
+        // read state_ptr from arg0 (X10 register)
+        let state_ptr = UInt::new_unchecked(|| "state_ptr", cb)?;
+        let prev_x10_ts = cb.create_witin(|| "prev_x10_ts");
+        let (_, lt_x10_cfg) = cb.register_read(
+            || "read x10",
+            E::BaseField::from(CENO_PLATFORM.reg_arg0() as u64),
+            prev_x10_ts.expr(),
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_RS2,
+            state_ptr.register_expr(),
+        )?;
+
+        let words = (0..KECCAK_STATE_WORDS)
+            .map(|i| {
+                let prev_value = cb.create_witin(|| format!("prev_word_{i}"));
+                let value = cb.create_witin(|| format!("word_{i}"));
+                let prev_ts = cb.create_witin(|| format!("prev_ts_{i}"));
+                let (_, lt_cfg) = cb.memory_write(
+                    || format!("write word_{i}"),
+                    &(state_ptr.address_expr() + i * WORD_SIZE),
+                    prev_ts.expr(),
+                    ecall_cfg.ts.expr() + Tracer::SUBCYCLE_MEM,
+                    prev_value.expr(),
+                    value.expr(),
+                )?;
+                Ok(StateWordConfig {
+                    prev_value,
+                    value,
+                    prev_ts,
+                    lt_cfg,
+                })
+            })
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+
+        // The rounds turn the previous values into the new values.
+        let record_in = keccak_state_record(
+            cb,
+            ecall_cfg.ts.expr(),
+            0.into(),
+            words.iter().map(|word| word.prev_value.expr()).collect(),
+        );
+        cb.write_record(|| "keccak_input", record_in)?;
+        let record_out = keccak_state_record(
+            cb,
+            ecall_cfg.ts.expr(),
+            KECCAK_ROUNDS.into(),
+            words.iter().map(|word| word.value.expr()).collect(),
+        );
+        cb.read_record(|| "keccak_output", record_out)?;
+
+        Ok(KeccakConfig {
+            ecall_cfg,
+            state_ptr,
+            prev_x10_ts,
+            lt_x10_cfg,
+            words,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        assert_eq!(
+            step.rs1().unwrap().value,
+            (ECALL_KECCAK_PERMUTE_OPCODE[0] + (ECALL_KECCAK_PERMUTE_OPCODE[1] << 16)) as u32
+        );
+
+        config
+            .ecall_cfg
+            .assign_instance::<E>(instance, lk_multiplicity, step)?;
+
+        // the access of X10 register is stored in rs2()
+        let state_ptr = step.rs2().unwrap();
+        config
+            .state_ptr
+            .assign_value(instance, Value::new_unchecked(state_ptr.value));
+        set_val!(instance, config.prev_x10_ts, state_ptr.previous_cycle);
+        config.lt_x10_cfg.assign_instance(
+            instance,
+            lk_multiplicity,
+            state_ptr.previous_cycle,
+            step.cycle() + Tracer::SUBCYCLE_RS2,
+        )?;
+
+        let mem_ops = &step.syscall().expect("missing syscall witness").mem_ops;
+        for (word, op) in config.words.iter().zip_eq(mem_ops) {
+            set_val!(instance, word.prev_value, op.value.before as u64);
+            set_val!(instance, word.value, op.value.after as u64);
+            set_val!(instance, word.prev_ts, op.previous_cycle);
+            word.lt_cfg.assign_instance(
+                instance,
+                lk_multiplicity,
+                op.previous_cycle,
+                step.cycle() + Tracer::SUBCYCLE_MEM,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        circuit_builder::ConstraintSystem,
+        instructions::riscv::ecall::test_utils::{
+            ECALL, assert_chain, ecall_step, eval_records, into_mles,
+        },
+        scheme::mock_prover::MockProver,
+        tables::{KeccakRoundsCircuit, TableCircuit},
+    };
+    use ceno_emul::ByteAddr;
+    use goldilocks::GoldilocksExt2;
+
+    type E = GoldilocksExt2;
+
+    #[test]
+    fn test_keccak_permute() {
+        let state_ptr = ByteAddr(CENO_PLATFORM.ram_start());
+        let state = (0..KECCAK_STATE_WORDS as u32)
+            .map(|i| 0x1234_5678_u32.wrapping_mul(i))
+            .collect();
+        let step = ecall_step(CENO_PLATFORM.ecall_keccak_permute(), &[state_ptr.0], &[(
+            state_ptr, state,
+        )]);
+
+        let mut ecall_cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut ecall_cs);
+        let config = cb
+            .namespace(
+                || "keccak",
+                |cb| Ok(KeccakInstruction::<E>::construct_circuit(cb)),
+            )
+            .unwrap()
+            .unwrap();
+        let (raw_witin, lkm) =
+            KeccakInstruction::<E>::assign_instances(&config, cb.cs.num_witin as usize, vec![
+                step.clone(),
+            ])
+            .unwrap();
+        let wits_in = into_mles(raw_witin);
+        MockProver::assert_satisfied(&cb, &wits_in, &[ECALL], None, Some(lkm));
+        let cs = &cb.cs;
+        let ecall_input = eval_records(
+            (&cs.w_expressions, &cs.w_expressions_namespace_map),
+            &wits_in,
+            1,
+            "keccak_input",
+        );
+        let ecall_output = eval_records(
+            (&cs.r_expressions, &cs.r_expressions_namespace_map),
+            &wits_in,
+            1,
+            "keccak_output",
+        );
+        assert_eq!(ecall_input.len(), 1);
+        assert_eq!(ecall_output.len(), 1);
+
+        let mut rounds_cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut rounds_cs);
+        let config = KeccakRoundsCircuit::<E>::construct_circuit(&mut cb, &CENO_PLATFORM).unwrap();
+        let table =
+            KeccakRoundsCircuit::<E>::assign_instances(&config, cb.cs.num_witin as usize, &[], &[
+                step,
+            ])
+            .unwrap();
+        assert_eq!(table.num_instances(), KECCAK_ROUNDS);
+        let wits_in = into_mles(table);
+        MockProver::assert_satisfied(&cb, &wits_in, &[], None, None);
+        let cs = &cb.cs;
+        let round_inputs = eval_records(
+            (&cs.r_expressions, &cs.r_expressions_namespace_map),
+            &wits_in,
+            KECCAK_ROUNDS,
+            "state_in",
+        );
+        let round_outputs = eval_records(
+            (&cs.w_expressions, &cs.w_expressions_namespace_map),
+            &wits_in,
+            KECCAK_ROUNDS,
+            "state_out",
+        );
+
+        // The rounds form a chain from the input to the output of the ecall.
+        assert_chain(vec![ecall_input, round_outputs], vec![
+            round_inputs,
+            ecall_output,
+        ]);
+    }
+}
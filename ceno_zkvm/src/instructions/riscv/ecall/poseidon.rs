@@ -0,0 +1,286 @@
+use crate::{
+    Value,
+    chip_handler::{MemoryChipOperations, RegisterChipOperations},
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    gadgets::{AssertLTConfig, IsZeroConfig, PoseidonPermutationConfig},
+    instructions::{
+        Instruction,
+        riscv::{
+            constants::{ECALL_POSEIDON_PERMUTE_OPCODE, UInt},
+            ecall_insn::EcallInstructionConfig,
+        },
+    },
+    set_val,
+    witness::LkMultiplicity,
+};
+use ceno_emul::{CENO_PLATFORM, POSEIDON_STATE_WORDS, StepRecord, Tracer, WORD_SIZE};
+use ff_ext::ExtensionField;
+use itertools::Itertools;
+use poseidon::SPONGE_WIDTH;
+use std::{marker::PhantomData, mem::MaybeUninit};
+
+/// The write of a word of the state.
+struct StateWordConfig<E: ExtensionField> {
+    prev_value: WitIn,
+    value: UInt<E>,
+    prev_ts: WitIn,
+    lt_cfg: AssertLTConfig,
+}
+
+impl<E: ExtensionField> StateWordConfig<E> {
+    fn construct_circuit(
+        cb: &mut CircuitBuilder<E>,
+        ecall_cfg: &EcallInstructionConfig,
+        state_ptr: &UInt<E>,
+        index: usize,
+    ) -> Result<Self, ZKVMError> {
+        let prev_value = cb.create_witin(|| format!("prev_word_{index}"));
+        let value = UInt::new(|| format!("word_{index}"), cb)?;
+        let prev_ts = cb.create_witin(|| format!("prev_ts_{index}"));
+        let (_, lt_cfg) = cb.memory_write(
+            || format!("write word_{index}"),
+            &(state_ptr.address_expr() + index * WORD_SIZE),
+            prev_ts.expr(),
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_MEM,
+            prev_value.expr(),
+            value.memory_expr(),
+        )?;
+        Ok(StateWordConfig {
+            prev_value,
+            value,
+            prev_ts,
+            lt_cfg,
+        })
+    }
+}
+
+/// An element of the state, as its low word and its high word.
+struct ElementConfig<E: ExtensionField> {
+    words: [StateWordConfig<E>; 2],
+    /// Whether the high word is `0xFFFFFFFF`, in which case the low word must be 0 for the
+    /// element to be canonical.
+    hi_is_max: IsZeroConfig,
+}
+
+pub struct PoseidonConfig<E: ExtensionField> {
+    ecall_cfg: EcallInstructionConfig,
+    state_ptr: UInt<E>,
+    prev_x10_ts: WitIn,
+    lt_x10_cfg: AssertLTConfig,
+    elements: Vec<ElementConfig<E>>,
+    permutation: PoseidonPermutationConfig,
+}
+
+/// The ecall POSEIDON_PERMUTE, which applies the Poseidon permutation over the Goldilocks field
+/// to the state at the address in arg0.
+///
+/// The state is 12 field elements, each stored as two little-endian words. The permutation is
+/// computed by `PoseidonPermutationConfig`. The inputs are reduced modulo the field order, and
+/// the outputs are constrained to be canonical.
+pub struct PoseidonInstruction<E>(PhantomData<E>);
+
+impl<E: ExtensionField> Instruction<E> for PoseidonInstruction<E> {
+    type InstructionConfig = PoseidonConfig<E>;
+
+    fn name() -> String {
+        "ECALL_POSEIDON_PERMUTE".into()
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let ecall_cfg = EcallInstructionConfig::construct_circuit(
+            cb,
+            [
+                ECALL_POSEIDON_PERMUTE_OPCODE[0].into(),
+                ECALL_POSEIDON_PERMUTE_OPCODE[1].into(),
+            ],
+            None,
+            None,
+        )?;
+
+        // read state_ptr from arg0 (X10 register)
+        let state_ptr = UInt::new_unchecked(|| "state_ptr", cb)?;
+        let prev_x10_ts = cb.create_witin(|| "prev_x10_ts");
+        let (_, lt_x10_cfg) = cb.register_read(
+            || "read x10",
+            E::BaseField::from(CENO_PLATFORM.reg_arg0() as u64),
+            prev_x10_ts.expr(),
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_RS2,
+            state_ptr.register_expr(),
+        )?;
+
+        let elements = (0..SPONGE_WIDTH)
+            .map(|i| {
+                let words = [
+                    StateWordConfig::construct_circuit(cb, &ecall_cfg, &state_ptr, 2 * i)?,
+                    StateWordConfig::construct_circuit(cb, &ecall_cfg, &state_ptr, 2 * i + 1)?,
+                ];
+                let hi_is_max = IsZeroConfig::construct_circuit(
+                    cb,
+                    || format!("hi_is_max_{i}"),
+                    words[1].value.value() - Expression::from(u32::MAX),
+                )?;
+                cb.require_zero(
+                    || format!("canonical_{i}"),
+                    hi_is_max.expr() * words[0].value.value(),
+                )?;
+                Ok(ElementConfig { words, hi_is_max })
+            })
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+
+        let element_expr = |lo: Expression<E>, hi: Expression<E>| lo + (hi << 32);
+        let state = elements
+            .iter()
+            .map(|element| {
+                element_expr(
+                    element.words[0].prev_value.expr(),
+                    element.words[1].prev_value.expr(),
+                )
+            })
+            .collect_vec();
+        let output = elements
+            .iter()
+            .map(|element| {
+                element_expr(
+                    element.words[0].value.value(),
+                    element.words[1].value.value(),
+                )
+            })
+            .collect_vec();
+
+        let permutation = PoseidonPermutationConfig::construct_circuit(cb, state, output)?;
+
+        Ok(PoseidonConfig {
+            ecall_cfg,
+            state_ptr,
+            prev_x10_ts,
+            lt_x10_cfg,
+            elements,
+            permutation,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        assert_eq!(
+            step.rs1().unwrap().value,
+            (ECALL_POSEIDON_PERMUTE_OPCODE[0] + (ECALL_POSEIDON_PERMUTE_OPCODE[1] << 16)) as u32
+        );
+
+        config
+            .ecall_cfg
+            .assign_instance::<E>(instance, lk_multiplicity, step)?;
+
+        // the access of X10 register is stored in rs2()
+        let state_ptr = step.rs2().unwrap();
+        config
+            .state_ptr
+            .assign_value(instance, Value::new_unchecked(state_ptr.value));
+        set_val!(instance, config.prev_x10_ts, state_ptr.previous_cycle);
+        config.lt_x10_cfg.assign_instance(
+            instance,
+            lk_multiplicity,
+            state_ptr.previous_cycle,
+            step.cycle() + Tracer::SUBCYCLE_RS2,
+        )?;
+
+        let mem_ops = &step.syscall().expect("missing syscall witness").mem_ops;
+        assert_eq!(mem_ops.len(), POSEIDON_STATE_WORDS);
+        let element_value = |lo: u32, hi: u32| E::BaseField::from(lo as u64 | (hi as u64) << 32);
+        let mut state = Vec::with_capacity(SPONGE_WIDTH);
+        for (element, ops) in config.elements.iter().zip_eq(mem_ops.chunks(2)) {
+            for (word, op) in element.words.iter().zip_eq(ops) {
+                set_val!(instance, word.prev_value, op.value.before as u64);
+                word.value
+                    .assign_value(instance, Value::new(op.value.after, lk_multiplicity));
+                set_val!(instance, word.prev_ts, op.previous_cycle);
+                word.lt_cfg.assign_instance(
+                    instance,
+                    lk_multiplicity,
+                    op.previous_cycle,
+                    step.cycle() + Tracer::SUBCYCLE_MEM,
+                )?;
+            }
+            element.hi_is_max.assign_instance(
+                instance,
+                E::BaseField::from(ops[1].value.after as u64) - E::BaseField::from(u32::MAX as u64),
+            )?;
+            state.push(element_value(ops[0].value.before, ops[1].value.before));
+        }
+
+        let state = config.permutation.assign_instance(instance, &state);
+        debug_assert!(
+            state
+                .iter()
+                .zip(mem_ops.chunks(2))
+                .all(|(&x, ops)| x == element_value(ops[0].value.after, ops[1].value.after))
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        circuit_builder::ConstraintSystem,
+        instructions::riscv::ecall::test_utils::{ECALL, ecall_step, into_mles},
+        scheme::mock_prover::MockProver,
+    };
+    use ceno_emul::{ByteAddr, poseidon_permutation};
+    use goldilocks::GoldilocksExt2;
+
+    type E = GoldilocksExt2;
+
+    fn words(elements: [u64; SPONGE_WIDTH]) -> Vec<u32> {
+        elements
+            .iter()
+            .flat_map(|&x| [x as u32, (x >> 32) as u32])
+            .collect()
+    }
+
+    #[test]
+    fn test_poseidon_permute() {
+        let state_ptr = ByteAddr(CENO_PLATFORM.ram_start());
+        // The last element is not canonical.
+        let mut input: [u64; SPONGE_WIDTH] =
+            std::array::from_fn(|i| (i as u64 + 1) * 0x0123_4567_89AB_CDEF);
+        input[SPONGE_WIDTH - 1] = u64::MAX;
+        let step = ecall_step(CENO_PLATFORM.ecall_poseidon_permute(), &[state_ptr.0], &[(
+            state_ptr,
+            words(input),
+        )]);
+        let mut output = input;
+        poseidon_permutation(&mut output);
+        let written = step
+            .syscall()
+            .unwrap()
+            .mem_ops
+            .iter()
+            .map(|op| op.value.after);
+        assert!(written.eq(words(output)));
+
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = cb
+            .namespace(
+                || "poseidon",
+                |cb| Ok(PoseidonInstruction::<E>::construct_circuit(cb)),
+            )
+            .unwrap()
+            .unwrap();
+        let (raw_witin, lkm) =
+            PoseidonInstruction::<E>::assign_instances(&config, cb.cs.num_witin as usize, vec![
+                step,
+            ])
+            .unwrap();
+
+        MockProver::assert_satisfied(&cb, &into_mles(raw_witin), &[ECALL], None, Some(lkm));
+    }
+}
@@ -0,0 +1,396 @@
+use crate::{
+    Value,
+    chip_handler::{MemoryChipOperations, RegisterChipOperations},
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{ToExpr, WitIn},
+    gadgets::AssertLTConfig,
+    instructions::{
+        Instruction,
+        riscv::{
+            constants::{ECALL_SHA256_COMPRESS_OPCODE, UInt},
+            ecall_insn::EcallInstructionConfig,
+        },
+    },
+    set_val,
+    witness::LkMultiplicity,
+};
+use ceno_emul::{
+    CENO_PLATFORM, SHA256_BLOCK_WORDS, SHA256_ROUNDS, SHA256_STATE_WORDS, StepRecord, Tracer,
+    WORD_SIZE, sha256_next_word,
+};
+use ff_ext::ExtensionField;
+use itertools::Itertools;
+use std::{marker::PhantomData, mem::MaybeUninit};
+
+use super::sha256_rounds::sha256_state_record;
+
+/// The update of a word of the state, by the addition of the output of the rounds.
+struct StateWordConfig<E: ExtensionField> {
+    prev_value: WitIn,
+    /// The word of the working state after the last round.
+    rounds_value: WitIn,
+    value: UInt<E>,
+    carry: WitIn,
+    prev_ts: WitIn,
+    lt_cfg: AssertLTConfig,
+}
+
+/// The read of a word of the block.
+struct BlockWordConfig {
+    value: WitIn,
+    prev_ts: WitIn,
+    lt_cfg: AssertLTConfig,
+}
+
+pub struct Sha256Config<E: ExtensionField> {
+    ecall_cfg: EcallInstructionConfig,
+    state_ptr: UInt<E>,
+    prev_x10_ts: WitIn,
+    lt_x10_cfg: AssertLTConfig,
+    block_ptr: UInt<E>,
+    prev_x11_ts: WitIn,
+    lt_x11_cfg: AssertLTConfig,
+    state: Vec<StateWordConfig<E>>,
+    block: Vec<BlockWordConfig>,
+    /// The window of the message schedule after the last round, which is not used.
+    window_out: Vec<WitIn>,
+}
+
+/// The ecall SHA256_COMPRESS, which compresses the block at the address in arg1 into the state
+/// at the address in arg0.
+///
+/// This circuit reads the block, replaces the state in memory, and delegates the rounds to the
+/// rows of `Sha256RoundsInstruction`: it writes the input of the first round and reads the output
+/// of the last round as `RAMType::Sha256State` records, identified by the cycle of the ecall.
+/// Both circuits must be registered together.
+pub struct Sha256Instruction<E>(PhantomData<E>);
+
+impl<E: ExtensionField> Instruction<E> for Sha256Instruction<E> {
+    type InstructionConfig = Sha256Config<E>;
+
+    fn name() -> String {
+        "ECALL_SHA256_COMPRESS".into()
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let ecall_cfg = EcallInstructionConfig::construct_circuit(
+            cb,
+            [
+                ECALL_SHA256_COMPRESS_OPCODE[0].into(),
+                ECALL_SHA256_COMPRESS_OPCODE[1].into(),
+            ],
+            None,
+            None,
+        )?;
+
+        // read state_ptr from arg0 (X10 register)
+        let state_ptr = UInt::new_unchecked(|| "state_ptr", cb)?;
+        let prev_x10_ts = cb.create_witin(|| "prev_x10_ts");
+        let (_, lt_x10_cfg) = cb.register_read(
+            || "read x10",
+            E::BaseField::from(CENO_PLATFORM.reg_arg0() as u64),
+            prev_x10_ts.expr(),
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_RS2,
+            state_ptr.register_expr(),
+        )?;
+
+        // read block_ptr from arg1 (X11 register), at the subcycle of rd
+        let block_ptr = UInt::new_unchecked(|| "block_ptr", cb)?;
+        let prev_x11_ts = cb.create_witin(|| "prev_x11_ts");
+        let (_, lt_x11_cfg) = cb.register_read(
+            || "read x11",
+            E::BaseField::from(CENO_PLATFORM.reg_arg1() as u64),
+            prev_x11_ts.expr(),
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_RD,
+            block_ptr.register_expr(),
+        )?;
+
+        let state = (0..SHA256_STATE_WORDS)
+            .map(|i| {
+                let prev_value = cb.create_witin(|| format!("prev_state_{i}"));
+                let rounds_value = cb.create_witin(|| format!("rounds_state_{i}"));
+                let value = UInt::new(|| format!("state_{i}"), cb)?;
+                let carry = cb.create_witin(|| format!("carry_{i}"));
+                cb.assert_bit(|| format!("carry_{i}"), carry.expr())?;
+                cb.require_equal(
+                    || format!("add state_{i}"),
+                    prev_value.expr() + rounds_value.expr(),
+                    value.value() + (carry.expr() << 32),
+                )?;
+                let prev_ts = cb.create_witin(|| format!("prev_state_ts_{i}"));
+                let (_, lt_cfg) = cb.memory_write(
+                    || format!("write state_{i}"),
+                    &(state_ptr.address_expr() + i * WORD_SIZE),
+                    prev_ts.expr(),
+                    ecall_cfg.ts.expr() + Tracer::SUBCYCLE_MEM,
+                    prev_value.expr(),
+                    value.memory_expr(),
+                )?;
+                Ok(StateWordConfig {
+                    prev_value,
+                    rounds_value,
+                    value,
+                    carry,
+                    prev_ts,
+                    lt_cfg,
+                })
+            })
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+
+        let block = (0..SHA256_BLOCK_WORDS)
+            .map(|i| {
+                let value = cb.create_witin(|| format!("block_{i}"));
+                let prev_ts = cb.create_witin(|| format!("prev_block_ts_{i}"));
+                let (_, lt_cfg) = cb.memory_read(
+                    || format!("read block_{i}"),
+                    &(block_ptr.address_expr() + i * WORD_SIZE),
+                    prev_ts.expr(),
+                    ecall_cfg.ts.expr() + Tracer::SUBCYCLE_MEM,
+                    value.expr(),
+                )?;
+                Ok(BlockWordConfig {
+                    value,
+                    prev_ts,
+                    lt_cfg,
+                })
+            })
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+
+        let window_out = (0..SHA256_BLOCK_WORDS)
+            .map(|i| cb.create_witin(|| format!("window_out_{i}")))
+            .collect_vec();
+
+        // The rounds turn the previous state and the block into the output of the rounds.
+        let record_in = sha256_state_record(
+            cb,
+            ecall_cfg.ts.expr(),
+            0.into(),
+            state.iter().map(|word| word.prev_value.expr()).collect(),
+            block.iter().map(|word| word.value.expr()).collect(),
+        );
+        cb.write_record(|| "sha256_input", record_in)?;
+        let record_out = sha256_state_record(
+            cb,
+            ecall_cfg.ts.expr(),
+            SHA256_ROUNDS.into(),
+            state.iter().map(|word| word.rounds_value.expr()).collect(),
+            window_out.iter().map(|word| word.expr()).collect(),
+        );
+        cb.read_record(|| "sha256_output", record_out)?;
+
+        Ok(Sha256Config {
+            ecall_cfg,
+            state_ptr,
+            prev_x10_ts,
+            lt_x10_cfg,
+            block_ptr,
+            prev_x11_ts,
+            lt_x11_cfg,
+            state,
+            block,
+            window_out,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        assert_eq!(
+            step.rs1().unwrap().value,
+            (ECALL_SHA256_COMPRESS_OPCODE[0] + (ECALL_SHA256_COMPRESS_OPCODE[1] << 16)) as u32
+        );
+
+        config
+            .ecall_cfg
+            .assign_instance::<E>(instance, lk_multiplicity, step)?;
+
+        // the access of X10 register is stored in rs2()
+        let state_ptr = step.rs2().unwrap();
+        config
+            .state_ptr
+            .assign_value(instance, Value::new_unchecked(state_ptr.value));
+        set_val!(instance, config.prev_x10_ts, state_ptr.previous_cycle);
+        config.lt_x10_cfg.assign_instance(
+            instance,
+            lk_multiplicity,
+            state_ptr.previous_cycle,
+            step.cycle() + Tracer::SUBCYCLE_RS2,
+        )?;
+
+        // the access of X11 register is stored in the syscall witness
+        let syscall = step.syscall().expect("missing syscall witness");
+        let block_ptr = &syscall.reg_ops[0];
+        config
+            .block_ptr
+            .assign_value(instance, Value::new_unchecked(block_ptr.value));
+        set_val!(instance, config.prev_x11_ts, block_ptr.previous_cycle);
+        config.lt_x11_cfg.assign_instance(
+            instance,
+            lk_multiplicity,
+            block_ptr.previous_cycle,
+            step.cycle() + Tracer::SUBCYCLE_RD,
+        )?;
+
+        let (state_ops, block_ops) = syscall.mem_ops.split_at(SHA256_STATE_WORDS);
+        for (word, op) in config.state.iter().zip_eq(state_ops) {
+            let rounds_value = op.value.after.wrapping_sub(op.value.before);
+            let carry = (op.value.before as u64 + rounds_value as u64) >> 32;
+            set_val!(instance, word.prev_value, op.value.before as u64);
+            set_val!(instance, word.rounds_value, rounds_value as u64);
+            word.value
+                .assign_value(instance, Value::new(op.value.after, lk_multiplicity));
+            set_val!(instance, word.carry, carry);
+            set_val!(instance, word.prev_ts, op.previous_cycle);
+            word.lt_cfg.assign_instance(
+                instance,
+                lk_multiplicity,
+                op.previous_cycle,
+                step.cycle() + Tracer::SUBCYCLE_MEM,
+            )?;
+        }
+        for (word, op) in config.block.iter().zip_eq(block_ops) {
+            set_val!(instance, word.value, op.value.before as u64);
+            set_val!(instance, word.prev_ts, op.previous_cycle);
+            word.lt_cfg.assign_instance(
+                instance,
+                lk_multiplicity,
+                op.previous_cycle,
+                step.cycle() + Tracer::SUBCYCLE_MEM,
+            )?;
+        }
+
+        // The rounds extend the message schedule past the block.
+        let mut window: [u32; SHA256_BLOCK_WORDS] =
+            std::array::from_fn(|i| block_ops[i].value.before);
+        for _ in 0..SHA256_ROUNDS {
+            let next = sha256_next_word(&window);
+            window.rotate_left(1);
+            window[SHA256_BLOCK_WORDS - 1] = next;
+        }
+        for (wit, word) in config.window_out.iter().zip(window) {
+            set_val!(instance, wit, word as u64);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        circuit_builder::ConstraintSystem,
+        instructions::riscv::ecall::{
+            Sha256RoundsInstruction,
+            test_utils::{ECALL, assert_chain, ecall_step, eval_records, into_mles},
+        },
+        scheme::mock_prover::MockProver,
+        structs::ROMType,
+    };
+    use ceno_emul::{ByteAddr, SHA256_INIT};
+    use goldilocks::GoldilocksExt2;
+    use strum::IntoEnumIterator;
+
+    type E = GoldilocksExt2;
+
+    #[test]
+    fn test_sha256_compress() {
+        let state_ptr = ByteAddr(CENO_PLATFORM.ram_start());
+        let block_ptr = ByteAddr(CENO_PLATFORM.ram_start() + 0x100);
+        let block = (0..SHA256_BLOCK_WORDS as u32)
+            .map(|i| 0x1234_5678_u32.wrapping_mul(i + 1))
+            .collect();
+        let step = ecall_step(
+            CENO_PLATFORM.ecall_sha256_compress(),
+            &[state_ptr.0, block_ptr.0],
+            &[(state_ptr, SHA256_INIT.to_vec()), (block_ptr, block)],
+        );
+
+        let mut ecall_cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut ecall_cs);
+        let config = cb
+            .namespace(
+                || "sha256",
+                |cb| Ok(Sha256Instruction::<E>::construct_circuit(cb)),
+            )
+            .unwrap()
+            .unwrap();
+        let (raw_witin, lkm) =
+            Sha256Instruction::<E>::assign_instances(&config, cb.cs.num_witin as usize, vec![
+                step.clone(),
+            ])
+            .unwrap();
+        let wits_in = into_mles(raw_witin);
+        MockProver::assert_satisfied(&cb, &wits_in, &[ECALL], None, Some(lkm));
+        let cs = &cb.cs;
+        let ecall_input = eval_records(
+            (&cs.w_expressions, &cs.w_expressions_namespace_map),
+            &wits_in,
+            1,
+            "sha256_input",
+        );
+        let ecall_output = eval_records(
+            (&cs.r_expressions, &cs.r_expressions_namespace_map),
+            &wits_in,
+            1,
+            "sha256_output",
+        );
+        assert_eq!(ecall_input.len(), 1);
+        assert_eq!(ecall_output.len(), 1);
+
+        let mut rounds_cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut rounds_cs);
+        let config = cb
+            .namespace(
+                || "sha256_rounds",
+                |cb| Ok(Sha256RoundsInstruction::<E>::construct_circuit(cb)),
+            )
+            .unwrap()
+            .unwrap();
+        let (raw_witin, lkm) = Sha256RoundsInstruction::<E>::assign_instances(
+            &config,
+            cb.cs.num_witin as usize,
+            vec![step],
+        )
+        .unwrap();
+        assert_eq!(raw_witin.num_instances(), SHA256_ROUNDS);
+        let wits_in = into_mles(raw_witin);
+        // The mock prover compares the multiplicities of the first row only, so compare the
+        // number of lookups of all rows.
+        MockProver::assert_satisfied(&cb, &wits_in, &[], None, None);
+        let cs = &cb.cs;
+        let lkm = lkm.into_finalize_result();
+        for (rom_type, lookups) in ROMType::iter().zip(&lkm) {
+            let num_lookups = (cs.lk_expressions_items_map.iter())
+                .filter(|(ty, _)| *ty as usize == rom_type as usize)
+                .count();
+            assert_eq!(
+                lookups.values().sum::<usize>(),
+                num_lookups * SHA256_ROUNDS,
+                "{rom_type:?}"
+            );
+        }
+        let round_inputs = eval_records(
+            (&cs.r_expressions, &cs.r_expressions_namespace_map),
+            &wits_in,
+            SHA256_ROUNDS,
+            "state_in",
+        );
+        let round_outputs = eval_records(
+            (&cs.w_expressions, &cs.w_expressions_namespace_map),
+            &wits_in,
+            SHA256_ROUNDS,
+            "state_out",
+        );
+
+        // The rounds form a chain from the input to the output of the ecall.
+        assert_chain(vec![ecall_input, round_outputs], vec![
+            round_inputs,
+            ecall_output,
+        ]);
+    }
+}
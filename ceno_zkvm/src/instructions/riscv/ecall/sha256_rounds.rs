@@ -0,0 +1,550 @@
+//! The rounds of the SHA-256 compression function, as a circuit with one row per round.
+//!
+//! Each row reads the working state and the window of the message schedule before its round from
+//! a `RAMType::Sha256State` record, and writes them after the round. The records of a
+//! compression are identified by the cycle of its ecall: `Sha256Instruction` writes the input of
+//! the first round and reads the output of the last round, so the rows of a compression must form
+//! a chain from its input to its output.
+//!
+//! The words `a`, `e` and the words of the schedule which are rotated are represented by their
+//! bits, the words `b`, `c`, `f`, `g` by their bytes. The bitwise operations are lookups into the
+//! tables `AndTable` and `XorTable`, byte by byte, and the additions modulo 2^32 are checked with
+//! a carry. Since the rows use lookups, this circuit is assigned like an opcode circuit.
+
+use std::{marker::PhantomData, mem::MaybeUninit};
+
+use ceno_emul::{
+    CENO_PLATFORM, SHA256_BLOCK_WORDS, SHA256_ROUND_CONSTANTS, SHA256_ROUNDS, SHA256_STATE_WORDS,
+    StepRecord, sha256_next_word, sha256_round,
+};
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+use itertools::Itertools;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+use crate::{
+    Value,
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    instructions::{Instruction, riscv::constants::UInt},
+    scheme::constants::MIN_PAR_SIZE,
+    set_val,
+    structs::RAMType,
+    witness::{LkMultiplicity, RowMajorMatrix},
+};
+
+const WORD_BITS: usize = 32;
+const WORD_BYTES: usize = 4;
+
+/// The record of the compression `id` before the round `round`: the 8 words of the working state
+/// followed by the 16 words of the window of the message schedule.
+pub fn sha256_state_record<E: ExtensionField>(
+    cb: &CircuitBuilder<E>,
+    id: Expression<E>,
+    round: Expression<E>,
+    state: Vec<Expression<E>>,
+    window: Vec<Expression<E>>,
+) -> Expression<E> {
+    assert_eq!(state.len(), SHA256_STATE_WORDS);
+    assert_eq!(window.len(), SHA256_BLOCK_WORDS);
+    cb.rlc_chip_record([vec![RAMType::Sha256State.into(), id, round], state, window].concat())
+}
+
+fn bits_value<E: ExtensionField>(bits: &[WitIn]) -> Expression<E> {
+    (bits.iter().enumerate())
+        .map(|(k, bit)| bit.expr() << k)
+        .sum()
+}
+
+fn bytes_value<E: ExtensionField>(bytes: impl IntoIterator<Item = Expression<E>>) -> Expression<E> {
+    (bytes.into_iter().enumerate())
+        .map(|(k, byte)| byte << (8 * k))
+        .sum()
+}
+
+fn exprs<E: ExtensionField>(wits: &[WitIn]) -> Vec<Expression<E>> {
+    wits.iter().map(|w| w.expr()).collect()
+}
+
+/// The bytes of the word `bits` rotated right by `n`, or shifted right if `shift`.
+fn rotr_bytes<E: ExtensionField>(bits: &[WitIn], n: usize, shift: bool) -> Vec<Expression<E>> {
+    (0..WORD_BYTES)
+        .map(|k| {
+            (0..8)
+                .filter(|j| !shift || 8 * k + j + n < WORD_BITS)
+                .map(|j| bits[(8 * k + j + n) % WORD_BITS].expr() << j)
+                .sum()
+        })
+        .collect()
+}
+
+fn rotr_value(x: u32, n: usize, shift: bool) -> u32 {
+    if shift {
+        x >> n
+    } else {
+        x.rotate_right(n as u32)
+    }
+}
+
+fn set_bits<F: SmallField>(instance: &mut [MaybeUninit<F>], bits: &[WitIn], value: u32) {
+    for (k, bit) in bits.iter().enumerate() {
+        set_val!(instance, bit, ((value >> k) & 1) as u64);
+    }
+}
+
+fn set_bytes<F: SmallField>(instance: &mut [MaybeUninit<F>], bytes: &[WitIn], value: u32) {
+    for (k, byte) in bytes.iter().enumerate() {
+        set_val!(instance, byte, ((value >> (8 * k)) & 0xFF) as u64);
+    }
+}
+
+/// The XOR of three rotations of a word, such as `Σ0(a)`, as two XORs of bytes.
+#[derive(Debug)]
+struct Xor3Config {
+    rotations: [(usize, bool); 3],
+    /// The XOR of the first two rotations.
+    tmp: Vec<WitIn>,
+    out: Vec<WitIn>,
+}
+
+impl Xor3Config {
+    fn construct_circuit<E: ExtensionField>(
+        cb: &mut CircuitBuilder<E>,
+        name: &str,
+        bits: &[WitIn],
+        rotations: [(usize, bool); 3],
+    ) -> Result<Self, ZKVMError> {
+        let tmp = (0..WORD_BYTES)
+            .map(|k| cb.create_witin(|| format!("{name}_tmp_{k}")))
+            .collect_vec();
+        let out = (0..WORD_BYTES)
+            .map(|k| cb.create_witin(|| format!("{name}_{k}")))
+            .collect_vec();
+        let [x, y, z] = rotations.map(|(n, shift)| rotr_bytes(bits, n, shift));
+        for k in 0..WORD_BYTES {
+            cb.lookup_xor_byte(x[k].clone(), y[k].clone(), tmp[k].expr())?;
+            cb.lookup_xor_byte(tmp[k].expr(), z[k].clone(), out[k].expr())?;
+        }
+        Ok(Self {
+            rotations,
+            tmp,
+            out,
+        })
+    }
+
+    fn value<E: ExtensionField>(&self) -> Expression<E> {
+        bytes_value(exprs(&self.out))
+    }
+
+    fn assign_instance<F: SmallField>(
+        &self,
+        instance: &mut [MaybeUninit<F>],
+        lk_multiplicity: &mut LkMultiplicity,
+        word: u32,
+    ) -> u32 {
+        let [x, y, z] = self.rotations.map(|(n, shift)| rotr_value(word, n, shift));
+        let (tmp, out) = (x ^ y, x ^ y ^ z);
+        for k in 0..WORD_BYTES {
+            let byte = |v: u32| ((v >> (8 * k)) & 0xFF) as u64;
+            lk_multiplicity.lookup_xor_byte(byte(x), byte(y));
+            lk_multiplicity.lookup_xor_byte(byte(tmp), byte(z));
+        }
+        set_bytes(instance, &self.tmp, tmp);
+        set_bytes(instance, &self.out, out);
+        out
+    }
+}
+
+/// The sum of a few words modulo 2^32, with the carry in `[0, 32)`.
+#[derive(Debug)]
+struct AddConfig<E: ExtensionField> {
+    sum: UInt<E>,
+    carry: WitIn,
+}
+
+impl<E: ExtensionField> AddConfig<E> {
+    fn construct_circuit(
+        cb: &mut CircuitBuilder<E>,
+        name: &str,
+        terms: Expression<E>,
+    ) -> Result<Self, ZKVMError> {
+        let sum = UInt::new(|| name.to_string(), cb)?;
+        let carry = cb.create_witin(|| format!("{name}_carry"));
+        cb.assert_ux::<_, _, 5>(|| format!("{name}_carry"), carry.expr())?;
+        cb.require_equal(
+            || format!("{name}_sum"),
+            terms,
+            sum.value() + (carry.expr() << WORD_BITS),
+        )?;
+        Ok(Self { sum, carry })
+    }
+
+    fn assign_instance(
+        &self,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        terms: &[u32],
+    ) -> u32 {
+        let total = terms.iter().map(|&t| t as u64).sum::<u64>();
+        let carry = total >> WORD_BITS;
+        lk_multiplicity.assert_ux::<5>(carry);
+        set_val!(instance, self.carry, carry);
+        self.sum
+            .assign_value(instance, Value::new(total as u32, lk_multiplicity));
+        total as u32
+    }
+}
+
+#[derive(Debug)]
+pub struct Sha256RoundsConfig<E: ExtensionField> {
+    /// The cycle of the ecall.
+    id: WitIn,
+    /// The index of the round, one-hot encoded.
+    round_flags: Vec<WitIn>,
+    /// The working state before the round.
+    a: Vec<WitIn>,
+    b: Vec<WitIn>,
+    c: Vec<WitIn>,
+    d: WitIn,
+    e: Vec<WitIn>,
+    f: Vec<WitIn>,
+    g: Vec<WitIn>,
+    h: WitIn,
+    /// The window of the message schedule before the round, and the bits of the words 1 and 14.
+    window: Vec<WitIn>,
+    w1: Vec<WitIn>,
+    w14: Vec<WitIn>,
+    sigma0_a: Xor3Config,
+    sigma1_e: Xor3Config,
+    sigma0_w1: Xor3Config,
+    sigma1_w14: Xor3Config,
+    /// `Ch(e, f, g) = (e & f) + (!e & g)`, since both terms have no common bit.
+    e_and_f: Vec<WitIn>,
+    not_e_and_g: Vec<WitIn>,
+    /// `Maj(a, b, c) = (a & b) + (c & (a ^ b))`, since both terms have no common bit.
+    a_and_b: Vec<WitIn>,
+    a_xor_b: Vec<WitIn>,
+    c_and_a_xor_b: Vec<WitIn>,
+    /// The new words `a`, `e`, and the next word of the message schedule.
+    new_a: AddConfig<E>,
+    new_e: AddConfig<E>,
+    new_w: AddConfig<E>,
+}
+
+impl<E: ExtensionField> Sha256RoundsConfig<E> {
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self, ZKVMError> {
+        let mut create_wits = |name: &str, n: usize| {
+            (0..n)
+                .map(|i| cb.create_witin(|| format!("{name}_{i}")))
+                .collect_vec()
+        };
+        let round_flags = create_wits("round", SHA256_ROUNDS);
+        let a = create_wits("a", WORD_BITS);
+        let b = create_wits("b", WORD_BYTES);
+        let c = create_wits("c", WORD_BYTES);
+        let e = create_wits("e", WORD_BITS);
+        let f = create_wits("f", WORD_BYTES);
+        let g = create_wits("g", WORD_BYTES);
+        let window = create_wits("w", SHA256_BLOCK_WORDS);
+        let w1 = create_wits("w1", WORD_BITS);
+        let w14 = create_wits("w14", WORD_BITS);
+        let e_and_f = create_wits("e_and_f", WORD_BYTES);
+        let not_e_and_g = create_wits("not_e_and_g", WORD_BYTES);
+        let a_and_b = create_wits("a_and_b", WORD_BYTES);
+        let a_xor_b = create_wits("a_xor_b", WORD_BYTES);
+        let c_and_a_xor_b = create_wits("c_and_a_xor_b", WORD_BYTES);
+        let id = cb.create_witin(|| "id");
+        let d = cb.create_witin(|| "d");
+        let h = cb.create_witin(|| "h");
+
+        for flag in &round_flags {
+            cb.assert_bit(|| "round flag", flag.expr())?;
+        }
+        cb.require_one(
+            || "one round",
+            round_flags.iter().map(|flag| flag.expr()).sum(),
+        )?;
+        let round: Expression<E> = (round_flags.iter().enumerate())
+            .map(|(r, flag)| flag.expr() * r)
+            .sum();
+        let round_constant: Expression<E> = (round_flags.iter().zip(SHA256_ROUND_CONSTANTS))
+            .map(|(flag, k)| flag.expr() * k as usize)
+            .sum();
+
+        for bit in [&a, &e, &w1, &w14].into_iter().flatten() {
+            cb.assert_bit(|| "bit", bit.expr())?;
+        }
+        cb.require_equal(|| "w1", window[1].expr(), bits_value(&w1))?;
+        cb.require_equal(|| "w14", window[14].expr(), bits_value(&w14))?;
+
+        let sigma0_a = Xor3Config::construct_circuit(cb, "sigma0_a", &a, [
+            (2, false),
+            (13, false),
+            (22, false),
+        ])?;
+        let sigma1_e = Xor3Config::construct_circuit(cb, "sigma1_e", &e, [
+            (6, false),
+            (11, false),
+            (25, false),
+        ])?;
+        let sigma0_w1 = Xor3Config::construct_circuit(cb, "sigma0_w1", &w1, [
+            (7, false),
+            (18, false),
+            (3, true),
+        ])?;
+        let sigma1_w14 = Xor3Config::construct_circuit(cb, "sigma1_w14", &w14, [
+            (17, false),
+            (19, false),
+            (10, true),
+        ])?;
+
+        // The bytes of b, c, f, g are checked by the lookups.
+        let (a_bytes, e_bytes) = (rotr_bytes(&a, 0, false), rotr_bytes(&e, 0, false));
+        for k in 0..WORD_BYTES {
+            cb.lookup_and_byte(e_bytes[k].clone(), f[k].expr(), e_and_f[k].expr())?;
+            cb.lookup_and_byte(
+                Expression::from(0xFF) - e_bytes[k].clone(),
+                g[k].expr(),
+                not_e_and_g[k].expr(),
+            )?;
+            cb.lookup_and_byte(a_bytes[k].clone(), b[k].expr(), a_and_b[k].expr())?;
+            cb.lookup_xor_byte(a_bytes[k].clone(), b[k].expr(), a_xor_b[k].expr())?;
+            cb.lookup_and_byte(c[k].expr(), a_xor_b[k].expr(), c_and_a_xor_b[k].expr())?;
+        }
+        let ch = bytes_value(exprs(&e_and_f)) + bytes_value(exprs(&not_e_and_g));
+        let maj = bytes_value(exprs(&a_and_b)) + bytes_value(exprs(&c_and_a_xor_b));
+
+        let t1 = h.expr() + sigma1_e.value() + ch + round_constant + window[0].expr();
+        let new_a = AddConfig::construct_circuit(cb, "new_a", t1.clone() + sigma0_a.value() + maj)?;
+        let new_e = AddConfig::construct_circuit(cb, "new_e", d.expr() + t1)?;
+        let new_w = AddConfig::construct_circuit(
+            cb,
+            "new_w",
+            sigma1_w14.value() + window[9].expr() + sigma0_w1.value() + window[0].expr(),
+        )?;
+
+        // Read the state before the round, and write the state after it.
+        let (a_value, e_value) = (bits_value(&a), bits_value(&e));
+        let (b_value, c_value) = (bytes_value(exprs(&b)), bytes_value(exprs(&c)));
+        let (f_value, g_value) = (bytes_value(exprs(&f)), bytes_value(exprs(&g)));
+        let record_in = sha256_state_record(
+            cb,
+            id.expr(),
+            round.clone(),
+            vec![
+                a_value.clone(),
+                b_value.clone(),
+                c_value.clone(),
+                d.expr(),
+                e_value.clone(),
+                f_value.clone(),
+                g_value.clone(),
+                h.expr(),
+            ],
+            exprs(&window),
+        );
+        cb.read_record(|| "state_in", record_in)?;
+        let record_out = sha256_state_record(
+            cb,
+            id.expr(),
+            round + 1,
+            vec![
+                new_a.sum.value(),
+                a_value,
+                b_value,
+                c_value,
+                new_e.sum.value(),
+                e_value,
+                f_value,
+                g_value,
+            ],
+            [exprs(&window[1..]), vec![new_w.sum.value()]].concat(),
+        );
+        cb.write_record(|| "state_out", record_out)?;
+
+        Ok(Self {
+            id,
+            round_flags,
+            a,
+            b,
+            c,
+            d,
+            e,
+            f,
+            g,
+            h,
+            window,
+            w1,
+            w14,
+            sigma0_a,
+            sigma1_e,
+            sigma0_w1,
+            sigma1_w14,
+            e_and_f,
+            not_e_and_g,
+            a_and_b,
+            a_xor_b,
+            c_and_a_xor_b,
+            new_a,
+            new_e,
+            new_w,
+        })
+    }
+
+    fn assign_instance(
+        &self,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        id: u64,
+        round: usize,
+        state: &[u32; SHA256_STATE_WORDS],
+        window: &[u32; SHA256_BLOCK_WORDS],
+    ) {
+        let [a, b, c, d, e, f, g, h] = *state;
+        set_val!(instance, self.id, id);
+        for (r, flag) in self.round_flags.iter().enumerate() {
+            set_val!(instance, flag, (r == round) as u64);
+        }
+        set_bits(instance, &self.a, a);
+        set_bytes(instance, &self.b, b);
+        set_bytes(instance, &self.c, c);
+        set_val!(instance, self.d, d as u64);
+        set_bits(instance, &self.e, e);
+        set_bytes(instance, &self.f, f);
+        set_bytes(instance, &self.g, g);
+        set_val!(instance, self.h, h as u64);
+        for (wit, &word) in self.window.iter().zip(window) {
+            set_val!(instance, wit, word as u64);
+        }
+        set_bits(instance, &self.w1, window[1]);
+        set_bits(instance, &self.w14, window[14]);
+
+        let sigma0_a = self.sigma0_a.assign_instance(instance, lk_multiplicity, a);
+        let sigma1_e = self.sigma1_e.assign_instance(instance, lk_multiplicity, e);
+        let sigma0_w1 = self
+            .sigma0_w1
+            .assign_instance(instance, lk_multiplicity, window[1]);
+        let sigma1_w14 = self
+            .sigma1_w14
+            .assign_instance(instance, lk_multiplicity, window[14]);
+
+        for k in 0..WORD_BYTES {
+            let byte = |v: u32| ((v >> (8 * k)) & 0xFF) as u64;
+            lk_multiplicity.lookup_and_byte(byte(e), byte(f));
+            lk_multiplicity.lookup_and_byte(byte(!e), byte(g));
+            lk_multiplicity.lookup_and_byte(byte(a), byte(b));
+            lk_multiplicity.lookup_xor_byte(byte(a), byte(b));
+            lk_multiplicity.lookup_and_byte(byte(c), byte(a ^ b));
+        }
+        set_bytes(instance, &self.e_and_f, e & f);
+        set_bytes(instance, &self.not_e_and_g, !e & g);
+        set_bytes(instance, &self.a_and_b, a & b);
+        set_bytes(instance, &self.a_xor_b, a ^ b);
+        set_bytes(instance, &self.c_and_a_xor_b, c & (a ^ b));
+        let (ch, maj) = ((e & f) + (!e & g), (a & b) + (c & (a ^ b)));
+
+        let t1 = [h, sigma1_e, ch, SHA256_ROUND_CONSTANTS[round], window[0]];
+        let new_a = self.new_a.assign_instance(
+            instance,
+            lk_multiplicity,
+            &[&t1[..], &[sigma0_a, maj]].concat(),
+        );
+        let new_e =
+            self.new_e
+                .assign_instance(instance, lk_multiplicity, &[&t1[..], &[d]].concat());
+        let new_w = self.new_w.assign_instance(instance, lk_multiplicity, &[
+            sigma1_w14, window[9], sigma0_w1, window[0],
+        ]);
+
+        debug_assert_eq!(new_w, sha256_next_word(window));
+        debug_assert_eq!([new_a, new_e], {
+            let mut next = *state;
+            sha256_round(&mut next, round, window[0]);
+            [next[0], next[4]]
+        });
+    }
+}
+
+/// The rounds of all the SHA-256 compressions of an execution, see the module documentation.
+///
+/// The steps are the ecalls SHA256_COMPRESS, and each of them is assigned to 64 rows.
+pub struct Sha256RoundsInstruction<E>(PhantomData<E>);
+
+impl<E: ExtensionField> Instruction<E> for Sha256RoundsInstruction<E> {
+    type InstructionConfig = Sha256RoundsConfig<E>;
+
+    fn name() -> String {
+        "SHA256_ROUNDS".into()
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        Sha256RoundsConfig::construct_circuit(cb)
+    }
+
+    fn assign_instance(
+        _config: &Self::InstructionConfig,
+        _instance: &mut [MaybeUninit<E::BaseField>],
+        _lk_multiplicity: &mut LkMultiplicity,
+        _step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        unreachable!("the rows of a step are assigned by assign_instances")
+    }
+
+    fn assign_instances(
+        config: &Self::InstructionConfig,
+        num_witin: usize,
+        steps: Vec<StepRecord>,
+    ) -> Result<(RowMajorMatrix<E::BaseField>, LkMultiplicity), ZKVMError> {
+        let mut rows = Vec::with_capacity(steps.len() * SHA256_ROUNDS);
+        for step in &steps {
+            assert_eq!(
+                step.rs1().unwrap().value,
+                CENO_PLATFORM.ecall_sha256_compress()
+            );
+            let words = step
+                .syscall()
+                .expect("missing syscall witness")
+                .mem_ops
+                .iter()
+                .map(|op| op.value.before)
+                .collect_vec();
+            let (state, block) = words.split_at(SHA256_STATE_WORDS);
+            let mut state: [u32; SHA256_STATE_WORDS] = state.try_into().unwrap();
+            let mut window: [u32; SHA256_BLOCK_WORDS] = block.try_into().unwrap();
+            for round in 0..SHA256_ROUNDS {
+                rows.push((step.cycle(), round, state, window));
+                sha256_round(&mut state, round, window[0]);
+                let next = sha256_next_word(&window);
+                window.rotate_left(1);
+                window[SHA256_BLOCK_WORDS - 1] = next;
+            }
+        }
+
+        let lk_multiplicity = LkMultiplicity::default();
+        let mut raw_witin = RowMajorMatrix::<E::BaseField>::new(rows.len(), num_witin);
+        raw_witin
+            .par_iter_mut()
+            .with_min_len(MIN_PAR_SIZE)
+            .zip(rows.par_iter())
+            .for_each_with(
+                lk_multiplicity.clone(),
+                |lk_multiplicity, (instance, (id, round, state, window))| {
+                    config.assign_instance(instance, lk_multiplicity, *id, *round, state, window);
+                },
+            );
+
+        // Like the other opcode circuits, pad with copies of the last row.
+        if raw_witin.num_padding_instances() > 0 {
+            let padding_instance = raw_witin[rows.len() - 1].to_vec();
+            raw_witin
+                .par_batch_iter_padding_mut(raw_witin.num_padding_instances())
+                .for_each(|row| {
+                    row.chunks_mut(num_witin)
+                        .for_each(|instance| instance.copy_from_slice(&padding_instance));
+                });
+        }
+
+        Ok((raw_witin, lk_multiplicity))
+    }
+}
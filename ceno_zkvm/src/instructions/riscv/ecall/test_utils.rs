@@ -0,0 +1,86 @@
+//! Helpers to test the circuits of syscalls, and the chain of records between them.
+
+use crate::{expression::Expression, scheme::utils::wit_infer_by_expr, witness::RowMajorMatrix};
+use ceno_emul::{
+    ByteAddr, CENO_PLATFORM, InsnKind::ADDI, Program, StepRecord, VMState, WORD_SIZE, Word,
+    encode_rv32,
+};
+use ff_ext::ExtensionField;
+use goldilocks::{Goldilocks, GoldilocksExt2};
+use itertools::Itertools;
+use multilinear_extensions::{mle::IntoMLEs, virtual_poly_v2::ArcMultilinearExtension};
+
+type E = GoldilocksExt2;
+
+pub const ECALL: u32 = 0x73;
+
+/// Run the ecall `function` with the arguments `args` in arg0 and arg1, on a memory initialized
+/// with `memory`, and return its step.
+pub fn ecall_step(function: u32, args: &[u32], memory: &[(ByteAddr, Vec<Word>)]) -> StepRecord {
+    let code = [ECALL, encode_rv32(ADDI, 0, 0, 5, 0), ECALL];
+    let program = Program::new(
+        CENO_PLATFORM.pc_base(),
+        CENO_PLATFORM.pc_base(),
+        code.to_vec(),
+        (CENO_PLATFORM.pc_base()..)
+            .step_by(WORD_SIZE)
+            .zip(code)
+            .collect(),
+    );
+    let mut vm = VMState::new(CENO_PLATFORM, program);
+    vm.init_register_unsafe(CENO_PLATFORM.reg_ecall(), function);
+    for (reg, &arg) in [CENO_PLATFORM.reg_arg0(), CENO_PLATFORM.reg_arg1()]
+        .into_iter()
+        .zip(args)
+    {
+        vm.init_register_unsafe(reg, arg);
+    }
+    for (addr, words) in memory {
+        for (i, &word) in words.iter().enumerate() {
+            vm.init_memory(addr.waddr() + i, word);
+        }
+    }
+    let steps = vm.iter_until_halt().collect::<Result<Vec<_>, _>>().unwrap();
+    steps.into_iter().next().unwrap()
+}
+
+pub fn into_mles(witness: RowMajorMatrix<Goldilocks>) -> Vec<ArcMultilinearExtension<'static, E>> {
+    witness
+        .de_interleaving()
+        .into_mles()
+        .into_iter()
+        .map(|mle| mle.into())
+        .collect_vec()
+}
+
+/// Evaluate the named records on the instances of a circuit.
+pub fn eval_records(
+    (exprs, names): (&[Expression<E>], &[String]),
+    wits_in: &[ArcMultilinearExtension<E>],
+    num_instances: usize,
+    name: &str,
+) -> Vec<E> {
+    let challenges = [E::from(1000), E::from(2000)];
+    exprs
+        .iter()
+        .zip_eq(names)
+        .filter(|(_, path)| path.contains(name))
+        .flat_map(|(expr, _)| {
+            wit_infer_by_expr(&[], wits_in, &[], &challenges, expr).get_ext_field_vec()
+                [..num_instances]
+                .to_vec()
+        })
+        .collect()
+}
+
+/// Check that the records written by some circuits are the records read by the others.
+pub fn assert_chain(writes: Vec<Vec<E>>, reads: Vec<Vec<E>>) {
+    let sorted = |records: Vec<Vec<E>>| {
+        records
+            .concat()
+            .into_iter()
+            .sorted_by_key(|record| record.to_canonical_u64_vec())
+            .collect_vec()
+    };
+    assert_eq!(sorted(writes), sorted(reads));
+}
@@ -0,0 +1,662 @@
+use crate::{
+    Value,
+    chip_handler::{MemoryChipOperations, RegisterChipOperations},
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    gadgets::AssertLTConfig,
+    instructions::{
+        Instruction,
+        riscv::{
+            constants::{
+                ECALL_UINT256_ADD_OPCODE, ECALL_UINT256_MUL_OPCODE, ECALL_UINT256_MULMOD_OPCODE,
+                UInt, UInt256, UInt512,
+            },
+            ecall_insn::EcallInstructionConfig,
+        },
+    },
+    set_val,
+    uint::{ValueAdd, ValueMul, util::max_carry_word_for_multiplication},
+    witness::LkMultiplicity,
+};
+use ceno_emul::{CENO_PLATFORM, StepRecord, Tracer, UINT256_WORDS, WORD_SIZE, Word};
+use ff_ext::ExtensionField;
+use itertools::Itertools;
+use std::{marker::PhantomData, mem::MaybeUninit};
+
+/// The number of 16-bit limbs of a 256-bit integer.
+const UINT256_LIMBS: usize = 2 * UINT256_WORDS;
+
+/// The 16-bit limbs of an integer, little-endian.
+pub(super) type Limbs = Vec<u16>;
+
+/// The 16-bit limbs of an integer, from its little-endian words.
+pub(super) fn u16_limbs(words: &[Word]) -> Limbs {
+    words
+        .iter()
+        .flat_map(|&word| [word as u16, (word >> 16) as u16])
+        .collect()
+}
+
+/// The outcome of `UIntLimbs::add` on the limbs `a` and `b`, with the range checks of the limbs
+/// of the sum.
+pub(super) fn add_outcome(
+    a: &[u16],
+    b: &[u16],
+    with_overflow: bool,
+    lkm: &mut LkMultiplicity,
+) -> ValueAdd {
+    let mut carry = 0;
+    let (limbs, mut carries): (Vec<u16>, Vec<u16>) = a
+        .iter()
+        .zip_eq(b)
+        .map(|(&a, &b)| {
+            let sum = a as u32 + b as u32 + carry;
+            carry = sum >> 16;
+            lkm.assert_ux::<16>(sum as u16 as u64);
+            (sum as u16, carry as u16)
+        })
+        .unzip();
+    if !with_overflow {
+        assert_eq!(carries.pop(), Some(0), "the sum overflows");
+    }
+    ValueAdd { limbs, carries }
+}
+
+/// The outcome of `UIntLimbs::mul` on the 256-bit limbs `a` and `b`, with `num_limbs` limbs of
+/// the product, and the range checks of these limbs.
+fn mul_outcome(
+    a: &[u16],
+    b: &[u16],
+    num_limbs: usize,
+    with_overflow: bool,
+    lkm: &mut LkMultiplicity,
+) -> ValueMul {
+    let mut columns = vec![0u64; num_limbs];
+    for (i, &a) in a.iter().enumerate() {
+        for (j, &b) in b.iter().enumerate() {
+            if i + j < num_limbs {
+                columns[i + j] += a as u64 * b as u64;
+            }
+        }
+    }
+    let mut carry = 0;
+    let (limbs, mut carries): (Vec<u16>, Vec<u64>) = columns
+        .into_iter()
+        .map(|column| {
+            let sum = column + carry;
+            carry = sum >> 16;
+            lkm.assert_ux::<16>(sum as u16 as u64);
+            (sum as u16, carry)
+        })
+        .unzip();
+    if !with_overflow {
+        assert_eq!(carries.pop(), Some(0), "the product overflows");
+    }
+    ValueMul {
+        limbs,
+        carries,
+        max_carry_value: max_carry_word_for_multiplication(2, 256, 16),
+    }
+}
+
+/// Assign the range-checked limbs of a 256-bit integer.
+pub(super) fn assign_uint256<E: ExtensionField>(
+    uint: &UInt256<E>,
+    instance: &mut [MaybeUninit<E::BaseField>],
+    lkm: &mut LkMultiplicity,
+    limbs: &[u16],
+) {
+    uint.assign_limbs(instance, limbs);
+    for &limb in limbs {
+        lkm.assert_ux::<16>(limb as u64);
+    }
+}
+
+/// The memory words of a 256-bit integer, from its 16-bit limbs.
+fn word_exprs<E: ExtensionField>(limbs: &[Expression<E>]) -> Vec<Expression<E>> {
+    limbs
+        .chunks(2)
+        .map(|limbs| limbs[0].clone() + (limbs[1].clone() << 16))
+        .collect()
+}
+
+/// The access of a word of memory.
+struct WordConfig {
+    prev_ts: WitIn,
+    lt_cfg: AssertLTConfig,
+}
+
+/// The read of the register arg1 (X11), which holds the address of the inputs.
+struct InputsPtrConfig<E: ExtensionField> {
+    inputs_ptr: UInt<E>,
+    prev_x11_ts: WitIn,
+    lt_x11_cfg: AssertLTConfig,
+}
+
+/// The operands of an ecall on 256-bit integers: the integers `x` at the address in arg0, which
+/// are replaced by the results, and the inputs at the address in arg1, if any.
+pub(super) struct OperandsConfig<E: ExtensionField> {
+    ecall_cfg: EcallInstructionConfig,
+    x_ptr: UInt<E>,
+    prev_x10_ts: WitIn,
+    lt_x10_cfg: AssertLTConfig,
+    inputs_ptr: Option<InputsPtrConfig<E>>,
+    pub x: Vec<UInt256<E>>,
+    pub inputs: Vec<UInt256<E>>,
+    x_words: Vec<WordConfig>,
+    input_words: Vec<WordConfig>,
+}
+
+impl<E: ExtensionField> OperandsConfig<E> {
+    /// Read the registers, `num_x` integers `x` and `num_inputs` inputs. The results must be
+    /// written with `write_results`.
+    pub fn construct_circuit(
+        cb: &mut CircuitBuilder<E>,
+        opcode: [usize; 2],
+        num_x: usize,
+        num_inputs: usize,
+    ) -> Result<Self, ZKVMError> {
+        let ecall_cfg = EcallInstructionConfig::construct_circuit(
+            cb,
+            [opcode[0].into(), opcode[1].into()],
+            None,
+            None,
+        )?;
+
+        // read x_ptr from arg0 (X10 register)
+        let x_ptr = UInt::new_unchecked(|| "x_ptr", cb)?;
+        let prev_x10_ts = cb.create_witin(|| "prev_x10_ts");
+        let (_, lt_x10_cfg) = cb.register_read(
+            || "read x10",
+            E::BaseField::from(CENO_PLATFORM.reg_arg0() as u64),
+            prev_x10_ts.expr(),
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_RS2,
+            x_ptr.register_expr(),
+        )?;
+
+        let x = (0..num_x)
+            .map(|i| UInt256::new(|| format!("x_{i}"), cb))
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+        let inputs = (0..num_inputs)
+            .map(|i| UInt256::new(|| format!("input_{i}"), cb))
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+        if num_inputs == 0 {
+            return Ok(OperandsConfig {
+                ecall_cfg,
+                x_ptr,
+                prev_x10_ts,
+                lt_x10_cfg,
+                inputs_ptr: None,
+                x,
+                inputs,
+                x_words: vec![],
+                input_words: vec![],
+            });
+        }
+
+        // read inputs_ptr from arg1 (X11 register), recorded by the syscall at the subcycle RD
+        let inputs_ptr = UInt::new_unchecked(|| "inputs_ptr", cb)?;
+        let prev_x11_ts = cb.create_witin(|| "prev_x11_ts");
+        let (_, lt_x11_cfg) = cb.register_read(
+            || "read x11",
+            E::BaseField::from(CENO_PLATFORM.reg_arg1() as u64),
+            prev_x11_ts.expr(),
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_RD,
+            inputs_ptr.register_expr(),
+        )?;
+
+        let input_words = inputs
+            .iter()
+            .flat_map(|input| word_exprs(&input.expr()))
+            .enumerate()
+            .map(|(i, value)| {
+                let prev_ts = cb.create_witin(|| format!("prev_input_ts_{i}"));
+                let (_, lt_cfg) = cb.memory_read(
+                    || format!("read input_{i}"),
+                    &(inputs_ptr.address_expr() + i * WORD_SIZE),
+                    prev_ts.expr(),
+                    ecall_cfg.ts.expr() + Tracer::SUBCYCLE_MEM,
+                    value,
+                )?;
+                Ok(WordConfig { prev_ts, lt_cfg })
+            })
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+
+        Ok(OperandsConfig {
+            ecall_cfg,
+            x_ptr,
+            prev_x10_ts,
+            lt_x10_cfg,
+            inputs_ptr: Some(InputsPtrConfig {
+                inputs_ptr,
+                prev_x11_ts,
+                lt_x11_cfg,
+            }),
+            x,
+            inputs,
+            x_words: vec![],
+            input_words,
+        })
+    }
+
+    /// Replace the integers `x` with the results, given by their 16-bit limbs.
+    pub fn write_results(
+        &mut self,
+        cb: &mut CircuitBuilder<E>,
+        results: &[Vec<Expression<E>>],
+    ) -> Result<(), ZKVMError> {
+        let prev_words = self.x.iter().flat_map(|x| word_exprs(&x.expr()));
+        let words = results.iter().flat_map(|result| word_exprs(result));
+        self.x_words = prev_words
+            .zip_eq(words)
+            .enumerate()
+            .map(|(i, (prev_value, value))| {
+                let prev_ts = cb.create_witin(|| format!("prev_x_ts_{i}"));
+                let (_, lt_cfg) = cb.memory_write(
+                    || format!("write x_{i}"),
+                    &(self.x_ptr.address_expr() + i * WORD_SIZE),
+                    prev_ts.expr(),
+                    self.ecall_cfg.ts.expr() + Tracer::SUBCYCLE_MEM,
+                    prev_value,
+                    value,
+                )?;
+                Ok(WordConfig { prev_ts, lt_cfg })
+            })
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+        Ok(())
+    }
+
+    /// Assign the accesses of the step, and return the limbs of the integers `x` and of the
+    /// inputs.
+    pub fn assign_instance(
+        &self,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+        opcode: [usize; 2],
+    ) -> Result<(Vec<Limbs>, Vec<Limbs>), ZKVMError> {
+        assert_eq!(
+            step.rs1().unwrap().value,
+            (opcode[0] + (opcode[1] << 16)) as u32
+        );
+
+        self.ecall_cfg
+            .assign_instance::<E>(instance, lk_multiplicity, step)?;
+
+        // the access of X10 register is stored in rs2()
+        let x_ptr = step.rs2().unwrap();
+        self.x_ptr
+            .assign_value(instance, Value::new_unchecked(x_ptr.value));
+        set_val!(instance, self.prev_x10_ts, x_ptr.previous_cycle);
+        self.lt_x10_cfg.assign_instance(
+            instance,
+            lk_multiplicity,
+            x_ptr.previous_cycle,
+            step.cycle() + Tracer::SUBCYCLE_RS2,
+        )?;
+
+        // the access of X11 register is stored in the syscall witness
+        let syscall = step.syscall().expect("missing syscall witness");
+        if let Some(config) = &self.inputs_ptr {
+            let inputs_ptr = &syscall.reg_ops[0];
+            config
+                .inputs_ptr
+                .assign_value(instance, Value::new_unchecked(inputs_ptr.value));
+            set_val!(instance, config.prev_x11_ts, inputs_ptr.previous_cycle);
+            config.lt_x11_cfg.assign_instance(
+                instance,
+                lk_multiplicity,
+                inputs_ptr.previous_cycle,
+                step.cycle() + Tracer::SUBCYCLE_RD,
+            )?;
+        }
+
+        let words = self.x_words.iter().chain(&self.input_words);
+        for (word, op) in words.zip_eq(&syscall.mem_ops) {
+            set_val!(instance, word.prev_ts, op.previous_cycle);
+            word.lt_cfg.assign_instance(
+                instance,
+                lk_multiplicity,
+                op.previous_cycle,
+                step.cycle() + Tracer::SUBCYCLE_MEM,
+            )?;
+        }
+
+        let mut uints = self.x.iter().chain(&self.inputs);
+        let mut limbs = syscall
+            .mem_ops
+            .chunks(UINT256_WORDS)
+            .zip_eq(&mut uints)
+            .map(|(ops, uint)| {
+                let limbs = u16_limbs(&ops.iter().map(|op| op.value.before).collect_vec());
+                assign_uint256(uint, instance, lk_multiplicity, &limbs);
+                limbs
+            })
+            .collect_vec();
+        let inputs = limbs.split_off(self.x.len());
+        Ok((limbs, inputs))
+    }
+}
+
+pub struct Uint256AddConfig<E: ExtensionField> {
+    operands: OperandsConfig<E>,
+    sum: UInt256<E>,
+}
+
+/// The ecall UINT256_ADD, which replaces `x` at the address in arg0 with `x + y mod 2^256`, where
+/// `y` is at the address in arg1.
+pub struct Uint256AddInstruction<E>(PhantomData<E>);
+
+impl<E: ExtensionField> Instruction<E> for Uint256AddInstruction<E> {
+    type InstructionConfig = Uint256AddConfig<E>;
+
+    fn name() -> String {
+        "ECALL_UINT256_ADD".into()
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let mut operands = OperandsConfig::construct_circuit(cb, ECALL_UINT256_ADD_OPCODE, 1, 1)?;
+        let sum = operands.x[0].add(|| "x_plus_y", cb, &operands.inputs[0], true)?;
+        operands.write_results(cb, &[sum.expr()])?;
+        Ok(Uint256AddConfig { operands, sum })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        let (x, inputs) = config.operands.assign_instance(
+            instance,
+            lk_multiplicity,
+            step,
+            ECALL_UINT256_ADD_OPCODE,
+        )?;
+        let sum = add_outcome(&x[0], &inputs[0], true, lk_multiplicity);
+        config.sum.assign_add_outcome(instance, &sum);
+        Ok(())
+    }
+}
+
+pub struct Uint256MulConfig<E: ExtensionField> {
+    operands: OperandsConfig<E>,
+    product: UInt256<E>,
+}
+
+/// The ecall UINT256_MUL, which replaces `x` at the address in arg0 with `x * y mod 2^256`, where
+/// `y` is at the address in arg1.
+pub struct Uint256MulInstruction<E>(PhantomData<E>);
+
+impl<E: ExtensionField> Instruction<E> for Uint256MulInstruction<E> {
+    type InstructionConfig = Uint256MulConfig<E>;
+
+    fn name() -> String {
+        "ECALL_UINT256_MUL".into()
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let mut operands = OperandsConfig::construct_circuit(cb, ECALL_UINT256_MUL_OPCODE, 1, 1)?;
+        let product = operands.x[0].mul(|| "x_times_y", cb, &mut operands.inputs[0], true)?;
+        operands.write_results(cb, &[product.expr()])?;
+        Ok(Uint256MulConfig { operands, product })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        let (x, inputs) = config.operands.assign_instance(
+            instance,
+            lk_multiplicity,
+            step,
+            ECALL_UINT256_MUL_OPCODE,
+        )?;
+        let product = mul_outcome(&x[0], &inputs[0], UINT256_LIMBS, true, lk_multiplicity);
+        config
+            .product
+            .assign_mul_outcome(instance, lk_multiplicity, &product)
+    }
+}
+
+pub struct Uint256MulModConfig<E: ExtensionField> {
+    operands: OperandsConfig<E>,
+    /// `x * y` over 512 bits.
+    product: UInt512<E>,
+    quotient: UInt256<E>,
+    remainder: UInt256<E>,
+    /// `quotient * m` over 512 bits.
+    multiple: UInt512<E>,
+    /// `quotient * m + remainder`, equal to the product.
+    multiple_plus_remainder: UInt512<E>,
+    /// `m - remainder - 1`, which fits in 256 bits when `remainder < m`.
+    gap: UInt256<E>,
+    remainder_plus_one: UInt256<E>,
+    /// `remainder + 1 + gap`, equal to `m`.
+    remainder_plus_gap: UInt256<E>,
+}
+
+/// The ecall UINT256_MULMOD, which replaces `x` at the address in arg0 with `x * y mod m`, where
+/// `y` followed by `m` are at the address in arg1.
+///
+/// The circuit checks that `x * y = quotient * m + remainder` over 512 bits with a 256-bit
+/// quotient, and that `remainder < m`. The emulator rejects the inputs for which the quotient
+/// does not fit, see `ceno_emul::uint256_mulmod`.
+pub struct Uint256MulModInstruction<E>(PhantomData<E>);
+
+impl<E: ExtensionField> Instruction<E> for Uint256MulModInstruction<E> {
+    type InstructionConfig = Uint256MulModConfig<E>;
+
+    fn name() -> String {
+        "ECALL_UINT256_MULMOD".into()
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let mut operands =
+            OperandsConfig::construct_circuit(cb, ECALL_UINT256_MULMOD_OPCODE, 1, 2)?;
+        let [y, m] = &mut operands.inputs[..] else {
+            unreachable!()
+        };
+
+        let product = operands.x[0].mul(|| "x_times_y", cb, y, false)?;
+        let mut quotient = UInt256::new(|| "quotient", cb)?;
+        let remainder = UInt256::new(|| "remainder", cb)?;
+        let multiple = quotient.mul(|| "quotient_times_m", cb, m, false)?;
+        let multiple_plus_remainder = multiple.add(
+            || "multiple_plus_remainder",
+            cb,
+            &UInt512::from_exprs_unchecked(remainder.expr()),
+            false,
+        )?;
+        for (i, (lhs, rhs)) in multiple_plus_remainder
+            .expr()
+            .into_iter()
+            .zip_eq(product.expr())
+            .enumerate()
+        {
+            cb.require_equal(|| format!("division_limb_{i}"), lhs, rhs)?;
+        }
+
+        // remainder < m
+        let gap = UInt256::new(|| "gap", cb)?;
+        let remainder_plus_one =
+            remainder.add_const(|| "remainder_plus_one", cb, 1.into(), false)?;
+        let remainder_plus_gap =
+            remainder_plus_one.add(|| "remainder_plus_gap", cb, &gap, false)?;
+        for (i, (lhs, rhs)) in remainder_plus_gap
+            .expr()
+            .into_iter()
+            .zip_eq(m.expr())
+            .enumerate()
+        {
+            cb.require_equal(|| format!("remainder_lt_m_limb_{i}"), lhs, rhs)?;
+        }
+
+        operands.write_results(cb, &[remainder.expr()])?;
+        Ok(Uint256MulModConfig {
+            operands,
+            product,
+            quotient,
+            remainder,
+            multiple,
+            multiple_plus_remainder,
+            gap,
+            remainder_plus_one,
+            remainder_plus_gap,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        let (x, inputs) = config.operands.assign_instance(
+            instance,
+            lk_multiplicity,
+            step,
+            ECALL_UINT256_MULMOD_OPCODE,
+        )?;
+        let [y, m] = &inputs[..] else { unreachable!() };
+
+        let words = |limbs: &[u16]| -> [Word; UINT256_WORDS] {
+            std::array::from_fn(|i| limbs[2 * i] as Word | (limbs[2 * i + 1] as Word) << 16)
+        };
+        let (quotient, remainder) = ceno_emul::uint256_mulmod(&words(&x[0]), &words(y), &words(m))
+            .expect("undefined UINT256_MULMOD");
+        let (quotient, remainder) = (u16_limbs(&quotient), u16_limbs(&remainder));
+
+        let product = mul_outcome(&x[0], y, 2 * UINT256_LIMBS, false, lk_multiplicity);
+        config
+            .product
+            .assign_mul_outcome(instance, lk_multiplicity, &product)?;
+        assign_uint256(&config.quotient, instance, lk_multiplicity, &quotient);
+        assign_uint256(&config.remainder, instance, lk_multiplicity, &remainder);
+        let multiple = mul_outcome(&quotient, m, 2 * UINT256_LIMBS, false, lk_multiplicity);
+        config
+            .multiple
+            .assign_mul_outcome(instance, lk_multiplicity, &multiple)?;
+        let wide_remainder = remainder
+            .iter()
+            .copied()
+            .pad_using(2 * UINT256_LIMBS, |_| 0)
+            .collect_vec();
+        let multiple_plus_remainder =
+            add_outcome(&multiple.limbs, &wide_remainder, false, lk_multiplicity);
+        config
+            .multiple_plus_remainder
+            .assign_add_outcome(instance, &multiple_plus_remainder);
+
+        // gap = m - remainder - 1
+        let mut borrow = 1;
+        let gap = m
+            .iter()
+            .zip_eq(&remainder)
+            .map(|(&m, &r)| {
+                let diff = m as i64 - r as i64 - borrow;
+                borrow = (diff < 0) as i64;
+                diff.rem_euclid(1 << 16) as u16
+            })
+            .collect_vec();
+        assert_eq!(borrow, 0, "the remainder is not less than the modulus");
+        assign_uint256(&config.gap, instance, lk_multiplicity, &gap);
+        let mut one = vec![0; UINT256_LIMBS];
+        one[0] = 1;
+        let remainder_plus_one = add_outcome(&remainder, &one, false, lk_multiplicity);
+        config
+            .remainder_plus_one
+            .assign_add_outcome(instance, &remainder_plus_one);
+        let remainder_plus_gap =
+            add_outcome(&remainder_plus_one.limbs, &gap, false, lk_multiplicity);
+        config
+            .remainder_plus_gap
+            .assign_add_outcome(instance, &remainder_plus_gap);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        circuit_builder::ConstraintSystem,
+        instructions::riscv::ecall::test_utils::{ECALL, ecall_step, into_mles},
+        scheme::mock_prover::MockProver,
+    };
+    use ceno_emul::ByteAddr;
+    use goldilocks::GoldilocksExt2;
+
+    type E = GoldilocksExt2;
+
+    fn verify<I: Instruction<E>>(function: u32, x: [Word; 8], inputs: Vec<Word>) -> Vec<Word> {
+        let x_ptr = ByteAddr(CENO_PLATFORM.ram_start());
+        let inputs_ptr = ByteAddr(CENO_PLATFORM.ram_start() + 0x100);
+        let step = ecall_step(function, &[x_ptr.0, inputs_ptr.0], &[
+            (x_ptr, x.to_vec()),
+            (inputs_ptr, inputs),
+        ]);
+        let result = step.syscall().unwrap().mem_ops[..UINT256_WORDS]
+            .iter()
+            .map(|op| op.value.after)
+            .collect();
+
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = cb
+            .namespace(|| I::name(), |cb| Ok(I::construct_circuit(cb)))
+            .unwrap()
+            .unwrap();
+        let (raw_witin, lkm) =
+            I::assign_instances(&config, cb.cs.num_witin as usize, vec![step]).unwrap();
+        MockProver::assert_satisfied(&cb, &into_mles(raw_witin), &[ECALL], None, Some(lkm));
+        result
+    }
+
+    #[test]
+    fn test_uint256_add() {
+        let x = [u32::MAX; 8];
+        let y = vec![2, 0, 0, 0, 0, 0, 0, 0];
+        let sum = verify::<Uint256AddInstruction<E>>(CENO_PLATFORM.ecall_uint256_add(), x, y);
+        assert_eq!(sum, [1, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_uint256_mul() {
+        let x = [u32::MAX; 8];
+        let y = vec![u32::MAX; 8];
+        let product = verify::<Uint256MulInstruction<E>>(CENO_PLATFORM.ecall_uint256_mul(), x, y);
+        assert_eq!(product, [1, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_uint256_mulmod() {
+        // Modulo the order of the field of secp256k1, 2^256 - 2^32 - 977.
+        let mut m = vec![u32::MAX; 8];
+        m[0] = 0xFFFF_FC2F;
+        m[1] = 0xFFFF_FFFE;
+        let x = std::array::from_fn(|i| 0x1234_5678 * (i as u32 + 1));
+        let inputs = [vec![u32::MAX - 5; 8], m.clone()].concat();
+        let expected =
+            ceno_emul::uint256_mulmod(&x, &[u32::MAX - 5; 8], &m.clone().try_into().unwrap())
+                .unwrap()
+                .1;
+        let remainder =
+            verify::<Uint256MulModInstruction<E>>(CENO_PLATFORM.ecall_uint256_mulmod(), x, inputs);
+        assert_eq!(remainder, expected);
+
+        // The remainder is m - 1.
+        let mut m_minus_one: [Word; 8] = m.clone().try_into().unwrap();
+        m_minus_one[0] -= 1;
+        let inputs = [vec![1, 0, 0, 0, 0, 0, 0, 0], m].concat();
+        let remainder = verify::<Uint256MulModInstruction<E>>(
+            CENO_PLATFORM.ecall_uint256_mulmod(),
+            m_minus_one,
+            inputs,
+        );
+        assert_eq!(remainder, m_minus_one);
+    }
+}
@@ -3,9 +3,9 @@ use crate::{
     instructions::Instruction,
     structs::{ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMWitnesses},
     tables::{
-        AndTableCircuit, LtuTableCircuit, MemCircuit, MemFinalRecord, MemInitRecord,
-        ProgramDataCircuit, PubIOCircuit, RegTableCircuit, TableCircuit, U14TableCircuit,
-        U16TableCircuit,
+        AndTableCircuit, KeccakRoundsCircuit, LtuTableCircuit, MemCircuit, MemFinalRecord,
+        MemInitRecord, ProgramDataCircuit, PubIOCircuit, PubIOHashCircuit, RegTableCircuit,
+        TableCircuit, U5TableCircuit, U14TableCircuit, U16TableCircuit, XorTableCircuit,
     },
 };
 use ceno_emul::{CENO_PLATFORM, InsnKind, StepRecord};
@@ -14,7 +14,12 @@ use ff_ext::ExtensionField;
 use super::{
     arith::AddInstruction,
     branch::BltuInstruction,
-    ecall::HaltInstruction,
+    ecall::{
+        Ed25519AddInstruction, Ed25519DoubleInstruction, HaltInstruction, KeccakInstruction,
+        PoseidonInstruction, Secp256k1AddInstruction, Secp256k1DoubleInstruction,
+        Sha256Instruction, Sha256RoundsInstruction, Uint256AddInstruction, Uint256MulInstruction,
+        Uint256MulModInstruction,
+    },
     jump::{JalInstruction, LuiInstruction},
     memory::LwInstruction,
 };
@@ -28,21 +33,56 @@ pub struct Rv32imConfig<E: ExtensionField> {
     pub lui_config: <LuiInstruction<E> as Instruction<E>>::InstructionConfig,
     pub lw_config: <LwInstruction<E> as Instruction<E>>::InstructionConfig,
 
+    // Precompiles.
+    pub keccak_config: <KeccakInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub keccak_rounds_config: <KeccakRoundsCircuit<E> as TableCircuit<E>>::TableConfig,
+    pub sha256_config: <Sha256Instruction<E> as Instruction<E>>::InstructionConfig,
+    pub sha256_rounds_config: <Sha256RoundsInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub poseidon_config: <PoseidonInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub uint256_add_config: <Uint256AddInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub uint256_mul_config: <Uint256MulInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub uint256_mulmod_config: <Uint256MulModInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub secp256k1_add_config: <Secp256k1AddInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub secp256k1_double_config:
+        <Secp256k1DoubleInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub ed25519_add_config: <Ed25519AddInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub ed25519_double_config: <Ed25519DoubleInstruction<E> as Instruction<E>>::InstructionConfig,
+
     // Tables.
     pub u16_range_config: <U16TableCircuit<E> as TableCircuit<E>>::TableConfig,
     pub u14_range_config: <U14TableCircuit<E> as TableCircuit<E>>::TableConfig,
+    pub u5_range_config: <U5TableCircuit<E> as TableCircuit<E>>::TableConfig,
     pub and_config: <AndTableCircuit<E> as TableCircuit<E>>::TableConfig,
+    pub xor_config: <XorTableCircuit<E> as TableCircuit<E>>::TableConfig,
     pub ltu_config: <LtuTableCircuit<E> as TableCircuit<E>>::TableConfig,
 
     // RW tables.
     pub reg_config: <RegTableCircuit<E> as TableCircuit<E>>::TableConfig,
     pub mem_config: <MemCircuit<E> as TableCircuit<E>>::TableConfig,
     pub program_data_config: <ProgramDataCircuit<E> as TableCircuit<E>>::TableConfig,
-    pub public_io_config: <PubIOCircuit<E> as TableCircuit<E>>::TableConfig,
+    pub public_io_config: PublicIOConfig<E>,
+}
+
+/// How the public IO is proven.
+pub enum PublicIOConfig<E: ExtensionField> {
+    /// The whole public IO is a public value.
+    Table(<PubIOCircuit<E> as TableCircuit<E>>::TableConfig),
+    /// Only the digest of the public IO is a public value, see `PubIOHashCircuit`. It requires
+    /// the global state `HashedIOGlobalState`.
+    Hashed(<PubIOHashCircuit<E> as TableCircuit<E>>::TableConfig),
 }
 
 impl<E: ExtensionField> Rv32imConfig<E> {
     pub fn construct_circuits(cs: &mut ZKVMConstraintSystem<E>) -> Self {
+        Self::construct_circuits_with_public_io(cs, false)
+    }
+
+    /// Construct the circuits, where only the digest of the public IO is a public value.
+    pub fn construct_circuits_with_hashed_io(cs: &mut ZKVMConstraintSystem<E>) -> Self {
+        Self::construct_circuits_with_public_io(cs, true)
+    }
+
+    fn construct_circuits_with_public_io(cs: &mut ZKVMConstraintSystem<E>, hashed: bool) -> Self {
         // opcode circuits
         let add_config = cs.register_opcode_circuit::<AddInstruction<E>>();
         let bltu_config = cs.register_opcode_circuit::<BltuInstruction>();
@@ -51,10 +91,26 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         let lui_config = cs.register_opcode_circuit::<LuiInstruction<E>>();
         let lw_config = cs.register_opcode_circuit::<LwInstruction<E>>();
 
+        // precompile circuits
+        let keccak_config = cs.register_opcode_circuit::<KeccakInstruction<E>>();
+        let keccak_rounds_config = cs.register_table_circuit::<KeccakRoundsCircuit<E>>();
+        let sha256_config = cs.register_opcode_circuit::<Sha256Instruction<E>>();
+        let sha256_rounds_config = cs.register_opcode_circuit::<Sha256RoundsInstruction<E>>();
+        let poseidon_config = cs.register_opcode_circuit::<PoseidonInstruction<E>>();
+        let uint256_add_config = cs.register_opcode_circuit::<Uint256AddInstruction<E>>();
+        let uint256_mul_config = cs.register_opcode_circuit::<Uint256MulInstruction<E>>();
+        let uint256_mulmod_config = cs.register_opcode_circuit::<Uint256MulModInstruction<E>>();
+        let secp256k1_add_config = cs.register_opcode_circuit::<Secp256k1AddInstruction<E>>();
+        let secp256k1_double_config = cs.register_opcode_circuit::<Secp256k1DoubleInstruction<E>>();
+        let ed25519_add_config = cs.register_opcode_circuit::<Ed25519AddInstruction<E>>();
+        let ed25519_double_config = cs.register_opcode_circuit::<Ed25519DoubleInstruction<E>>();
+
         // tables
         let u16_range_config = cs.register_table_circuit::<U16TableCircuit<E>>();
         let u14_range_config = cs.register_table_circuit::<U14TableCircuit<E>>();
+        let u5_range_config = cs.register_table_circuit::<U5TableCircuit<E>>();
         let and_config = cs.register_table_circuit::<AndTableCircuit<E>>();
+        let xor_config = cs.register_table_circuit::<XorTableCircuit<E>>();
         let ltu_config = cs.register_table_circuit::<LtuTableCircuit<E>>();
 
         // RW tables
@@ -63,7 +119,11 @@ impl<E: ExtensionField> Rv32imConfig<E> {
 
         // RO tables
         let program_data_config = cs.register_table_circuit::<ProgramDataCircuit<E>>();
-        let public_io_config = cs.register_table_circuit::<PubIOCircuit<E>>();
+        let public_io_config = if hashed {
+            PublicIOConfig::Hashed(cs.register_table_circuit::<PubIOHashCircuit<E>>())
+        } else {
+            PublicIOConfig::Table(cs.register_table_circuit::<PubIOCircuit<E>>())
+        };
 
         Self {
             add_config,
@@ -72,9 +132,23 @@ impl<E: ExtensionField> Rv32imConfig<E> {
             halt_config,
             lui_config,
             lw_config,
+            keccak_config,
+            keccak_rounds_config,
+            sha256_config,
+            sha256_rounds_config,
+            poseidon_config,
+            uint256_add_config,
+            uint256_mul_config,
+            uint256_mulmod_config,
+            secp256k1_add_config,
+            secp256k1_double_config,
+            ed25519_add_config,
+            ed25519_double_config,
             u16_range_config,
             u14_range_config,
+            u5_range_config,
             and_config,
+            xor_config,
             ltu_config,
 
             reg_config,
@@ -98,9 +172,24 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         fixed.register_opcode_circuit::<LuiInstruction<E>>(cs);
         fixed.register_opcode_circuit::<LwInstruction<E>>(cs);
 
+        fixed.register_opcode_circuit::<KeccakInstruction<E>>(cs);
+        fixed.register_table_circuit::<KeccakRoundsCircuit<E>>(cs, &self.keccak_rounds_config, &());
+        fixed.register_opcode_circuit::<Sha256Instruction<E>>(cs);
+        fixed.register_opcode_circuit::<Sha256RoundsInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<PoseidonInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<Uint256AddInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<Uint256MulInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<Uint256MulModInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<Secp256k1AddInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<Secp256k1DoubleInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<Ed25519AddInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<Ed25519DoubleInstruction<E>>(cs);
+
         fixed.register_table_circuit::<U16TableCircuit<E>>(cs, &self.u16_range_config, &());
         fixed.register_table_circuit::<U14TableCircuit<E>>(cs, &self.u14_range_config, &());
+        fixed.register_table_circuit::<U5TableCircuit<E>>(cs, &self.u5_range_config, &());
         fixed.register_table_circuit::<AndTableCircuit<E>>(cs, &self.and_config, &());
+        fixed.register_table_circuit::<XorTableCircuit<E>>(cs, &self.xor_config, &());
         fixed.register_table_circuit::<LtuTableCircuit<E>>(cs, &self.ltu_config, &());
 
         fixed.register_table_circuit::<RegTableCircuit<E>>(cs, &self.reg_config, reg_init);
@@ -109,15 +198,24 @@ impl<E: ExtensionField> Rv32imConfig<E> {
             &self.program_data_config,
             program_data_init,
         );
-        fixed.register_table_circuit::<PubIOCircuit<E>>(cs, &self.public_io_config, &());
+        match &self.public_io_config {
+            PublicIOConfig::Table(config) => {
+                fixed.register_table_circuit::<PubIOCircuit<E>>(cs, config, &())
+            }
+            PublicIOConfig::Hashed(config) => {
+                fixed.register_table_circuit::<PubIOHashCircuit<E>>(cs, config, &())
+            }
+        }
     }
 
+    /// Assign the opcode circuits with the steps of an execution. Return the steps of the ecalls
+    /// KECCAK_PERMUTE, which are also the witness of the table `KeccakRoundsCircuit`.
     pub fn assign_opcode_circuit(
         &self,
         cs: &ZKVMConstraintSystem<E>,
         witness: &mut ZKVMWitnesses<E>,
         steps: Vec<StepRecord>,
-    ) -> Result<(), ZKVMError> {
+    ) -> Result<Vec<StepRecord>, ZKVMError> {
         use InsnKind::*;
 
         let mut add_records = Vec::new();
@@ -126,19 +224,55 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         let mut halt_records = Vec::new();
         let mut lui_records = Vec::new();
         let mut lw_records = Vec::new();
-        steps
-            .into_iter()
-            .for_each(|record| match record.insn().codes().kind {
-                ADD => add_records.push(record),
-                BLTU => bltu_records.push(record),
-                JAL => jal_records.push(record),
-                EANY if record.rs1().unwrap().value == CENO_PLATFORM.ecall_halt() => {
-                    halt_records.push(record);
-                }
-                LUI => lui_records.push(record),
-                LW => lw_records.push(record),
+        let mut keccak_records = Vec::new();
+        let mut sha256_records = Vec::new();
+        let mut poseidon_records = Vec::new();
+        let mut uint256_add_records = Vec::new();
+        let mut uint256_mul_records = Vec::new();
+        let mut uint256_mulmod_records = Vec::new();
+        let mut secp256k1_add_records = Vec::new();
+        let mut secp256k1_double_records = Vec::new();
+        let mut ed25519_add_records = Vec::new();
+        let mut ed25519_double_records = Vec::new();
+        for record in steps {
+            let records = match record.insn().codes().kind {
+                ADD => &mut add_records,
+                BLTU => &mut bltu_records,
+                JAL => &mut jal_records,
+                LUI => &mut lui_records,
+                LW => &mut lw_records,
+                // The function of an ecall is selected by its code in rs1.
+                EANY => match record.rs1().unwrap().value {
+                    code if code == CENO_PLATFORM.ecall_halt() => &mut halt_records,
+                    code if code == CENO_PLATFORM.ecall_keccak_permute() => &mut keccak_records,
+                    code if code == CENO_PLATFORM.ecall_sha256_compress() => &mut sha256_records,
+                    code if code == CENO_PLATFORM.ecall_poseidon_permute() => &mut poseidon_records,
+                    code if code == CENO_PLATFORM.ecall_uint256_add() => &mut uint256_add_records,
+                    code if code == CENO_PLATFORM.ecall_uint256_mul() => &mut uint256_mul_records,
+                    code if code == CENO_PLATFORM.ecall_uint256_mulmod() => {
+                        &mut uint256_mulmod_records
+                    }
+                    code if code == CENO_PLATFORM.ecall_secp256k1_add() => {
+                        &mut secp256k1_add_records
+                    }
+                    code if code == CENO_PLATFORM.ecall_secp256k1_double() => {
+                        &mut secp256k1_double_records
+                    }
+                    code if code == CENO_PLATFORM.ecall_ed25519_add() => &mut ed25519_add_records,
+                    code if code == CENO_PLATFORM.ecall_ed25519_double() => {
+                        &mut ed25519_double_records
+                    }
+                    code => {
+                        return Err(ZKVMError::InvalidWitness(format!(
+                            "unknown ecall function code {code:#x} at pc {:#x}",
+                            record.pc().before.0
+                        )));
+                    }
+                },
                 i => unimplemented!("instruction {i:?}"),
-            });
+            };
+            records.push(record);
+        }
 
         tracing::info!(
             "tracer generated {} ADD records, {} BLTU records, {} JAL records",
@@ -154,9 +288,69 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         witness.assign_opcode_circuit::<HaltInstruction<E>>(cs, &self.halt_config, halt_records)?;
         witness.assign_opcode_circuit::<LuiInstruction<E>>(cs, &self.lui_config, lui_records)?;
         witness.assign_opcode_circuit::<LwInstruction<E>>(cs, &self.lw_config, lw_records)?;
-        Ok(())
+
+        witness.assign_opcode_circuit::<KeccakInstruction<E>>(
+            cs,
+            &self.keccak_config,
+            keccak_records.clone(),
+        )?;
+        // A compression is proven by both circuits: the ecall, and its 64 rounds.
+        witness.assign_opcode_circuit::<Sha256Instruction<E>>(
+            cs,
+            &self.sha256_config,
+            sha256_records.clone(),
+        )?;
+        witness.assign_opcode_circuit::<Sha256RoundsInstruction<E>>(
+            cs,
+            &self.sha256_rounds_config,
+            sha256_records,
+        )?;
+        witness.assign_opcode_circuit::<PoseidonInstruction<E>>(
+            cs,
+            &self.poseidon_config,
+            poseidon_records,
+        )?;
+        witness.assign_opcode_circuit::<Uint256AddInstruction<E>>(
+            cs,
+            &self.uint256_add_config,
+            uint256_add_records,
+        )?;
+        witness.assign_opcode_circuit::<Uint256MulInstruction<E>>(
+            cs,
+            &self.uint256_mul_config,
+            uint256_mul_records,
+        )?;
+        witness.assign_opcode_circuit::<Uint256MulModInstruction<E>>(
+            cs,
+            &self.uint256_mulmod_config,
+            uint256_mulmod_records,
+        )?;
+        witness.assign_opcode_circuit::<Secp256k1AddInstruction<E>>(
+            cs,
+            &self.secp256k1_add_config,
+            secp256k1_add_records,
+        )?;
+        witness.assign_opcode_circuit::<Secp256k1DoubleInstruction<E>>(
+            cs,
+            &self.secp256k1_double_config,
+            secp256k1_double_records,
+        )?;
+        witness.assign_opcode_circuit::<Ed25519AddInstruction<E>>(
+            cs,
+            &self.ed25519_add_config,
+            ed25519_add_records,
+        )?;
+        witness.assign_opcode_circuit::<Ed25519DoubleInstruction<E>>(
+            cs,
+            &self.ed25519_double_config,
+            ed25519_double_records,
+        )?;
+        Ok(keccak_records)
     }
 
+    /// Assign the table circuits, where `keccak_steps` are the steps returned by
+    /// [`Self::assign_opcode_circuit`].
+    #[allow(clippy::too_many_arguments)]
     pub fn assign_table_circuit(
         &self,
         cs: &ZKVMConstraintSystem<E>,
@@ -165,12 +359,21 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         mem_final: &[MemFinalRecord],
         program_data_final: &[MemFinalRecord],
         public_io_final: &[MemFinalRecord],
+        keccak_steps: &[StepRecord],
     ) -> Result<(), ZKVMError> {
         witness.assign_table_circuit::<U16TableCircuit<E>>(cs, &self.u16_range_config, &())?;
         witness.assign_table_circuit::<U14TableCircuit<E>>(cs, &self.u14_range_config, &())?;
+        witness.assign_table_circuit::<U5TableCircuit<E>>(cs, &self.u5_range_config, &())?;
         witness.assign_table_circuit::<AndTableCircuit<E>>(cs, &self.and_config, &())?;
+        witness.assign_table_circuit::<XorTableCircuit<E>>(cs, &self.xor_config, &())?;
         witness.assign_table_circuit::<LtuTableCircuit<E>>(cs, &self.ltu_config, &())?;
 
+        witness.assign_table_circuit::<KeccakRoundsCircuit<E>>(
+            cs,
+            &self.keccak_rounds_config,
+            keccak_steps,
+        )?;
+
         // assign register finalization.
         witness
             .assign_table_circuit::<RegTableCircuit<E>>(cs, &self.reg_config, reg_final)
@@ -188,10 +391,47 @@ impl<E: ExtensionField> Rv32imConfig<E> {
             )
             .unwrap();
 
-        witness
-            .assign_table_circuit::<PubIOCircuit<E>>(cs, &self.public_io_config, public_io_final)
-            .unwrap();
+        match &self.public_io_config {
+            PublicIOConfig::Table(config) => witness
+                .assign_table_circuit::<PubIOCircuit<E>>(cs, config, public_io_final)
+                .unwrap(),
+            PublicIOConfig::Hashed(config) => witness
+                .assign_table_circuit::<PubIOHashCircuit<E>>(cs, config, public_io_final)
+                .unwrap(),
+        }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::GoldilocksExt2;
+
+    use super::*;
+    use crate::structs::ROMType;
+
+    #[test]
+    fn test_lookup_tables_registered() {
+        let mut cs = ZKVMConstraintSystem::<GoldilocksExt2>::default();
+        Rv32imConfig::construct_circuits(&mut cs);
+        for (name, circuit_cs) in &cs.circuit_css {
+            for (rom_type, _) in &circuit_cs.lk_expressions_items_map {
+                let table = match rom_type {
+                    ROMType::U5 | ROMType::U8 | ROMType::U14 | ROMType::U16 => {
+                        format!("RANGE_{rom_type:?}")
+                    }
+                    ROMType::And | ROMType::Or | ROMType::Xor | ROMType::Ltu | ROMType::Pow => {
+                        format!("OPS_{rom_type:?}")
+                    }
+                    // The program table is registered along with the program.
+                    ROMType::Instruction => continue,
+                };
+                assert!(
+                    cs.circuit_css.contains_key(&table),
+                    "{name} looks up the table {table}, which is not registered"
+                );
+            }
+        }
+    }
+}
@@ -1,38 +1,115 @@
+use std::{collections::BTreeMap, mem};
+
 use crate::{
     error::ZKVMError,
-    structs::{ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMProvingKey},
+    structs::{ProvingKey, ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMProvingKey},
 };
 use ff_ext::ExtensionField;
+use itertools::Itertools;
 use mpcs::PolynomialCommitmentScheme;
+use multilinear_extensions::mle::DenseMultilinearExtension;
+
+mod cache;
+
+/// The fixed traces of each circuit, in column-major order.
+type FixedMles<E> = BTreeMap<String, Option<Vec<DenseMultilinearExtension<E>>>>;
 
 impl<E: ExtensionField> ZKVMConstraintSystem<E> {
     pub fn key_gen<PCS: PolynomialCommitmentScheme<E>>(
         self,
         pp: PCS::ProverParam,
         vp: PCS::VerifierParam,
-        mut vm_fixed_traces: ZKVMFixedTraces<E>,
+        vm_fixed_traces: ZKVMFixedTraces<E>,
     ) -> Result<ZKVMProvingKey<E, PCS>, ZKVMError> {
-        let mut vm_pk = ZKVMProvingKey::new(pp, vp);
-
-        for (c_name, cs) in self.circuit_css.into_iter() {
-            // fixed_traces is optional
-            // verifier will check it existent if cs.num_fixed > 0
-            let fixed_traces = if cs.num_fixed > 0 {
-                vm_fixed_traces
-                    .circuit_fixed_traces
-                    .remove(&c_name)
-                    .ok_or(ZKVMError::FixedTraceNotFound(c_name.clone()))?
-            } else {
-                None
-            };
-
-            let circuit_pk = cs.key_gen(&vm_pk.pp, fixed_traces);
-            assert!(vm_pk.circuit_pks.insert(c_name, circuit_pk).is_none());
+        self.lint()?;
+        let fixed_traces = self.fixed_mles(vm_fixed_traces)?;
+        let fixed_commit_wds = commit_fixed_traces::<E, PCS>(&pp, &fixed_traces);
+        self.key_gen_with_commitments(pp, vp, fixed_traces, fixed_commit_wds)
+    }
+
+    fn lint(&self) -> Result<(), ZKVMError> {
+        for (c_name, stats) in self.stats() {
+            if !stats.is_ok() {
+                let unused = stats
+                    .unused_witin
+                    .iter()
+                    .map(|name| format!("unconstrained witness {name}"));
+                return Err(ZKVMError::InvalidCircuit(format!(
+                    "{c_name}: {}",
+                    stats.errors.iter().cloned().chain(unused).join("; ")
+                )));
+            }
         }
+        Ok(())
+    }
 
+    fn fixed_mles(
+        &self,
+        mut vm_fixed_traces: ZKVMFixedTraces<E>,
+    ) -> Result<FixedMles<E>, ZKVMError> {
+        self.circuit_css
+            .iter()
+            .map(|(c_name, cs)| {
+                // fixed_traces is optional
+                // verifier will check it existent if cs.num_fixed > 0
+                let fixed_traces = if cs.num_fixed > 0 {
+                    vm_fixed_traces
+                        .circuit_fixed_traces
+                        .remove(c_name)
+                        .ok_or(ZKVMError::FixedTraceNotFound(c_name.clone()))?
+                } else {
+                    None
+                };
+                // transpose from row-major to column-major
+                let fixed_mles = fixed_traces.map(|t| t.into_mles().into_iter().collect_vec());
+                Ok((c_name.clone(), fixed_mles))
+            })
+            .collect()
+    }
+
+    fn key_gen_with_commitments<PCS: PolynomialCommitmentScheme<E>>(
+        mut self,
+        pp: PCS::ProverParam,
+        vp: PCS::VerifierParam,
+        mut fixed_traces: FixedMles<E>,
+        mut fixed_commit_wds: BTreeMap<String, PCS::CommitmentWithData>,
+    ) -> Result<ZKVMProvingKey<E, PCS>, ZKVMError> {
+        let circuit_pks = mem::take(&mut self.circuit_css)
+            .into_iter()
+            .map(|(c_name, cs)| {
+                let fixed_traces = fixed_traces.remove(&c_name).flatten();
+                let fixed_commit_wd = fixed_commit_wds.remove(&c_name);
+                assert_eq!(fixed_traces.is_some(), fixed_commit_wd.is_some());
+                let circuit_pk = cs.key_gen_with_commitment(fixed_traces, fixed_commit_wd);
+                (c_name, circuit_pk)
+            })
+            .collect();
+        Ok(self.key_gen_with_circuit_pks(pp, vp, circuit_pks))
+    }
+
+    fn key_gen_with_circuit_pks<PCS: PolynomialCommitmentScheme<E>>(
+        self,
+        pp: PCS::ProverParam,
+        vp: PCS::VerifierParam,
+        circuit_pks: BTreeMap<String, ProvingKey<E, PCS>>,
+    ) -> ZKVMProvingKey<E, PCS> {
+        let mut vm_pk = ZKVMProvingKey::new(pp, vp, self.platform);
+        vm_pk.circuit_pks = circuit_pks;
         vm_pk.initial_global_state_expr = self.initial_global_state_expr;
         vm_pk.finalize_global_state_expr = self.finalize_global_state_expr;
-
-        Ok(vm_pk)
+        vm_pk
     }
 }
+
+fn commit_fixed_traces<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>>(
+    pp: &PCS::ProverParam,
+    fixed_traces: &FixedMles<E>,
+) -> BTreeMap<String, PCS::CommitmentWithData> {
+    fixed_traces
+        .iter()
+        .filter_map(|(c_name, traces)| {
+            let traces = traces.as_ref()?;
+            Some((c_name.clone(), PCS::batch_commit(pp, traces).unwrap()))
+        })
+        .collect()
+}
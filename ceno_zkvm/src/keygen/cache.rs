@@ -0,0 +1,269 @@
+//! A cache on disk of the proving keys of the circuits, whose commitments to the fixed traces
+//! dominate key generation.
+//!
+//! The constraint systems and the fixed traces are still built to look up their entry, and the
+//! fixed traces of the entry are checked against them.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    path::Path,
+};
+
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+use mpcs::PolynomialCommitmentScheme;
+use multilinear_extensions::mle::{MultilinearExtension, with_base_fields};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+
+use super::{FixedMles, commit_fixed_traces};
+use crate::{
+    error::ZKVMError,
+    structs::{
+        CircuitProvingKey, ProvingKey, ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMProvingKey,
+    },
+};
+
+/// Bumped whenever the content of the cache entries changes.
+const KEY_CACHE_VERSION: u64 = 2;
+
+/// An entry of the cache: the proving keys of the circuits, under the digest of their inputs.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
+struct CacheEntry<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    version: u64,
+    digest: [u8; 32],
+    circuit_pks: BTreeMap<String, CircuitProvingKey<E, PCS>>,
+}
+
+impl<E> ZKVMConstraintSystem<E>
+where
+    E: ExtensionField + DeserializeOwned,
+    E::BaseField: DeserializeOwned,
+{
+    /// Generate the proving key as [`Self::key_gen`], with the proving keys of the circuits
+    /// loaded from `cache_dir` when present, and stored there otherwise.
+    ///
+    /// An entry is keyed by a digest of the circuits, their fixed traces and the prover
+    /// parameters, and an entry whose digest or fixed traces do not match is overwritten.
+    pub fn key_gen_with_cache<PCS: PolynomialCommitmentScheme<E>>(
+        self,
+        pp: PCS::ProverParam,
+        vp: PCS::VerifierParam,
+        vm_fixed_traces: ZKVMFixedTraces<E>,
+        cache_dir: impl AsRef<Path>,
+    ) -> Result<ZKVMProvingKey<E, PCS>, ZKVMError> {
+        self.lint()?;
+        let fixed_traces = self.fixed_mles(vm_fixed_traces)?;
+        let digest = self.entry_digest::<PCS>(&pp, &fixed_traces);
+        let path = cache_dir.as_ref().join(format!("{}.keys", hex(&digest)));
+
+        if let Some(circuit_pks) = load_keys::<E, PCS>(&path, &digest, &fixed_traces) {
+            tracing::debug!("loaded the proving keys from {}", path.display());
+            return Ok(self.key_gen_with_circuit_pks(pp, vp, circuit_pks));
+        }
+        let fixed_commit_wds = commit_fixed_traces::<E, PCS>(&pp, &fixed_traces);
+        let vm_pk = self.key_gen_with_commitments(pp, vp, fixed_traces, fixed_commit_wds)?;
+        if let Err(e) = store_keys(&path, &digest, &vm_pk.circuit_pks) {
+            tracing::warn!(
+                "failed to cache the proving keys in {}: {e}",
+                path.display()
+            );
+        }
+        Ok(vm_pk)
+    }
+}
+
+impl<E: ExtensionField> ZKVMConstraintSystem<E> {
+    fn entry_digest<PCS: PolynomialCommitmentScheme<E>>(
+        &self,
+        pp: &PCS::ProverParam,
+        fixed_traces: &FixedMles<E>,
+    ) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        // every item is prefixed by its length, so that consecutive items are not ambiguous
+        let mut update = |bytes: &[u8]| {
+            hasher.update((bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        };
+        update(&KEY_CACHE_VERSION.to_le_bytes());
+        update(std::any::type_name::<PCS>().as_bytes());
+        update(&bincode::serialize(pp).expect("prover parameters are serializable"));
+        for (c_name, cs) in &self.circuit_css {
+            update(c_name.as_bytes());
+            update(cs.to_ir().to_json().as_bytes());
+            for trace in fixed_traces[c_name].iter().flatten() {
+                let evals = trace
+                    .get_base_field_vec()
+                    .iter()
+                    .flat_map(|v| v.to_canonical_u64().to_le_bytes())
+                    .collect::<Vec<_>>();
+                update(&evals);
+            }
+        }
+        hasher.finalize().into()
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, b| {
+        write!(hex, "{b:02x}").unwrap();
+        hex
+    })
+}
+
+/// The cached proving keys, if the entry exists, matches `digest` and holds the same fixed
+/// traces as `fixed_traces`.
+fn load_keys<E, PCS>(
+    path: &Path,
+    digest: &[u8; 32],
+    fixed_traces: &FixedMles<E>,
+) -> Option<BTreeMap<String, ProvingKey<E, PCS>>>
+where
+    E: ExtensionField + DeserializeOwned,
+    E::BaseField: DeserializeOwned,
+    PCS: PolynomialCommitmentScheme<E>,
+{
+    let file = File::open(path).ok()?;
+    let entry: CacheEntry<E, PCS> =
+        with_base_fields(|| bincode::deserialize_from(BufReader::new(file))).ok()?;
+    let matches = entry.version == KEY_CACHE_VERSION
+        && entry.digest == *digest
+        && entry.circuit_pks.len() == fixed_traces.len()
+        && entry.circuit_pks.iter().all(|(c_name, circuit_pk)| {
+            fixed_traces
+                .get(c_name)
+                .is_some_and(|traces| circuit_pk.fixed_traces == *traces)
+        });
+    if !matches {
+        return None;
+    }
+    entry
+        .circuit_pks
+        .into_iter()
+        .map(|(c_name, circuit_pk)| Some((c_name, circuit_pk.into_proving_key().ok()?)))
+        .collect()
+}
+
+fn store_keys<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>>(
+    path: &Path,
+    digest: &[u8; 32],
+    circuit_pks: &BTreeMap<String, ProvingKey<E, PCS>>,
+) -> io::Result<()> {
+    let entry = CacheEntry {
+        version: KEY_CACHE_VERSION,
+        digest: *digest,
+        circuit_pks: circuit_pks
+            .iter()
+            .map(|(c_name, pk)| (c_name.clone(), CircuitProvingKey::<E, PCS>::new(pk)))
+            .collect(),
+    };
+    let dir = path.parent().expect("an entry is in the cache directory");
+    fs::create_dir_all(dir)?;
+    // write to a temporary file first, so that no half-written entry is ever read
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    with_base_fields(|| bincode::serialize_into(BufWriter::new(&mut file), &entry))
+        .map_err(io::Error::other)?;
+    file.persist(path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use goldilocks::{Goldilocks, GoldilocksExt2};
+    use mpcs::{Basefold, BasefoldRSParams, PolynomialCommitmentScheme};
+    use multilinear_extensions::mle::DenseMultilinearExtension;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::tables::{TableCircuit, U5TableCircuit, U8TableCircuit};
+
+    type E = GoldilocksExt2;
+    type Pcs = Basefold<GoldilocksExt2, BasefoldRSParams, ChaCha8Rng>;
+
+    fn circuits<TC: TableCircuit<E, FixedInput = ()>>()
+    -> (ZKVMConstraintSystem<E>, ZKVMFixedTraces<E>) {
+        let mut zkvm_cs = ZKVMConstraintSystem::default();
+        let config = zkvm_cs.register_table_circuit::<TC>();
+        let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
+        zkvm_fixed_traces.register_table_circuit::<TC>(&zkvm_cs, &config, &());
+        (zkvm_cs, zkvm_fixed_traces)
+    }
+
+    /// The proving keys of the circuits, and the program ID of the key.
+    fn keys(pk: ZKVMProvingKey<E, Pcs>) -> (Vec<u8>, Vec<u8>) {
+        let circuit_pks = pk
+            .circuit_pks
+            .values()
+            .map(CircuitProvingKey::new)
+            .collect::<Vec<_>>();
+        let circuit_pks = with_base_fields(|| bincode::serialize(&circuit_pks)).unwrap();
+        (circuit_pks, bincode::serialize(&pk.program_id()).unwrap())
+    }
+
+    #[test]
+    fn test_key_gen_with_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let param = Pcs::setup(1 << 8).unwrap();
+        let (pp, vp) = Pcs::trim(&param, 1 << 8).unwrap();
+        let cached_key_gen = || {
+            let (zkvm_cs, zkvm_fixed_traces) = circuits::<U5TableCircuit<E>>();
+            zkvm_cs
+                .key_gen_with_cache::<Pcs>(pp.clone(), vp.clone(), zkvm_fixed_traces, dir.path())
+                .unwrap()
+        };
+        let entries = || {
+            fs::read_dir(dir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .collect::<Vec<_>>()
+        };
+
+        let (zkvm_cs, zkvm_fixed_traces) = circuits::<U5TableCircuit<E>>();
+        let expected = keys(
+            zkvm_cs
+                .key_gen::<Pcs>(pp.clone(), vp.clone(), zkvm_fixed_traces)
+                .unwrap(),
+        );
+
+        // a miss stores the entry, which the next key generation loads
+        assert_eq!(keys(cached_key_gen()), expected);
+        let [entry] = &entries()[..] else {
+            panic!("expected a single entry")
+        };
+        let (zkvm_cs, zkvm_fixed_traces) = circuits::<U5TableCircuit<E>>();
+        let fixed_traces = zkvm_cs.fixed_mles(zkvm_fixed_traces).unwrap();
+        let digest = zkvm_cs.entry_digest::<Pcs>(&pp, &fixed_traces);
+        assert!(load_keys::<E, Pcs>(entry, &digest, &fixed_traces).is_some());
+        assert_eq!(keys(cached_key_gen()), expected);
+
+        // an entry of other fixed traces is not loaded
+        let mut other_fixed_traces = fixed_traces.clone();
+        let traces = other_fixed_traces.values_mut().flatten().next().unwrap();
+        traces[0] = DenseMultilinearExtension::from_evaluations_vec(
+            traces[0].num_vars(),
+            vec![Goldilocks::ZERO; traces[0].evaluations().len()],
+        );
+        assert!(load_keys::<E, Pcs>(entry, &digest, &other_fixed_traces).is_none());
+
+        // a corrupted entry is overwritten
+        fs::write(entry, b"corrupted").unwrap();
+        assert!(load_keys::<E, Pcs>(entry, &digest, &fixed_traces).is_none());
+        assert_eq!(keys(cached_key_gen()), expected);
+        assert!(load_keys::<E, Pcs>(entry, &digest, &fixed_traces).is_some());
+
+        // other circuits have their own entry
+        let (zkvm_cs, zkvm_fixed_traces) = circuits::<U8TableCircuit<E>>();
+        zkvm_cs
+            .key_gen_with_cache::<Pcs>(pp.clone(), vp.clone(), zkvm_fixed_traces, dir.path())
+            .unwrap();
+        assert_eq!(entries().len(), 2);
+    }
+}
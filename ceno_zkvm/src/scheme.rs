@@ -1,14 +1,23 @@
+use ff::Field;
 use ff_ext::ExtensionField;
+use goldilocks::SmallField;
 use itertools::Itertools;
 use mpcs::PolynomialCommitmentScheme;
-use serde::{Deserialize, Serialize};
+use poseidon::DIGEST_WIDTH;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use std::{collections::BTreeMap, fmt::Debug};
 use sumcheck::structs::IOPProverMessage;
 
-use crate::structs::TowerProofs;
+use crate::{error::ZKVMError, structs::TowerProofs, tables::public_io_digest};
+use prover::{CircuitProof, ProvenCircuit};
+use report::ProvingReport;
 
 pub mod constants;
+#[cfg(unix)]
+pub mod distributed;
 pub mod prover;
+pub mod receipt;
+pub mod report;
 pub mod utils;
 pub mod verifier;
 
@@ -17,7 +26,11 @@ pub mod mock_prover;
 #[cfg(test)]
 mod tests;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub struct ZKVMOpcodeProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     // TODO support >1 opcodes
     pub num_instances: usize,
@@ -46,6 +59,10 @@ pub struct ZKVMOpcodeProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>
 }
 
 #[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub struct ZKVMTableProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     // tower evaluation at layer 1
     pub r_out_evals: Vec<[E; 2]>,
@@ -69,7 +86,7 @@ pub struct ZKVMTableProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>>
 }
 
 /// each field will be interpret to (constant) polynomial
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PublicValues<T: Default + Clone + Debug> {
     exit_code: T,
     init_pc: T,
@@ -77,6 +94,9 @@ pub struct PublicValues<T: Default + Clone + Debug> {
     end_pc: T,
     end_cycle: T,
     public_io: Vec<T>,
+    /// The low and high words of each element of the digest of the public IO when it is hashed,
+    /// see `PubIOHashCircuit`, in which case `public_io` is empty. Otherwise empty.
+    public_io_digest: Vec<T>,
 }
 
 impl PublicValues<u32> {
@@ -95,28 +115,173 @@ impl PublicValues<u32> {
             end_pc,
             end_cycle,
             public_io,
+            public_io_digest: vec![],
+        }
+    }
+
+    /// The public values of an execution whose public IO is hashed, see `PubIOHashCircuit`.
+    pub fn new_with_hashed_io(
+        exit_code: u32,
+        init_pc: u32,
+        init_cycle: u32,
+        end_pc: u32,
+        end_cycle: u32,
+        public_io: &[u32],
+    ) -> Self {
+        let public_io_digest = public_io_digest(public_io)
+            .0
+            .iter()
+            .flat_map(|e| {
+                let e = e.to_canonical_u64();
+                [e as u32, (e >> 32) as u32]
+            })
+            .collect();
+        Self {
+            exit_code,
+            init_pc,
+            init_cycle,
+            end_pc,
+            end_cycle,
+            public_io: vec![],
+            public_io_digest,
+        }
+    }
+
+    pub fn exit_code(&self) -> u32 {
+        self.exit_code
+    }
+
+    pub fn init_pc(&self) -> u32 {
+        self.init_pc
+    }
+
+    pub fn init_cycle(&self) -> u32 {
+        self.init_cycle
+    }
+
+    pub fn end_pc(&self) -> u32 {
+        self.end_pc
+    }
+
+    pub fn end_cycle(&self) -> u32 {
+        self.end_cycle
+    }
+
+    /// The public IO, or nothing if it is hashed.
+    pub fn public_io(&self) -> &[u32] {
+        &self.public_io
+    }
+
+    /// The digest of the public IO, if it is hashed.
+    pub fn public_io_digest(&self) -> Option<[u64; DIGEST_WIDTH]> {
+        if self.public_io_digest.is_empty() {
+            return None;
         }
+        let digest = self
+            .public_io_digest
+            .chunks(2)
+            .map(|limbs| limbs[0] as u64 | ((limbs[1] as u64) << 32))
+            .collect_vec();
+        Some(digest.try_into().unwrap())
     }
+
     pub fn to_vec<E: ExtensionField>(&self) -> Vec<Vec<E::BaseField>> {
-        vec![
+        let mut raw_pi = vec![
             vec![E::BaseField::from((self.exit_code & 0xffff) as u64)],
             vec![E::BaseField::from(((self.exit_code >> 16) & 0xffff) as u64)],
             vec![E::BaseField::from(self.init_pc as u64)],
             vec![E::BaseField::from(self.init_cycle as u64)],
             vec![E::BaseField::from(self.end_pc as u64)],
             vec![E::BaseField::from(self.end_cycle as u64)],
-            self.public_io
-                .iter()
-                .map(|e| E::BaseField::from(*e as u64))
-                .collect(),
-        ]
+        ];
+        // Every public value is a non-empty polynomial.
+        if self.public_io.is_empty() {
+            raw_pi.push(vec![E::BaseField::ZERO]);
+        } else {
+            raw_pi.push(
+                self.public_io
+                    .iter()
+                    .map(|e| E::BaseField::from(*e as u64))
+                    .collect(),
+            );
+        }
+        // The digest is zero if the public IO is not hashed.
+        let num_limbs = 2 * DIGEST_WIDTH;
+        raw_pi.extend((0..num_limbs).map(|i| {
+            let limb = self.public_io_digest.get(i).copied().unwrap_or_default();
+            vec![E::BaseField::from(limb as u64)]
+        }));
+        raw_pi
+    }
+
+    /// Decode the public values encoded by [`Self::to_vec`].
+    pub fn from_vec<E: ExtensionField>(raw_pi: &[Vec<E::BaseField>]) -> Result<Self, ZKVMError> {
+        let invalid = || ZKVMError::InvalidPublicValues(format!("{raw_pi:?}"));
+        let [
+            exit_code_lo,
+            exit_code_hi,
+            init_pc,
+            init_cycle,
+            end_pc,
+            end_cycle,
+            public_io,
+            public_io_digest @ ..,
+        ] = raw_pi
+        else {
+            return Err(invalid());
+        };
+        if public_io_digest.len() != 2 * DIGEST_WIDTH {
+            return Err(invalid());
+        }
+        let word = |v: &E::BaseField| u32::try_from(v.to_canonical_u64()).map_err(|_| invalid());
+        let constant = |values: &[E::BaseField]| match values {
+            [v] => word(v),
+            _ => Err(invalid()),
+        };
+        let (exit_code_lo, exit_code_hi) = (constant(exit_code_lo)?, constant(exit_code_hi)?);
+        if exit_code_lo > 0xffff || exit_code_hi > 0xffff {
+            return Err(invalid());
+        }
+        let mut public_io = public_io.iter().map(word).collect::<Result<Vec<_>, _>>()?;
+        let mut public_io_digest = public_io_digest
+            .iter()
+            .map(|limb| constant(limb))
+            .collect::<Result<Vec<_>, _>>()?;
+        if public_io_digest.iter().all(|&limb| limb == 0) {
+            public_io_digest.clear();
+        } else {
+            // The public IO is hashed, and each element of the digest is canonical.
+            if public_io != [0] {
+                return Err(invalid());
+            }
+            public_io.clear();
+            for limbs in public_io_digest.chunks(2) {
+                let e = limbs[0] as u64 | ((limbs[1] as u64) << 32);
+                if E::BaseField::from(e).to_canonical_u64() != e {
+                    return Err(invalid());
+                }
+            }
+        }
+        Ok(Self {
+            exit_code: exit_code_lo | (exit_code_hi << 16),
+            init_pc: constant(init_pc)?,
+            init_cycle: constant(init_cycle)?,
+            end_pc: constant(end_pc)?,
+            end_cycle: constant(end_cycle)?,
+            public_io,
+            public_io_digest,
+        })
     }
 }
 
 /// Map circuit names to
 /// - an opcode or table proof,
 /// - an index unique across both types.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub struct ZKVMProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     // TODO preserve in serde only for auxiliary public input
     // other raw value can be construct by verifier directly.
@@ -154,6 +319,34 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProof<E, PCS> {
     pub fn update_pi_eval(&mut self, idx: usize, v: E) {
         self.pi_evals[idx] = v;
     }
+
+    /// Add the proof of a circuit, and its shape and proving times to `report`.
+    pub(crate) fn insert_circuit_proof(
+        &mut self,
+        proven: ProvenCircuit<E, PCS>,
+        report: &mut ProvingReport,
+    ) {
+        let ProvenCircuit {
+            circuit_name,
+            index,
+            proof,
+            report: circuit_report,
+        } = proven;
+        match proof {
+            CircuitProof::Opcode(opcode_proof) => {
+                self.opcode_proofs
+                    .insert(circuit_name.clone(), (index, opcode_proof));
+            }
+            CircuitProof::Table(table_proof, pi_in_evals) => {
+                self.table_proofs
+                    .insert(circuit_name.clone(), (index, table_proof));
+                for (idx, eval) in pi_in_evals {
+                    self.update_pi_eval(idx, eval);
+                }
+            }
+        }
+        report.circuits.insert(circuit_name, circuit_report);
+    }
 }
 
 impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProof<E, PCS> {
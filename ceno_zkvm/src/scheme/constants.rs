@@ -1,6 +1,8 @@
 pub(crate) const MIN_PAR_SIZE: usize = 64;
 pub(crate) const MAINCONSTRAIN_SUMCHECK_BATCH_SIZE: usize = 3; // read/write/lookup
 pub(crate) const SEL_DEGREE: usize = 2;
+/// The maximal degree of the products in the main sumcheck, see `IOPProverStateV2`.
+pub(crate) const MAX_MAIN_SUMCHECK_DEGREE: usize = 3;
 
 pub const NUM_FANIN: usize = 2;
 pub const NUM_FANIN_LOGUP: usize = 2;
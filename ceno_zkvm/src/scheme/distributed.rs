@@ -0,0 +1,418 @@
+//! Distributed proving, with the same proofs as [`ZKVMProver::create_proof`].
+//!
+//! A coordinator commits to the witnesses and derives the challenges, and worker processes prove
+//! the circuits, which only depend on their own witness, fork of the transcript and proving key,
+//! see [`ZKVMProver::commit_witnesses`].
+//!
+//! The coordinator connects to each worker over a unix socket, sends it the prover parameters,
+//! then one circuit at a time: its [`CircuitProvingTask`] with the public values and the
+//! challenges, preceded by its proving key the first time the worker proves the circuit. The
+//! worker answers each task with the [`ProvenCircuit`] or the error, and the coordinator ends the
+//! session with `None`. Messages are bincode encoded, in frames prefixed by their length.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    io::{BufReader, BufWriter, Read, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::Path,
+    sync::{Condvar, Mutex},
+    thread,
+};
+
+use ff_ext::ExtensionField;
+use mpcs::PolynomialCommitmentScheme;
+use multilinear_extensions::mle::with_base_fields;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use transcript::Transcript;
+
+use super::{
+    PublicValues, ZKVMProof,
+    prover::{CircuitProvingTask, ProvenCircuit, ZKVMProver},
+    report::ProvingReport,
+};
+use crate::{
+    error::ZKVMError,
+    structs::{CircuitProvingKey, ZKVMWitnesses},
+};
+
+/// A request of the coordinator, or `None` to end the session.
+type ProvingRequest<E, PCS> = Option<Request<E, PCS>>;
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
+enum Request<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    /// The proving key of a circuit, sent before the first task of the circuit.
+    ProvingKey(String, CircuitProvingKey<E, PCS>),
+    /// A circuit to prove, with the public values and the challenges.
+    Prove(
+        Vec<Vec<<E as ExtensionField>::BaseField>>,
+        [E; 2],
+        CircuitProvingTask<E, PCS>,
+    ),
+}
+
+/// The answer of a worker to a request.
+type ProvingResponse<E, PCS> = Result<ProvenCircuit<E, PCS>, String>;
+
+/// The requests left to prove, shared by the threads talking to the workers.
+struct RequestQueue<T> {
+    state: Mutex<RequestQueueState<T>>,
+    changed: Condvar,
+}
+
+struct RequestQueueState<T> {
+    requests: Vec<T>,
+    /// The requests sent to a worker and not answered yet.
+    in_flight: usize,
+}
+
+impl<T> RequestQueue<T> {
+    fn new(requests: Vec<T>) -> Self {
+        Self {
+            state: Mutex::new(RequestQueueState {
+                requests,
+                in_flight: 0,
+            }),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// Take the next request, waiting while other workers may still hand theirs back. Return
+    /// `None` once every request is answered.
+    fn take(&self) -> Option<T> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(request) = state.requests.pop() {
+                state.in_flight += 1;
+                return Some(request);
+            }
+            if state.in_flight == 0 {
+                return None;
+            }
+            state = self.changed.wait(state).unwrap();
+        }
+    }
+
+    /// Mark a taken request as answered.
+    fn answered(&self) {
+        self.state.lock().unwrap().in_flight -= 1;
+        self.changed.notify_all();
+    }
+
+    /// Hand a taken request back, for another worker to serve it.
+    fn hand_back(&self, request: T) {
+        let mut state = self.state.lock().unwrap();
+        state.requests.push(request);
+        state.in_flight -= 1;
+        self.changed.notify_all();
+    }
+}
+
+/// The largest frame a connection sends or receives, so that a corrupted length prefix is an
+/// error rather than an allocation of up to 16 EiB.
+const MAX_FRAME_LEN: u64 = 1 << 34;
+
+/// A session over a unix socket, exchanging length-prefixed frames.
+struct Connection {
+    name: String,
+    reader: BufReader<UnixStream>,
+    writer: BufWriter<UnixStream>,
+}
+
+impl Connection {
+    fn new(name: String, stream: UnixStream) -> Result<Self, ZKVMError> {
+        let reader = stream.try_clone().map_err(|e| worker_error(&name, e))?;
+        Ok(Self {
+            reader: BufReader::new(reader),
+            writer: BufWriter::new(stream),
+            name,
+        })
+    }
+
+    fn send(&mut self, frame: &[u8]) -> Result<(), ZKVMError> {
+        if frame.len() as u64 > MAX_FRAME_LEN {
+            return Err(self.frame_too_long(frame.len() as u64));
+        }
+        (self.writer.write_all(&(frame.len() as u64).to_le_bytes()))
+            .and_then(|_| self.writer.write_all(frame))
+            .and_then(|_| self.writer.flush())
+            .map_err(|e| worker_error(&self.name, e))
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, ZKVMError> {
+        let mut len = [0; 8];
+        self.reader
+            .read_exact(&mut len)
+            .map_err(|e| worker_error(&self.name, e))?;
+        let len = u64::from_le_bytes(len);
+        if len > MAX_FRAME_LEN {
+            return Err(self.frame_too_long(len));
+        }
+        let mut frame = vec![0; len as usize];
+        self.reader
+            .read_exact(&mut frame)
+            .map_err(|e| worker_error(&self.name, e))?;
+        Ok(frame)
+    }
+
+    fn frame_too_long(&self, len: u64) -> ZKVMError {
+        worker_error(
+            &self.name,
+            format!("frame of {len} bytes, above the maximum of {MAX_FRAME_LEN}"),
+        )
+    }
+}
+
+fn encode<T: Serialize>(message: &T) -> Vec<u8> {
+    with_base_fields(|| bincode::serialize(message)).expect("messages are serializable")
+}
+
+fn decode<T: DeserializeOwned>(frame: &[u8]) -> Result<T, ZKVMError> {
+    with_base_fields(|| bincode::deserialize(frame))
+        .map_err(|e| ZKVMError::WorkerError(format!("bad message: {e}")))
+}
+
+fn worker_error(name: &str, e: impl Display) -> ZKVMError {
+    ZKVMError::WorkerError(format!("{name}: {e}"))
+}
+
+/// Serve the coordinators connecting to the unix socket `socket`, one at a time. A failed
+/// session is logged, and does not stop the worker.
+pub fn run_worker<E, PCS>(socket: impl AsRef<Path>) -> Result<(), ZKVMError>
+where
+    E: ExtensionField + DeserializeOwned,
+    E::BaseField: DeserializeOwned,
+    PCS: PolynomialCommitmentScheme<E>,
+{
+    let name = socket.as_ref().display().to_string();
+    let listener = UnixListener::bind(socket).map_err(|e| worker_error(&name, e))?;
+    tracing::info!("worker listening on {name}");
+    for stream in listener.incoming() {
+        let stream = stream.map_err(|e| worker_error(&name, e))?;
+        if let Err(e) = serve_connection::<E, PCS>(stream) {
+            tracing::warn!("worker session failed: {e:?}");
+        }
+    }
+    Ok(())
+}
+
+/// Serve a session of a coordinator, until it is ended.
+pub fn serve_connection<E, PCS>(stream: UnixStream) -> Result<(), ZKVMError>
+where
+    E: ExtensionField + DeserializeOwned,
+    E::BaseField: DeserializeOwned,
+    PCS: PolynomialCommitmentScheme<E>,
+{
+    let mut connection = Connection::new("coordinator".into(), stream)?;
+    let pp: PCS::ProverParam = decode(&connection.receive()?)?;
+    // the proving keys received in the session, or the error decoding them
+    let mut circuit_pks = BTreeMap::new();
+    while let Some(request) = decode::<ProvingRequest<E, PCS>>(&connection.receive()?)? {
+        let (raw_pi, challenges, task) = match request {
+            Request::ProvingKey(circuit_name, circuit_pk) => {
+                let circuit_pk = circuit_pk.into_proving_key().map_err(|e| format!("{e:?}"));
+                circuit_pks.insert(circuit_name, circuit_pk);
+                continue;
+            }
+            Request::Prove(raw_pi, challenges, task) => (raw_pi, challenges, task),
+        };
+        let circuit_name = task.circuit_name.clone();
+        tracing::debug!("worker proving circuit {circuit_name}");
+        let response: ProvingResponse<E, PCS> = match circuit_pks.get(&circuit_name) {
+            Some(Ok(circuit_pk)) => {
+                ZKVMProver::prove_circuit(&pp, circuit_pk, &raw_pi, &challenges, task)
+                    .map_err(|e| format!("{e:?}"))
+            }
+            Some(Err(e)) => Err(e.clone()),
+            None => Err("no proving key".into()),
+        }
+        .map_err(|e| format!("proving circuit {circuit_name}: {e}"));
+        connection.send(&encode(&response))?;
+    }
+    Ok(())
+}
+
+impl<E, PCS> ZKVMProver<E, PCS>
+where
+    E: ExtensionField + DeserializeOwned,
+    E::BaseField: DeserializeOwned,
+    PCS: PolynomialCommitmentScheme<E>,
+{
+    /// Prove as [`Self::create_proof`], with the circuits proven by the workers listening on the
+    /// unix sockets `workers`, see [`run_worker`].
+    pub fn create_proof_distributed(
+        &self,
+        witnesses: ZKVMWitnesses<E>,
+        pi: PublicValues<u32>,
+        transcript: Transcript<E>,
+        workers: &[impl AsRef<Path> + Sync],
+    ) -> Result<ZKVMProof<E, PCS>, ZKVMError> {
+        let (vm_proof, report) =
+            self.create_proof_distributed_with_report(witnesses, pi, transcript, workers)?;
+        tracing::info!("proving report: {}", report.to_json());
+        Ok(vm_proof)
+    }
+
+    /// Prove as [`Self::create_proof_with_report`], with the circuits proven by the workers
+    /// listening on the unix sockets `workers`.
+    ///
+    /// Each worker proves circuits until all are proven. The circuit of a worker which fails to
+    /// answer is handed to another worker, while any is still running.
+    pub fn create_proof_distributed_with_report(
+        &self,
+        witnesses: ZKVMWitnesses<E>,
+        pi: PublicValues<u32>,
+        transcript: Transcript<E>,
+        workers: &[impl AsRef<Path> + Sync],
+    ) -> Result<(ZKVMProof<E, PCS>, ProvingReport), ZKVMError> {
+        if workers.is_empty() {
+            return Err(ZKVMError::WorkerError("no workers".into()));
+        }
+        let (mut vm_proof, challenges, tasks) = self.commit_witnesses(witnesses, pi, transcript)?;
+
+        // encode the requests upfront, so that the threads talking to the workers only handle
+        // bytes, and a request is encoded once even if it is sent to several workers
+        let pp = encode(&self.pk.pp);
+        let num_tasks = tasks.len();
+        let circuit_pks = tasks
+            .iter()
+            .map(|task| {
+                let circuit_pk = CircuitProvingKey::new(&self.pk.circuit_pks[&task.circuit_name]);
+                let request: ProvingRequest<E, PCS> =
+                    Some(Request::ProvingKey(task.circuit_name.clone(), circuit_pk));
+                (task.circuit_name.clone(), encode(&request))
+            })
+            .collect::<BTreeMap<_, _>>();
+        // reversed, as the workers pop them from the back
+        let requests = tasks
+            .into_iter()
+            .rev()
+            .map(|task| {
+                let circuit_name = task.circuit_name.clone();
+                let request: ProvingRequest<E, PCS> =
+                    Some(Request::Prove(vm_proof.raw_pi.clone(), challenges, task));
+                (circuit_name, encode(&request))
+            })
+            .collect();
+        let requests = RequestQueue::new(requests);
+
+        let results = thread::scope(|s| {
+            let handles = workers
+                .iter()
+                .map(|worker| {
+                    s.spawn(|| prove_on_worker(worker.as_ref(), &pp, &circuit_pks, &requests))
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("worker thread panicked"))
+                .collect::<Vec<_>>()
+        });
+
+        let mut report = ProvingReport::default();
+        let mut error = None;
+        for (responses, worker_error) in results {
+            for response in responses {
+                match decode::<ProvingResponse<E, PCS>>(&response)? {
+                    Ok(proven) => vm_proof.insert_circuit_proof(proven, &mut report),
+                    Err(e) => return Err(ZKVMError::WorkerError(e)),
+                }
+            }
+            if let Some(e) = worker_error {
+                tracing::warn!("{e:?}");
+                error.get_or_insert(e);
+            }
+        }
+        if report.circuits.len() < num_tasks {
+            return Err(error.expect("a circuit is only left unproven by a failed worker"));
+        }
+        Ok((vm_proof, report))
+    }
+}
+
+/// Send the requests of `requests` to the worker listening on `worker`, until all are answered or
+/// the worker fails, each preceded by the proving key of its circuit from `circuit_pks` unless
+/// the worker already has it. Return the responses, and the failure if any.
+fn prove_on_worker(
+    worker: &Path,
+    pp: &[u8],
+    circuit_pks: &BTreeMap<String, Vec<u8>>,
+    requests: &RequestQueue<(String, Vec<u8>)>,
+) -> (Vec<Vec<u8>>, Option<ZKVMError>) {
+    let name = worker.display().to_string();
+    let mut responses = vec![];
+    let connection = UnixStream::connect(worker)
+        .map_err(|e| worker_error(&name, e))
+        .and_then(|stream| Connection::new(name, stream))
+        .and_then(|mut connection| connection.send(pp).map(|_| connection));
+    let mut connection = match connection {
+        Ok(connection) => connection,
+        Err(e) => return (responses, Some(e)),
+    };
+
+    let mut sent_pks = BTreeSet::new();
+    while let Some(request) = requests.take() {
+        let (circuit_name, frame) = &request;
+        let sent_pk = if sent_pks.insert(circuit_name.clone()) {
+            connection.send(&circuit_pks[circuit_name])
+        } else {
+            Ok(())
+        };
+        match sent_pk
+            .and_then(|_| connection.send(frame))
+            .and_then(|_| connection.receive())
+        {
+            Ok(response) => {
+                responses.push(response);
+                requests.answered();
+            }
+            Err(e) => {
+                requests.hand_back(request);
+                return (responses, Some(e));
+            }
+        }
+    }
+    let end: Option<()> = None;
+    if let Err(e) = connection.send(&encode(&end)) {
+        tracing::warn!("{e:?}");
+    }
+    (responses, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Write, os::unix::net::UnixStream, thread, time::Duration};
+
+    use super::{Connection, MAX_FRAME_LEN, RequestQueue};
+
+    #[test]
+    fn test_request_queue_hand_back() {
+        let queue = RequestQueue::new(vec![vec![1], vec![2]]);
+        let first = queue.take().unwrap();
+        assert_eq!(queue.take(), Some(vec![1]));
+        queue.answered();
+
+        thread::scope(|s| {
+            // no request is left, but the first one may still be handed back
+            let waiting = s.spawn(|| queue.take());
+            thread::sleep(Duration::from_millis(100));
+            assert!(!waiting.is_finished());
+            queue.hand_back(first);
+            assert_eq!(waiting.join().unwrap(), Some(vec![2]));
+        });
+        queue.answered();
+        assert_eq!(queue.take(), None);
+    }
+
+    #[test]
+    fn test_connection_frame_too_long() {
+        let (stream, mut peer) = UnixStream::pair().unwrap();
+        let mut connection = Connection::new("peer".into(), stream).unwrap();
+        peer.write_all(&(MAX_FRAME_LEN + 1).to_le_bytes()).unwrap();
+        assert!(connection.receive().is_err());
+    }
+}
@@ -30,6 +30,8 @@ use std::{
 };
 use strum::IntoEnumIterator;
 
+mod perturb;
+
 const MAX_CONSTRAINT_DEGREE: usize = 2;
 const MOCK_PROGRAM_SIZE: usize = 32;
 pub const MOCK_PC_START: ByteAddr = ByteAddr(CENO_PLATFORM.pc_base());
@@ -407,41 +409,7 @@ impl<'a, E: ExtensionField + Hash> MockProver<E> {
         challenge: Option<[E; 2]>,
         lkm: Option<LkMultiplicity>,
     ) -> Result<(), Vec<MockProverError<E>>> {
-        // fix the program table
-        let instructions = input_programs
-            .iter()
-            .cloned()
-            .chain(std::iter::repeat(0))
-            .take(MOCK_PROGRAM_SIZE)
-            .collect_vec();
-        let image = instructions
-            .iter()
-            .enumerate()
-            .map(|(insn_idx, &insn)| {
-                (
-                    CENO_PLATFORM.pc_base() + (insn_idx * PC_WORD_SIZE) as u32,
-                    insn,
-                )
-            })
-            .collect::<BTreeMap<u32, u32>>();
-        let program = Program::new(
-            CENO_PLATFORM.pc_base(),
-            CENO_PLATFORM.pc_base(),
-            instructions,
-            image,
-        );
-
-        // load tables
-        let (challenge, mut table) = if let Some(challenge) = challenge {
-            (challenge, load_tables(cb, challenge))
-        } else {
-            load_once_tables(cb)
-        };
-        let mut prog_table = vec![];
-        Self::load_program_table(&mut prog_table, &program, challenge);
-        for prog in prog_table {
-            table.insert(prog);
-        }
+        let (challenge, table) = Self::load_all_tables(cb, input_programs, challenge);
 
         let mut errors = vec![];
         // Assert zero expressions
@@ -634,11 +602,56 @@ impl<'a, E: ExtensionField + Hash> MockProver<E> {
         }
     }
 
+    /// The challenges, and the lookup tables including the program table of `input_programs`.
+    fn load_all_tables(
+        cb: &CircuitBuilder<E>,
+        input_programs: &[u32],
+        challenge: Option<[E; 2]>,
+    ) -> ([E; 2], HashSet<Vec<u64>>) {
+        // fix the program table
+        let instructions = input_programs
+            .iter()
+            .cloned()
+            .chain(std::iter::repeat(0))
+            .take(MOCK_PROGRAM_SIZE)
+            .collect_vec();
+        let image = instructions
+            .iter()
+            .enumerate()
+            .map(|(insn_idx, &insn)| {
+                (
+                    CENO_PLATFORM.pc_base() + (insn_idx * PC_WORD_SIZE) as u32,
+                    insn,
+                )
+            })
+            .collect::<BTreeMap<u32, u32>>();
+        let program = Program::new(
+            CENO_PLATFORM.pc_base(),
+            CENO_PLATFORM.pc_base(),
+            instructions,
+            image,
+        );
+
+        // load tables
+        let (challenge, mut table) = if let Some(challenge) = challenge {
+            (challenge, load_tables(cb, challenge))
+        } else {
+            load_once_tables(cb)
+        };
+        let mut prog_table = vec![];
+        Self::load_program_table(&mut prog_table, &program, challenge);
+        for prog in prog_table {
+            table.insert(prog);
+        }
+        (challenge, table)
+    }
+
     fn load_program_table(t_vec: &mut Vec<Vec<u64>>, program: &Program, challenge: [E; 2]) {
         let mut cs = ConstraintSystem::<E>::new(|| "mock_program");
         let mut cb = CircuitBuilder::new(&mut cs);
         let config =
-            ProgramTableCircuit::<_, MOCK_PROGRAM_SIZE>::construct_circuit(&mut cb).unwrap();
+            ProgramTableCircuit::<_, MOCK_PROGRAM_SIZE>::construct_circuit(&mut cb, &CENO_PLATFORM)
+                .unwrap();
         let fixed = ProgramTableCircuit::<E, MOCK_PROGRAM_SIZE>::generate_fixed_traces(
             &config,
             cs.num_fixed,
@@ -720,6 +733,10 @@ Hints:
         challenge: Option<[E; 2]>,
         lkm: Option<LkMultiplicity>,
     ) {
+        #[cfg(feature = "checked_witness")]
+        raw_witin
+            .check_assigned(&cb.cs.ns.get_namespaces()[0], &cb.cs.witin_namespace_map)
+            .unwrap();
         let wits_in = raw_witin
             .de_interleaving()
             .into_mles()
@@ -0,0 +1,347 @@
+//! Detection of under-constrained witness columns, by perturbing valid witnesses.
+//!
+//! An opcode circuit reads its inputs and writes its outputs with its read and write records.
+//! Given the records it reads and the program it fetches from, the records it writes must be
+//! determined. A perturbation of the witness which satisfies every constraint and lookup, and
+//! keeps the read records, but changes a write record, breaks that property.
+
+use std::hash::Hash;
+
+use ff::Field;
+use ff_ext::ExtensionField;
+use itertools::Itertools;
+use multilinear_extensions::{mle::IntoMLEs, virtual_poly_v2::ArcMultilinearExtension};
+use rand_chacha::{ChaCha8Rng, rand_core::SeedableRng};
+
+use super::MockProver;
+use crate::{
+    circuit_builder::CircuitBuilder, expression::CompiledExpressions, witness::RowMajorMatrix,
+};
+
+/// Witness columns of an instance which can change together without violating any constraint
+/// or lookup, nor changing any read record, while changing a write record.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct UnderConstrained {
+    /// The namespaces of the perturbed columns, from `witin_namespace_map`.
+    pub columns: Vec<String>,
+    pub inst_id: usize,
+    /// The namespaces of the changed write records.
+    pub writes: Vec<String>,
+}
+
+/// The columns which are not constrained beyond their neighbourhood of a valid value are found
+/// by the smallest steps.
+const DELTAS: [i64; 2] = [1, -1];
+
+/// The values at the boundaries of the field and of the 16 and 32 bits limbs, where range checks
+/// are the most likely to be wrong: 0, p - 1, 2^16 and 2^32.
+const BOUNDARY_VALUES: [i64; 4] = [0, -1, 1 << 16, 1 << 32];
+
+/// The number of random field elements which every column is also set to, drawn from a fixed seed
+/// so that the reports are reproducible.
+const NUM_RANDOM_VALUES: usize = 2;
+
+/// A change of the value of a witness column.
+#[derive(Clone, Copy, Debug)]
+enum Perturbation<F> {
+    Add(F),
+    Set(F),
+}
+
+impl<F: Field> Perturbation<F> {
+    fn apply<E: ExtensionField<BaseField = F>>(self, value: E) -> E {
+        match self {
+            Perturbation::Add(delta) => value + E::from(delta),
+            Perturbation::Set(new_value) => E::from(new_value),
+        }
+    }
+}
+
+fn signed_to_field<F: Field + From<u64>>(value: i64) -> F {
+    let abs = F::from(value.unsigned_abs());
+    if value < 0 { -abs } else { abs }
+}
+
+impl<'a, E: ExtensionField + Hash> MockProver<E> {
+    /// Perturb every witness column of the first `num_instances` instances, and every group of up
+    /// to `max_group_size` columns, by small deltas and by setting them to boundary and random
+    /// values, and report the under-constrained ones.
+    ///
+    /// A group is only reported if none of its columns was reported in a smaller group, and only
+    /// for its first instance. The witness must satisfy the circuit, see `assert_satisfied`.
+    pub fn find_under_constrained(
+        cb: &CircuitBuilder<E>,
+        wits_in: &[ArcMultilinearExtension<'a, E>],
+        num_instances: usize,
+        programs: &[u32],
+        max_group_size: usize,
+    ) -> Vec<UnderConstrained> {
+        assert!(
+            cb.cs.instance_name_map.is_empty(),
+            "circuits with public inputs are not supported"
+        );
+        let (challenge, table) = Self::load_all_tables(cb, programs, None);
+        let cs = &cb.cs;
+        let constraints = cs
+            .assert_zero_expressions
+            .iter()
+            .chain(&cs.assert_zero_sumcheck_expressions)
+            .collect_vec();
+        let compiled = CompiledExpressions::new(
+            constraints
+                .iter()
+                .copied()
+                .chain(&cs.lk_expressions)
+                .chain(&cs.r_expressions)
+                .chain(&cs.w_expressions),
+        );
+        let (num_constraints, num_lookups, num_reads) = (
+            constraints.len(),
+            cs.lk_expressions.len(),
+            cs.r_expressions.len(),
+        );
+        let evaluate = |row: &[E]| {
+            compiled.evaluate_with_instance::<E>(
+                &|_| unreachable!("fixed columns are not supported"),
+                &|witness_id| row[witness_id as usize],
+                &|_| unreachable!(),
+                &|scalar| scalar.into(),
+                &|challenge_id, pow, scalar, offset| {
+                    challenge[challenge_id as usize].pow([pow as u64]) * scalar + offset
+                },
+                &|a, b| a + b,
+                &|a, b| a * b,
+                &|x, a, b| a * x + b,
+            )
+        };
+        // whether the evaluations satisfy the constraints and the lookups
+        let is_satisfied = |evals: &[E]| {
+            evals[..num_constraints].iter().all(|eval| *eval == E::ZERO)
+                && evals[num_constraints..][..num_lookups]
+                    .iter()
+                    .all(|eval| table.contains(&eval.to_canonical_u64_vec()))
+        };
+
+        let columns = wits_in
+            .iter()
+            .map(|wit| wit.get_base_field_vec())
+            .collect_vec();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let perturbations = DELTAS
+            .into_iter()
+            .map(|delta| Perturbation::Add(signed_to_field(delta)))
+            .chain(
+                BOUNDARY_VALUES
+                    .into_iter()
+                    .map(|value| Perturbation::Set(signed_to_field(value))),
+            )
+            .chain(
+                (0..NUM_RANDOM_VALUES).map(|_| Perturbation::Set(E::BaseField::random(&mut rng))),
+            )
+            .collect_vec();
+        let mut reported = vec![false; columns.len()];
+        let mut found = vec![];
+        for group_size in 1..=max_group_size {
+            for inst_id in 0..num_instances {
+                let row = columns
+                    .iter()
+                    .map(|column| E::from(column[inst_id]))
+                    .collect_vec();
+                let expected = evaluate(&row);
+                assert!(
+                    is_satisfied(&expected),
+                    "instance {inst_id} does not satisfy the circuit"
+                );
+                let (expected_reads, expected_writes) =
+                    expected[num_constraints + num_lookups..].split_at(num_reads);
+
+                for group in (0..columns.len()).combinations(group_size) {
+                    if group.iter().any(|&column| reported[column]) {
+                        continue;
+                    }
+                    for group_perturbations in
+                        itertools::repeat_n(&perturbations, group_size).multi_cartesian_product()
+                    {
+                        let mut row = row.clone();
+                        for (&column, perturbation) in group.iter().zip(group_perturbations) {
+                            row[column] = perturbation.apply(row[column]);
+                        }
+                        let evals = evaluate(&row);
+                        let (reads, writes) =
+                            evals[num_constraints + num_lookups..].split_at(num_reads);
+                        if !is_satisfied(&evals) || reads != expected_reads {
+                            continue;
+                        }
+                        let changed_writes = (writes.iter().zip(expected_writes))
+                            .zip(&cs.w_expressions_namespace_map)
+                            .filter(|((write, expected), _)| write != expected)
+                            .map(|(_, name)| name.clone())
+                            .collect_vec();
+                        if !changed_writes.is_empty() {
+                            for &column in &group {
+                                reported[column] = true;
+                            }
+                            found.push(UnderConstrained {
+                                columns: group
+                                    .iter()
+                                    .map(|&column| cs.witin_namespace_map[column].clone())
+                                    .collect(),
+                                inst_id,
+                                writes: changed_writes,
+                            });
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    /// Panic if a column, or a pair of columns, of the witness is under-constrained.
+    pub fn assert_constrained_raw(
+        cb: &CircuitBuilder<E>,
+        raw_witin: RowMajorMatrix<E::BaseField>,
+        programs: &[u32],
+    ) {
+        let num_instances = raw_witin.num_instances();
+        let wits_in = raw_witin
+            .de_interleaving()
+            .into_mles()
+            .into_iter()
+            .map(|v| v.into())
+            .collect_vec();
+        let found = Self::find_under_constrained(cb, &wits_in, num_instances, programs, 2);
+        if !found.is_empty() {
+            println!("======================================================");
+            for under_constrained in &found {
+                println!(
+                    "Error: {:?} of instance {} can change the writes {:?}",
+                    under_constrained.columns, under_constrained.inst_id, under_constrained.writes
+                );
+            }
+            println!("======================================================");
+            panic!("{} under-constrained column groups", found.len());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ceno_emul::{Change, InsnKind, StepRecord, encode_rv32};
+    use goldilocks::{Goldilocks, GoldilocksExt2};
+    use multilinear_extensions::mle::IntoMLE;
+
+    use super::*;
+    use crate::{
+        circuit_builder::ConstraintSystem,
+        expression::ToExpr,
+        instructions::{
+            Instruction,
+            riscv::arith::{AddInstruction, SubInstruction},
+        },
+        scheme::mock_prover::MOCK_PC_START,
+    };
+
+    type E = GoldilocksExt2;
+
+    #[test]
+    fn test_under_constrained() {
+        let mut cs = ConstraintSystem::<E>::new(|| "test");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let a = cb.create_witin(|| "a");
+        let b = cb.create_witin(|| "b");
+        let c = cb.create_witin(|| "c");
+        let d = cb.create_witin(|| "d");
+        // b is determined by a, but c and d only by their sum
+        cb.read_record(|| "read a", a.expr()).unwrap();
+        cb.require_equal(|| "b = a + 1", b.expr(), a.expr() + 1)
+            .unwrap();
+        cb.require_equal(|| "c + d = a", c.expr() + d.expr(), a.expr())
+            .unwrap();
+        cb.write_record(|| "write b", b.expr()).unwrap();
+        cb.write_record(|| "write c", c.expr()).unwrap();
+
+        let wits_in = [[3, 4], [4, 5], [1, 2], [2, 2]]
+            .into_iter()
+            .map(|column| column.map(Goldilocks::from).to_vec().into_mle().into())
+            .collect_vec();
+        MockProver::assert_satisfied(&cb, &wits_in, &[], None, None);
+
+        let found = MockProver::find_under_constrained(&cb, &wits_in, 2, &[], 1);
+        assert!(found.is_empty(), "{found:?}");
+        let found = MockProver::find_under_constrained(&cb, &wits_in, 2, &[], 2);
+        assert_eq!(found, vec![UnderConstrained {
+            columns: vec!["test/c".to_string(), "test/d".to_string()],
+            inst_id: 0,
+            writes: vec!["test/write c".to_string()],
+        }]);
+    }
+
+    #[test]
+    fn test_under_constrained_boundary_value() {
+        let mut cs = ConstraintSystem::<E>::new(|| "test");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let a = cb.create_witin(|| "a");
+        let b = cb.create_witin(|| "b");
+        // b may be a or 0, which no small step from a reaches
+        cb.read_record(|| "read a", a.expr()).unwrap();
+        cb.require_zero(|| "b * (b - a) = 0", b.expr() * (b.expr() - a.expr()))
+            .unwrap();
+        cb.write_record(|| "write b", b.expr()).unwrap();
+
+        let wits_in = [[5, 7], [5, 7]]
+            .into_iter()
+            .map(|column| column.map(Goldilocks::from).to_vec().into_mle().into())
+            .collect_vec();
+        MockProver::assert_satisfied(&cb, &wits_in, &[], None, None);
+
+        let found = MockProver::find_under_constrained(&cb, &wits_in, 2, &[], 1);
+        assert_eq!(found, vec![UnderConstrained {
+            columns: vec!["test/b".to_string()],
+            inst_id: 0,
+            writes: vec!["test/write b".to_string()],
+        }]);
+    }
+
+    #[test]
+    fn test_opcodes_constrained() {
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = AddInstruction::construct_circuit(&mut cb).unwrap();
+        let insn_code = encode_rv32(InsnKind::ADD, 2, 3, 4, 0);
+        let (raw_witin, _) =
+            AddInstruction::assign_instances(&config, cb.cs.num_witin as usize, vec![
+                StepRecord::new_r_instruction(
+                    3,
+                    MOCK_PC_START,
+                    insn_code,
+                    11,
+                    0xfffffffe,
+                    Change::new(0, 11_u32.wrapping_add(0xfffffffe)),
+                    0,
+                ),
+            ])
+            .unwrap();
+        MockProver::assert_constrained_raw(&cb, raw_witin, &[insn_code]);
+
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = SubInstruction::construct_circuit(&mut cb).unwrap();
+        let insn_code = encode_rv32(InsnKind::SUB, 2, 3, 4, 0);
+        let (raw_witin, _) =
+            SubInstruction::assign_instances(&config, cb.cs.num_witin as usize, vec![
+                StepRecord::new_r_instruction(
+                    3,
+                    MOCK_PC_START,
+                    insn_code,
+                    11,
+                    2,
+                    Change::new(0, 11_u32.wrapping_sub(2)),
+                    0,
+                ),
+            ])
+            .unwrap();
+        MockProver::assert_constrained_raw(&cb, raw_witin, &[insn_code]);
+    }
+}
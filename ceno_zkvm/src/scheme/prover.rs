@@ -2,18 +2,21 @@ use ff_ext::ExtensionField;
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap},
     sync::Arc,
+    time::Duration,
 };
 
 use ff::Field;
 use itertools::{Itertools, izip};
 use mpcs::PolynomialCommitmentScheme;
 use multilinear_extensions::{
-    mle::{IntoMLE, MultilinearExtension},
+    mle::{DenseMultilinearExtension, IntoMLE, MultilinearExtension},
     util::ceil_log2,
     virtual_poly::build_eq_x_r_vec,
     virtual_poly_v2::ArcMultilinearExtension,
 };
+use poseidon::digest::Digest;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use sumcheck::{
     entered_span, exit_span,
     structs::{IOPProverMessage, IOPProverStateV2},
@@ -28,7 +31,7 @@ use crate::{
         constants::{MAINCONSTRAIN_SUMCHECK_BATCH_SIZE, NUM_FANIN, NUM_FANIN_LOGUP},
         utils::{
             infer_tower_logup_witness, infer_tower_product_witness, interleaving_mles_to_mles,
-            wit_infer_by_expr,
+            wit_infer_by_expr, wit_infer_by_exprs,
         },
     },
     structs::{
@@ -38,10 +41,59 @@ use crate::{
     virtual_polys::VirtualPolynomials,
 };
 
-use super::{PublicValues, ZKVMOpcodeProof, ZKVMProof, ZKVMTableProof};
+use super::{
+    PublicValues, ZKVMOpcodeProof, ZKVMProof, ZKVMTableProof,
+    report::{CircuitReport, ProofTimes, ProvingReport},
+};
 
 type ResultCreateTableProof<E, PCS> = (ZKVMTableProof<E, PCS>, HashMap<usize, E>);
 
+/// The proof without circuit proofs, the challenges, and the proving task of each circuit, see
+/// [`ZKVMProver::commit_witnesses`].
+pub type CommittedWitnesses<E, PCS> = (ZKVMProof<E, PCS>, [E; 2], Vec<CircuitProvingTask<E, PCS>>);
+
+/// The proving of a circuit after the witnesses of all circuits are committed: it only depends on
+/// its own witness and fork of the transcript, so that circuits can be proven anywhere.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
+pub struct CircuitProvingTask<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    pub circuit_name: String,
+    /// The index of the fork of the transcript, unique across the circuits of the proof.
+    pub index: usize,
+    pub witness: Vec<DenseMultilinearExtension<E>>,
+    pub num_instances: usize,
+    pub wits_commit: PCS::CommitmentWithData,
+    pub commit_time: Duration,
+    pub transcript: Transcript<E>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
+pub enum CircuitProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    Opcode(ZKVMOpcodeProof<E, PCS>),
+    /// The proof of a table, and the evaluations of the public values it opens.
+    Table(ZKVMTableProof<E, PCS>, HashMap<usize, E>),
+}
+
+/// The result of a [`CircuitProvingTask`].
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
+pub struct ProvenCircuit<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    pub circuit_name: String,
+    pub index: usize,
+    pub proof: CircuitProof<E, PCS>,
+    pub report: CircuitReport,
+}
+
 pub struct ZKVMProver<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     pub pk: ZKVMProvingKey<E, PCS>,
 }
@@ -51,14 +103,54 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         ZKVMProver { pk }
     }
 
-    /// create proof for zkvm execution
+    pub fn program_id(&self) -> Digest<E::BaseField> {
+        self.pk.program_id()
+    }
+
+    /// create proof for zkvm execution, and log its report as JSON
     pub fn create_proof(
         &self,
         witnesses: ZKVMWitnesses<E>,
         pi: PublicValues<u32>,
-        mut transcript: Transcript<E>,
+        transcript: Transcript<E>,
     ) -> Result<ZKVMProof<E, PCS>, ZKVMError> {
-        let mut vm_proof = ZKVMProof::empty(pi);
+        let (vm_proof, report) = self.create_proof_with_report(witnesses, pi, transcript)?;
+        tracing::info!("proving report: {}", report.to_json());
+        Ok(vm_proof)
+    }
+
+    /// create proof for zkvm execution, along with the shape and proving times of each circuit
+    pub fn create_proof_with_report(
+        &self,
+        witnesses: ZKVMWitnesses<E>,
+        pi: PublicValues<u32>,
+        transcript: Transcript<E>,
+    ) -> Result<(ZKVMProof<E, PCS>, ProvingReport), ZKVMError> {
+        let (mut vm_proof, challenges, tasks) = self.commit_witnesses(witnesses, pi, transcript)?;
+        let mut report = ProvingReport::default();
+        for task in tasks {
+            let circuit_pk = &self.pk.circuit_pks[&task.circuit_name];
+            let proven =
+                Self::prove_circuit(&self.pk.pp, circuit_pk, &vm_proof.raw_pi, &challenges, task)?;
+            vm_proof.insert_circuit_proof(proven, &mut report);
+        }
+        Ok((vm_proof, report))
+    }
+
+    /// Commit to the witnesses of all circuits and derive the challenges they share. Return the
+    /// proof without circuit proofs, the challenges, and the proving task of each circuit.
+    pub fn commit_witnesses(
+        &self,
+        witnesses: ZKVMWitnesses<E>,
+        pi: PublicValues<u32>,
+        mut transcript: Transcript<E>,
+    ) -> Result<CommittedWitnesses<E, PCS>, ZKVMError> {
+        let vm_proof = ZKVMProof::empty(pi);
+
+        // bind the proof to the verifying key
+        for v in &self.pk.program_id().0 {
+            transcript.append_field_element(v);
+        }
 
         // including raw public input to transcript
         vm_proof
@@ -66,15 +158,6 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             .iter()
             .for_each(|v| v.iter().for_each(|v| transcript.append_field_element(v)));
 
-        let pi: Vec<ArcMultilinearExtension<E>> = vm_proof
-            .raw_pi
-            .iter()
-            .map(|p| {
-                let pi_mle: ArcMultilinearExtension<E> = p.to_vec().into_mle().into();
-                pi_mle
-            })
-            .collect();
-
         // commit to fixed commitment
         for (_, pk) in self.pk.circuit_pks.iter() {
             if let Some(fixed_commit) = &pk.vk.fixed_commit {
@@ -86,9 +169,20 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         // commit to main traces
         let mut commitments = BTreeMap::new();
         let mut wits = BTreeMap::new();
-        // sort by circuit name, and we rely on an assumption that
-        // table circuit witnesses come after opcode circuit witnesses
-        for (circuit_name, witness) in witnesses.witnesses {
+        // sort by circuit name, with opcode circuit witnesses before table circuit witnesses,
+        // in the order the verifier reads the commitments
+        let (opcode_witnesses, table_witnesses): (Vec<_>, Vec<_>) = witnesses
+            .witnesses
+            .into_iter()
+            .partition(|(circuit_name, _)| {
+                self.pk.circuit_pks.get(circuit_name).is_some_and(|pk| {
+                    let cs = pk.get_cs();
+                    cs.lk_table_expressions.is_empty()
+                        && cs.r_table_expressions.is_empty()
+                        && cs.w_table_expressions.is_empty()
+                })
+            });
+        for (circuit_name, witness) in opcode_witnesses.into_iter().chain(table_witnesses) {
             let commit_dur = std::time::Instant::now();
             let num_instances = witness.num_instances();
             let witness = witness.into_mles();
@@ -97,12 +191,9 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                 PCS::batch_commit_and_write(&self.pk.pp, &witness, &mut transcript)
                     .map_err(ZKVMError::PCSError)?,
             );
-            tracing::info!(
-                "commit to {} traces took {:?}",
-                circuit_name,
-                commit_dur.elapsed()
-            );
-            wits.insert(circuit_name, (witness, num_instances));
+            let commit_time = commit_dur.elapsed();
+            tracing::info!("commit to {} traces took {:?}", circuit_name, commit_time);
+            wits.insert(circuit_name, (witness, num_instances, commit_time));
         }
 
         // squeeze two challenges from transcript
@@ -112,81 +203,128 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         ];
         tracing::debug!("challenges in prover: {:?}", challenges);
 
-        let mut transcripts = transcript.fork(self.pk.circuit_pks.len());
-        for ((circuit_name, pk), (i, transcript)) in self
-            .pk
-            .circuit_pks
-            .iter() // Sorted by key.
-            .zip_eq(transcripts.iter_mut().enumerate())
-        {
-            let (witness, num_instances) = wits
-                .remove(circuit_name)
-                .ok_or(ZKVMError::WitnessNotFound(circuit_name.clone()))?;
-            let wits_commit = commitments.remove(circuit_name).unwrap();
-            // TODO: add an enum for circuit type either in constraint_system or vk
-            let cs = pk.get_cs();
-            let is_opcode_circuit = cs.lk_table_expressions.is_empty()
-                && cs.r_table_expressions.is_empty()
-                && cs.w_table_expressions.is_empty();
-
-            if is_opcode_circuit {
-                tracing::debug!(
-                    "opcode circuit {} has {} witnesses, {} reads, {} writes, {} lookups",
-                    circuit_name,
-                    cs.num_witin,
-                    cs.r_expressions.len(),
-                    cs.w_expressions.len(),
-                    cs.lk_expressions.len(),
-                );
-                for lk_s in cs.lk_expressions_namespace_map.iter() {
-                    tracing::debug!("opcode circuit {}: {}", circuit_name, lk_s);
+        // only the circuits with a witness are proven, the verifier checks the circuits in the
+        // proof
+        let transcripts = transcript.fork(wits.len());
+        let tasks = wits
+            .into_iter() // Sorted by key.
+            .zip_eq(transcripts)
+            .enumerate()
+            .map(|(index, ((circuit_name, witness), transcript))| {
+                if !self.pk.circuit_pks.contains_key(&circuit_name) {
+                    return Err(ZKVMError::VKNotFound(circuit_name));
                 }
-                let opcode_proof = self.create_opcode_proof(
+                let (witness, num_instances, commit_time) = witness;
+                Ok(CircuitProvingTask {
+                    wits_commit: commitments.remove(&circuit_name).unwrap(),
                     circuit_name,
-                    &self.pk.pp,
-                    pk,
-                    witness.into_iter().map(|w| w.into()).collect_vec(),
-                    wits_commit,
-                    &pi,
+                    index,
+                    witness,
                     num_instances,
+                    commit_time,
                     transcript,
-                    &challenges,
-                )?;
-                tracing::info!(
-                    "generated proof for opcode {} with num_instances={}",
-                    circuit_name,
-                    num_instances
-                );
-                vm_proof
-                    .opcode_proofs
-                    .insert(circuit_name.clone(), (i, opcode_proof));
-            } else {
-                let (table_proof, pi_in_evals) = self.create_table_proof(
-                    circuit_name,
-                    &self.pk.pp,
-                    pk,
-                    witness.into_iter().map(|v| v.into()).collect_vec(),
-                    wits_commit,
-                    &pi,
-                    transcript,
-                    &challenges,
-                )?;
-                tracing::info!(
-                    "generated proof for table {} with num_instances={}",
-                    circuit_name,
-                    num_instances
-                );
-                vm_proof
-                    .table_proofs
-                    .insert(circuit_name.clone(), (i, table_proof));
-                for (idx, eval) in pi_in_evals {
-                    vm_proof.update_pi_eval(idx, eval);
-                }
-            }
-        }
+                })
+            })
+            .collect::<Result<_, ZKVMError>>()?;
 
-        Ok(vm_proof)
+        Ok((vm_proof, challenges, tasks))
+    }
+
+    /// Prove a circuit of a proof with the public values `raw_pi`, given the challenges derived by
+    /// [`Self::commit_witnesses`].
+    pub fn prove_circuit(
+        pp: &PCS::ProverParam,
+        circuit_pk: &ProvingKey<E, PCS>,
+        raw_pi: &[Vec<E::BaseField>],
+        challenges: &[E; 2],
+        task: CircuitProvingTask<E, PCS>,
+    ) -> Result<ProvenCircuit<E, PCS>, ZKVMError> {
+        let CircuitProvingTask {
+            circuit_name,
+            index,
+            witness,
+            num_instances,
+            wits_commit,
+            commit_time,
+            mut transcript,
+        } = task;
+        let pi: Vec<ArcMultilinearExtension<E>> = raw_pi
+            .iter()
+            .map(|p| {
+                let pi_mle: ArcMultilinearExtension<E> = p.to_vec().into_mle().into();
+                pi_mle
+            })
+            .collect();
+
+        // TODO: add an enum for circuit type either in constraint_system or vk
+        let cs = circuit_pk.get_cs();
+        let num_vars = witness.first().map_or_else(
+            || ceil_log2(next_pow2_instance_padding(num_instances)),
+            |w| w.num_vars(),
+        );
+        let mut circuit_report = CircuitReport::new(cs, num_instances, num_vars);
+        circuit_report.commit_time = commit_time;
+        let is_opcode_circuit = cs.lk_table_expressions.is_empty()
+            && cs.r_table_expressions.is_empty()
+            && cs.w_table_expressions.is_empty();
+
+        let proof = if is_opcode_circuit {
+            tracing::debug!(
+                "opcode circuit {} has {} witnesses, {} reads, {} writes, {} lookups",
+                circuit_name,
+                cs.num_witin,
+                cs.r_expressions.len(),
+                cs.w_expressions.len(),
+                cs.lk_expressions.len(),
+            );
+            for lk_s in cs.lk_expressions_namespace_map.iter() {
+                tracing::debug!("opcode circuit {}: {}", circuit_name, lk_s);
+            }
+            let (opcode_proof, times) = Self::create_opcode_proof_with_times(
+                &circuit_name,
+                pp,
+                circuit_pk,
+                witness.into_iter().map(|w| w.into()).collect_vec(),
+                wits_commit,
+                &pi,
+                num_instances,
+                &mut transcript,
+                challenges,
+            )?;
+            circuit_report.set_times(times);
+            tracing::info!(
+                "generated proof for opcode {} with num_instances={}",
+                circuit_name,
+                num_instances
+            );
+            CircuitProof::Opcode(opcode_proof)
+        } else {
+            let ((table_proof, pi_in_evals), times) = Self::create_table_proof_with_times(
+                &circuit_name,
+                pp,
+                circuit_pk,
+                witness.into_iter().map(|v| v.into()).collect_vec(),
+                wits_commit,
+                &pi,
+                &mut transcript,
+                challenges,
+            )?;
+            circuit_report.set_times(times);
+            tracing::info!(
+                "generated proof for table {} with num_instances={}",
+                circuit_name,
+                num_instances
+            );
+            CircuitProof::Table(table_proof, pi_in_evals)
+        };
+        Ok(ProvenCircuit {
+            circuit_name,
+            index,
+            proof,
+            report: circuit_report,
+        })
     }
+
     /// create proof giving witness and num_instances
     /// major flow break down into
     /// 1: witness layer inferring from input -> output
@@ -204,6 +342,35 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         transcript: &mut Transcript<E>,
         challenges: &[E; 2],
     ) -> Result<ZKVMOpcodeProof<E, PCS>, ZKVMError> {
+        Self::create_opcode_proof_with_times(
+            name,
+            pp,
+            circuit_pk,
+            witnesses,
+            wits_commit,
+            pi,
+            num_instances,
+            transcript,
+            challenges,
+        )
+        .map(|(proof, _)| proof)
+    }
+
+    /// create proof giving witness and num_instances, along with the time spent on its
+    /// sumchecks and on its opening
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_opcode_proof_with_times(
+        name: &str,
+        pp: &PCS::ProverParam,
+        circuit_pk: &ProvingKey<E, PCS>,
+        witnesses: Vec<ArcMultilinearExtension<'_, E>>,
+        wits_commit: PCS::CommitmentWithData,
+        pi: &[ArcMultilinearExtension<'_, E>],
+        num_instances: usize,
+        transcript: &mut Transcript<E>,
+        challenges: &[E; 2],
+    ) -> Result<(ZKVMOpcodeProof<E, PCS>, ProofTimes), ZKVMError> {
+        let sumcheck_dur = std::time::Instant::now();
         let cs = circuit_pk.get_cs();
         let next_pow2_instances = next_pow2_instance_padding(num_instances);
         let log2_num_instances = ceil_log2(next_pow2_instances);
@@ -219,16 +386,8 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
 
         // main constraint: read/write record witness inference
         let span = entered_span!("wit_inference::record");
-        let records_wit: Vec<ArcMultilinearExtension<'_, E>> = cs
-            .r_expressions
-            .par_iter()
-            .chain(cs.w_expressions.par_iter())
-            .chain(cs.lk_expressions.par_iter())
-            .map(|expr| {
-                assert_eq!(expr.degree(), 1);
-                wit_infer_by_expr(&[], &witnesses, pi, challenges, expr)
-            })
-            .collect();
+        let records_wit: Vec<ArcMultilinearExtension<'_, E>> =
+            wit_infer_by_exprs(&[], &witnesses, pi, challenges, &circuit_pk.records);
         let (r_records_wit, w_lk_records_wit) = records_wit.split_at(cs.r_expressions.len());
         let (w_records_wit, lk_records_wit) = w_lk_records_wit.split_at(cs.w_expressions.len());
         exit_span!(span);
@@ -239,10 +398,11 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             cs.w_expressions.len(),
             cs.lk_expressions.len(),
         );
+        // a circuit without lookups has one padding lookup per instance
         let (log2_r_count, log2_w_count, log2_lk_count) = (
             ceil_log2(r_counts_per_instance),
             ceil_log2(w_counts_per_instance),
-            ceil_log2(lk_counts_per_instance),
+            ceil_log2(lk_counts_per_instance.max(1)),
         );
         // process last layer by interleaving all the read/write record respectively
         // as last layer is the output of sel stage
@@ -279,6 +439,17 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
 
         let span = entered_span!("wit_inference::tower_witness_lk_last_layer");
         // TODO optimize last layer to avoid alloc new vector to save memory
+        let padding_lk_records_wit: Vec<ArcMultilinearExtension<'_, E>>;
+        let lk_records_wit = if lk_records_wit.is_empty() {
+            padding_lk_records_wit = vec![
+                vec![chip_record_alpha; next_pow2_instances]
+                    .into_mle()
+                    .into(),
+            ];
+            &padding_lk_records_wit
+        } else {
+            lk_records_wit
+        };
         let lk_records_last_layer =
             interleaving_mles_to_mles(lk_records_wit, num_instances, NUM_FANIN, chip_record_alpha);
         assert_eq!(lk_records_last_layer.len(), 2);
@@ -581,6 +752,7 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             .collect();
         exit_span!(span);
 
+        let sumcheck_time = sumcheck_dur.elapsed();
         let span = entered_span!("pcs_open");
         let opening_dur = std::time::Instant::now();
         tracing::debug!(
@@ -598,15 +770,16 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             transcript,
         )
         .map_err(ZKVMError::PCSError)?;
+        let opening_time = opening_dur.elapsed();
         tracing::info!(
             "[opcode {}] build opening proof took {:?}",
             name,
-            opening_dur.elapsed(),
+            opening_time,
         );
         exit_span!(span);
         let wits_commit = PCS::get_pure_commitment(&wits_commit);
 
-        Ok(ZKVMOpcodeProof {
+        let proof = ZKVMOpcodeProof {
             num_instances,
             record_r_out_evals,
             record_w_out_evals,
@@ -622,7 +795,11 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             wits_commit,
             wits_opening_proof,
             wits_in_evals,
-        })
+        };
+        Ok((proof, ProofTimes {
+            sumcheck_time,
+            opening_time,
+        }))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -640,6 +817,32 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         transcript: &mut Transcript<E>,
         challenges: &[E; 2],
     ) -> Result<ResultCreateTableProof<E, PCS>, ZKVMError> {
+        Self::create_table_proof_with_times(
+            name,
+            pp,
+            circuit_pk,
+            witnesses,
+            wits_commit,
+            pi,
+            transcript,
+            challenges,
+        )
+        .map(|(proof, _)| proof)
+    }
+
+    /// create a table proof, along with the time spent on its sumchecks and on its openings
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_table_proof_with_times(
+        name: &str,
+        pp: &PCS::ProverParam,
+        circuit_pk: &ProvingKey<E, PCS>,
+        witnesses: Vec<ArcMultilinearExtension<'_, E>>,
+        wits_commit: PCS::CommitmentWithData,
+        pi: &[ArcMultilinearExtension<'_, E>],
+        transcript: &mut Transcript<E>,
+        challenges: &[E; 2],
+    ) -> Result<(ResultCreateTableProof<E, PCS>, ProofTimes), ZKVMError> {
+        let sumcheck_dur = std::time::Instant::now();
         let cs = circuit_pk.get_cs();
         let fixed = circuit_pk
             .fixed_traces
@@ -674,22 +877,8 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
 
         // main constraint: lookup denominator and numerator record witness inference
         let span = entered_span!("wit_inference::record");
-        let mut records_wit: Vec<ArcMultilinearExtension<'_, E>> = cs
-            .r_table_expressions
-            .par_iter()
-            .map(|r| &r.expr)
-            .chain(cs.w_table_expressions.par_iter().map(|w| &w.expr))
-            .chain(
-                cs.lk_table_expressions
-                    .par_iter()
-                    .map(|lk| &lk.multiplicity),
-            )
-            .chain(cs.lk_table_expressions.par_iter().map(|lk| &lk.values))
-            .map(|expr| {
-                assert_eq!(expr.degree(), 1);
-                wit_infer_by_expr(&fixed, &witnesses, pi, challenges, expr)
-            })
-            .collect();
+        let mut records_wit: Vec<ArcMultilinearExtension<'_, E>> =
+            wit_infer_by_exprs(&fixed, &witnesses, pi, challenges, &circuit_pk.records);
         let max_log2_num_instance = records_wit.iter().map(|mle| mle.num_vars()).max().unwrap();
         let min_log2_num_instance = records_wit.iter().map(|mle| mle.num_vars()).min().unwrap();
         let (r_set_wit, remains) = records_wit.split_at_mut(cs.r_table_expressions.len());
@@ -1016,7 +1205,9 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             .collect_vec();
         // TODO implement mechanism to skip commitment
 
+        let sumcheck_time = sumcheck_dur.elapsed();
         let span = entered_span!("pcs_opening");
+        let opening_dur = std::time::Instant::now();
         let (fixed_opening_proof, fixed_commit) = if !fixed.is_empty() {
             (
                 Some(
@@ -1055,6 +1246,7 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             transcript,
         )
         .map_err(ZKVMError::PCSError)?;
+        let opening_time = opening_dur.elapsed();
         exit_span!(span);
         let wits_commit = PCS::get_pure_commitment(&wits_commit);
         tracing::debug!(
@@ -1066,7 +1258,7 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             wits_commit,
         );
 
-        Ok((
+        let proof = (
             ZKVMTableProof {
                 r_out_evals,
                 w_out_evals,
@@ -1083,7 +1275,11 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                 wits_opening_proof,
             },
             pi_in_evals,
-        ))
+        );
+        Ok((proof, ProofTimes {
+            sumcheck_time,
+            opening_time,
+        }))
     }
 }
 
@@ -0,0 +1,124 @@
+//! A receipt of a proven execution: the proof, its decoded public values, and the program it
+//! proves, so that applications can pass it around and read its outputs.
+
+use ff_ext::ExtensionField;
+use mpcs::PolynomialCommitmentScheme;
+use poseidon::{DIGEST_WIDTH, digest::Digest};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use transcript::Transcript;
+
+use super::{PublicValues, ZKVMProof, prover::ZKVMProver, verifier::ZKVMVerifier};
+use crate::{
+    error::ZKVMError,
+    structs::{ZKVMVerifyingKey, ZKVMWitnesses},
+};
+
+/// The transcript label of the proofs in receipts.
+pub const RECEIPT_TRANSCRIPT_LABEL: &[u8] = b"riscv";
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
+pub struct Receipt<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    program_id: Digest<E::BaseField>,
+    public_values: PublicValues<u32>,
+    proof: ZKVMProof<E, PCS>,
+}
+
+impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> Receipt<E, PCS> {
+    /// Bundle a proof of the program `program_id`, decoding its public values.
+    pub fn new(
+        program_id: Digest<E::BaseField>,
+        proof: ZKVMProof<E, PCS>,
+    ) -> Result<Self, ZKVMError> {
+        let public_values = PublicValues::from_vec::<E>(&proof.raw_pi)?;
+        Ok(Self {
+            program_id,
+            public_values,
+            proof,
+        })
+    }
+
+    /// Verify the proof against `vk`, and that it proves the public values and the program of the
+    /// receipt.
+    pub fn verify(&self, vk: &ZKVMVerifyingKey<E, PCS>) -> Result<(), ZKVMError> {
+        if vk.program_id() != self.program_id {
+            return Err(ZKVMError::VerifyError(
+                "receipt of another program than the verifying key".into(),
+            ));
+        }
+        if self.public_values.to_vec::<E>() != self.proof.raw_pi {
+            return Err(ZKVMError::VerifyError(
+                "receipt public values do not match the proof".into(),
+            ));
+        }
+        let transcript = Transcript::new(RECEIPT_TRANSCRIPT_LABEL);
+        if ZKVMVerifier::new(vk.clone()).verify_proof(self.proof.clone(), transcript)? {
+            Ok(())
+        } else {
+            Err(ZKVMError::VerifyError("invalid receipt proof".into()))
+        }
+    }
+
+    pub fn program_id(&self) -> &Digest<E::BaseField> {
+        &self.program_id
+    }
+
+    pub fn public_values(&self) -> &PublicValues<u32> {
+        &self.public_values
+    }
+
+    pub fn exit_code(&self) -> u32 {
+        self.public_values.exit_code()
+    }
+
+    pub fn init_pc(&self) -> u32 {
+        self.public_values.init_pc()
+    }
+
+    pub fn init_cycle(&self) -> u32 {
+        self.public_values.init_cycle()
+    }
+
+    pub fn end_pc(&self) -> u32 {
+        self.public_values.end_pc()
+    }
+
+    pub fn end_cycle(&self) -> u32 {
+        self.public_values.end_cycle()
+    }
+
+    /// The public input and output words committed by the program, or nothing if they are
+    /// hashed.
+    pub fn public_io(&self) -> &[u32] {
+        self.public_values.public_io()
+    }
+
+    /// The digest of the public input and output words, if they are hashed.
+    pub fn public_io_digest(&self) -> Option<[u64; DIGEST_WIDTH]> {
+        self.public_values.public_io_digest()
+    }
+
+    pub fn proof(&self) -> &ZKVMProof<E, PCS> {
+        &self.proof
+    }
+
+    pub fn into_proof(self) -> ZKVMProof<E, PCS> {
+        self.proof
+    }
+}
+
+impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
+    /// Prove the execution as [`Self::create_proof`], in a receipt.
+    pub fn create_receipt(
+        &self,
+        witnesses: ZKVMWitnesses<E>,
+        pi: PublicValues<u32>,
+    ) -> Result<Receipt<E, PCS>, ZKVMError> {
+        let transcript = Transcript::new(RECEIPT_TRANSCRIPT_LABEL);
+        let proof = self.create_proof(witnesses, pi, transcript)?;
+        Receipt::new(self.program_id(), proof)
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, time::Duration};
+
+use crate::circuit_builder::ConstraintSystem;
+use ff_ext::ExtensionField;
+
+/// The shape and the proving times of every circuit of a proof, by circuit name.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProvingReport {
+    pub circuits: BTreeMap<String, CircuitReport>,
+}
+
+impl ProvingReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a report is always serializable")
+    }
+
+    /// The sum of the commit, sumcheck and opening times of all circuits.
+    pub fn total_time(&self) -> Duration {
+        self.circuits.values().map(CircuitReport::total_time).sum()
+    }
+}
+
+/// The shape of a circuit and the time spent on each phase of its proof.
+///
+/// Times are serialized as fractional seconds.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CircuitReport {
+    pub is_table: bool,
+    pub num_instances: usize,
+    /// The number of variables of the padded witness columns.
+    pub num_vars: usize,
+    pub num_witin: usize,
+    pub num_reads: usize,
+    pub num_writes: usize,
+    pub num_lookups: usize,
+    /// Committing to the witness columns.
+    #[serde(with = "secs")]
+    pub commit_time: Duration,
+    /// Inferring the towers and running the tower and main sumchecks.
+    #[serde(with = "secs")]
+    pub sumcheck_time: Duration,
+    /// Opening the witness and fixed columns.
+    #[serde(with = "secs")]
+    pub opening_time: Duration,
+}
+
+impl CircuitReport {
+    pub fn new<E: ExtensionField>(
+        cs: &ConstraintSystem<E>,
+        num_instances: usize,
+        num_vars: usize,
+    ) -> Self {
+        let is_table = !(cs.lk_table_expressions.is_empty()
+            && cs.r_table_expressions.is_empty()
+            && cs.w_table_expressions.is_empty());
+        CircuitReport {
+            is_table,
+            num_instances,
+            num_vars,
+            num_witin: cs.num_witin as usize,
+            num_reads: cs.r_expressions.len() + cs.r_table_expressions.len(),
+            num_writes: cs.w_expressions.len() + cs.w_table_expressions.len(),
+            num_lookups: cs.lk_expressions.len() + cs.lk_table_expressions.len(),
+            ..Default::default()
+        }
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.commit_time + self.sumcheck_time + self.opening_time
+    }
+
+    pub fn set_times(&mut self, times: ProofTimes) {
+        self.sumcheck_time = times.sumcheck_time;
+        self.opening_time = times.opening_time;
+    }
+}
+
+/// The time spent on the sumchecks and on the openings of a circuit proof.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ProofTimes {
+    pub sumcheck_time: Duration,
+    pub opening_time: Duration,
+}
+
+mod secs {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(duration.as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let secs = f64::deserialize(deserializer)?;
+        Duration::try_from_secs_f64(secs).map_err(serde::de::Error::custom)
+    }
+}
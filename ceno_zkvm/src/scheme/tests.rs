@@ -1,15 +1,27 @@
+#[cfg(unix)]
+use std::{
+    io::Read,
+    os::unix::net::{UnixListener, UnixStream},
+    path::Path,
+    process::{self, Child, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
 use std::{marker::PhantomData, mem::MaybeUninit};
 
 use ceno_emul::{
-    CENO_PLATFORM,
-    InsnKind::{ADD, EANY},
-    PC_WORD_SIZE, Program, StepRecord, VMState,
+    ByteAddr, CENO_PLATFORM, EmuContext,
+    InsnKind::{ADD, EANY, LUI, LW},
+    PC_WORD_SIZE, Program, SHA256_BLOCK_WORDS, SHA256_STATE_WORDS, StepRecord, Tracer, VMState,
+    WordAddr, encode_rv32, sha256_compress_block,
 };
 use ff::Field;
 use ff_ext::ExtensionField;
-use goldilocks::GoldilocksExt2;
+use goldilocks::{Goldilocks, GoldilocksExt2, SmallField};
 use itertools::Itertools;
 use mpcs::{Basefold, BasefoldDefault, BasefoldRSParams, PolynomialCommitmentScheme};
+use multilinear_extensions::mle::with_base_fields;
+use poseidon::digest::Digest;
 use rand_chacha::ChaCha8Rng;
 use transcript::Transcript;
 
@@ -20,18 +32,34 @@ use crate::{
     expression::{ToExpr, WitIn},
     instructions::{
         Instruction,
-        riscv::{arith::AddInstruction, ecall::HaltInstruction},
+        riscv::{
+            Rv32imConfig,
+            arith::AddInstruction,
+            constants::{EXIT_PC, PUBLIC_IO_IDX},
+            ecall::{
+                HaltInstruction, KeccakInstruction, Sha256Instruction, Sha256RoundsInstruction,
+            },
+        },
     },
     set_val,
+    state::{GlobalState, HashedIOGlobalState},
     structs::{PointAndEval, ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMWitnesses},
-    tables::{ProgramTableCircuit, U16TableCircuit},
+    tables::{
+        DynVolatileRamTable, KeccakRoundsCircuit, MemFinalRecord, MemInitRecord, MemTable,
+        ProgramTableCircuit, TableCircuit, U16TableCircuit, init_program_data, init_public_io,
+        initial_registers, public_io_digest,
+    },
     witness::LkMultiplicity,
 };
 
+#[cfg(unix)]
+use super::distributed::run_worker;
 use super::{
     PublicValues,
     constants::{MAX_NUM_VARIABLES, NUM_FANIN},
     prover::ZKVMProver,
+    receipt::Receipt,
+    report::{CircuitReport, ProvingReport},
     verifier::ZKVMVerifier,
 };
 
@@ -171,6 +199,8 @@ fn test_rw_lk_expression_combination() {
 
     // <lookup count, rw count>
     test_rw_lk_expression_combination_inner::<19, 17>();
+    // a circuit without lookups has one padding lookup per instance
+    test_rw_lk_expression_combination_inner::<0, 17>();
     test_rw_lk_expression_combination_inner::<61, 17>();
     test_rw_lk_expression_combination_inner::<17, 61>();
 }
@@ -193,6 +223,95 @@ const PROGRAM_CODE: [u32; PROGRAM_SIZE] = {
     program
 };
 
+#[test]
+fn test_proving_report_json() {
+    let circuit_report = CircuitReport {
+        num_instances: 3,
+        num_vars: 2,
+        commit_time: std::time::Duration::from_millis(1500),
+        ..Default::default()
+    };
+    let report = ProvingReport {
+        circuits: [("ADD".to_string(), circuit_report)].into(),
+    };
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(json["circuits"]["ADD"]["num_vars"], 2);
+    assert_eq!(json["circuits"]["ADD"]["commit_time"], 1.5);
+    assert_eq!(
+        serde_json::from_value::<ProvingReport>(json).unwrap(),
+        report
+    );
+}
+
+#[test]
+fn test_public_values_round_trip() {
+    type E = GoldilocksExt2;
+    let pv = PublicValues::new(0xdead_beef, 0x2000_0000, 4, 0x2000_0010, 20, vec![1, 2, 3]);
+    let raw_pi = pv.to_vec::<E>();
+    assert_eq!(PublicValues::from_vec::<E>(&raw_pi).unwrap(), pv);
+
+    // the exit code limbs are 16 bits, and the other values are words
+    let mut invalid = raw_pi.clone();
+    invalid[1] = vec![Goldilocks::from(1 << 16)];
+    assert!(PublicValues::from_vec::<E>(&invalid).is_err());
+    let mut invalid = raw_pi.clone();
+    invalid[2] = vec![Goldilocks::from(1 << 32)];
+    assert!(PublicValues::from_vec::<E>(&invalid).is_err());
+    assert!(PublicValues::from_vec::<E>(&raw_pi[..6]).is_err());
+
+    // the public io is replaced by its digest
+    let hashed = PublicValues::new_with_hashed_io(0, 0x2000_0000, 4, 0x2000_0010, 20, &[1, 2, 3]);
+    assert!(hashed.public_io().is_empty());
+    let digest = public_io_digest(&[1, 2, 3]).0.map(|e| e.to_canonical_u64());
+    assert_eq!(hashed.public_io_digest(), Some(digest));
+    assert_eq!(pv.public_io_digest(), None);
+    let raw_pi = hashed.to_vec::<E>();
+    assert_eq!(PublicValues::from_vec::<E>(&raw_pi).unwrap(), hashed);
+    let mut invalid = raw_pi.clone();
+    invalid[PUBLIC_IO_IDX] = vec![Goldilocks::ONE];
+    assert!(PublicValues::from_vec::<E>(&invalid).is_err());
+}
+
+#[test]
+fn test_program_id() {
+    type E = GoldilocksExt2;
+    type Pcs = Basefold<GoldilocksExt2, BasefoldRSParams, ChaCha8Rng>;
+
+    let pcs_param = Pcs::setup(1 << 8).expect("Basefold PCS setup");
+    let (pp, vp) = Pcs::trim(&pcs_param, 1 << 8).expect("Basefold trim");
+    let key_gen = |code: &[u32]| {
+        let program = Program::new(
+            CENO_PLATFORM.pc_base(),
+            CENO_PLATFORM.pc_base(),
+            code.to_vec(),
+            Default::default(),
+        );
+        let mut zkvm_cs = ZKVMConstraintSystem::default();
+        zkvm_cs.register_opcode_circuit::<AddInstruction<E>>();
+        let prog_config = zkvm_cs.register_table_circuit::<ProgramTableCircuit<E, PROGRAM_SIZE>>();
+        let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
+        zkvm_fixed_traces.register_opcode_circuit::<AddInstruction<E>>(&zkvm_cs);
+        zkvm_fixed_traces.register_table_circuit::<ProgramTableCircuit<E, PROGRAM_SIZE>>(
+            &zkvm_cs,
+            &prog_config,
+            &program,
+        );
+        zkvm_cs
+            .key_gen::<Pcs>(pp.clone(), vp.clone(), zkvm_fixed_traces)
+            .expect("keygen failed")
+    };
+
+    let pk = key_gen(&PROGRAM_CODE);
+    let program_id = pk.program_id();
+    assert_eq!(pk.get_vk().program_id(), program_id);
+    assert_eq!(key_gen(&PROGRAM_CODE).program_id(), program_id);
+
+    // another program has another fixed commitment to the program table
+    let mut code = PROGRAM_CODE;
+    code[1] = PROGRAM_CODE[0];
+    assert_ne!(key_gen(&code).program_id(), program_id);
+}
+
 #[ignore = "this case is already tested in riscv_example as ecall_halt has only one instance"]
 #[test]
 fn test_single_add_instance_e2e() {
@@ -309,3 +428,645 @@ fn test_single_add_instance_e2e() {
             .expect("verify proof return with error"),
     );
 }
+
+#[test]
+fn test_circuits_without_instances_e2e() {
+    type E = GoldilocksExt2;
+    type Pcs = Basefold<GoldilocksExt2, BasefoldRSParams, ChaCha8Rng>;
+
+    let program = Program::new(
+        CENO_PLATFORM.pc_base(),
+        CENO_PLATFORM.pc_base(),
+        PROGRAM_CODE.to_vec(),
+        PROGRAM_CODE
+            .iter()
+            .enumerate()
+            .map(|(insn_idx, &insn)| {
+                (
+                    (insn_idx * PC_WORD_SIZE) as u32 + CENO_PLATFORM.pc_base(),
+                    insn,
+                )
+            })
+            .collect(),
+    );
+
+    let pcs_param = Pcs::setup(1 << MAX_NUM_VARIABLES).expect("Basefold PCS setup");
+    let (pp, vp) = Pcs::trim(&pcs_param, 1 << MAX_NUM_VARIABLES).expect("Basefold trim");
+    let mut zkvm_cs = ZKVMConstraintSystem::default();
+    let add_config = zkvm_cs.register_opcode_circuit::<AddInstruction<E>>();
+    let halt_config = zkvm_cs.register_opcode_circuit::<HaltInstruction<E>>();
+    // the program does not call the precompile
+    let keccak_config = zkvm_cs.register_opcode_circuit::<KeccakInstruction<E>>();
+    let keccak_rounds_config = zkvm_cs.register_table_circuit::<KeccakRoundsCircuit<E>>();
+    let u16_range_config = zkvm_cs.register_table_circuit::<U16TableCircuit<E>>();
+    let prog_config = zkvm_cs.register_table_circuit::<ProgramTableCircuit<E, PROGRAM_SIZE>>();
+
+    let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
+    zkvm_fixed_traces.register_opcode_circuit::<AddInstruction<E>>(&zkvm_cs);
+    zkvm_fixed_traces.register_opcode_circuit::<HaltInstruction<E>>(&zkvm_cs);
+    zkvm_fixed_traces.register_opcode_circuit::<KeccakInstruction<E>>(&zkvm_cs);
+    zkvm_fixed_traces.register_table_circuit::<KeccakRoundsCircuit<E>>(
+        &zkvm_cs,
+        &keccak_rounds_config,
+        &(),
+    );
+    zkvm_fixed_traces.register_table_circuit::<U16TableCircuit<E>>(
+        &zkvm_cs,
+        &u16_range_config,
+        &(),
+    );
+    zkvm_fixed_traces.register_table_circuit::<ProgramTableCircuit<E, PROGRAM_SIZE>>(
+        &zkvm_cs,
+        &prog_config,
+        &program,
+    );
+
+    let pk = zkvm_cs
+        .clone()
+        .key_gen::<Pcs>(pp, vp, zkvm_fixed_traces)
+        .expect("keygen failed");
+    let vk = pk.get_vk();
+
+    let mut vm = VMState::new(CENO_PLATFORM, program.clone());
+    let (add_records, halt_records): (Vec<_>, Vec<_>) = vm
+        .iter_until_halt()
+        .collect::<Result<Vec<StepRecord>, _>>()
+        .expect("vm exec failed")
+        .into_iter()
+        .partition(|record| record.insn().codes().kind == ADD);
+
+    let mut zkvm_witness = ZKVMWitnesses::default();
+    zkvm_witness
+        .assign_opcode_circuit::<AddInstruction<E>>(&zkvm_cs, &add_config, add_records)
+        .unwrap();
+    zkvm_witness
+        .assign_opcode_circuit::<HaltInstruction<E>>(&zkvm_cs, &halt_config, halt_records)
+        .unwrap();
+    zkvm_witness
+        .assign_opcode_circuit::<KeccakInstruction<E>>(&zkvm_cs, &keccak_config, vec![])
+        .unwrap();
+    zkvm_witness.finalize_lk_multiplicities();
+    zkvm_witness
+        .assign_table_circuit::<KeccakRoundsCircuit<E>>(&zkvm_cs, &keccak_rounds_config, &[])
+        .unwrap();
+    zkvm_witness
+        .assign_table_circuit::<U16TableCircuit<E>>(&zkvm_cs, &u16_range_config, &())
+        .unwrap();
+    zkvm_witness
+        .assign_table_circuit::<ProgramTableCircuit<E, PROGRAM_SIZE>>(
+            &zkvm_cs,
+            &prog_config,
+            &program,
+        )
+        .unwrap();
+
+    let prover = ZKVMProver::new(pk);
+    let pi = PublicValues::new(0, 0, 0, 0, 0, vec![0]);
+    let zkvm_proof = prover
+        .create_proof(zkvm_witness, pi, Transcript::new(b"riscv"))
+        .expect("create_proof failed");
+
+    // the circuits without instances are left out of the proof
+    assert!(
+        !zkvm_proof
+            .opcode_proofs
+            .contains_key(&KeccakInstruction::<E>::name())
+    );
+    assert!(
+        !zkvm_proof
+            .table_proofs
+            .contains_key(&KeccakRoundsCircuit::<E>::name())
+    );
+    assert_eq!(zkvm_proof.num_circuits(), vk.circuit_vks.len() - 2);
+
+    let verifier = ZKVMVerifier::new(vk);
+    assert!(
+        verifier
+            .verify_proof(zkvm_proof, Transcript::new(b"riscv"))
+            .expect("verify proof return with error"),
+    );
+}
+
+const RV32IM_PROGRAM_SIZE: usize = 16;
+#[allow(clippy::unusual_byte_groupings)]
+const RV32IM_ADD_CODE: [u32; RV32IM_PROGRAM_SIZE] = {
+    let mut program: [u32; RV32IM_PROGRAM_SIZE] = [ECALL_HALT; RV32IM_PROGRAM_SIZE];
+    declare_program!(
+        program,
+        encode_rv32(ADD, 1, 4, 4, 0), // add x4, x1, x4
+        ECALL_HALT,                   // ecall halt
+    );
+    program
+};
+
+/// A program proven with the circuits of `Rv32imConfig`, as in the example `riscv_opcodes`.
+struct Rv32imProgram {
+    program: Program,
+    public_io: Vec<u32>,
+    hashed_io: bool,
+    zkvm_cs: ZKVMConstraintSystem<GoldilocksExt2>,
+    config: Rv32imConfig<GoldilocksExt2>,
+    prog_config: <ProgramTableCircuit<GoldilocksExt2, RV32IM_PROGRAM_SIZE> as TableCircuit<
+        GoldilocksExt2,
+    >>::TableConfig,
+    prover: ZKVMProver<GoldilocksExt2, Basefold<GoldilocksExt2, BasefoldRSParams, ChaCha8Rng>>,
+    verifier: ZKVMVerifier<GoldilocksExt2, Basefold<GoldilocksExt2, BasefoldRSParams, ChaCha8Rng>>,
+}
+
+impl Rv32imProgram {
+    fn new(code: [u32; RV32IM_PROGRAM_SIZE], public_io: &[u32], hashed_io: bool) -> Self {
+        type E = GoldilocksExt2;
+        type Pcs = Basefold<GoldilocksExt2, BasefoldRSParams, ChaCha8Rng>;
+
+        let program = Program::new(
+            CENO_PLATFORM.pc_base(),
+            CENO_PLATFORM.pc_base(),
+            code.to_vec(),
+            code.iter()
+                .enumerate()
+                .map(|(insn_idx, &insn)| {
+                    (
+                        (insn_idx * PC_WORD_SIZE) as u32 + CENO_PLATFORM.pc_base(),
+                        insn,
+                    )
+                })
+                .collect(),
+        );
+
+        let pcs_param = Pcs::setup(1 << MAX_NUM_VARIABLES).expect("Basefold PCS setup");
+        let (pp, vp) = Pcs::trim(&pcs_param, 1 << MAX_NUM_VARIABLES).expect("Basefold trim");
+        let mut zkvm_cs = ZKVMConstraintSystem::default();
+        let config = if hashed_io {
+            Rv32imConfig::<E>::construct_circuits_with_hashed_io(&mut zkvm_cs)
+        } else {
+            Rv32imConfig::<E>::construct_circuits(&mut zkvm_cs)
+        };
+        let prog_config =
+            zkvm_cs.register_table_circuit::<ProgramTableCircuit<E, RV32IM_PROGRAM_SIZE>>();
+        if hashed_io {
+            zkvm_cs.register_global_state::<HashedIOGlobalState>();
+        } else {
+            zkvm_cs.register_global_state::<GlobalState>();
+        }
+
+        let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
+        zkvm_fixed_traces.register_table_circuit::<ProgramTableCircuit<E, RV32IM_PROGRAM_SIZE>>(
+            &zkvm_cs,
+            &prog_config,
+            &program,
+        );
+        config.generate_fixed_traces(
+            &zkvm_cs,
+            &mut zkvm_fixed_traces,
+            &initial_registers(&CENO_PLATFORM),
+            &init_program_data(&CENO_PLATFORM, &[]),
+        );
+        let pk = zkvm_cs
+            .clone()
+            .key_gen::<Pcs>(pp, vp, zkvm_fixed_traces)
+            .expect("keygen failed");
+        let verifier = ZKVMVerifier::new(pk.get_vk());
+
+        Rv32imProgram {
+            program,
+            public_io: public_io.to_vec(),
+            hashed_io,
+            zkvm_cs,
+            config,
+            prog_config,
+            prover: ZKVMProver::new(pk),
+            verifier,
+        }
+    }
+
+    /// Execute the program, and return the final state of the VM with the witnesses and the
+    /// public values of the execution.
+    fn execute(&self) -> (VMState, ZKVMWitnesses<GoldilocksExt2>, PublicValues<u32>) {
+        let reg_init = initial_registers(&CENO_PLATFORM);
+        let program_data_init = init_program_data(&CENO_PLATFORM, &[]);
+        let public_io_init = init_public_io(&CENO_PLATFORM, &self.public_io);
+
+        let mut vm = VMState::new(CENO_PLATFORM, self.program.clone());
+        for record in program_data_init.iter().chain(public_io_init.iter()) {
+            vm.init_memory(record.addr.into(), record.value);
+        }
+        let all_records = vm
+            .iter_until_halt()
+            .collect::<Result<Vec<StepRecord>, _>>()
+            .expect("vm exec failed");
+        let exit_code = all_records
+            .last()
+            .and_then(|record| record.rs2())
+            .expect("halt record not found")
+            .value;
+        let end_cycle = vm.tracer().cycle() as u32;
+        // the public io is the whole region, padded with zeros
+        let public_io = public_io_init.iter().map(|rec| rec.value).collect_vec();
+        let pi = if self.hashed_io {
+            PublicValues::new_with_hashed_io(
+                exit_code,
+                CENO_PLATFORM.rom_start(),
+                Tracer::SUBCYCLES_PER_INSN as u32,
+                EXIT_PC as u32,
+                end_cycle,
+                &public_io,
+            )
+        } else {
+            PublicValues::new(
+                exit_code,
+                CENO_PLATFORM.rom_start(),
+                Tracer::SUBCYCLES_PER_INSN as u32,
+                EXIT_PC as u32,
+                end_cycle,
+                public_io,
+            )
+        };
+
+        let mut zkvm_witness = ZKVMWitnesses::default();
+        let keccak_steps = self
+            .config
+            .assign_opcode_circuit(&self.zkvm_cs, &mut zkvm_witness, all_records)
+            .unwrap();
+        zkvm_witness.finalize_lk_multiplicities();
+
+        let final_access = vm.tracer().latest_accesses();
+        let final_cycle = |addr: WordAddr| *final_access.get(&addr).unwrap_or(&0);
+        let reg_final = reg_init
+            .iter()
+            .map(|rec| {
+                let index = rec.addr as usize;
+                if index < VMState::REG_COUNT {
+                    MemFinalRecord {
+                        addr: rec.addr,
+                        value: vm.peek_register(index),
+                        cycle: final_cycle(CENO_PLATFORM.register_vma(index).into()),
+                    }
+                } else {
+                    // The table is padded beyond the number of registers.
+                    MemFinalRecord {
+                        addr: rec.addr,
+                        value: 0,
+                        cycle: 0,
+                    }
+                }
+            })
+            .collect_vec();
+        let ro_final = |init: &[MemInitRecord]| {
+            init.iter()
+                .map(|rec| MemFinalRecord {
+                    addr: rec.addr,
+                    value: rec.value,
+                    cycle: final_cycle(rec.addr.into()),
+                })
+                .collect_vec()
+        };
+        let mem_final = (0..1 << 12)
+            .map(|entry_index| {
+                let byte_addr = ByteAddr::from(MemTable::addr(&CENO_PLATFORM, entry_index));
+                MemFinalRecord {
+                    addr: byte_addr.0,
+                    value: vm.peek_memory(byte_addr.waddr()),
+                    cycle: final_cycle(byte_addr.waddr()),
+                }
+            })
+            .collect_vec();
+        self.config
+            .assign_table_circuit(
+                &self.zkvm_cs,
+                &mut zkvm_witness,
+                &reg_final,
+                &mem_final,
+                &ro_final(&program_data_init),
+                &ro_final(&public_io_init),
+                &keccak_steps,
+            )
+            .unwrap();
+        zkvm_witness
+            .assign_table_circuit::<ProgramTableCircuit<GoldilocksExt2, RV32IM_PROGRAM_SIZE>>(
+                &self.zkvm_cs,
+                &self.prog_config,
+                &self.program,
+            )
+            .unwrap();
+
+        (vm, zkvm_witness, pi)
+    }
+}
+
+#[test]
+fn test_sha256_e2e() {
+    // The block of the compression, followed by the function code of the ecall.
+    let block: [u32; SHA256_BLOCK_WORDS] = std::array::from_fn(|i| 0x0101_0101 * i as u32);
+    let public_io = [&block[..], &[CENO_PLATFORM.ecall_sha256_compress()]].concat();
+    let state_addr = CENO_PLATFORM.ram_start();
+    let mut code = [ECALL_HALT; RV32IM_PROGRAM_SIZE];
+    declare_program!(
+        code,
+        encode_rv32(LUI, 0, 0, 10, state_addr), // lui a0, state
+        encode_rv32(LUI, 0, 0, 11, CENO_PLATFORM.public_io_start()), // lui a1, public_io
+        encode_rv32(LW, 11, 0, 5, (4 * SHA256_BLOCK_WORDS) as u32), // lw t0, 64(a1)
+        ECALL_HALT,                             // ecall sha256_compress
+        encode_rv32(ADD, 0, 0, 5, 0),           // add t0, x0, x0
+        encode_rv32(ADD, 0, 0, 10, 0),          // add a0, x0, x0
+        ECALL_HALT,                             // ecall halt
+    );
+    let program = Rv32imProgram::new(code, &public_io, false);
+
+    let (vm, zkvm_witness, pi) = program.execute();
+    // the state starts zeroed in RAM
+    let mut expected = [0; SHA256_STATE_WORDS];
+    sha256_compress_block(&mut expected, &block);
+    let state = (0..SHA256_STATE_WORDS as u32)
+        .map(|i| vm.peek_memory(ByteAddr(state_addr).waddr() + i))
+        .collect_vec();
+    assert_eq!(state, expected);
+    for circuit in [
+        Sha256Instruction::<GoldilocksExt2>::name(),
+        Sha256RoundsInstruction::<GoldilocksExt2>::name(),
+    ] {
+        assert!(
+            zkvm_witness.witnesses.contains_key(&circuit),
+            "{circuit} is not assigned"
+        );
+    }
+
+    let zkvm_proof = program
+        .prover
+        .create_proof(zkvm_witness, pi, Transcript::new(b"riscv"))
+        .expect("create_proof failed");
+    assert!(
+        program
+            .verifier
+            .verify_proof(zkvm_proof, Transcript::new(b"riscv"))
+            .expect("verify proof return with error"),
+    );
+}
+
+#[test]
+fn test_proving_report_e2e() {
+    type E = GoldilocksExt2;
+
+    let program = Rv32imProgram::new(RV32IM_ADD_CODE, &[], false);
+    let (_, zkvm_witness, pi) = program.execute();
+    let (zkvm_proof, report) = program
+        .prover
+        .create_proof_with_report(zkvm_witness, pi, Transcript::new(b"riscv"))
+        .expect("create_proof failed");
+
+    // one report per proven circuit, which survives a JSON round trip
+    assert_eq!(
+        report.circuits.keys().collect_vec(),
+        zkvm_proof
+            .opcode_proofs
+            .keys()
+            .chain(zkvm_proof.table_proofs.keys())
+            .sorted()
+            .collect_vec(),
+    );
+    let add_report = &report.circuits[&AddInstruction::<E>::name()];
+    assert!(!add_report.is_table);
+    assert_eq!(add_report.num_instances, 1);
+    assert_eq!(add_report.num_reads, add_report.num_writes);
+    assert!(report.circuits[&ProgramTableCircuit::<E, RV32IM_PROGRAM_SIZE>::name()].is_table);
+    assert_eq!(
+        serde_json::from_str::<ProvingReport>(&report.to_json())
+            .unwrap()
+            .circuits
+            .keys()
+            .collect_vec(),
+        report.circuits.keys().collect_vec(),
+    );
+
+    assert!(
+        program
+            .verifier
+            .verify_proof(zkvm_proof, Transcript::new(b"riscv"))
+            .expect("verify proof return with error"),
+    );
+}
+
+#[test]
+fn test_hashed_public_io_e2e() {
+    let public_io = [7, 8];
+    let program = Rv32imProgram::new(RV32IM_ADD_CODE, &public_io, true);
+    let (_, zkvm_witness, pi) = program.execute();
+
+    // the public values hold the digest of the whole public io region instead of its words
+    let region = init_public_io(&CENO_PLATFORM, &public_io)
+        .iter()
+        .map(|rec| rec.value)
+        .collect_vec();
+    let digest = public_io_digest(&region).0.map(|e| e.to_canonical_u64());
+    assert!(pi.public_io().is_empty());
+    assert_eq!(pi.public_io_digest(), Some(digest));
+
+    let zkvm_proof = program
+        .prover
+        .create_proof(zkvm_witness, pi, Transcript::new(b"riscv"))
+        .expect("create_proof failed");
+    assert!(
+        program
+            .verifier
+            .verify_proof(zkvm_proof, Transcript::new(b"riscv"))
+            .expect("verify proof return with error"),
+    );
+}
+
+#[test]
+fn test_receipt_e2e() {
+    type E = GoldilocksExt2;
+    type Pcs = Basefold<GoldilocksExt2, BasefoldRSParams, ChaCha8Rng>;
+
+    let program = Rv32imProgram::new(RV32IM_ADD_CODE, &[7, 8], false);
+    let (_, zkvm_witness, pi) = program.execute();
+    let receipt = program
+        .prover
+        .create_receipt(zkvm_witness, pi)
+        .expect("create_receipt failed");
+    assert_eq!(receipt.program_id(), &program.verifier.program_id());
+    assert_eq!(receipt.exit_code(), 0);
+    assert_eq!(&receipt.public_io()[..2], &[7, 8]);
+
+    // the receipt survives a bincode round trip
+    let vk = &program.verifier.vk;
+    let receipt: Receipt<E, Pcs> =
+        with_base_fields(|| bincode::deserialize(&bincode::serialize(&receipt).unwrap()).unwrap());
+    receipt.verify(vk).expect("verify receipt failed");
+
+    // the proof, claimed for another program
+    let other_program = Receipt::new(Digest::default(), receipt.proof().clone()).unwrap();
+    assert!(other_program.verify(vk).is_err());
+
+    // public values which the proof does not prove, in a well-formed receipt
+    let mut public_io = receipt.public_io().to_vec();
+    public_io[0] += 1;
+    let public_values = PublicValues::new(
+        receipt.exit_code(),
+        receipt.init_pc(),
+        receipt.init_cycle(),
+        receipt.end_pc(),
+        receipt.end_cycle(),
+        public_io,
+    );
+    let tampered: Receipt<E, Pcs> = with_base_fields(|| {
+        bincode::deserialize(
+            &bincode::serialize(&(receipt.program_id(), &public_values, receipt.proof())).unwrap(),
+        )
+        .unwrap()
+    });
+    assert_eq!(tampered.public_io()[0], 8);
+    assert!(tampered.verify(vk).is_err());
+}
+
+#[test]
+fn test_program_id_binding() {
+    let program = Rv32imProgram::new(RV32IM_ADD_CODE, &[], false);
+    let (_, zkvm_witness, pi) = program.execute();
+    let receipt = program
+        .prover
+        .create_receipt(zkvm_witness, pi)
+        .expect("create_receipt failed");
+
+    // the program_id follows the verifying key, so that the same circuits and commitments under
+    // another memory layout are another program
+    let mut vk = program.verifier.vk.clone();
+    vk.platform.info_out_start += 4;
+    assert_ne!(vk.program_id(), program.verifier.program_id());
+    assert!(matches!(
+        receipt.verify(&vk),
+        Err(ZKVMError::VerifyError(_))
+    ));
+    receipt
+        .verify(&program.verifier.vk)
+        .expect("verify receipt failed");
+}
+
+/// The socket of the worker process run by [`distributed_worker`].
+#[cfg(unix)]
+const WORKER_SOCKET_ENV: &str = "CENO_TEST_WORKER_SOCKET";
+/// Set for a worker process which exits while proving its first circuit.
+#[cfg(unix)]
+const WORKER_CRASH_ENV: &str = "CENO_TEST_WORKER_CRASH";
+
+/// A worker process of the distributed tests, which run this test binary with only this test.
+#[cfg(unix)]
+#[test]
+#[ignore = "run in a process of its own by the distributed tests"]
+fn distributed_worker() {
+    type Pcs = Basefold<GoldilocksExt2, BasefoldRSParams, ChaCha8Rng>;
+
+    let socket = std::env::var(WORKER_SOCKET_ENV).expect("no worker socket");
+    if std::env::var_os(WORKER_CRASH_ENV).is_none() {
+        run_worker::<GoldilocksExt2, Pcs>(socket).expect("worker failed");
+        return;
+    }
+    let listener = UnixListener::bind(socket).unwrap();
+    for stream in listener.incoming() {
+        // skip the connections which only probe whether the worker is listening
+        let mut stream = stream.unwrap();
+        let mut read_frame = || {
+            let mut len = [0; 8];
+            stream.read_exact(&mut len)?;
+            stream.read_exact(&mut vec![0; u64::from_le_bytes(len) as usize])
+        };
+        // the prover parameters, then the proving key and the task of the first request
+        if (0..3).try_for_each(|_| read_frame()).is_ok() {
+            process::exit(1);
+        }
+    }
+}
+
+/// A worker process, killed when dropped.
+#[cfg(unix)]
+struct WorkerProcess(Child);
+
+#[cfg(unix)]
+impl WorkerProcess {
+    /// Spawn a worker listening on `socket`, and wait until it listens.
+    fn spawn(socket: &Path, crash: bool) -> Self {
+        let mut command = Command::new(std::env::current_exe().unwrap());
+        command
+            .args(["--ignored", "--exact", "scheme::tests::distributed_worker"])
+            .env(WORKER_SOCKET_ENV, socket)
+            .stdout(Stdio::null());
+        if crash {
+            command.env(WORKER_CRASH_ENV, "1");
+        }
+        let worker = WorkerProcess(command.spawn().unwrap());
+        let start = Instant::now();
+        while UnixStream::connect(socket).is_err() {
+            assert!(
+                start.elapsed() < Duration::from_secs(60),
+                "worker not listening"
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+        worker
+    }
+}
+
+#[cfg(unix)]
+impl Drop for WorkerProcess {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Prove `program` with the workers listening on `sockets`, check that the proof is the same as
+/// without workers, and verify it.
+#[cfg(unix)]
+fn prove_distributed(program: &Rv32imProgram, sockets: &[impl AsRef<Path> + Sync]) {
+    let (_, zkvm_witness, pi) = program.execute();
+    let zkvm_proof = program
+        .prover
+        .create_proof(zkvm_witness, pi, Transcript::new(b"riscv"))
+        .expect("create_proof failed");
+    let (_, zkvm_witness, pi) = program.execute();
+    let distributed_proof = program
+        .prover
+        .create_proof_distributed(zkvm_witness, pi, Transcript::new(b"riscv"), sockets)
+        .expect("create_proof_distributed failed");
+    assert_eq!(
+        with_base_fields(|| bincode::serialize(&distributed_proof)).unwrap(),
+        with_base_fields(|| bincode::serialize(&zkvm_proof)).unwrap(),
+    );
+    assert!(
+        program
+            .verifier
+            .verify_proof(distributed_proof, Transcript::new(b"riscv"))
+            .expect("verify proof return with error"),
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_distributed_e2e() {
+    let program = Rv32imProgram::new(RV32IM_ADD_CODE, &[], false);
+    let dir = tempfile::tempdir().unwrap();
+    let sockets = (0..2)
+        .map(|i| dir.path().join(format!("worker_{i}.sock")))
+        .collect_vec();
+    let _workers = sockets
+        .iter()
+        .map(|socket| WorkerProcess::spawn(socket, false))
+        .collect_vec();
+    prove_distributed(&program, &sockets);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_distributed_worker_crash() {
+    let program = Rv32imProgram::new(RV32IM_ADD_CODE, &[], false);
+    let dir = tempfile::tempdir().unwrap();
+    let sockets = [
+        dir.path().join("crash.sock"),
+        dir.path().join("worker.sock"),
+    ];
+    let mut crashing = WorkerProcess::spawn(&sockets[0], true);
+    let _worker = WorkerProcess::spawn(&sockets[1], false);
+
+    // the circuit of the crashed worker is proven by the other one
+    prove_distributed(&program, &sockets);
+    assert!(!crashing.0.wait().unwrap().success());
+}
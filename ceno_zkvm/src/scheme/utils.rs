@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use ark_std::iterable::Iterable;
 use ff_ext::ExtensionField;
@@ -19,7 +19,9 @@ use rayon::{
 };
 
 use crate::{
-    expression::Expression, scheme::constants::MIN_PAR_SIZE, utils::next_pow2_instance_padding,
+    expression::{CompiledExpressions, Expression},
+    scheme::constants::MIN_PAR_SIZE,
+    utils::next_pow2_instance_padding,
 };
 
 /// interleaving multiple mles into mles, and num_limbs indicate number of final limbs vector
@@ -246,107 +248,175 @@ pub(crate) fn wit_infer_by_expr<'a, E: ExtensionField, const N: usize>(
         &|f| fixed[f.0].clone(),
         &|witness_id| witnesses[witness_id as usize].clone(),
         &|i| instance[i.0].clone(),
-        &|scalar| {
-            let scalar: ArcMultilinearExtension<E> =
-                Arc::new(DenseMultilinearExtension::from_evaluations_vec(0, vec![
-                    scalar,
-                ]));
-            scalar
-        },
+        &mle_constant,
         &|challenge_id, pow, scalar, offset| {
-            // TODO cache challenge power to be acquired once for each power
-            let challenge = challenges[challenge_id as usize];
-            let challenge: ArcMultilinearExtension<E> = Arc::new(
-                DenseMultilinearExtension::from_evaluations_ext_vec(0, vec![
-                    challenge.pow([pow as u64]) * scalar + offset,
-                ]),
-            );
-            challenge
+            mle_challenge(challenges, challenge_id, pow, scalar, offset)
         },
-        &|a, b| {
-            commutative_op_mle_pair!(|a, b| {
-                match (a.len(), b.len()) {
-                    (1, 1) => Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
-                        0,
-                        vec![a[0] + b[0]],
-                    )),
-                    (1, _) => Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
-                        ceil_log2(b.len()),
-                        b.par_iter()
-                            .with_min_len(MIN_PAR_SIZE)
-                            .map(|b| a[0] + *b)
-                            .collect(),
-                    )),
-                    (_, 1) => Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
-                        ceil_log2(a.len()),
-                        a.par_iter()
-                            .with_min_len(MIN_PAR_SIZE)
-                            .map(|a| *a + b[0])
-                            .collect(),
-                    )),
-                    (_, _) => Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
-                        ceil_log2(a.len()),
-                        a.par_iter()
-                            .zip(b.par_iter())
-                            .with_min_len(MIN_PAR_SIZE)
-                            .map(|(a, b)| *a + b)
-                            .collect(),
-                    )),
-                }
-            })
+        &mle_sum,
+        &mle_product,
+        &mle_scaled,
+    )
+}
+
+/// Infer the witnesses of several expressions at once, compiled beforehand so that their common
+/// sub-expressions are evaluated only once.
+pub(crate) fn wit_infer_by_exprs<'a, E: ExtensionField, const N: usize>(
+    fixed: &[ArcMultilinearExtension<'a, E>],
+    witnesses: &[ArcMultilinearExtension<'a, E>],
+    instance: &[ArcMultilinearExtension<'a, E>],
+    challenges: &[E; N],
+    compiled: &CompiledExpressions<E>,
+) -> Vec<ArcMultilinearExtension<'a, E>> {
+    let mles = compiled.evaluate_with_instance::<ArcMultilinearExtension<'_, E>>(
+        &|f| fixed[f.0].clone(),
+        &|witness_id| witnesses[witness_id as usize].clone(),
+        &|i| instance[i.0].clone(),
+        &mle_constant,
+        &|challenge_id, pow, scalar, offset| {
+            mle_challenge(challenges, challenge_id, pow, scalar, offset)
         },
-        &|a, b| {
-            commutative_op_mle_pair!(|a, b| {
-                match (a.len(), b.len()) {
-                    (1, 1) => Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
-                        0,
-                        vec![a[0] * b[0]],
-                    )),
-                    (1, _) => Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
-                        ceil_log2(b.len()),
-                        b.par_iter()
-                            .with_min_len(MIN_PAR_SIZE)
-                            .map(|b| a[0] * *b)
-                            .collect(),
-                    )),
-                    (_, 1) => Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
-                        ceil_log2(a.len()),
-                        a.par_iter()
-                            .with_min_len(MIN_PAR_SIZE)
-                            .map(|a| *a * b[0])
-                            .collect(),
-                    )),
-                    (_, _) => {
-                        assert_eq!(a.len(), b.len());
-                        // we do the pointwise evaluation multiplication here without involving FFT
-                        // the evaluations outside of range will be checked via sumcheck + identity polynomial
-                        Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
-                            ceil_log2(a.len()),
-                            a.par_iter()
-                                .zip(b.par_iter())
-                                .with_min_len(MIN_PAR_SIZE)
-                                .map(|(a, b)| *a * b)
-                                .collect(),
-                        ))
+        &mle_sum,
+        &mle_product,
+        &mle_scaled,
+    );
+    // virtual polynomials identify mles by address, so equal expressions must still give
+    // distinct mles to keep one evaluation per expression
+    let mut seen = HashSet::new();
+    mles.into_iter()
+        .map(|mle| {
+            if seen.insert(Arc::as_ptr(&mle) as *const () as usize) {
+                mle
+            } else {
+                let copy = match mle.evaluations() {
+                    FieldType::Base(evals) => {
+                        DenseMultilinearExtension::from_evaluations_slice(mle.num_vars(), evals)
                     }
-                }
-            })
-        },
-        &|x, a, b| {
-            op_mle_xa_b!(|x, a, b| {
-                assert_eq!(a.len(), 1);
-                assert_eq!(b.len(), 1);
-                let (a, b) = (a[0], b[0]);
+                    FieldType::Ext(evals) => {
+                        DenseMultilinearExtension::from_evaluations_ext_slice(mle.num_vars(), evals)
+                    }
+                    FieldType::Unreachable => unreachable!(),
+                };
+                Arc::new(copy)
+            }
+        })
+        .collect()
+}
+
+fn mle_constant<'a, E: ExtensionField>(scalar: E::BaseField) -> ArcMultilinearExtension<'a, E> {
+    Arc::new(DenseMultilinearExtension::from_evaluations_vec(0, vec![
+        scalar,
+    ]))
+}
+
+fn mle_challenge<'a, E: ExtensionField, const N: usize>(
+    challenges: &[E; N],
+    challenge_id: u16,
+    pow: usize,
+    scalar: E,
+    offset: E,
+) -> ArcMultilinearExtension<'a, E> {
+    // TODO cache challenge power to be acquired once for each power
+    let challenge = challenges[challenge_id as usize];
+    Arc::new(DenseMultilinearExtension::from_evaluations_ext_vec(
+        0,
+        vec![challenge.pow([pow as u64]) * scalar + offset],
+    ))
+}
+
+fn mle_sum<'a, E: ExtensionField>(
+    a: ArcMultilinearExtension<'a, E>,
+    b: ArcMultilinearExtension<'a, E>,
+) -> ArcMultilinearExtension<'a, E> {
+    commutative_op_mle_pair!(|a, b| {
+        match (a.len(), b.len()) {
+            (1, 1) => Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
+                0,
+                vec![a[0] + b[0]],
+            )),
+            (1, _) => Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
+                ceil_log2(b.len()),
+                b.par_iter()
+                    .with_min_len(MIN_PAR_SIZE)
+                    .map(|b| a[0] + *b)
+                    .collect(),
+            )),
+            (_, 1) => Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
+                ceil_log2(a.len()),
+                a.par_iter()
+                    .with_min_len(MIN_PAR_SIZE)
+                    .map(|a| *a + b[0])
+                    .collect(),
+            )),
+            (_, _) => Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
+                ceil_log2(a.len()),
+                a.par_iter()
+                    .zip(b.par_iter())
+                    .with_min_len(MIN_PAR_SIZE)
+                    .map(|(a, b)| *a + b)
+                    .collect(),
+            )),
+        }
+    })
+}
+
+fn mle_product<'a, E: ExtensionField>(
+    a: ArcMultilinearExtension<'a, E>,
+    b: ArcMultilinearExtension<'a, E>,
+) -> ArcMultilinearExtension<'a, E> {
+    commutative_op_mle_pair!(|a, b| {
+        match (a.len(), b.len()) {
+            (1, 1) => Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
+                0,
+                vec![a[0] * b[0]],
+            )),
+            (1, _) => Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
+                ceil_log2(b.len()),
+                b.par_iter()
+                    .with_min_len(MIN_PAR_SIZE)
+                    .map(|b| a[0] * *b)
+                    .collect(),
+            )),
+            (_, 1) => Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
+                ceil_log2(a.len()),
+                a.par_iter()
+                    .with_min_len(MIN_PAR_SIZE)
+                    .map(|a| *a * b[0])
+                    .collect(),
+            )),
+            (_, _) => {
+                assert_eq!(a.len(), b.len());
+                // we do the pointwise evaluation multiplication here without involving FFT
+                // the evaluations outside of range will be checked via sumcheck + identity polynomial
                 Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
-                    ceil_log2(x.len()),
-                    x.par_iter()
+                    ceil_log2(a.len()),
+                    a.par_iter()
+                        .zip(b.par_iter())
                         .with_min_len(MIN_PAR_SIZE)
-                        .map(|x| a * x + b)
+                        .map(|(a, b)| *a * b)
                         .collect(),
                 ))
-            })
-        },
-    )
+            }
+        }
+    })
+}
+
+fn mle_scaled<'a, E: ExtensionField>(
+    x: ArcMultilinearExtension<'a, E>,
+    a: ArcMultilinearExtension<'a, E>,
+    b: ArcMultilinearExtension<'a, E>,
+) -> ArcMultilinearExtension<'a, E> {
+    op_mle_xa_b!(|x, a, b| {
+        assert_eq!(a.len(), 1);
+        assert_eq!(b.len(), 1);
+        let (a, b) = (a[0], b[0]);
+        Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
+            ceil_log2(x.len()),
+            x.par_iter()
+                .with_min_len(MIN_PAR_SIZE)
+                .map(|x| a * x + b)
+                .collect(),
+        ))
+    })
 }
 
 #[cfg(test)]
@@ -365,19 +435,7 @@ pub(crate) fn eval_by_expr_with_fixed<E: ExtensionField>(
     challenges: &[E],
     expr: &Expression<E>,
 ) -> E {
-    expr.evaluate::<E>(
-        &|f| fixed[f.0],
-        &|witness_id| witnesses[witness_id as usize],
-        &|scalar| scalar.into(),
-        &|challenge_id, pow, scalar, offset| {
-            // TODO cache challenge power to be acquired once for each power
-            let challenge = challenges[challenge_id as usize];
-            challenge.pow([pow as u64]) * scalar + offset
-        },
-        &|a, b| a + b,
-        &|a, b| a * b,
-        &|x, a, b| a * x + b,
-    )
+    eval_by_expr_with_instance(fixed, witnesses, &[], challenges, expr)
 }
 
 pub(crate) fn eval_by_expr_with_instance<E: ExtensionField>(
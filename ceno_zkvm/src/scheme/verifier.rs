@@ -11,6 +11,7 @@ use multilinear_extensions::{
     util::ceil_log2,
     virtual_poly::{VPAuxInfo, build_eq_x_r_vec_sequential, eq_eval},
 };
+use poseidon::digest::Digest;
 use sumcheck::structs::{IOPProof, IOPVerifierState};
 use transcript::Transcript;
 
@@ -43,6 +44,10 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
         ZKVMVerifier { vk }
     }
 
+    pub fn program_id(&self) -> Digest<E::BaseField> {
+        self.vk.program_id()
+    }
+
     pub fn verify_proof(
         &self,
         vm_proof: ZKVMProof<E, PCS>,
@@ -70,6 +75,11 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
 
         let pi_evals = &vm_proof.pi_evals;
 
+        // bind the proof to the verifying key
+        for v in &self.vk.program_id().0 {
+            transcript.append_field_element(v);
+        }
+
         // TODO fix soundness: construct raw public input by ourself and trustless from proof
         // including raw public input to transcript
         vm_proof
@@ -142,13 +152,15 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
             tracing::info!("verified proof for opcode {}", name);
 
             // getting the number of dummy padding item that we used in this opcode circuit
+            // a circuit without lookups has one padding lookup per instance
             let num_lks = circuit_vk.get_cs().lk_expressions.len();
-            let num_padded_lks_per_instance = next_pow2_instance_padding(num_lks) - num_lks;
+            let num_lks_per_instance = num_lks.max(1).next_power_of_two();
+            let num_padded_lks_per_instance = num_lks_per_instance - num_lks;
             let num_padded_instance =
                 next_pow2_instance_padding(opcode_proof.num_instances) - opcode_proof.num_instances;
             dummy_table_item_multiplicity += num_padded_lks_per_instance
                 * opcode_proof.num_instances
-                + num_lks.next_power_of_two() * num_padded_instance;
+                + num_lks_per_instance * num_padded_instance;
 
             prod_r *= opcode_proof.record_r_out_evals.iter().product::<E>();
             prod_w *= opcode_proof.record_w_out_evals.iter().product::<E>();
@@ -246,10 +258,11 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
             cs.w_expressions.len(),
             cs.lk_expressions.len(),
         );
+        // a circuit without lookups has one padding lookup per instance
         let (log2_r_count, log2_w_count, log2_lk_count) = (
             ceil_log2(r_counts_per_instance),
             ceil_log2(w_counts_per_instance),
-            ceil_log2(lk_counts_per_instance),
+            ceil_log2(lk_counts_per_instance.max(1)),
         );
         let (chip_record_alpha, _) = (challenges[0], challenges[1]);
 
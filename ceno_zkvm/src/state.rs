@@ -1,18 +1,23 @@
+use ceno_emul::Platform;
 use ff_ext::ExtensionField;
+use poseidon::DIGEST_WIDTH;
 
 use crate::{
     circuit_builder::CircuitBuilder,
     error::ZKVMError,
     expression::{Expression, ToExpr},
     structs::RAMType,
+    tables::{pubio_hash_state_record, public_io_num_chunks},
 };
 
 pub trait StateCircuit<E: ExtensionField> {
     fn initial_global_state(
         circuit_builder: &mut CircuitBuilder<E>,
+        platform: &Platform,
     ) -> Result<Expression<E>, ZKVMError>;
     fn finalize_global_state(
         circuit_builder: &mut CircuitBuilder<E>,
+        platform: &Platform,
     ) -> Result<Expression<E>, ZKVMError>;
 }
 
@@ -21,6 +26,7 @@ pub struct GlobalState;
 impl<E: ExtensionField> StateCircuit<E> for GlobalState {
     fn initial_global_state(
         circuit_builder: &mut crate::circuit_builder::CircuitBuilder<E>,
+        _platform: &Platform,
     ) -> Result<Expression<E>, ZKVMError> {
         let states: Vec<Expression<E>> = vec![
             Expression::Constant(E::BaseField::from(RAMType::GlobalState as u64)),
@@ -33,6 +39,7 @@ impl<E: ExtensionField> StateCircuit<E> for GlobalState {
 
     fn finalize_global_state(
         circuit_builder: &mut crate::circuit_builder::CircuitBuilder<E>,
+        _platform: &Platform,
     ) -> Result<crate::expression::Expression<E>, crate::error::ZKVMError> {
         let states: Vec<Expression<E>> = vec![
             Expression::Constant(E::BaseField::from(RAMType::GlobalState as u64)),
@@ -43,3 +50,41 @@ impl<E: ExtensionField> StateCircuit<E> for GlobalState {
         Ok(circuit_builder.rlc_chip_record(states))
     }
 }
+
+/// The global state of `GlobalState`, and the Poseidon sponge of `PubIOHashCircuit`.
+///
+/// The sponge starts with an empty capacity before the first chunk of the public IO, and ends with
+/// its digest after the last chunk, which is the public value in place of the public IO.
+pub struct HashedIOGlobalState;
+
+impl<E: ExtensionField> StateCircuit<E> for HashedIOGlobalState {
+    fn initial_global_state(
+        circuit_builder: &mut CircuitBuilder<E>,
+        platform: &Platform,
+    ) -> Result<Expression<E>, ZKVMError> {
+        let state = GlobalState::initial_global_state(circuit_builder, platform)?;
+        let sponge = pubio_hash_state_record(circuit_builder, Expression::ZERO, vec![
+                Expression::ZERO;
+                DIGEST_WIDTH
+            ]);
+        Ok(state * sponge)
+    }
+
+    fn finalize_global_state(
+        circuit_builder: &mut CircuitBuilder<E>,
+        platform: &Platform,
+    ) -> Result<Expression<E>, ZKVMError> {
+        let state = GlobalState::finalize_global_state(circuit_builder, platform)?;
+        let digest = circuit_builder
+            .query_public_io_digest()?
+            .into_iter()
+            .map(|[low, high]| low.expr() + high.expr() * Expression::from(1u64 << 32))
+            .collect();
+        let sponge = pubio_hash_state_record(
+            circuit_builder,
+            public_io_num_chunks(platform).into(),
+            digest,
+        );
+        Ok(state * sponge)
+    }
+}
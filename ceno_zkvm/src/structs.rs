@@ -1,20 +1,25 @@
 use crate::{
-    circuit_builder::{CircuitBuilder, ConstraintSystem},
+    circuit_builder::{
+        CircuitBuilder, ConstraintSystem,
+        analysis::CircuitStats,
+        ir::{CircuitIr, ExprIr},
+    },
     error::ZKVMError,
-    expression::Expression,
+    expression::{CompiledExpressions, Expression},
     instructions::Instruction,
     state::StateCircuit,
     tables::TableCircuit,
     witness::{LkMultiplicity, RowMajorMatrix},
 };
-use ceno_emul::StepRecord;
+use ceno_emul::{CENO_PLATFORM, Platform, StepRecord};
 use ff_ext::ExtensionField;
 use itertools::Itertools;
 use mpcs::PolynomialCommitmentScheme;
 use multilinear_extensions::{
     mle::DenseMultilinearExtension, virtual_poly_v2::ArcMultilinearExtension,
 };
-use serde::{Deserialize, Serialize};
+use poseidon::{digest::Digest, poseidon_hash::PoseidonHash};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use std::collections::{BTreeMap, HashMap};
 use strum_macros::EnumIter;
 use sumcheck::structs::IOPProverMessage;
@@ -62,6 +67,12 @@ pub enum RAMType {
     GlobalState,
     Register,
     Memory,
+    /// The state of a Keccak-f permutation between its rounds.
+    KeccakState,
+    /// The working state and the message schedule of a SHA-256 compression between its rounds.
+    Sha256State,
+    /// The capacity of the Poseidon sponge hashing the public IO between its chunks.
+    PubIOHashState,
 }
 
 /// A point is a vector of num_var length
@@ -105,9 +116,46 @@ pub struct ProvingKey<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     pub fixed_traces: Option<Vec<DenseMultilinearExtension<E>>>,
     pub fixed_commit_wd: Option<PCS::CommitmentWithData>,
     pub vk: VerifyingKey<E, PCS>,
+    /// The read, write and lookup records of the circuit, whose witnesses the prover infers.
+    pub(crate) records: CompiledExpressions<E>,
 }
 
 impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ProvingKey<E, PCS> {
+    /// Build the key and compile the expressions that the prover evaluates for every proof.
+    pub fn new(
+        fixed_traces: Option<Vec<DenseMultilinearExtension<E>>>,
+        fixed_commit_wd: Option<PCS::CommitmentWithData>,
+        vk: VerifyingKey<E, PCS>,
+    ) -> Self {
+        let cs = vk.get_cs();
+        let is_opcode_circuit = cs.lk_table_expressions.is_empty()
+            && cs.r_table_expressions.is_empty()
+            && cs.w_table_expressions.is_empty();
+        let records = if is_opcode_circuit {
+            cs.r_expressions
+                .iter()
+                .chain(cs.w_expressions.iter())
+                .chain(cs.lk_expressions.iter())
+                .collect_vec()
+        } else {
+            cs.r_table_expressions
+                .iter()
+                .map(|r| &r.expr)
+                .chain(cs.w_table_expressions.iter().map(|w| &w.expr))
+                .chain(cs.lk_table_expressions.iter().map(|lk| &lk.multiplicity))
+                .chain(cs.lk_table_expressions.iter().map(|lk| &lk.values))
+                .collect_vec()
+        };
+        assert!(records.iter().all(|expr| expr.degree() == 1));
+        let records = CompiledExpressions::new(records);
+        ProvingKey {
+            fixed_traces,
+            fixed_commit_wd,
+            vk,
+            records,
+        }
+    }
+
     pub fn get_cs(&self) -> &ConstraintSystem<E> {
         self.vk.get_cs()
     }
@@ -125,24 +173,74 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> VerifyingKey<E, PCS>
     }
 }
 
+/// The proving key of a circuit in a serializable form, as cached on disk and sent to workers.
+/// It must be (de)serialized within [`with_base_fields`], which keeps the fixed traces.
+///
+/// [`with_base_fields`]: multilinear_extensions::mle::with_base_fields
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
+pub(crate) struct CircuitProvingKey<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    /// The JSON of the [`CircuitIr`], whose tagged expressions bincode cannot decode.
+    cs: String,
+    pub(crate) fixed_traces: Option<Vec<DenseMultilinearExtension<E>>>,
+    fixed_commit_wd: Option<PCS::CommitmentWithData>,
+    fixed_commit: Option<PCS::Commitment>,
+}
+
+impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> CircuitProvingKey<E, PCS> {
+    pub(crate) fn new(pk: &ProvingKey<E, PCS>) -> Self {
+        Self {
+            cs: pk.get_cs().to_ir().to_json(),
+            fixed_traces: pk.fixed_traces.clone(),
+            fixed_commit_wd: pk.fixed_commit_wd.clone(),
+            fixed_commit: pk.vk.fixed_commit.clone(),
+        }
+    }
+
+    pub(crate) fn into_proving_key(self) -> Result<ProvingKey<E, PCS>, ZKVMError> {
+        Ok(ProvingKey::new(
+            self.fixed_traces,
+            self.fixed_commit_wd,
+            VerifyingKey {
+                cs: CircuitIr::from_json(&self.cs)?.into_constraint_system()?,
+                fixed_commit: self.fixed_commit,
+            },
+        ))
+    }
+}
+
 #[derive(Clone)]
 pub struct ZKVMConstraintSystem<E: ExtensionField> {
     pub(crate) circuit_css: BTreeMap<String, ConstraintSystem<E>>,
     pub(crate) initial_global_state_expr: Expression<E>,
     pub(crate) finalize_global_state_expr: Expression<E>,
+    /// The memory layout that the tables are sized for.
+    pub(crate) platform: Platform,
 }
 
 impl<E: ExtensionField> Default for ZKVMConstraintSystem<E> {
     fn default() -> Self {
+        Self::new_with_platform(CENO_PLATFORM)
+    }
+}
+
+impl<E: ExtensionField> ZKVMConstraintSystem<E> {
+    pub fn new_with_platform(platform: Platform) -> Self {
         ZKVMConstraintSystem {
             circuit_css: BTreeMap::new(),
             initial_global_state_expr: Expression::ZERO,
             finalize_global_state_expr: Expression::ZERO,
+            platform,
         }
     }
-}
 
-impl<E: ExtensionField> ZKVMConstraintSystem<E> {
+    pub fn platform(&self) -> &Platform {
+        &self.platform
+    }
+
     pub fn register_opcode_circuit<OC: Instruction<E>>(&mut self) -> OC::InstructionConfig {
         let mut cs = ConstraintSystem::new(|| format!("riscv_opcode/{}", OC::name()));
         let mut circuit_builder = CircuitBuilder::<E>::new(&mut cs);
@@ -155,7 +253,7 @@ impl<E: ExtensionField> ZKVMConstraintSystem<E> {
     pub fn register_table_circuit<TC: TableCircuit<E>>(&mut self) -> TC::TableConfig {
         let mut cs = ConstraintSystem::new(|| format!("riscv_table/{}", TC::name()));
         let mut circuit_builder = CircuitBuilder::<E>::new(&mut cs);
-        let config = TC::construct_circuit(&mut circuit_builder).unwrap();
+        let config = TC::construct_circuit(&mut circuit_builder, &self.platform).unwrap();
         assert!(self.circuit_css.insert(TC::name(), cs).is_none());
 
         config
@@ -165,14 +263,24 @@ impl<E: ExtensionField> ZKVMConstraintSystem<E> {
         let mut cs = ConstraintSystem::new(|| "riscv_state");
         let mut circuit_builder = CircuitBuilder::<E>::new(&mut cs);
         self.initial_global_state_expr =
-            SC::initial_global_state(&mut circuit_builder).expect("global_state_in failed");
+            SC::initial_global_state(&mut circuit_builder, &self.platform)
+                .expect("global_state_in failed");
         self.finalize_global_state_expr =
-            SC::finalize_global_state(&mut circuit_builder).expect("global_state_out failed");
+            SC::finalize_global_state(&mut circuit_builder, &self.platform)
+                .expect("global_state_out failed");
     }
 
     pub fn get_cs(&self, name: &String) -> Option<&ConstraintSystem<E>> {
         self.circuit_css.get(name)
     }
+
+    /// The statistics and lints of every registered circuit, by name.
+    pub fn stats(&self) -> BTreeMap<String, CircuitStats> {
+        self.circuit_css
+            .iter()
+            .map(|(name, cs)| (name.clone(), cs.stats()))
+            .collect()
+    }
 }
 
 #[derive(Default)]
@@ -218,10 +326,16 @@ impl<E: ExtensionField> ZKVMWitnesses<E> {
         records: Vec<StepRecord>,
     ) -> Result<(), ZKVMError> {
         assert!(self.combined_lk_mlt.is_none());
+        // a circuit without instances is not proven
+        if records.is_empty() {
+            return Ok(());
+        }
 
         let cs = cs.get_cs(&OC::name()).unwrap();
         let (witness, logup_multiplicity) =
             OC::assign_instances(config, cs.num_witin as usize, records)?;
+        #[cfg(feature = "checked_witness")]
+        witness.check_assigned(&OC::name(), &cs.witin_namespace_map)?;
         assert!(self.witnesses.insert(OC::name(), witness).is_none());
         assert!(
             self.lk_mlts
@@ -273,6 +387,12 @@ impl<E: ExtensionField> ZKVMWitnesses<E> {
             self.combined_lk_mlt.as_ref().unwrap(),
             input,
         )?;
+        // a table without instances, such as the rounds of absent precompiles, is not proven
+        if witness.num_instances() == 0 {
+            return Ok(());
+        }
+        #[cfg(feature = "checked_witness")]
+        witness.check_assigned(&TC::name(), &cs.witin_namespace_map)?;
         assert!(self.witnesses.insert(TC::name(), witness).is_none());
 
         Ok(())
@@ -289,16 +409,20 @@ pub struct ZKVMProvingKey<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>>
     // expression for global state in/out
     pub initial_global_state_expr: Expression<E>,
     pub finalize_global_state_expr: Expression<E>,
+
+    // memory layout of the tables
+    pub platform: Platform,
 }
 
 impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProvingKey<E, PCS> {
-    pub(crate) fn new(pp: PCS::ProverParam, vp: PCS::VerifierParam) -> Self {
+    pub(crate) fn new(pp: PCS::ProverParam, vp: PCS::VerifierParam, platform: Platform) -> Self {
         Self {
             pp,
             vp,
             circuit_pks: BTreeMap::new(),
             initial_global_state_expr: Expression::ZERO,
             finalize_global_state_expr: Expression::ZERO,
+            platform,
         }
     }
 }
@@ -315,10 +439,63 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProvingKey<E, PC
             // expression for global state in/out
             initial_global_state_expr: self.initial_global_state_expr.clone(),
             finalize_global_state_expr: self.finalize_global_state_expr.clone(),
+            platform: self.platform,
         }
     }
 }
 
+impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProvingKey<E, PCS> {
+    /// The digest of the verifying key, see [`ZKVMVerifyingKey::program_id`].
+    pub fn program_id(&self) -> Digest<E::BaseField> {
+        program_id(
+            &self.vp,
+            self.circuit_pks.iter().map(|(name, pk)| (name, &pk.vk)),
+            [
+                &self.initial_global_state_expr,
+                &self.finalize_global_state_expr,
+            ],
+            &self.platform,
+        )
+    }
+}
+
+/// A Poseidon digest of the parameters, the circuits and the fixed commitments of a verifying
+/// key, which identifies a program under a version of the circuits.
+fn program_id<'a, E: ExtensionField, PCS: PolynomialCommitmentScheme<E> + 'a>(
+    vp: &PCS::VerifierParam,
+    circuit_vks: impl Iterator<Item = (&'a String, &'a VerifyingKey<E, PCS>)>,
+    global_state_exprs: [&Expression<E>; 2],
+    platform: &Platform,
+) -> Digest<E::BaseField> {
+    let mut bytes = vec![];
+    // every item is prefixed by its length, so that consecutive items are not ambiguous
+    let mut append = |item: &[u8]| {
+        bytes.extend((item.len() as u64).to_le_bytes());
+        bytes.extend(item);
+    };
+    append(&bincode::serialize(vp).expect("verifier parameters are serializable"));
+    for (name, vk) in circuit_vks {
+        append(name.as_bytes());
+        append(vk.cs.to_ir().to_json().as_bytes());
+        append(&bincode::serialize(&vk.fixed_commit).expect("commitments are serializable"));
+    }
+    for expr in global_state_exprs {
+        append(&serde_json::to_vec(&ExprIr::from(expr)).expect("expressions are serializable"));
+    }
+    append(&bincode::serialize(platform).expect("platforms are serializable"));
+
+    // 7 bytes per element, below the modulus
+    let elements = bytes
+        .chunks(7)
+        .map(|chunk| {
+            let mut limb = [0; 8];
+            limb[..chunk.len()].copy_from_slice(chunk);
+            E::BaseField::from(u64::from_le_bytes(limb))
+        })
+        .collect_vec();
+    PoseidonHash::hash_or_noop(&elements)
+}
+
 #[derive(Clone)]
 pub struct ZKVMVerifyingKey<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     pub vp: PCS::VerifierParam,
@@ -327,4 +504,23 @@ pub struct ZKVMVerifyingKey<E: ExtensionField, PCS: PolynomialCommitmentScheme<E
     // expression for global state in/out
     pub initial_global_state_expr: Expression<E>,
     pub finalize_global_state_expr: Expression<E>,
+    // memory layout of the tables
+    pub platform: Platform,
+}
+
+impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifyingKey<E, PCS> {
+    /// A digest which identifies the program and the circuits proving it, to be absorbed in the
+    /// transcript and to identify the proofs. It is computed from the key on every call, so
+    /// that it always matches the key.
+    pub fn program_id(&self) -> Digest<E::BaseField> {
+        program_id(
+            &self.vp,
+            self.circuit_vks.iter(),
+            [
+                &self.initial_global_state_expr,
+                &self.finalize_global_state_expr,
+            ],
+            &self.platform,
+        )
+    }
 }
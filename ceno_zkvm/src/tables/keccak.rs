@@ -0,0 +1,361 @@
+//! The rounds of the Keccak-f[1600] permutation, as a circuit with one row per round.
+//!
+//! Each row reads the state before its round from a `RAMType::KeccakState` record, and writes
+//! the state after the round. The records of a permutation are identified by the cycle of its
+//! ecall: `KeccakInstruction` writes the input of the first round and reads the output of the
+//! last round, so the rows of a permutation must form a chain from its input to its output.
+//!
+//! The state is represented by its 1600 bits. All constraints have degree 2, which requires
+//! intermediate bits after theta, rho and pi, and in chi.
+
+use std::{collections::HashMap, marker::PhantomData, mem::MaybeUninit};
+
+use ceno_emul::{
+    CENO_PLATFORM, KECCAK_RHO_OFFSETS, KECCAK_ROUND_CONSTANTS, KECCAK_ROUNDS, KECCAK_STATE_WORDS,
+    Platform, StepRecord, keccak_round,
+};
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+use itertools::Itertools;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    scheme::constants::MIN_PAR_SIZE,
+    set_val,
+    structs::RAMType,
+    tables::TableCircuit,
+    witness::RowMajorMatrix,
+};
+
+const LANE_BITS: usize = 64;
+const STATE_BITS: usize = 25 * LANE_BITS;
+const WORD_BITS: usize = 32;
+
+/// The index of bit `z` of lane `(x, y)`, with coordinates modulo the size of the state.
+fn bit_index(x: usize, y: usize, z: usize) -> usize {
+    (x % 5 + 5 * (y % 5)) * LANE_BITS + z % LANE_BITS
+}
+
+/// The bits of lane `(0, 0)` which are set in some round constant.
+fn iota_bits() -> Vec<usize> {
+    let mask = KECCAK_ROUND_CONSTANTS.iter().fold(0, |mask, rc| mask | rc);
+    (0..LANE_BITS).filter(|z| (mask >> z) & 1 == 1).collect()
+}
+
+fn xor<E: ExtensionField>(a: Expression<E>, b: Expression<E>) -> Expression<E> {
+    a.clone() + b.clone() - a * b * 2
+}
+
+/// The record of the state of the permutation `id` before the round `round`, as 50 words.
+pub fn keccak_state_record<E: ExtensionField>(
+    cb: &CircuitBuilder<E>,
+    id: Expression<E>,
+    round: Expression<E>,
+    words: Vec<Expression<E>>,
+) -> Expression<E> {
+    assert_eq!(words.len(), KECCAK_STATE_WORDS);
+    cb.rlc_chip_record([vec![RAMType::KeccakState.into(), id, round], words].concat())
+}
+
+#[derive(Clone, Debug)]
+pub struct KeccakRoundsConfig {
+    /// The cycle of the ecall.
+    id: WitIn,
+    /// The index of the round, one-hot encoded.
+    round_flags: Vec<WitIn>,
+    /// The state before the round.
+    input: Vec<WitIn>,
+    /// The parity of each column of theta, and the carries of the sum of the column.
+    parity: Vec<WitIn>,
+    parity_carries: Vec<[WitIn; 2]>,
+    /// The value XORed into each column by theta.
+    theta: Vec<WitIn>,
+    /// The state after theta, rho and pi.
+    rho_pi: Vec<WitIn>,
+    /// The term `!a[x + 1] & a[x + 2]` of chi.
+    and_not: Vec<WitIn>,
+    /// The output of chi, before iota, on the bits of `iota_bits()`.
+    chi: Vec<WitIn>,
+    /// The state after the round.
+    output: Vec<WitIn>,
+}
+
+impl KeccakRoundsConfig {
+    fn construct_circuit<E: ExtensionField>(cb: &mut CircuitBuilder<E>) -> Result<Self, ZKVMError> {
+        let mut create_bits = |name: &str, n: usize| {
+            (0..n)
+                .map(|i| cb.create_witin(|| format!("{name}_{i}")))
+                .collect_vec()
+        };
+        let round_flags = create_bits("round", KECCAK_ROUNDS);
+        let input = create_bits("input", STATE_BITS);
+        let parity = create_bits("parity", 5 * LANE_BITS);
+        let parity_carries = create_bits("parity_carry", 2 * 5 * LANE_BITS)
+            .into_iter()
+            .tuples()
+            .map(|(c0, c1)| [c0, c1])
+            .collect_vec();
+        let theta = create_bits("theta", 5 * LANE_BITS);
+        let rho_pi = create_bits("rho_pi", STATE_BITS);
+        let and_not = create_bits("and_not", STATE_BITS);
+        let chi = create_bits("chi", iota_bits().len());
+        let output = create_bits("output", STATE_BITS);
+        let id = cb.create_witin(|| "id");
+
+        for flag in &round_flags {
+            cb.assert_bit(|| "round flag", flag.expr())?;
+        }
+        cb.require_one(
+            || "one round",
+            round_flags.iter().map(|flag| flag.expr()).sum(),
+        )?;
+        let round: Expression<E> = (round_flags.iter().enumerate())
+            .map(|(r, flag)| flag.expr() * r)
+            .sum();
+
+        // The bits of the input are not constrained by the previous round or by the memory.
+        for bit in &input {
+            cb.assert_bit(|| "input bit", bit.expr())?;
+        }
+
+        // Theta: the parity of each column, as the low bit of the sum of the column.
+        for x in 0..5 {
+            for z in 0..LANE_BITS {
+                let i = x * LANE_BITS + z;
+                let [c0, c1] = parity_carries[i];
+                cb.assert_bit(|| "parity", parity[i].expr())?;
+                cb.assert_bit(|| "parity carry 0", c0.expr())?;
+                cb.assert_bit(|| "parity carry 1", c1.expr())?;
+                cb.require_equal(
+                    || "column sum",
+                    (0..5).map(|y| input[bit_index(x, y, z)].expr()).sum(),
+                    parity[i].expr() + c0.expr() * 2 + c1.expr() * 4,
+                )?;
+            }
+        }
+        let parity_bit = |x: usize, z: usize| parity[(x % 5) * LANE_BITS + z % LANE_BITS].expr();
+        for x in 0..5 {
+            for z in 0..LANE_BITS {
+                cb.require_equal(
+                    || "theta",
+                    theta[x * LANE_BITS + z].expr(),
+                    xor(parity_bit(x + 4, z), parity_bit(x + 1, z + LANE_BITS - 1)),
+                )?;
+            }
+        }
+
+        // Theta, rho and pi: the lane (x, y) is rotated and moves to (y, 2x + 3y).
+        for x in 0..5 {
+            for y in 0..5 {
+                let offset = KECCAK_RHO_OFFSETS[x + 5 * y] as usize;
+                for z in 0..LANE_BITS {
+                    cb.require_equal(
+                        || "rho_pi",
+                        rho_pi[bit_index(y, 2 * x + 3 * y, z + offset)].expr(),
+                        xor(
+                            input[bit_index(x, y, z)].expr(),
+                            theta[x * LANE_BITS + z].expr(),
+                        ),
+                    )?;
+                }
+            }
+        }
+
+        // Chi and iota.
+        let iota_bits = iota_bits();
+        for x in 0..5 {
+            for y in 0..5 {
+                for z in 0..LANE_BITS {
+                    let i = bit_index(x, y, z);
+                    cb.require_equal(
+                        || "and_not",
+                        and_not[i].expr(),
+                        (1 - rho_pi[bit_index(x + 1, y, z)].expr())
+                            * rho_pi[bit_index(x + 2, y, z)].expr(),
+                    )?;
+                    let chi_out = xor(rho_pi[i].expr(), and_not[i].expr());
+
+                    // The bits of lane (0, 0) are the first ones.
+                    match iota_bits.iter().position(|&iota_i| i == iota_i) {
+                        Some(k) => {
+                            cb.require_equal(|| "chi", chi[k].expr(), chi_out)?;
+                            let round_constant = (round_flags.iter().zip(KECCAK_ROUND_CONSTANTS))
+                                .filter(|(_, rc)| (rc >> z) & 1 == 1)
+                                .map(|(flag, _)| flag.expr())
+                                .sum();
+                            cb.require_equal(
+                                || "iota",
+                                output[i].expr(),
+                                xor(chi[k].expr(), round_constant),
+                            )?;
+                        }
+                        None => cb.require_equal(|| "chi", output[i].expr(), chi_out)?,
+                    }
+                }
+            }
+        }
+
+        // Read the state before the round, and write the state after it.
+        let words = |bits: &[WitIn]| {
+            bits.chunks(WORD_BITS)
+                .map(|word| {
+                    (word.iter().enumerate())
+                        .map(|(k, bit)| bit.expr() << k)
+                        .sum()
+                })
+                .collect_vec()
+        };
+        let record_in = keccak_state_record(cb, id.expr(), round.clone(), words(&input));
+        cb.read_record(|| "state_in", record_in)?;
+        let record_out = keccak_state_record(cb, id.expr(), round + 1, words(&output));
+        cb.write_record(|| "state_out", record_out)?;
+
+        Ok(Self {
+            id,
+            round_flags,
+            input,
+            parity,
+            parity_carries,
+            theta,
+            rho_pi,
+            and_not,
+            chi,
+            output,
+        })
+    }
+
+    fn assign_instance<F: SmallField>(
+        &self,
+        instance: &mut [MaybeUninit<F>],
+        id: u64,
+        round: usize,
+        state: &[u64; 25],
+    ) {
+        let set_lane = |instance: &mut [MaybeUninit<F>], bits: &[WitIn], lane: u64| {
+            for (z, bit) in bits.iter().enumerate() {
+                set_val!(instance, bit, (lane >> z) & 1);
+            }
+        };
+
+        set_val!(instance, self.id, id);
+        for (r, flag) in self.round_flags.iter().enumerate() {
+            set_val!(instance, flag, (r == round) as u64);
+        }
+
+        let parity: [u64; 5] = std::array::from_fn(|x| (0..5).fold(0, |c, y| c ^ state[x + 5 * y]));
+        let theta: [u64; 5] =
+            std::array::from_fn(|x| parity[(x + 4) % 5] ^ parity[(x + 1) % 5].rotate_left(1));
+        let mut rho_pi = [0; 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                rho_pi[y + 5 * ((2 * x + 3 * y) % 5)] =
+                    (state[x + 5 * y] ^ theta[x]).rotate_left(KECCAK_RHO_OFFSETS[x + 5 * y]);
+            }
+        }
+        let and_not: [u64; 25] = std::array::from_fn(|i| {
+            let (x, y) = (i % 5, i / 5);
+            !rho_pi[(x + 1) % 5 + 5 * y] & rho_pi[(x + 2) % 5 + 5 * y]
+        });
+        let mut output: [u64; 25] = std::array::from_fn(|i| rho_pi[i] ^ and_not[i]);
+        for (chi, z) in self.chi.iter().zip(iota_bits()) {
+            set_val!(instance, chi, (output[0] >> z) & 1);
+        }
+        output[0] ^= KECCAK_ROUND_CONSTANTS[round];
+
+        for x in 0..5 {
+            let lane = x * LANE_BITS..(x + 1) * LANE_BITS;
+            set_lane(instance, &self.parity[lane.clone()], parity[x]);
+            set_lane(instance, &self.theta[lane.clone()], theta[x]);
+            for (z, carries) in self.parity_carries[lane].iter().enumerate() {
+                let sum = (0..5).map(|y| (state[x + 5 * y] >> z) & 1).sum::<u64>();
+                let carry = sum >> 1;
+                set_val!(instance, carries[0], carry & 1);
+                set_val!(instance, carries[1], carry >> 1);
+            }
+        }
+        for i in 0..25 {
+            let lane = i * LANE_BITS..(i + 1) * LANE_BITS;
+            set_lane(instance, &self.input[lane.clone()], state[i]);
+            set_lane(instance, &self.rho_pi[lane.clone()], rho_pi[i]);
+            set_lane(instance, &self.and_not[lane.clone()], and_not[i]);
+            set_lane(instance, &self.output[lane], output[i]);
+        }
+    }
+}
+
+/// The rounds of all the Keccak-f permutations of an execution, see the module documentation.
+pub struct KeccakRoundsCircuit<E>(PhantomData<E>);
+
+impl<E: ExtensionField> TableCircuit<E> for KeccakRoundsCircuit<E> {
+    type TableConfig = KeccakRoundsConfig;
+    type FixedInput = ();
+    /// The steps of the ecalls KECCAK_PERMUTE.
+    type WitnessInput = [StepRecord];
+
+    fn name() -> String {
+        "KECCAK_ROUNDS".into()
+    }
+
+    fn construct_circuit(
+        cb: &mut CircuitBuilder<E>,
+        _platform: &Platform,
+    ) -> Result<KeccakRoundsConfig, ZKVMError> {
+        cb.namespace(|| Self::name(), KeccakRoundsConfig::construct_circuit)
+    }
+
+    fn generate_fixed_traces(
+        _config: &KeccakRoundsConfig,
+        num_fixed: usize,
+        _input: &(),
+    ) -> RowMajorMatrix<E::BaseField> {
+        RowMajorMatrix::new(0, num_fixed)
+    }
+
+    fn assign_instances(
+        config: &KeccakRoundsConfig,
+        num_witin: usize,
+        _multiplicity: &[HashMap<u64, usize>],
+        steps: &[StepRecord],
+    ) -> Result<RowMajorMatrix<E::BaseField>, ZKVMError> {
+        let mut rows = Vec::with_capacity(steps.len() * KECCAK_ROUNDS);
+        for step in steps {
+            assert_eq!(
+                step.rs1().unwrap().value,
+                CENO_PLATFORM.ecall_keccak_permute()
+            );
+            let words = step
+                .syscall()
+                .expect("missing syscall witness")
+                .mem_ops
+                .iter()
+                .map(|op| op.value.before as u64)
+                .collect_vec();
+            assert_eq!(words.len(), KECCAK_STATE_WORDS);
+            let mut state: [u64; 25] =
+                std::array::from_fn(|i| words[2 * i] | (words[2 * i + 1] << 32));
+            for round in 0..KECCAK_ROUNDS {
+                rows.push((step.cycle(), round, state));
+                keccak_round(&mut state, round);
+            }
+        }
+
+        let mut table = RowMajorMatrix::<E::BaseField>::new(rows.len(), num_witin);
+        // Like opcode circuits, pad with copies of the last row, so that all rows satisfy the
+        // constraints. The records of the padding are ignored by the prover.
+        match rows.last() {
+            Some(&last) => rows.resize(rows.len() + table.num_padding_instances(), last),
+            None => Self::padding_zero(&mut table, num_witin)?,
+        }
+        table
+            .par_iter_mut()
+            .with_min_len(MIN_PAR_SIZE)
+            .zip(rows.par_iter())
+            .for_each(|(instance, (id, round, state))| {
+                config.assign_instance(instance, *id, *round, state);
+            });
+        Ok(table)
+    }
+}
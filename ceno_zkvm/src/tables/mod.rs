@@ -2,6 +2,7 @@ use crate::{
     circuit_builder::CircuitBuilder, error::ZKVMError, scheme::constants::MIN_PAR_SIZE,
     witness::RowMajorMatrix,
 };
+use ceno_emul::Platform;
 use ff::Field;
 use ff_ext::ExtensionField;
 use rayon::iter::{IndexedParallelIterator, ParallelIterator};
@@ -18,6 +19,15 @@ pub use program::{InsnRecord, ProgramTableCircuit};
 mod ram;
 pub use ram::*;
 
+mod keccak;
+pub use keccak::{KeccakRoundsCircuit, KeccakRoundsConfig, keccak_state_record};
+
+mod pubio_hash;
+pub use pubio_hash::{
+    PubIOHashCircuit, PubIOHashConfig, pubio_hash_state_record, public_io_digest,
+    public_io_num_chunks,
+};
+
 pub trait TableCircuit<E: ExtensionField> {
     type TableConfig: Send + Sync;
     type FixedInput: Send + Sync + ?Sized;
@@ -27,6 +37,7 @@ pub trait TableCircuit<E: ExtensionField> {
 
     fn construct_circuit(
         circuit_builder: &mut CircuitBuilder<E>,
+        platform: &Platform,
     ) -> Result<Self::TableConfig, ZKVMError>;
 
     fn generate_fixed_traces(
@@ -8,6 +8,7 @@ use crate::{
     circuit_builder::CircuitBuilder, error::ZKVMError, structs::ROMType, tables::TableCircuit,
     witness::RowMajorMatrix,
 };
+use ceno_emul::Platform;
 use ff_ext::ExtensionField;
 
 /// Use this trait as parameter to OpsTableCircuit.
@@ -39,7 +40,10 @@ impl<E: ExtensionField, OP: OpsTable> TableCircuit<E> for OpsTableCircuit<E, OP>
         format!("OPS_{:?}", OP::ROM_TYPE)
     }
 
-    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<OpTableConfig, ZKVMError> {
+    fn construct_circuit(
+        cb: &mut CircuitBuilder<E>,
+        _platform: &Platform,
+    ) -> Result<OpTableConfig, ZKVMError> {
         cb.namespace(
             || Self::name(),
             |cb| OpTableConfig::construct_circuit(cb, OP::ROM_TYPE, OP::len()),
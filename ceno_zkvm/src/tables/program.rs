@@ -10,7 +10,7 @@ use crate::{
     tables::TableCircuit,
     witness::RowMajorMatrix,
 };
-use ceno_emul::{DecodedInstruction, PC_STEP_SIZE, Program, WORD_SIZE};
+use ceno_emul::{DecodedInstruction, PC_STEP_SIZE, Platform, Program, WORD_SIZE};
 use ff_ext::ExtensionField;
 use goldilocks::SmallField;
 use itertools::Itertools;
@@ -103,7 +103,10 @@ impl<E: ExtensionField, const PROGRAM_SIZE: usize> TableCircuit<E>
         "PROGRAM".into()
     }
 
-    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<ProgramTableConfig, ZKVMError> {
+    fn construct_circuit(
+        cb: &mut CircuitBuilder<E>,
+        _platform: &Platform,
+    ) -> Result<ProgramTableConfig, ZKVMError> {
         let record = InsnRecord([
             cb.create_fixed(|| "pc")?,
             cb.create_fixed(|| "kind")?,
@@ -189,6 +192,52 @@ impl<E: ExtensionField, const PROGRAM_SIZE: usize> TableCircuit<E>
                 set_val!(row, config.mlt, E::BaseField::from(mlt as u64));
             });
 
+        Self::padding_zero(&mut witness, num_witin)?;
         Ok(witness)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{circuit_builder::ConstraintSystem, witness::LkMultiplicity};
+    use ceno_emul::{CENO_PLATFORM, InsnKind::ADD, encode_rv32};
+    use ff::Field;
+    use goldilocks::{Goldilocks, GoldilocksExt2};
+
+    type E = GoldilocksExt2;
+
+    #[test]
+    fn test_program_padding() {
+        let program = Program::new(
+            CENO_PLATFORM.pc_base(),
+            CENO_PLATFORM.pc_base(),
+            vec![encode_rv32(ADD, 1, 2, 3, 0); 3],
+            Default::default(),
+        );
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config =
+            ProgramTableCircuit::<E, 4>::construct_circuit(&mut cb, &CENO_PLATFORM).unwrap();
+
+        let mut lkm = LkMultiplicity::default();
+        lkm.fetch(CENO_PLATFORM.pc_base() + PC_STEP_SIZE as u32);
+        let witness = ProgramTableCircuit::<E, 4>::assign_instances(
+            &config,
+            cb.cs.num_witin as usize,
+            &lkm.into_finalize_result(),
+            &program,
+        )
+        .unwrap();
+        #[cfg(feature = "checked_witness")]
+        witness
+            .check_assigned("PROGRAM", &cb.cs.witin_namespace_map)
+            .unwrap();
+
+        let mlt = witness.de_interleaving().remove(config.mlt.id as usize);
+        let one = Goldilocks::ONE;
+        let zero = Goldilocks::ZERO;
+        // only the second instruction is fetched, and the padding row is zero
+        assert_eq!(mlt, vec![zero, one, zero, zero]);
+    }
+}
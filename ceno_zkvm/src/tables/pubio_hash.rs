@@ -0,0 +1,328 @@
+//! The Poseidon hash of the public IO region, in place of `PubIOCircuit`, so that only the
+//! digest of the public IO is a public value and the verifier does not depend on its size.
+//!
+//! Each row absorbs a chunk of `SPONGE_RATE` words of the region into the sponge of
+//! `hash_n_to_hash_no_pad`. Like the public IO table, it writes the initial memory records of its
+//! words and reads their final records. The rows are chained by `RAMType::PubIOHashState` records
+//! of the capacity of the sponge, identified by the index of the chunk: `HashedIOGlobalState`
+//! writes the empty capacity before the first chunk, and reads the digest after the last chunk,
+//! which writes it in place of its capacity.
+
+use std::{collections::HashMap, marker::PhantomData, mem::MaybeUninit};
+
+use ceno_emul::{Platform, WORD_SIZE, Word};
+use ff::Field;
+use ff_ext::ExtensionField;
+use goldilocks::{Goldilocks, SmallField};
+use itertools::Itertools;
+use poseidon::{
+    DIGEST_WIDTH, SPONGE_CAPACITY, SPONGE_RATE, SPONGE_WIDTH, digest::Digest,
+    poseidon_hash::hash_n_to_hash_no_pad,
+};
+
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    gadgets::{IsEqualConfig, PoseidonPermutationConfig},
+    set_val,
+    structs::RAMType,
+    tables::{MemFinalRecord, NonVolatileTable, PubIOTable, TableCircuit},
+    witness::RowMajorMatrix,
+};
+
+// The last chunk writes the digest in place of the capacity.
+const _: () = assert!(SPONGE_CAPACITY == DIGEST_WIDTH);
+
+/// The number of chunks of the public IO region.
+pub fn public_io_num_chunks(platform: &Platform) -> usize {
+    let len = PubIOTable::len(platform);
+    assert_eq!(
+        len % SPONGE_RATE,
+        0,
+        "the public IO region is not a whole number of chunks"
+    );
+    len / SPONGE_RATE
+}
+
+/// The digest of the content of the whole public IO region, as proven by `PubIOHashCircuit`.
+pub fn public_io_digest(public_io: &[Word]) -> Digest<Goldilocks> {
+    let elements = public_io
+        .iter()
+        .map(|&word| Goldilocks::from(word as u64))
+        .collect_vec();
+    hash_n_to_hash_no_pad(&elements)
+}
+
+/// The record of the capacity of the sponge before the chunk `chunk`, or of the digest after
+/// the last chunk.
+pub fn pubio_hash_state_record<E: ExtensionField>(
+    cb: &CircuitBuilder<E>,
+    chunk: Expression<E>,
+    lanes: Vec<Expression<E>>,
+) -> Expression<E> {
+    assert_eq!(lanes.len(), SPONGE_CAPACITY);
+    cb.rlc_chip_record([vec![RAMType::PubIOHashState.into(), chunk], lanes].concat())
+}
+
+pub struct PubIOHashConfig {
+    /// The index of the chunk.
+    chunk: WitIn,
+    /// The words of the chunk, and the cycles of their final accesses.
+    words: Vec<WitIn>,
+    final_cycles: Vec<WitIn>,
+    /// The capacity before the chunk.
+    capacity: Vec<WitIn>,
+    permutation: PoseidonPermutationConfig,
+    /// The state after the chunk.
+    output: Vec<WitIn>,
+    is_last: IsEqualConfig,
+    /// The capacity after the chunk, or the digest after the last chunk.
+    next_capacity: Vec<WitIn>,
+
+    platform: Platform,
+}
+
+impl PubIOHashConfig {
+    fn construct_circuit<E: ExtensionField>(
+        cb: &mut CircuitBuilder<E>,
+        platform: &Platform,
+    ) -> Result<Self, ZKVMError> {
+        let num_chunks = public_io_num_chunks(platform);
+        let mut create_witins = |name: &str, n: usize| {
+            (0..n)
+                .map(|i| cb.create_witin(|| format!("{name}_{i}")))
+                .collect_vec()
+        };
+        let words = create_witins("word", SPONGE_RATE);
+        let final_cycles = create_witins("final_cycle", SPONGE_RATE);
+        let capacity = create_witins("capacity", SPONGE_CAPACITY);
+        let output = create_witins("output", SPONGE_WIDTH);
+        let next_capacity = create_witins("next_capacity", SPONGE_CAPACITY);
+        let chunk = cb.create_witin(|| "chunk");
+
+        // The words overwrite the rate of the state.
+        let permutation = PoseidonPermutationConfig::construct_circuit(
+            cb,
+            words.iter().chain(&capacity).map(|w| w.expr()).collect(),
+            output.iter().map(|w| w.expr()).collect(),
+        )?;
+
+        let is_last = IsEqualConfig::construct_circuit(
+            cb,
+            || "is_last",
+            chunk.expr(),
+            (num_chunks - 1).into(),
+        )?;
+        for (k, next) in next_capacity.iter().enumerate() {
+            let lane = output[SPONGE_RATE + k].expr();
+            cb.require_equal(
+                || "next_capacity",
+                next.expr(),
+                lane.clone() + is_last.expr() * (output[k].expr() - lane),
+            )?;
+        }
+
+        for (j, (word, final_cycle)) in words.iter().zip(&final_cycles).enumerate() {
+            let addr =
+                chunk.expr() * (SPONGE_RATE * WORD_SIZE) + (PubIOTable::addr(platform, j) as usize);
+            let ram_type: Expression<E> = (PubIOTable::RAM_TYPE as usize).into();
+            let init = cb.rlc_chip_record(vec![
+                ram_type.clone(),
+                addr.clone(),
+                word.expr(),
+                Expression::ZERO, // Initial cycle.
+            ]);
+            cb.write_record(|| "init_word", init)?;
+            let last = cb.rlc_chip_record(vec![ram_type, addr, word.expr(), final_cycle.expr()]);
+            cb.read_record(|| "final_word", last)?;
+        }
+
+        let record_in = pubio_hash_state_record(
+            cb,
+            chunk.expr(),
+            capacity.iter().map(|w| w.expr()).collect(),
+        );
+        cb.read_record(|| "capacity", record_in)?;
+        let record_out = pubio_hash_state_record(
+            cb,
+            chunk.expr() + 1,
+            next_capacity.iter().map(|w| w.expr()).collect(),
+        );
+        cb.write_record(|| "next_capacity", record_out)?;
+
+        Ok(Self {
+            chunk,
+            words,
+            final_cycles,
+            capacity,
+            permutation,
+            output,
+            is_last,
+            next_capacity,
+            platform: *platform,
+        })
+    }
+
+    /// Assign the chunk `chunk`, and return the capacity after it.
+    fn assign_instance<F: SmallField>(
+        &self,
+        instance: &mut [MaybeUninit<F>],
+        chunk: usize,
+        records: &[MemFinalRecord],
+        capacity: &[F],
+    ) -> Result<Vec<F>, ZKVMError> {
+        set_val!(instance, self.chunk, chunk as u64);
+        for ((word, final_cycle), record) in self.words.iter().zip(&self.final_cycles).zip(records)
+        {
+            set_val!(instance, word, record.value as u64);
+            set_val!(instance, final_cycle, record.cycle);
+        }
+        for (wit, &x) in self.capacity.iter().zip(capacity) {
+            set_val!(instance, wit, x);
+        }
+
+        let input = records
+            .iter()
+            .map(|record| F::from(record.value as u64))
+            .chain(capacity.iter().copied())
+            .collect_vec();
+        let output = self.permutation.assign_instance(instance, &input);
+        for (wit, &x) in self.output.iter().zip(&output) {
+            set_val!(instance, wit, x);
+        }
+
+        let num_chunks = public_io_num_chunks(&self.platform);
+        self.is_last.assign_instance(
+            instance,
+            F::from(chunk as u64),
+            F::from(num_chunks as u64 - 1),
+        )?;
+        let next_capacity = if chunk + 1 == num_chunks {
+            &output[..DIGEST_WIDTH]
+        } else {
+            &output[SPONGE_RATE..]
+        };
+        for (wit, &x) in self.next_capacity.iter().zip(next_capacity) {
+            set_val!(instance, wit, x);
+        }
+        Ok(output[SPONGE_RATE..].to_vec())
+    }
+}
+
+/// The hash of the public IO region, see the module documentation.
+pub struct PubIOHashCircuit<E>(PhantomData<E>);
+
+impl<E: ExtensionField> TableCircuit<E> for PubIOHashCircuit<E> {
+    type TableConfig = PubIOHashConfig;
+    type FixedInput = ();
+    /// The final records of the public IO region, as for `PubIOCircuit`.
+    type WitnessInput = [MemFinalRecord];
+
+    fn name() -> String {
+        "PUBIO_HASH".into()
+    }
+
+    fn construct_circuit(
+        cb: &mut CircuitBuilder<E>,
+        platform: &Platform,
+    ) -> Result<PubIOHashConfig, ZKVMError> {
+        cb.namespace(
+            || Self::name(),
+            |cb| PubIOHashConfig::construct_circuit(cb, platform),
+        )
+    }
+
+    fn generate_fixed_traces(
+        _config: &PubIOHashConfig,
+        num_fixed: usize,
+        _input: &(),
+    ) -> RowMajorMatrix<E::BaseField> {
+        RowMajorMatrix::new(0, num_fixed)
+    }
+
+    fn assign_instances(
+        config: &PubIOHashConfig,
+        num_witin: usize,
+        _multiplicity: &[HashMap<u64, usize>],
+        final_mem: &[MemFinalRecord],
+    ) -> Result<RowMajorMatrix<E::BaseField>, ZKVMError> {
+        let num_chunks = public_io_num_chunks(&config.platform);
+        assert_eq!(final_mem.len(), num_chunks * SPONGE_RATE);
+
+        let mut table = RowMajorMatrix::<E::BaseField>::new(num_chunks, num_witin);
+        let mut rows = table.iter_mut().collect_vec();
+        let (rows, padding) = rows.split_at_mut(num_chunks);
+        let mut capacity = vec![E::BaseField::ZERO; SPONGE_CAPACITY];
+        for (chunk, (row, records)) in rows
+            .iter_mut()
+            .zip(final_mem.chunks(SPONGE_RATE))
+            .enumerate()
+        {
+            capacity = config.assign_instance(row, chunk, records, &capacity)?;
+        }
+        // Like opcode circuits, pad with copies of the last row, so that all rows satisfy the
+        // constraints. The records of the padding are ignored by the prover.
+        for row in padding {
+            row.copy_from_slice(rows[num_chunks - 1]);
+        }
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{circuit_builder::ConstraintSystem, scheme::mock_prover::MockProver};
+    use ceno_emul::CENO_PLATFORM;
+    use goldilocks::GoldilocksExt2;
+    use multilinear_extensions::mle::IntoMLEs;
+
+    type E = GoldilocksExt2;
+
+    #[test]
+    fn test_pubio_hash() {
+        let len = PubIOTable::len(&CENO_PLATFORM);
+        let public_io = (0..len as u32)
+            .map(|i| 0x1234_5678_u32.wrapping_mul(i))
+            .collect_vec();
+        let final_mem = public_io
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| MemFinalRecord {
+                addr: PubIOTable::addr(&CENO_PLATFORM, i),
+                cycle: i as u64,
+                value,
+            })
+            .collect_vec();
+
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = PubIOHashCircuit::<E>::construct_circuit(&mut cb, &CENO_PLATFORM).unwrap();
+        let table = PubIOHashCircuit::<E>::assign_instances(
+            &config,
+            cb.cs.num_witin as usize,
+            &[],
+            &final_mem,
+        )
+        .unwrap();
+        let num_chunks = public_io_num_chunks(&CENO_PLATFORM);
+        assert_eq!(table.num_instances(), num_chunks);
+
+        let wits_in = table
+            .de_interleaving()
+            .into_mles()
+            .into_iter()
+            .map(|mle| mle.into())
+            .collect_vec();
+        MockProver::assert_satisfied(&cb, &wits_in, &[], None, None);
+
+        // the last chunk writes the digest
+        let digest = config
+            .next_capacity
+            .iter()
+            .map(|wit| wits_in[wit.id as usize].get_base_field_vec()[num_chunks - 1])
+            .collect_vec();
+        assert_eq!(digest, public_io_digest(&public_io).0);
+    }
+}
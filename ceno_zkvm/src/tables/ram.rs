@@ -1,13 +1,11 @@
-use ceno_emul::{Addr, CENO_PLATFORM, VMState, WORD_SIZE, Word};
-use ram_circuit::{
-    DynVolatileRamCircuit, NonVolatileRamCircuit, NonVolatileTable, PubIORamCircuit,
-};
+use ceno_emul::{Addr, Platform, VMState, WORD_SIZE, Word};
+use ram_circuit::{DynVolatileRamCircuit, NonVolatileRamCircuit, PubIORamCircuit};
 
 use crate::{instructions::riscv::constants::UINT_LIMBS, structs::RAMType};
 
 mod ram_circuit;
 mod ram_impl;
-pub use ram_circuit::{DynVolatileRamTable, MemFinalRecord, MemInitRecord};
+pub use ram_circuit::{DynVolatileRamTable, MemFinalRecord, MemInitRecord, NonVolatileTable};
 
 #[derive(Clone)]
 pub struct MemTable;
@@ -15,15 +13,21 @@ pub struct MemTable;
 impl DynVolatileRamTable for MemTable {
     const RAM_TYPE: RAMType = RAMType::Memory;
     const V_LIMBS: usize = 1; // See `MemoryExpr`.
-    const OFFSET_ADDR: Addr = CENO_PLATFORM.ram_start();
-    const END_ADDR: Addr = CENO_PLATFORM.ram_end() + 1;
 
     fn name() -> &'static str {
         "MemTable"
     }
 
-    fn max_len() -> usize {
-        let max_size = (Self::END_ADDR - Self::OFFSET_ADDR) / WORD_SIZE as Addr;
+    fn offset_addr(platform: &Platform) -> Addr {
+        platform.ram_start()
+    }
+
+    fn end_addr(platform: &Platform) -> Addr {
+        platform.ram_end() + 1
+    }
+
+    fn max_len(platform: &Platform) -> usize {
+        let max_size = (Self::end_addr(platform) - Self::offset_addr(platform)) / WORD_SIZE as Addr;
         1 << (u32::BITS - 1 - max_size.leading_zeros()) // prev_power_of_2
     }
 }
@@ -38,18 +42,24 @@ impl NonVolatileTable for RegTable {
     const RAM_TYPE: RAMType = RAMType::Register;
     const V_LIMBS: usize = UINT_LIMBS; // See `RegisterExpr`.
     const WRITABLE: bool = true;
-    const OFFSET_ADDR: Addr = 0;
-    const END_ADDR: Addr = 0;
 
     fn name() -> &'static str {
         "RegTable"
     }
 
-    fn len() -> usize {
+    fn offset_addr(_platform: &Platform) -> Addr {
+        0
+    }
+
+    fn end_addr(_platform: &Platform) -> Addr {
+        0
+    }
+
+    fn len(_platform: &Platform) -> usize {
         VMState::REG_COUNT.next_power_of_two()
     }
 
-    fn addr(entry_index: usize) -> Addr {
+    fn addr(_platform: &Platform, entry_index: usize) -> Addr {
         entry_index as Addr
     }
 }
@@ -63,12 +73,18 @@ impl NonVolatileTable for ProgramDataTable {
     const RAM_TYPE: RAMType = RAMType::Memory;
     const V_LIMBS: usize = 1; // See `MemoryExpr`.
     const WRITABLE: bool = false;
-    const OFFSET_ADDR: Addr = CENO_PLATFORM.program_data_start();
-    const END_ADDR: Addr = CENO_PLATFORM.program_data_end() + 1;
 
     fn name() -> &'static str {
         "ProgramDataTable"
     }
+
+    fn offset_addr(platform: &Platform) -> Addr {
+        platform.program_data_start()
+    }
+
+    fn end_addr(platform: &Platform) -> Addr {
+        platform.program_data_end() + 1
+    }
 }
 
 pub type ProgramDataCircuit<E> = NonVolatileRamCircuit<E, ProgramDataTable>;
@@ -80,30 +96,36 @@ impl NonVolatileTable for PubIOTable {
     const RAM_TYPE: RAMType = RAMType::Memory;
     const V_LIMBS: usize = 1; // See `MemoryExpr`.
     const WRITABLE: bool = false;
-    const OFFSET_ADDR: Addr = CENO_PLATFORM.public_io_start();
-    const END_ADDR: Addr = CENO_PLATFORM.public_io_end() + 1;
 
     fn name() -> &'static str {
         "PubIOTable"
     }
+
+    fn offset_addr(platform: &Platform) -> Addr {
+        platform.public_io_start()
+    }
+
+    fn end_addr(platform: &Platform) -> Addr {
+        platform.public_io_end() + 1
+    }
 }
 
 pub type PubIOCircuit<E> = PubIORamCircuit<E, PubIOTable>;
 
-pub fn initial_registers() -> Vec<MemInitRecord> {
-    RegTable::init_state()
+pub fn initial_registers(platform: &Platform) -> Vec<MemInitRecord> {
+    RegTable::init_state(platform)
 }
 
-pub fn init_program_data(program_data_content: &[Word]) -> Vec<MemInitRecord> {
-    let mut program_data_init = ProgramDataTable::init_state();
+pub fn init_program_data(platform: &Platform, program_data_content: &[Word]) -> Vec<MemInitRecord> {
+    let mut program_data_init = ProgramDataTable::init_state(platform);
     for (i, value) in program_data_content.iter().enumerate() {
         program_data_init[i].value = *value;
     }
     program_data_init
 }
 
-pub fn init_public_io(init_public_io: &[Word]) -> Vec<MemInitRecord> {
-    let mut pubio_table = PubIOTable::init_state();
+pub fn init_public_io(platform: &Platform, init_public_io: &[Word]) -> Vec<MemInitRecord> {
+    let mut pubio_table = PubIOTable::init_state(platform);
     for (i, value) in init_public_io.iter().enumerate() {
         pubio_table[i].value = *value;
     }
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, marker::PhantomData};
 
-use ceno_emul::{Addr, Cycle, WORD_SIZE, Word};
+use ceno_emul::{Addr, Cycle, Platform, WORD_SIZE, Word};
 use ff_ext::ExtensionField;
 
 use crate::{
@@ -32,23 +32,27 @@ pub trait NonVolatileTable {
     const RAM_TYPE: RAMType;
     const V_LIMBS: usize;
     const WRITABLE: bool;
-    const OFFSET_ADDR: Addr;
-    const END_ADDR: Addr;
 
     fn name() -> &'static str;
 
-    fn len() -> usize {
-        (Self::END_ADDR - Self::OFFSET_ADDR) as usize / WORD_SIZE
+    /// The first address of the table in the memory layout of the platform.
+    fn offset_addr(platform: &Platform) -> Addr;
+
+    /// The address just past the end of the table.
+    fn end_addr(platform: &Platform) -> Addr;
+
+    fn len(platform: &Platform) -> usize {
+        (Self::end_addr(platform) - Self::offset_addr(platform)) as usize / WORD_SIZE
     }
 
-    fn addr(entry_index: usize) -> Addr {
-        Self::OFFSET_ADDR + (entry_index * WORD_SIZE) as Addr
+    fn addr(platform: &Platform, entry_index: usize) -> Addr {
+        Self::offset_addr(platform) + (entry_index * WORD_SIZE) as Addr
     }
 
-    fn init_state() -> Vec<MemInitRecord> {
-        (0..Self::len())
+    fn init_state(platform: &Platform) -> Vec<MemInitRecord> {
+        (0..Self::len(platform))
             .map(|i| MemInitRecord {
-                addr: Self::addr(i),
+                addr: Self::addr(platform, i),
                 value: 0,
             })
             .collect()
@@ -69,10 +73,13 @@ impl<E: ExtensionField, NVRAM: NonVolatileTable + Send + Sync + Clone> TableCirc
         format!("RAM_{:?}_{}", NVRAM::RAM_TYPE, NVRAM::name())
     }
 
-    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::TableConfig, ZKVMError> {
+    fn construct_circuit(
+        cb: &mut CircuitBuilder<E>,
+        platform: &Platform,
+    ) -> Result<Self::TableConfig, ZKVMError> {
         cb.namespace(
             || Self::name(),
-            |cb| Self::TableConfig::construct_circuit(cb),
+            |cb| Self::TableConfig::construct_circuit(cb, platform),
         )
     }
 
@@ -109,10 +116,13 @@ impl<E: ExtensionField, NVRAM: NonVolatileTable + Send + Sync + Clone> TableCirc
         format!("RAM_{:?}_{}", NVRAM::RAM_TYPE, NVRAM::name())
     }
 
-    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::TableConfig, ZKVMError> {
+    fn construct_circuit(
+        cb: &mut CircuitBuilder<E>,
+        platform: &Platform,
+    ) -> Result<Self::TableConfig, ZKVMError> {
         cb.namespace(
             || Self::name(),
-            |cb| Self::TableConfig::construct_circuit(cb),
+            |cb| Self::TableConfig::construct_circuit(cb, platform),
         )
     }
 
@@ -143,17 +153,20 @@ pub trait DynVolatileRamTable {
     const RAM_TYPE: RAMType;
     const V_LIMBS: usize;
 
-    const OFFSET_ADDR: Addr;
-    const END_ADDR: Addr;
-
     fn name() -> &'static str;
 
-    fn max_len() -> usize {
-        (Self::END_ADDR - Self::OFFSET_ADDR) as usize / WORD_SIZE
+    /// The first address of the table in the memory layout of the platform.
+    fn offset_addr(platform: &Platform) -> Addr;
+
+    /// The address just past the end of the table.
+    fn end_addr(platform: &Platform) -> Addr;
+
+    fn max_len(platform: &Platform) -> usize {
+        (Self::end_addr(platform) - Self::offset_addr(platform)) as usize / WORD_SIZE
     }
 
-    fn addr(entry_index: usize) -> Addr {
-        Self::OFFSET_ADDR + (entry_index * WORD_SIZE) as Addr
+    fn addr(platform: &Platform, entry_index: usize) -> Addr {
+        Self::offset_addr(platform) + (entry_index * WORD_SIZE) as Addr
     }
 }
 
@@ -170,10 +183,13 @@ impl<E: ExtensionField, DVRAM: DynVolatileRamTable + Send + Sync + Clone> TableC
         format!("RAM_{:?}", DVRAM::RAM_TYPE)
     }
 
-    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::TableConfig, ZKVMError> {
+    fn construct_circuit(
+        cb: &mut CircuitBuilder<E>,
+        platform: &Platform,
+    ) -> Result<Self::TableConfig, ZKVMError> {
         cb.namespace(
             || Self::name(),
-            |cb| Self::TableConfig::construct_circuit(cb),
+            |cb| Self::TableConfig::construct_circuit(cb, platform),
         )
     }
 
@@ -1,5 +1,6 @@
 use std::{marker::PhantomData, mem::MaybeUninit};
 
+use ceno_emul::Platform;
 use ff_ext::ExtensionField;
 use goldilocks::SmallField;
 use itertools::Itertools;
@@ -29,12 +30,14 @@ pub struct NonVolatileTableConfig<NVRAM: NonVolatileTable + Send + Sync + Clone>
     final_v: Option<Vec<WitIn>>,
     final_cycle: WitIn,
 
+    platform: Platform,
     phantom: PhantomData<NVRAM>,
 }
 
 impl<NVRAM: NonVolatileTable + Send + Sync + Clone> NonVolatileTableConfig<NVRAM> {
     pub fn construct_circuit<E: ExtensionField>(
         cb: &mut CircuitBuilder<E>,
+        platform: &Platform,
     ) -> Result<Self, ZKVMError> {
         let init_v = (0..NVRAM::V_LIMBS)
             .map(|i| cb.create_fixed(|| format!("init_v_limb_{i}")))
@@ -81,8 +84,8 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> NonVolatileTableConfig<NVRAM
             SetTableSpec {
                 addr_type: SetTableAddrType::FixedAddr,
                 addr_witin_id: None,
-                offset: NVRAM::OFFSET_ADDR,
-                len: NVRAM::len(),
+                offset: NVRAM::offset_addr(platform),
+                len: NVRAM::len(platform),
             },
             init_table,
         )?;
@@ -91,8 +94,8 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> NonVolatileTableConfig<NVRAM
             SetTableSpec {
                 addr_type: SetTableAddrType::FixedAddr,
                 addr_witin_id: None,
-                offset: NVRAM::OFFSET_ADDR,
-                len: NVRAM::len(),
+                offset: NVRAM::offset_addr(platform),
+                len: NVRAM::len(platform),
             },
             final_table,
         )?;
@@ -102,6 +105,7 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> NonVolatileTableConfig<NVRAM
             final_v,
             addr,
             final_cycle,
+            platform: *platform,
             phantom: PhantomData,
         })
     }
@@ -113,11 +117,11 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> NonVolatileTableConfig<NVRAM
         num_fixed: usize,
         init_mem: &[MemInitRecord],
     ) -> RowMajorMatrix<F> {
-        assert!(NVRAM::len().is_power_of_two());
-        assert!(init_mem.len() <= NVRAM::len());
+        assert!(NVRAM::len(&self.platform).is_power_of_two());
+        assert!(init_mem.len() <= NVRAM::len(&self.platform));
 
         // for ram in memory offline check
-        let mut init_table = RowMajorMatrix::<F>::new(NVRAM::len(), num_fixed);
+        let mut init_table = RowMajorMatrix::<F>::new(NVRAM::len(&self.platform), num_fixed);
         assert_eq!(init_table.num_padding_instances(), 0);
 
         init_table
@@ -139,7 +143,7 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> NonVolatileTableConfig<NVRAM
             });
 
         // set padding with well-form address with 0 value
-        if NVRAM::len() - init_mem.len() > 0 {
+        if NVRAM::len(&self.platform) - init_mem.len() > 0 {
             let paddin_entry_start = init_mem.len();
             init_table
                 .par_iter_mut()
@@ -154,7 +158,7 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> NonVolatileTableConfig<NVRAM
                     set_fixed_val!(
                         row,
                         self.addr,
-                        (NVRAM::addr(paddin_entry_start + i) as u64).into()
+                        (NVRAM::addr(&self.platform, paddin_entry_start + i) as u64).into()
                     );
                 });
         }
@@ -167,8 +171,8 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> NonVolatileTableConfig<NVRAM
         num_witness: usize,
         final_mem: &[MemFinalRecord],
     ) -> Result<RowMajorMatrix<F>, ZKVMError> {
-        assert!(final_mem.len() <= NVRAM::len());
-        let mut final_table = RowMajorMatrix::<F>::new(NVRAM::len(), num_witness);
+        assert!(final_mem.len() <= NVRAM::len(&self.platform));
+        let mut final_table = RowMajorMatrix::<F>::new(NVRAM::len(&self.platform), num_witness);
 
         final_table
             .par_iter_mut()
@@ -190,7 +194,7 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> NonVolatileTableConfig<NVRAM
                 set_val!(row, self.final_cycle, rec.cycle);
             });
 
-        if NVRAM::len() - final_mem.len() > 0 {
+        if NVRAM::len(&self.platform) - final_mem.len() > 0 {
             final_table
                 .par_iter_mut()
                 .skip(final_mem.len())
@@ -213,12 +217,14 @@ pub struct PubIOTableConfig<NVRAM: NonVolatileTable + Send + Sync + Clone> {
 
     final_cycle: WitIn,
 
+    platform: Platform,
     phantom: PhantomData<NVRAM>,
 }
 
 impl<NVRAM: NonVolatileTable + Send + Sync + Clone> PubIOTableConfig<NVRAM> {
     pub fn construct_circuit<E: ExtensionField>(
         cb: &mut CircuitBuilder<E>,
+        platform: &Platform,
     ) -> Result<Self, ZKVMError> {
         assert!(!NVRAM::WRITABLE);
         let init_v = cb.query_public_io()?;
@@ -252,8 +258,8 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> PubIOTableConfig<NVRAM> {
             SetTableSpec {
                 addr_type: SetTableAddrType::FixedAddr,
                 addr_witin_id: None,
-                offset: NVRAM::OFFSET_ADDR,
-                len: NVRAM::len(),
+                offset: NVRAM::offset_addr(platform),
+                len: NVRAM::len(platform),
             },
             init_table,
         )?;
@@ -262,8 +268,8 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> PubIOTableConfig<NVRAM> {
             SetTableSpec {
                 addr_type: SetTableAddrType::FixedAddr,
                 addr_witin_id: None,
-                offset: NVRAM::OFFSET_ADDR,
-                len: NVRAM::len(),
+                offset: NVRAM::offset_addr(platform),
+                len: NVRAM::len(platform),
             },
             final_table,
         )?;
@@ -271,16 +277,17 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> PubIOTableConfig<NVRAM> {
         Ok(Self {
             addr,
             final_cycle,
+            platform: *platform,
             phantom: PhantomData,
         })
     }
 
     /// assign to fixed address
     pub fn gen_init_state<F: SmallField>(&self, num_fixed: usize) -> RowMajorMatrix<F> {
-        assert!(NVRAM::len().is_power_of_two());
+        assert!(NVRAM::len(&self.platform).is_power_of_two());
 
         // for ram in memory offline check
-        let mut init_table = RowMajorMatrix::<F>::new(NVRAM::len(), num_fixed);
+        let mut init_table = RowMajorMatrix::<F>::new(NVRAM::len(&self.platform), num_fixed);
         assert_eq!(init_table.num_padding_instances(), 0);
 
         init_table
@@ -288,7 +295,11 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> PubIOTableConfig<NVRAM> {
             .enumerate()
             .with_min_len(MIN_PAR_SIZE)
             .for_each(|(i, row)| {
-                set_fixed_val!(row, self.addr, (NVRAM::addr(i) as u64).into());
+                set_fixed_val!(
+                    row,
+                    self.addr,
+                    (NVRAM::addr(&self.platform, i) as u64).into()
+                );
             });
         init_table
     }
@@ -299,8 +310,8 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> PubIOTableConfig<NVRAM> {
         num_witness: usize,
         final_mem: &[MemFinalRecord],
     ) -> Result<RowMajorMatrix<F>, ZKVMError> {
-        assert!(final_mem.len() == NVRAM::len());
-        let mut final_table = RowMajorMatrix::<F>::new(NVRAM::len(), num_witness);
+        assert!(final_mem.len() == NVRAM::len(&self.platform));
+        let mut final_table = RowMajorMatrix::<F>::new(NVRAM::len(&self.platform), num_witness);
 
         final_table
             .par_iter_mut()
@@ -323,12 +334,14 @@ pub struct DynVolatileRamTableConfig<DVRAM: DynVolatileRamTable + Send + Sync +
     final_v: Vec<WitIn>,
     final_cycle: WitIn,
 
+    platform: Platform,
     phantom: PhantomData<DVRAM>,
 }
 
 impl<DVRAM: DynVolatileRamTable + Send + Sync + Clone> DynVolatileRamTableConfig<DVRAM> {
     pub fn construct_circuit<E: ExtensionField>(
         cb: &mut CircuitBuilder<E>,
+        platform: &Platform,
     ) -> Result<Self, ZKVMError> {
         let addr = cb.create_witin(|| "addr");
 
@@ -363,8 +376,8 @@ impl<DVRAM: DynVolatileRamTable + Send + Sync + Clone> DynVolatileRamTableConfig
             SetTableSpec {
                 addr_type: SetTableAddrType::DynamicAddr,
                 addr_witin_id: Some(addr.id.into()),
-                offset: DVRAM::OFFSET_ADDR,
-                len: DVRAM::max_len(),
+                offset: DVRAM::offset_addr(platform),
+                len: DVRAM::max_len(platform),
             },
             init_table,
         )?;
@@ -373,8 +386,8 @@ impl<DVRAM: DynVolatileRamTable + Send + Sync + Clone> DynVolatileRamTableConfig
             SetTableSpec {
                 addr_type: SetTableAddrType::DynamicAddr,
                 addr_witin_id: Some(addr.id.into()),
-                offset: DVRAM::OFFSET_ADDR,
-                len: DVRAM::max_len(),
+                offset: DVRAM::offset_addr(platform),
+                len: DVRAM::max_len(platform),
             },
             final_table,
         )?;
@@ -383,6 +396,7 @@ impl<DVRAM: DynVolatileRamTable + Send + Sync + Clone> DynVolatileRamTableConfig
             addr,
             final_v,
             final_cycle,
+            platform: *platform,
             phantom: PhantomData,
         })
     }
@@ -393,8 +407,8 @@ impl<DVRAM: DynVolatileRamTable + Send + Sync + Clone> DynVolatileRamTableConfig
         num_witness: usize,
         final_mem: &[MemFinalRecord],
     ) -> Result<RowMajorMatrix<F>, ZKVMError> {
-        assert!(final_mem.len() <= DVRAM::max_len());
-        assert!(DVRAM::max_len().is_power_of_two());
+        assert!(final_mem.len() <= DVRAM::max_len(&self.platform));
+        assert!(DVRAM::max_len(&self.platform).is_power_of_two());
         let mut final_table =
             RowMajorMatrix::<F>::new(final_mem.len().next_power_of_two(), num_witness);
 
@@ -430,7 +444,11 @@ impl<DVRAM: DynVolatileRamTable + Send + Sync + Clone> DynVolatileRamTableConfig
                     self.final_v.iter().for_each(|limb| {
                         set_val!(row, limb, 0u64);
                     });
-                    set_val!(row, self.addr, DVRAM::addr(paddin_entry_start + i) as u64);
+                    set_val!(
+                        row,
+                        self.addr,
+                        DVRAM::addr(&self.platform, paddin_entry_start + i) as u64
+                    );
                 });
         }
 
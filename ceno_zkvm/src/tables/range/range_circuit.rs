@@ -8,6 +8,7 @@ use crate::{
     circuit_builder::CircuitBuilder, error::ZKVMError, structs::ROMType, tables::TableCircuit,
     witness::RowMajorMatrix,
 };
+use ceno_emul::Platform;
 use ff_ext::ExtensionField;
 
 /// Use this trait as parameter to RangeTableCircuit.
@@ -32,7 +33,10 @@ impl<E: ExtensionField, RANGE: RangeTable> TableCircuit<E> for RangeTableCircuit
         format!("RANGE_{:?}", RANGE::ROM_TYPE)
     }
 
-    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<RangeTableConfig, ZKVMError> {
+    fn construct_circuit(
+        cb: &mut CircuitBuilder<E>,
+        _platform: &Platform,
+    ) -> Result<RangeTableConfig, ZKVMError> {
         cb.namespace(
             || Self::name(),
             |cb| RangeTableConfig::construct_circuit(cb, RANGE::ROM_TYPE, RANGE::len()),
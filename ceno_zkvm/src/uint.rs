@@ -219,6 +219,8 @@ impl<const M: usize, const C: usize, E: ExtensionField> UIntLimbs<M, C, E> {
                     .chain(std::iter::repeat(E::BaseField::ZERO)),
             ) {
                 instance[wire.id as usize] = MaybeUninit::new(limb);
+                #[cfg(feature = "checked_witness")]
+                crate::witness::mark_assigned(&instance[wire.id as usize]);
             }
         }
     }
@@ -245,6 +247,8 @@ impl<const M: usize, const C: usize, E: ExtensionField> UIntLimbs<M, C, E> {
                     .chain(std::iter::repeat(E::BaseField::ZERO)),
             ) {
                 instance[wire.id as usize] = MaybeUninit::new(carry);
+                #[cfg(feature = "checked_witness")]
+                crate::witness::mark_assigned(&instance[wire.id as usize]);
             }
         }
     }
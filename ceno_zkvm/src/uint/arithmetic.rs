@@ -83,7 +83,8 @@ impl<const M: usize, const C: usize, E: ExtensionField> UIntLimbs<M, C, E> {
             // convert Expression::Constant to limbs
             let b_limbs = (0..Self::NUM_LIMBS)
                 .map(|i| {
-                    Expression::Constant(E::BaseField::from((b >> (C * i)) & Self::LIMB_BIT_MASK))
+                    let limb = b.checked_shr((C * i) as u32).unwrap_or(0);
+                    Expression::Constant(E::BaseField::from(limb & Self::LIMB_BIT_MASK))
                 })
                 .collect_vec();
 
@@ -19,6 +19,19 @@ use rayon::{
 };
 use thread_local::ThreadLocal;
 
+#[cfg(feature = "checked_witness")]
+use crate::error::ZKVMError;
+#[cfg(feature = "checked_witness")]
+use itertools::Itertools;
+#[cfg(feature = "checked_witness")]
+use std::{
+    collections::BTreeMap,
+    sync::{
+        RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
 use crate::{
     structs::ROMType,
     tables::{AndTable, LtuTable, OpsTable, OrTable, PowTable, XorTable},
@@ -27,16 +40,40 @@ use crate::{
 
 #[macro_export]
 macro_rules! set_val {
-    ($ins:ident, $field:expr, $val:expr) => {
+    ($ins:ident, $field:expr, $val:expr) => {{
         $ins[$field.id as usize] = MaybeUninit::new($val.into());
-    };
+        #[cfg(feature = "checked_witness")]
+        $crate::witness::mark_assigned(&$ins[$field.id as usize]);
+    }};
 }
 
 #[macro_export]
 macro_rules! set_fixed_val {
-    ($ins:ident, $field:expr, $val:expr) => {
+    ($ins:ident, $field:expr, $val:expr) => {{
         $ins[$field.0] = MaybeUninit::new($val);
-    };
+        #[cfg(feature = "checked_witness")]
+        $crate::witness::mark_assigned(&$ins[$field.0]);
+    }};
+}
+
+/// The bitmaps of the assigned cells of the live matrices, by the address range of their values,
+/// so that a cell written through a row slice finds its matrix.
+#[cfg(feature = "checked_witness")]
+#[allow(clippy::type_complexity)]
+static ASSIGNED: RwLock<BTreeMap<usize, (usize, Arc<[AtomicU64]>)>> = RwLock::new(BTreeMap::new());
+
+/// Record that a cell of a [`RowMajorMatrix`] is assigned. Cells outside of any matrix, such as
+/// the padding rows built on the side, are ignored.
+#[cfg(feature = "checked_witness")]
+pub fn mark_assigned<T>(cell: &MaybeUninit<T>) {
+    let addr = cell as *const MaybeUninit<T> as usize;
+    let assigned = ASSIGNED.read().unwrap();
+    if let Some((&start, (end, bitmap))) = assigned.range(..=addr).next_back() {
+        if addr < *end {
+            let i = (addr - start) / mem::size_of::<MaybeUninit<T>>();
+            bitmap[i / 64].fetch_or(1 << (i % 64), Ordering::Relaxed);
+        }
+    }
 }
 
 pub struct RowMajorMatrix<T: Sized + Sync + Clone + Send> {
@@ -44,16 +81,34 @@ pub struct RowMajorMatrix<T: Sized + Sync + Clone + Send> {
     values: Vec<MaybeUninit<T>>,
     num_padding_rows: usize,
     num_col: usize,
+    /// One bit per cell, set once the cell is assigned, see [`Self::unassigned_cells`].
+    #[cfg(feature = "checked_witness")]
+    assigned: Arc<[AtomicU64]>,
 }
 
 impl<T: Sized + Sync + Clone + Send> RowMajorMatrix<T> {
     pub fn new(num_rows: usize, num_col: usize) -> Self {
         let num_total_rows = next_pow2_instance_padding(num_rows);
         let num_padding_rows = num_total_rows - num_rows;
+        let values: Vec<MaybeUninit<T>> = create_uninit_vec(num_total_rows * num_col);
+        #[cfg(feature = "checked_witness")]
+        let assigned: Arc<[AtomicU64]> = (0..values.len().div_ceil(64))
+            .map(|_| AtomicU64::new(0))
+            .collect();
+        #[cfg(feature = "checked_witness")]
+        if !values.is_empty() {
+            let range = values.as_ptr_range();
+            ASSIGNED
+                .write()
+                .unwrap()
+                .insert(range.start as usize, (range.end as usize, assigned.clone()));
+        }
         RowMajorMatrix {
-            values: create_uninit_vec(num_total_rows * num_col),
+            values,
             num_padding_rows,
             num_col,
+            #[cfg(feature = "checked_witness")]
+            assigned,
         }
     }
 
@@ -108,6 +163,61 @@ impl<T: Sized + Sync + Clone + Send> RowMajorMatrix<T> {
     }
 }
 
+#[cfg(feature = "checked_witness")]
+impl<T: Sized + Sync + Clone + Send> Drop for RowMajorMatrix<T> {
+    fn drop(&mut self) {
+        if !self.values.is_empty() {
+            ASSIGNED
+                .write()
+                .unwrap()
+                .remove(&(self.values.as_ptr() as usize));
+        }
+    }
+}
+
+impl<T: Sized + Sync + Clone + Send> RowMajorMatrix<T> {
+    /// The cells of the instances which are not assigned, by row and column. The padding rows
+    /// are left to the padding strategy of each circuit.
+    #[cfg(feature = "checked_witness")]
+    pub fn unassigned_cells(&self) -> Vec<(usize, usize)> {
+        (0..self.num_instances() * self.num_col)
+            .filter(|i| self.assigned[i / 64].load(Ordering::Relaxed) & (1 << (i % 64)) == 0)
+            .map(|i| (i / self.num_col, i % self.num_col))
+            .collect()
+    }
+
+    /// Fail with the witness namespaces of the unassigned cells of the circuit `name`.
+    #[cfg(feature = "checked_witness")]
+    pub fn check_assigned(
+        &self,
+        name: &str,
+        witin_namespace_map: &[String],
+    ) -> Result<(), ZKVMError> {
+        let unassigned = self.unassigned_cells();
+        if unassigned.is_empty() {
+            return Ok(());
+        }
+        let columns = unassigned
+            .into_iter()
+            .into_group_map_by(|(_, col)| *col)
+            .into_iter()
+            .sorted_by_key(|(col, _)| *col)
+            .map(|(col, cells)| match cells.len() {
+                1 => format!("{} at row {}", witin_namespace_map[col], cells[0].0),
+                n => format!(
+                    "{} at row {} and {} more",
+                    witin_namespace_map[col],
+                    cells[0].0,
+                    n - 1
+                ),
+            })
+            .join(", ");
+        Err(ZKVMError::InvalidWitness(format!(
+            "{name}: unassigned witnesses {columns}"
+        )))
+    }
+}
+
 impl<F: Field> RowMajorMatrix<F> {
     pub fn into_mles<E: ff_ext::ExtensionField<BaseField = F>>(
         self,
@@ -210,6 +320,11 @@ mod tests {
     use std::thread;
 
     use crate::{structs::ROMType, witness::LkMultiplicity};
+    #[cfg(feature = "checked_witness")]
+    use {
+        super::RowMajorMatrix, crate::expression::WitIn, goldilocks::Goldilocks,
+        std::mem::MaybeUninit,
+    };
 
     #[test]
     fn test_lk_multiplicity_threads() {
@@ -227,4 +342,29 @@ mod tests {
         // check multiplicity counts of assert_byte
         assert_eq!(res[ROMType::U8 as usize][&8], thread_count);
     }
+
+    #[cfg(feature = "checked_witness")]
+    #[test]
+    fn test_unassigned_cells() {
+        let mut witness = RowMajorMatrix::<Goldilocks>::new(3, 2);
+        // the padding row is not checked
+        let (a, b) = (WitIn { id: 0 }, WitIn { id: 1 });
+        for (i, row) in witness.iter_mut().take(3).enumerate() {
+            set_val!(row, a, i as u64);
+            if i != 1 {
+                set_val!(row, b, 0u64);
+            }
+        }
+        assert_eq!(witness.unassigned_cells(), vec![(1, 1)]);
+
+        let names = ["test/a".to_string(), "test/b".to_string()];
+        let err = witness.check_assigned("test", &names).unwrap_err();
+        assert_eq!(
+            format!("{err:?}"),
+            r#"InvalidWitness("test: unassigned witnesses test/b at row 1")"#
+        );
+
+        let witness = RowMajorMatrix::<Goldilocks>::new(3, 2);
+        assert_eq!(witness.unassigned_cells().len(), 3 * 2);
+    }
 }
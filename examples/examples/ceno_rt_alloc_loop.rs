@@ -0,0 +1,27 @@
+#![no_main]
+#![no_std]
+
+extern crate ceno_rt;
+use ceno_rt::println;
+use core::fmt::Write;
+
+extern crate alloc;
+use alloc::{string::String, vec::Vec};
+
+ceno_rt::entry!(main);
+fn main() {
+    // Allocate and drop in a loop, like a parser would.
+    let mut total = 0u32;
+    for i in 0..20 {
+        let mut v = Vec::new();
+        for j in 0..200 {
+            v.push(i * j);
+        }
+        let mut s = String::new();
+        for _ in 0..i {
+            s.push('x');
+        }
+        total = total.wrapping_add(v.iter().sum::<u32>() + s.len() as u32);
+    }
+    println!("{total}");
+}
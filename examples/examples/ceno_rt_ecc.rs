@@ -0,0 +1,54 @@
+#![no_main]
+#![no_std]
+
+extern crate ceno_rt;
+use ceno_rt::{
+    syscall_ed25519_add, syscall_ed25519_double, syscall_secp256k1_add, syscall_secp256k1_double,
+};
+
+/// The generator of secp256k1, `x` then `y` in little-endian words.
+const SECP256K1_G: [u32; 16] = [
+    0x16F81798, 0x59F2815B, 0x2DCE28D9, 0x029BFCDB, 0xCE870B07, 0x55A06295, 0xF9DCBBAC, 0x79BE667E,
+    0xFB10D4B8, 0x9C47D08F, 0xA6855419, 0xFD17B448, 0x0E1108A8, 0x5DA4FBFC, 0x26A3C465, 0x483ADA77,
+];
+
+/// The base point of ed25519, `x` then `y` in little-endian words.
+const ED25519_B: [u32; 16] = [
+    0x8F25D51A, 0xC9562D60, 0x9525A7B2, 0x692CC760, 0xFDD6DC5C, 0xC0A4E231, 0xCD6E53FE, 0x216936D3,
+    0x66666658, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666,
+];
+
+const SCALAR: u32 = 1_000_003;
+
+/// `k * point` for `k > 0`, by double-and-add from the top bit.
+fn scalar_mul(
+    point: &[u32; 16],
+    k: u32,
+    add: fn(&mut [u32; 16], &[u32; 16]),
+    double: fn(&mut [u32; 16]),
+) -> [u32; 16] {
+    let mut acc = *point;
+    for i in (0..31 - k.leading_zeros()).rev() {
+        double(&mut acc);
+        if k >> i & 1 == 1 {
+            add(&mut acc, point);
+        }
+    }
+    acc
+}
+
+ceno_rt::entry!(main);
+fn main() {
+    ceno_rt::write(&scalar_mul(
+        &SECP256K1_G,
+        SCALAR,
+        syscall_secp256k1_add,
+        syscall_secp256k1_double,
+    ));
+    ceno_rt::write(&scalar_mul(
+        &ED25519_B,
+        SCALAR,
+        syscall_ed25519_add,
+        syscall_ed25519_double,
+    ));
+}
@@ -0,0 +1,17 @@
+#![no_main]
+#![no_std]
+
+extern crate ceno_rt;
+
+extern crate alloc;
+use alloc::{string::String, vec::Vec};
+
+ceno_rt::entry!(main);
+fn main() {
+    let (factor, values): (u32, Vec<u32>) = ceno_rt::read();
+    let name: &str = ceno_rt::read();
+
+    let scaled = values.iter().map(|v| v * factor).collect::<Vec<_>>();
+    ceno_rt::write(&scaled);
+    ceno_rt::write(&(String::from("Hello, ") + name));
+}
@@ -0,0 +1,16 @@
+#![no_main]
+#![no_std]
+
+extern crate ceno_rt;
+use ceno_rt::syscall_keccak_permute;
+
+const ITERATIONS: usize = 100;
+
+ceno_rt::entry!(main);
+fn main() {
+    let mut state = [0_u64; 25];
+    for _ in 0..ITERATIONS {
+        syscall_keccak_permute(&mut state);
+    }
+    ceno_rt::write(&state);
+}
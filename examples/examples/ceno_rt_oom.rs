@@ -0,0 +1,15 @@
+#![no_main]
+#![no_std]
+
+extern crate ceno_rt;
+
+extern crate alloc;
+use alloc::vec;
+use core::ptr::read_volatile;
+
+ceno_rt::entry!(main);
+fn main() {
+    // Allocate more than the heap.
+    let v = vec![0u8; 0x6000_0000];
+    unsafe { read_volatile(&v[0]) };
+}
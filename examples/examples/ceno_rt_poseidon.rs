@@ -0,0 +1,17 @@
+#![no_main]
+#![no_std]
+
+extern crate ceno_rt;
+use ceno_rt::poseidon::{hash_or_noop, merkle_root, two_to_one};
+
+ceno_rt::entry!(main);
+fn main() {
+    let inputs: [u64; 10] = core::array::from_fn(|i| i as u64);
+    let leaf = hash_or_noop(&inputs);
+    ceno_rt::write(&leaf);
+
+    // The path of the leaf 2 of a tree with 4 leaves.
+    let siblings = [[1, 2, 3, 4], two_to_one(&[5, 6, 7, 8], &[9, 10, 11, 12])];
+    let root = merkle_root(&leaf, 2, &siblings);
+    ceno_rt::write(&root);
+}
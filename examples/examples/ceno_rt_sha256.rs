@@ -0,0 +1,15 @@
+#![no_main]
+#![no_std]
+
+extern crate ceno_rt;
+use ceno_rt::sha256::{Sha256, sha256};
+
+ceno_rt::entry!(main);
+fn main() {
+    ceno_rt::write(&sha256(b"abc"));
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"abcdbcdecdefdefgefghfghighij");
+    hasher.update(b"hijkijkljklmklmnlmnomnopnopq");
+    ceno_rt::write(&hasher.finalize());
+}
@@ -0,0 +1,25 @@
+#![no_main]
+#![no_std]
+
+extern crate ceno_rt;
+use core::ptr::read_volatile;
+
+ceno_rt::entry!(main);
+fn main() {
+    recurse(u32::MAX);
+}
+
+/// Recurse with a large stack frame, until the stack overflows.
+#[inline(never)]
+fn recurse(depth: u32) -> u32 {
+    let frame = [depth; 256];
+    let frame = black_box(&frame);
+    if frame[0] == 0 {
+        return 0;
+    }
+    frame[255].wrapping_add(recurse(depth - 1))
+}
+
+fn black_box<T>(x: T) -> T {
+    unsafe { read_volatile(&x) }
+}
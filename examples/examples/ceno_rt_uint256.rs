@@ -0,0 +1,38 @@
+#![no_main]
+#![no_std]
+
+extern crate ceno_rt;
+use ceno_rt::{syscall_uint256_add, syscall_uint256_mulmod};
+
+/// The order of the field of secp256k1, 2^256 - 2^32 - 977, in little-endian words.
+const P: [u32; 8] = [
+    0xFFFF_FC2F,
+    0xFFFF_FFFE,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+];
+
+/// `base^exponent mod P`, by square-and-multiply.
+fn pow_mod(base: &[u32; 8], exponent: &[u32; 8]) -> [u32; 8] {
+    let mut result = [1, 0, 0, 0, 0, 0, 0, 0];
+    for i in (0..256).rev() {
+        let square = [result, P];
+        syscall_uint256_mulmod(&mut result, &square);
+        if exponent[i / 32] >> (i % 32) & 1 == 1 {
+            syscall_uint256_mulmod(&mut result, &[*base, P]);
+        }
+    }
+    result
+}
+
+ceno_rt::entry!(main);
+fn main() {
+    // Fermat's little theorem: 3^(P-1) = 1 mod P.
+    let mut p_minus_one = P;
+    syscall_uint256_add(&mut p_minus_one, &[u32::MAX; 8]);
+    ceno_rt::write(&pow_mod(&[3, 0, 0, 0, 0, 0, 0, 0], &p_minus_one));
+}
@@ -1,14 +1,15 @@
-use std::{any::TypeId, borrow::Cow, mem, sync::Arc};
+use std::{any::TypeId, borrow::Cow, cell::Cell, mem, sync::Arc};
 
 use crate::{op_mle, util::ceil_log2};
 use ark_std::{end_timer, rand::RngCore, start_timer};
 use core::hash::Hash;
 use ff::Field;
 use ff_ext::ExtensionField;
+use goldilocks::SmallField;
 use rayon::iter::{
     IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
 };
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::Debug;
 
 pub trait MultilinearExtension<E: ExtensionField>: Send + Sync {
@@ -122,16 +123,95 @@ impl<F: Field, E: ExtensionField<BaseField = F>> IntoMLEs<DenseMultilinearExtens
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Hash, Default, Debug, Serialize, Deserialize)]
-#[serde(untagged)]
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug)]
 /// Differentiate inner vector on base/extension field.
 pub enum FieldType<E: ExtensionField> {
-    Base(#[serde(skip)] Vec<E::BaseField>),
+    Base(Vec<E::BaseField>),
     Ext(Vec<E>),
     #[default]
     Unreachable,
 }
 
+thread_local! {
+    static KEEP_BASE_FIELDS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` with [`FieldType`] serialized along with its base field vectors.
+///
+/// The base field vectors are skipped by default. Whatever is serialized inside `f` must be
+/// deserialized inside this function as well.
+pub fn with_base_fields<T>(f: impl FnOnce() -> T) -> T {
+    struct Restore(bool);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            KEEP_BASE_FIELDS.set(self.0);
+        }
+    }
+
+    let _restore = Restore(KEEP_BASE_FIELDS.replace(true));
+    f()
+}
+
+/// The default serialization of [`FieldType`], which skips the base field vectors.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged, rename = "FieldType")]
+enum UntaggedFieldType<B, X> {
+    Base(#[serde(skip)] B),
+    Ext(X),
+    Unreachable,
+}
+
+/// The serialization of [`FieldType`] inside [`with_base_fields`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "FieldType")]
+enum TaggedFieldType<X> {
+    Base(Vec<u64>),
+    Ext(X),
+    Unreachable,
+}
+
+impl<E: ExtensionField> Serialize for FieldType<E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if KEEP_BASE_FIELDS.get() {
+            match self {
+                FieldType::Base(content) => TaggedFieldType::Base(
+                    content.iter().map(SmallField::to_canonical_u64).collect(),
+                ),
+                FieldType::Ext(content) => TaggedFieldType::Ext(content),
+                FieldType::Unreachable => TaggedFieldType::Unreachable,
+            }
+            .serialize(serializer)
+        } else {
+            match self {
+                FieldType::Base(_) => UntaggedFieldType::<(), &Vec<E>>::Base(()),
+                FieldType::Ext(content) => UntaggedFieldType::Ext(content),
+                FieldType::Unreachable => UntaggedFieldType::Unreachable,
+            }
+            .serialize(serializer)
+        }
+    }
+}
+
+impl<'de, E: ExtensionField + Deserialize<'de>> Deserialize<'de> for FieldType<E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(if KEEP_BASE_FIELDS.get() {
+            match TaggedFieldType::<Vec<E>>::deserialize(deserializer)? {
+                TaggedFieldType::Base(content) => {
+                    FieldType::Base(content.into_iter().map(E::BaseField::from).collect())
+                }
+                TaggedFieldType::Ext(content) => FieldType::Ext(content),
+                TaggedFieldType::Unreachable => FieldType::Unreachable,
+            }
+        } else {
+            match UntaggedFieldType::<Vec<E::BaseField>, Vec<E>>::deserialize(deserializer)? {
+                UntaggedFieldType::Base(content) => FieldType::Base(content),
+                UntaggedFieldType::Ext(content) => FieldType::Ext(content),
+                UntaggedFieldType::Unreachable => FieldType::Unreachable,
+            }
+        })
+    }
+}
+
 impl<E: ExtensionField> FieldType<E> {
     pub fn len(&self) -> usize {
         match self {
@@ -1,12 +1,15 @@
 use ark_std::test_rng;
 use ff::Field;
 use ff_ext::ExtensionField;
-use goldilocks::GoldilocksExt2;
+use goldilocks::{Goldilocks, GoldilocksExt2};
 
 type E = GoldilocksExt2;
 
 use crate::{
-    mle::{ArcDenseMultilinearExtension, DenseMultilinearExtension, MultilinearExtension},
+    mle::{
+        ArcDenseMultilinearExtension, DenseMultilinearExtension, FieldType, MultilinearExtension,
+        with_base_fields,
+    },
     util::bit_decompose,
     virtual_poly::{VirtualPolynomial, build_eq_x_r},
 };
@@ -104,6 +107,28 @@ fn test_fix_high_variables() {
 //      eq(x,y) = \prod_i=1^num_var (x_i * y_i + (1-x_i)*(1-y_i))
 // over r, which is
 //      eq(x,y) = \prod_i=1^num_var (x_i * r_i + (1-x_i)*(1-r_i))
+#[test]
+fn test_field_type_serde() {
+    let field_types = [
+        FieldType::<E>::Base(vec![Goldilocks::ONE]),
+        FieldType::<E>::Ext(vec![E::ONE]),
+        FieldType::<E>::Unreachable,
+    ];
+
+    // by default, the base field vectors are skipped
+    assert_eq!(
+        bincode::serialize(&field_types[0]).unwrap(),
+        bincode::serialize(&field_types[2]).unwrap()
+    );
+
+    for field_type in field_types {
+        let deserialized = with_base_fields(|| {
+            bincode::deserialize::<FieldType<E>>(&bincode::serialize(&field_type).unwrap()).unwrap()
+        });
+        assert_eq!(deserialized, field_type);
+    }
+}
+
 fn build_eq_x_r_for_test<E: ExtensionField>(r: &[E]) -> ArcDenseMultilinearExtension<E> {
     // we build eq(x,r) from its evaluations
     // we want to evaluate eq(x,r) over x \in {0, 1}^num_vars
@@ -1,6 +1,10 @@
 extern crate core;
 
 pub(crate) mod constants;
+pub use constants::{
+    ALL_ROUND_CONSTANTS, DIGEST_WIDTH, HALF_N_FULL_ROUNDS, N_PARTIAL_ROUNDS, N_ROUNDS,
+    SPONGE_CAPACITY, SPONGE_RATE, SPONGE_WIDTH,
+};
 pub mod digest;
 pub mod poseidon;
 mod poseidon_goldilocks;
@@ -1,9 +1,11 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{
     constants::{SPONGE_RATE, SPONGE_WIDTH},
     poseidon::Poseidon,
 };
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct PoseidonPermutation<T: Poseidon> {
     state: [T; SPONGE_WIDTH],
 }
@@ -2,10 +2,15 @@ use ff::Field;
 use ff_ext::ExtensionField;
 use goldilocks::SmallField;
 use poseidon::poseidon_permutation::PoseidonPermutation;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
 use crate::Challenge;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E::BaseField: DeserializeOwned"
+))]
 pub struct Transcript<E: ExtensionField> {
     permutation: PoseidonPermutation<E::BaseField>,
 }